@@ -1,9 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use embedded_io_adapters::std::FromStd;
-use sbus_rs::{
-    pack_channels, SbusPacket, SbusParser, CHANNEL_COUNT, CHANNEL_MAX, SBUS_FOOTER,
-    SBUS_FRAME_LENGTH, SBUS_HEADER,
-};
+use sbus_rs::test_util::{create_frame, create_stream};
+use sbus_rs::{SbusPacket, SbusParser, CHANNEL_COUNT, CHANNEL_MAX, SBUS_FRAME_LENGTH};
 use std::io::Cursor;
 
 const fn generate_alternating() -> [u16; CHANNEL_COUNT] {
@@ -34,34 +32,16 @@ const SCENARIOS: &[(&str, [u16; CHANNEL_COUNT])] = &[
     ("ascending", generate_ascending()),
 ];
 
-fn create_test_frame(channels: &[u16; 16], flags: u8) -> [u8; SBUS_FRAME_LENGTH] {
-    let mut buffer = [0u8; SBUS_FRAME_LENGTH];
-    buffer[0] = SBUS_HEADER;
-    buffer[SBUS_FRAME_LENGTH - 1] = SBUS_FOOTER;
-
-    // Pack channels using the same logic from tests
-    pack_channels(&mut buffer, channels);
-    buffer[23] = flags;
-
-    buffer
-}
-
 fn create_streaming_buffer(frame_count: usize) -> Vec<u8> {
-    let mut buffer = Vec::with_capacity(SBUS_FRAME_LENGTH * frame_count);
-    let channels = [1000u16; 16]; // Mid-range value for all channels
-    let frame = create_test_frame(&channels, 0);
-
-    for _ in 0..frame_count {
-        buffer.extend_from_slice(&frame);
-    }
-    buffer
+    // Mid-range value for all channels
+    create_stream(&create_frame(&[1000u16; 16], 0), frame_count)
 }
 
 fn bench_sync_frame_parsing(c: &mut Criterion) {
     let mut group = c.benchmark_group("sync/frame_parsing");
 
     for (name, channels) in SCENARIOS {
-        let frame = create_test_frame(channels, 0);
+        let frame = create_frame(channels, 0);
         group.bench_with_input(
             BenchmarkId::new("sync/parse_frame", name),
             &frame,
@@ -99,7 +79,7 @@ fn bench_sync_streaming_parser(c: &mut Criterion) {
 fn bench_sync_frame_validation(c: &mut Criterion) {
     let mut group = c.benchmark_group("sync/frame_validation");
 
-    let valid_frame = create_test_frame(&[1000u16; 16], 0);
+    let valid_frame = create_frame(&[1000u16; 16], 0);
     let mut invalid_header = valid_frame;
     invalid_header[0] = 0x00;
     let mut invalid_footer = valid_frame;
@@ -124,6 +104,35 @@ fn bench_sync_frame_validation(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_sync_resync(c: &mut Criterion) {
+    use sbus_rs::Deframer;
+
+    let mut group = c.benchmark_group("sync/resync");
+
+    for garbage in [64, 1024] {
+        let mut buffer = vec![0xAA; garbage];
+        buffer.extend_from_slice(&create_frame(&[1000u16; 16], 0));
+
+        group.bench_with_input(
+            BenchmarkId::new("sync/resync_garbage", garbage),
+            &buffer,
+            |b, data| {
+                b.iter(|| {
+                    let mut deframer = Deframer::new();
+                    for chunk in data.chunks(32) {
+                        deframer.push_bytes(chunk);
+                        if let Some(packet) = deframer.try_parse() {
+                            black_box(packet);
+                        }
+                    }
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
 #[cfg(feature = "async")]
 fn bench_async_parser(c: &mut Criterion) {
     use embedded_io_adapters::tokio_1::FromTokio;
@@ -160,14 +169,14 @@ fn bench_async_parser(c: &mut Criterion) {
 criterion_group! {
     name = benches;
     config = Criterion::default().sample_size(200);
-    targets = bench_sync_frame_parsing, bench_sync_streaming_parser, bench_sync_frame_validation
+    targets = bench_sync_frame_parsing, bench_sync_streaming_parser, bench_sync_frame_validation, bench_sync_resync
 }
 
 #[cfg(feature = "async")]
 criterion_group! {
     name = benches;
     config = Criterion::default().sample_size(200);
-    targets = bench_sync_frame_parsing, bench_sync_streaming_parser, bench_sync_frame_validation, bench_async_parser
+    targets = bench_sync_frame_parsing, bench_sync_streaming_parser, bench_sync_frame_validation, bench_sync_resync, bench_async_parser
 }
 
 criterion_main!(benches);
@@ -0,0 +1,179 @@
+//! `sbus-mon`: monitors, records and replays SBUS links on serial ports
+use std::env;
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use embedded_io_adapters::std::FromStd;
+use sbus_rs::{
+    Channel, FrameKind, SbusError, SbusFrameRate, SbusPacket, SbusParser, SbusPlayer, SbusRecorder,
+    SbusStats, SbusUartConfig, SbusWriter, SerialPortIo,
+};
+
+const USAGE: &str = "\
+Usage:
+  sbus-mon monitor <port> [--high-speed]        Show live channels, flags and statistics
+  sbus-mon record <port> <log> [--high-speed]   Record frames and errors to a log
+  sbus-mon replay <log> [<port>]                Print a log, or send it to a port with its
+                                                original timing
+
+Ports are opened at 100000 baud 8E2; use an adapter that inverts the signal.";
+
+/// Time between redraws of the monitor view
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+/// Read timeout of the port, so reads on a dead link still give up after the refresh interval
+const READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let high_speed = args.iter().any(|arg| arg == "--high-speed");
+    let positional: Vec<&str> = args
+        .iter()
+        .filter(|arg| !arg.starts_with("--"))
+        .map(String::as_str)
+        .collect();
+    let frame_rate = if high_speed {
+        SbusFrameRate::HighSpeed
+    } else {
+        SbusFrameRate::Analog
+    };
+
+    let result = match positional[..] {
+        ["monitor", port] => monitor(port, frame_rate),
+        ["record", port, log] => record(port, log, frame_rate),
+        ["replay", log] => replay(log, None),
+        ["replay", log, port] => replay(log, Some(port)),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("sbus-mon: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn open_port(path: &str, frame_rate: SbusFrameRate) -> io::Result<SerialPortIo> {
+    let port = SbusUartConfig::for_frame_rate(frame_rate)
+        .serialport_builder(path)
+        .timeout(READ_TIMEOUT)
+        .open()?;
+    Ok(FromStd::new(port))
+}
+
+fn into_io(err: SbusError<io::Error>) -> io::Error {
+    match err {
+        SbusError::Read(err) | SbusError::Write(err) => err,
+        err => io::Error::new(io::ErrorKind::InvalidData, err.to_string()),
+    }
+}
+
+fn monitor(path: &str, frame_rate: SbusFrameRate) -> io::Result<()> {
+    let mut parser = SbusParser::new(open_port(path, frame_rate)?);
+    let mut latest = None;
+    let mut last_error = None;
+    let mut redraw_at = Instant::now();
+    loop {
+        match parser.read_frame_timeout(REFRESH_INTERVAL) {
+            Ok(packet) => latest = Some((packet, parser.last_frame_kind())),
+            Err(SbusError::Timeout) => {}
+            Err(SbusError::Read(err)) => return Err(err),
+            Err(err) => last_error = Some(err.to_string()),
+        }
+        if Instant::now() >= redraw_at {
+            redraw_at += REFRESH_INTERVAL;
+            draw(path, latest.as_ref(), parser.stats(), last_error.as_deref())?;
+        }
+    }
+}
+
+fn draw(
+    path: &str,
+    packet: Option<&(SbusPacket, FrameKind)>,
+    stats: &SbusStats,
+    last_error: Option<&str>,
+) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    // Move to the top left and clear the screen
+    write!(out, "\x1b[H\x1b[2J")?;
+    writeln!(out, "sbus-mon on {path}, Ctrl-C to quit\n")?;
+    match packet {
+        Some((packet, kind)) => {
+            for channel in Channel::ALL {
+                let value = packet.value(channel);
+                let bar = "#".repeat(value as usize * 40 / 2048);
+                writeln!(out, "{:>4} {value:>5} {bar}", format!("{channel:?}"))?;
+            }
+            let flags = packet.flags;
+            writeln!(
+                out,
+                "\nframe lost: {}  failsafe: {}  kind: {:?}",
+                flags.frame_lost, flags.failsafe, kind
+            )?;
+        }
+        None => writeln!(out, "waiting for frames...")?,
+    }
+    writeln!(
+        out,
+        "\nframes: {}  invalid header: {}  invalid footer: {}  invalid flags: {}  \
+         bytes discarded: {}",
+        stats.frames_ok,
+        stats.invalid_header,
+        stats.invalid_footer,
+        stats.invalid_flag_byte,
+        stats.bytes_discarded
+    )?;
+    if let Some(error) = last_error {
+        writeln!(out, "last error: {error}")?;
+    }
+    out.flush()
+}
+
+fn record(path: &str, log: &str, frame_rate: SbusFrameRate) -> io::Result<()> {
+    let mut parser = SbusParser::new(open_port(path, frame_rate)?);
+    // Unbuffered, so the log is complete up to the last frame when stopped with Ctrl-C
+    let mut recorder = SbusRecorder::new(File::create(log)?)?;
+    let start = Instant::now();
+    eprintln!("Recording {path} to {log}, Ctrl-C to stop");
+    loop {
+        let result = parser.read_frame_timeout(REFRESH_INTERVAL);
+        match &result {
+            Err(SbusError::Timeout) => continue,
+            Err(SbusError::Read(_)) => return result.map(drop).map_err(into_io),
+            _ => {}
+        }
+        recorder.record_result(&result, start.elapsed())?;
+    }
+}
+
+fn replay(log: &str, port: Option<&str>) -> io::Result<()> {
+    let player = SbusPlayer::new(BufReader::new(File::open(log)?))?;
+    let Some(path) = port else {
+        for stamped in player {
+            let channels = stamped.packet.channels.map(|value| format!("{value:>4}"));
+            println!(
+                "{:>10.3} {} {:?}",
+                stamped.received_at.as_secs_f64(),
+                channels.join(" "),
+                stamped.packet.flags
+            );
+        }
+        return Ok(());
+    };
+
+    let mut writer = SbusWriter::new(open_port(path, SbusFrameRate::Analog)?);
+    let start = Instant::now();
+    for stamped in player {
+        if let Some(wait) = stamped.received_at.checked_sub(start.elapsed()) {
+            thread::sleep(wait);
+        }
+        writer.write_frame(&stamped.packet).map_err(into_io)?;
+    }
+    Ok(())
+}
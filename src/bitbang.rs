@@ -0,0 +1,259 @@
+use core::time::Duration;
+
+use crate::{Deframer, SbusFrameRate, SbusPacket, SbusStats};
+
+/// Bits on the wire per byte: start bit, 8 data bits, parity and 2 stop bits
+const BITS_PER_BYTE: u8 = 12;
+
+/// Software SBUS receiver decoding bytes from GPIO edges or level samples
+///
+/// For MCUs whose UART cannot do 100000 baud 8E2 inverted, SBUS can be received with an input
+/// capture timer or a sampled GPIO instead. Each byte is reconstructed by sampling the middle of
+/// every bit after its start bit, its even parity and stop bits are checked, and the bytes are
+/// fed into a [`Deframer`], which drops any frame containing an errored byte.
+///
+/// Levels are the pin levels. By default the line is treated as the raw, inverted SBUS signal,
+/// which idles low; use [`SbusBitDecoder::with_inverted`] with `false` behind a hardware inverter.
+/// Timestamps are measured from any fixed epoch.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # fn example(capture: impl Iterator<Item = (core::time::Duration, bool)>, now: core::time::Duration) {
+/// use sbus_rs::{SbusBitDecoder, SbusFrameRate};
+///
+/// let mut decoder = SbusBitDecoder::new(SbusFrameRate::Analog);
+///
+/// // From the input capture interrupt
+/// for (timestamp, level) in capture {
+///     decoder.push_edge(timestamp, level);
+/// }
+///
+/// // From the main loop, flushes the last byte of a frame once the line went idle
+/// decoder.poll(now);
+/// if let Some(packet) = decoder.try_parse() {
+///     // use the packet
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SbusBitDecoder {
+    bit_time_ns: u64,
+    inverted: bool,
+    /// The logical UART level currently on the line
+    level: bool,
+    byte_start_ns: Option<u64>,
+    next_bit: u8,
+    data: u8,
+    parity: bool,
+    deframer: Deframer,
+    parity_errors: u32,
+    framing_errors: u32,
+}
+
+impl SbusBitDecoder {
+    /// Creates a decoder for the baud rate of `frame_rate`, expecting the raw inverted signal
+    pub const fn new(frame_rate: SbusFrameRate) -> Self {
+        Self {
+            bit_time_ns: 1_000_000_000 / frame_rate.baud_rate() as u64,
+            inverted: true,
+            level: true,
+            byte_start_ns: None,
+            next_bit: 0,
+            data: 0,
+            parity: false,
+            deframer: Deframer::new(),
+            parity_errors: 0,
+            framing_errors: 0,
+        }
+    }
+
+    /// Sets whether the pin carries the raw inverted SBUS signal, `true` by default
+    pub const fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    /// Records that the pin changed to `level` at `timestamp`
+    pub fn push_edge(&mut self, timestamp: Duration, level: bool) {
+        let now = timestamp.as_nanos() as u64;
+        self.advance(now);
+
+        self.level = level != self.inverted;
+        if self.byte_start_ns.is_none() && !self.level {
+            self.byte_start_ns = Some(now);
+            self.next_bit = 0;
+            self.data = 0;
+        }
+    }
+
+    /// Records a pin level sampled at `timestamp`
+    ///
+    /// Samples must be taken several times per bit, at least 4 times is recommended.
+    pub fn push_sample(&mut self, timestamp: Duration, level: bool) {
+        if (level != self.inverted) != self.level {
+            self.push_edge(timestamp, level);
+        } else {
+            self.advance(timestamp.as_nanos() as u64);
+        }
+    }
+
+    /// Records pin levels sampled every `period`, the first of them at `start`
+    pub fn push_samples(&mut self, start: Duration, period: Duration, levels: &[bool]) {
+        for (i, &level) in levels.iter().enumerate() {
+            self.push_sample(start + period * i as u32, level);
+        }
+    }
+
+    /// Completes the byte in progress if its stop bits have passed by `now`
+    ///
+    /// The stop bits of the last byte of a frame do not end with an edge, so call this
+    /// regularly, e.g. before [`SbusBitDecoder::try_parse`].
+    pub fn poll(&mut self, now: Duration) {
+        self.advance(now.as_nanos() as u64);
+    }
+
+    /// Returns the next valid packet from the decoded bytes, if a complete one is available
+    pub fn try_parse(&mut self) -> Option<SbusPacket> {
+        self.deframer.try_parse()
+    }
+
+    /// Bytes received with a wrong parity bit
+    pub fn parity_errors(&self) -> u32 {
+        self.parity_errors
+    }
+
+    /// Bytes received without their stop bits
+    pub fn framing_errors(&self) -> u32 {
+        self.framing_errors
+    }
+
+    /// Statistics about the frames and bytes seen so far
+    pub fn stats(&self) -> &SbusStats {
+        self.deframer.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.deframer.reset_stats();
+        self.parity_errors = 0;
+        self.framing_errors = 0;
+    }
+
+    /// Samples the current level at every bit centre of the byte in progress before `until`
+    fn advance(&mut self, until: u64) {
+        while let Some(start) = self.byte_start_ns {
+            let centre = start + self.bit_time_ns * self.next_bit as u64 + self.bit_time_ns / 2;
+            if centre >= until {
+                break;
+            }
+            self.sample(self.level);
+        }
+    }
+
+    fn sample(&mut self, bit: bool) {
+        match self.next_bit {
+            // A start bit that is gone by its centre was a glitch
+            0 if bit => {
+                self.byte_start_ns = None;
+                return;
+            }
+            0 => {}
+            1..=8 => self.data |= (bit as u8) << (self.next_bit - 1),
+            9 => self.parity = bit,
+            _ if !bit => {
+                self.framing_errors = self.framing_errors.saturating_add(1);
+                self.byte_start_ns = None;
+                self.deframer.push_error();
+                return;
+            }
+            _ => {}
+        }
+
+        self.next_bit += 1;
+        if self.next_bit == BITS_PER_BYTE {
+            self.byte_start_ns = None;
+            let parity_ok = (self.data.count_ones() % 2 == 1) == self.parity;
+            if !parity_ok {
+                self.parity_errors = self.parity_errors.saturating_add(1);
+            }
+            self.deframer.push_byte_with_error(self.data, !parity_ok);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BIT: Duration = Duration::from_micros(10);
+
+    /// Pin levels of the raw inverted SBUS signal for `bytes`, one entry per bit
+    fn bits(bytes: &[u8]) -> Vec<bool> {
+        let mut levels = Vec::new();
+        for &byte in bytes {
+            let parity = byte.count_ones() % 2 == 1;
+            levels.push(false);
+            levels.extend((0..8).map(|i| byte & (1 << i) != 0));
+            levels.extend([parity, true, true]);
+        }
+        levels.into_iter().map(|level| !level).collect()
+    }
+
+    fn edges(levels: &[bool]) -> Vec<(Duration, bool)> {
+        let mut previous = false;
+        let mut edges = Vec::new();
+        for (i, &level) in levels.iter().enumerate() {
+            if level != previous {
+                edges.push((BIT * i as u32, level));
+                previous = level;
+            }
+        }
+        edges
+    }
+
+    #[test]
+    fn test_decode_frame_from_edges() {
+        let packet = SbusPacket::neutral_throttle_low(2);
+        let levels = bits(&packet.to_array());
+        let mut decoder = SbusBitDecoder::new(SbusFrameRate::Analog);
+
+        for (timestamp, level) in edges(&levels) {
+            decoder.push_edge(timestamp, level);
+        }
+        decoder.poll(BIT * (levels.len() as u32 + 1));
+
+        assert_eq!(decoder.try_parse(), Some(packet));
+        assert_eq!(decoder.parity_errors(), 0);
+        assert_eq!(decoder.framing_errors(), 0);
+    }
+
+    #[test]
+    fn test_decode_frame_from_samples() {
+        let packet = SbusPacket::failsafe_default();
+        let levels = bits(&packet.to_array());
+        let samples: Vec<bool> = levels.iter().flat_map(|&level| [level; 4]).collect();
+        let mut decoder = SbusBitDecoder::new(SbusFrameRate::Analog);
+
+        decoder.push_samples(Duration::ZERO, BIT / 4, &samples);
+        decoder.poll(BIT * (levels.len() as u32 + 1));
+
+        assert_eq!(decoder.try_parse(), Some(packet));
+    }
+
+    #[test]
+    fn test_parity_error_drops_byte() {
+        let mut levels = bits(&SbusPacket::neutral().to_array());
+        // Flip the first data bit of the second byte without fixing its parity
+        levels[12 + 1] = !levels[12 + 1];
+        let mut decoder = SbusBitDecoder::new(SbusFrameRate::Analog);
+
+        for (timestamp, level) in edges(&levels) {
+            decoder.push_edge(timestamp, level);
+        }
+        decoder.poll(BIT * (levels.len() as u32 + 1));
+
+        assert_eq!(decoder.parity_errors(), 1);
+        assert_eq!(decoder.try_parse(), None);
+    }
+}
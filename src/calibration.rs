@@ -0,0 +1,301 @@
+use crate::{
+    Channel, PacketTransform, SbusPacket, CHANNEL_COUNT, CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MID,
+    CHANNEL_FUTABA_MIN,
+};
+
+/// The endpoints and centre a radio actually sends on one channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelCalibration {
+    pub min: u16,
+    pub center: u16,
+    pub max: u16,
+}
+
+impl ChannelCalibration {
+    /// The Futaba range, which [`normalize`](Self::normalize) leaves unchanged
+    pub const DEFAULT: Self = Self::new(CHANNEL_FUTABA_MIN, CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MAX);
+
+    pub const fn new(min: u16, center: u16, max: u16) -> Self {
+        Self { min, center, max }
+    }
+
+    /// Maps `value` onto the Futaba range, moving `min`, `center` and `max` to
+    /// [`CHANNEL_FUTABA_MIN`], [`CHANNEL_FUTABA_MID`] and [`CHANNEL_FUTABA_MAX`]
+    ///
+    /// Values beyond the endpoints are clamped to the Futaba range.
+    pub const fn normalize(&self, value: u16) -> u16 {
+        if value <= self.min {
+            CHANNEL_FUTABA_MIN
+        } else if value >= self.max {
+            CHANNEL_FUTABA_MAX
+        } else if value < self.center {
+            CHANNEL_FUTABA_MID
+                - scale(
+                    self.center - value,
+                    self.center - self.min,
+                    CHANNEL_FUTABA_MID - CHANNEL_FUTABA_MIN,
+                )
+        } else {
+            CHANNEL_FUTABA_MID
+                + scale(
+                    value - self.center,
+                    self.max - self.center,
+                    CHANNEL_FUTABA_MAX - CHANNEL_FUTABA_MID,
+                )
+        }
+    }
+}
+
+impl Default for ChannelCalibration {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// `offset * to / from`, rounded, or 0 for an empty `from` range
+const fn scale(offset: u16, from: u16, to: u16) -> u16 {
+    if from == 0 {
+        0
+    } else {
+        ((offset as u32 * to as u32 + from as u32 / 2) / from as u32) as u16
+    }
+}
+
+/// Per-channel calibration normalizing packets from a particular radio to the Futaba range
+///
+/// Record one with a [`CalibrationRecorder`]. With the `serde` feature a calibration can be
+/// stored, e.g. with `postcard` in flash, and restored on the next start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Calibration {
+    channels: [ChannelCalibration; CHANNEL_COUNT],
+}
+
+impl Calibration {
+    /// A calibration leaving all channels unchanged
+    pub const fn new() -> Self {
+        Self {
+            channels: [ChannelCalibration::DEFAULT; CHANNEL_COUNT],
+        }
+    }
+
+    /// The calibration of a proportional channel, or `None` for the digital channels
+    pub fn channel(&self, channel: Channel) -> Option<&ChannelCalibration> {
+        self.channels.get(channel.index())
+    }
+
+    /// The calibration of a proportional channel, or `None` for the digital channels
+    pub fn channel_mut(&mut self, channel: Channel) -> Option<&mut ChannelCalibration> {
+        self.channels.get_mut(channel.index())
+    }
+
+    /// Returns the normalized copy of `packet`, keeping its flags
+    pub fn normalize(&self, packet: &SbusPacket) -> SbusPacket {
+        let mut output = *packet;
+        for (value, calibration) in output.channels.iter_mut().zip(self.channels.iter()) {
+            *value = calibration.normalize(*value);
+        }
+        output
+    }
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PacketTransform for Calibration {
+    fn transform(&mut self, packet: &mut SbusPacket) {
+        *packet = self.normalize(packet);
+    }
+}
+
+/// Observes packets while the user moves every stick and switch to its endpoints
+///
+/// Failsafe and lost frames are ignored. Capture the centres with
+/// [`capture_center`](Self::capture_center) while the sticks rest centred, otherwise the
+/// midpoint of the observed range is used.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{CalibrationRecorder, SbusPacket};
+///
+/// let mut recorder = CalibrationRecorder::new();
+/// for value in [200, 1000, 1790] {
+///     let mut packet = SbusPacket::neutral();
+///     packet.channels = [value; 16];
+///     recorder.observe(&packet);
+/// }
+/// let calibration = recorder.finish();
+///
+/// let mut packet = SbusPacket::neutral();
+/// packet.channels = [1790; 16];
+/// assert_eq!(calibration.normalize(&packet).channels[0], 1811);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct CalibrationRecorder {
+    min: [u16; CHANNEL_COUNT],
+    max: [u16; CHANNEL_COUNT],
+    center: Option<[u16; CHANNEL_COUNT]>,
+    frames: u32,
+}
+
+impl CalibrationRecorder {
+    pub const fn new() -> Self {
+        Self {
+            min: [u16::MAX; CHANNEL_COUNT],
+            max: [0; CHANNEL_COUNT],
+            center: None,
+            frames: 0,
+        }
+    }
+
+    /// Widens the observed ranges by the channels of `packet`
+    pub fn observe(&mut self, packet: &SbusPacket) {
+        if packet.flags.failsafe || packet.flags.frame_lost {
+            return;
+        }
+        for (i, &value) in packet.channels.iter().enumerate() {
+            self.min[i] = self.min[i].min(value);
+            self.max[i] = self.max[i].max(value);
+        }
+        self.frames += 1;
+    }
+
+    /// Records the channels of `packet` as the centres
+    pub fn capture_center(&mut self, packet: &SbusPacket) {
+        self.observe(packet);
+        self.center = Some(packet.channels);
+    }
+
+    /// Number of packets observed so far
+    pub const fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Builds the calibration from the observed ranges
+    ///
+    /// Channels that never moved keep the default calibration.
+    pub fn finish(&self) -> Calibration {
+        let mut calibration = Calibration::new();
+        for (i, channel) in calibration.channels.iter_mut().enumerate() {
+            let (min, max) = (self.min[i], self.max[i]);
+            if min >= max {
+                continue;
+            }
+            let center = match self.center {
+                Some(center) => center[i].clamp(min, max),
+                None => min + (max - min) / 2,
+            };
+            *channel = ChannelCalibration::new(min, center, max);
+        }
+        calibration
+    }
+
+    /// Starts over
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for CalibrationRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_normalize() {
+        let default = ChannelCalibration::DEFAULT;
+        for value in [
+            CHANNEL_FUTABA_MIN,
+            500,
+            CHANNEL_FUTABA_MID,
+            1500,
+            CHANNEL_FUTABA_MAX,
+        ] {
+            assert_eq!(default.normalize(value), value);
+        }
+
+        let narrow = ChannelCalibration::new(300, 1000, 1700);
+        assert_eq!(narrow.normalize(0), CHANNEL_FUTABA_MIN);
+        assert_eq!(narrow.normalize(300), CHANNEL_FUTABA_MIN);
+        assert_eq!(narrow.normalize(650), CHANNEL_FUTABA_MID - 410);
+        assert_eq!(narrow.normalize(1000), CHANNEL_FUTABA_MID);
+        assert_eq!(narrow.normalize(1700), CHANNEL_FUTABA_MAX);
+        assert_eq!(narrow.normalize(2047), CHANNEL_FUTABA_MAX);
+
+        let flat = ChannelCalibration::new(1000, 1000, 1000);
+        assert_eq!(flat.normalize(1000), CHANNEL_FUTABA_MIN);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let channel = ChannelCalibration::new(300, 1000, 1700);
+        let json = serde_json::to_string(&channel).unwrap();
+        assert_eq!(json, r#"{"min":300,"center":1000,"max":1700}"#);
+        assert_eq!(
+            serde_json::from_str::<ChannelCalibration>(&json).unwrap(),
+            channel
+        );
+
+        let mut calibration = Calibration::new();
+        *calibration.channel_mut(Channel::Ch3).unwrap() = channel;
+        let json = serde_json::to_string(&calibration).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Calibration>(&json).unwrap(),
+            calibration
+        );
+    }
+
+    #[test]
+    fn test_recorder() {
+        let mut recorder = CalibrationRecorder::new();
+        assert_eq!(recorder.finish(), Calibration::new());
+
+        let mut packet = SbusPacket::neutral();
+        packet.channels[0] = 250;
+        recorder.observe(&packet);
+        packet.channels[0] = 1750;
+        recorder.observe(&packet);
+        packet.channels[0] = 1010;
+        recorder.capture_center(&packet);
+
+        let mut failsafe = packet;
+        failsafe.flags.failsafe = true;
+        failsafe.channels[0] = 0;
+        recorder.observe(&failsafe);
+        assert_eq!(recorder.frames(), 3);
+
+        let calibration = recorder.finish();
+        assert_eq!(
+            calibration.channel(Channel::Ch1),
+            Some(&ChannelCalibration::new(250, 1010, 1750))
+        );
+        assert_eq!(
+            calibration.channel(Channel::Ch2),
+            Some(&ChannelCalibration::DEFAULT)
+        );
+        assert_eq!(calibration.channel(Channel::Ch17), None);
+
+        packet.channels[0] = 1010;
+        assert_eq!(
+            calibration.normalize(&packet).channels[0],
+            CHANNEL_FUTABA_MID
+        );
+
+        recorder.reset();
+        assert_eq!(recorder.frames(), 0);
+    }
+}
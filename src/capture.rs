@@ -0,0 +1,329 @@
+//! Decoding SBUS from logic analyzer captures
+
+use std::io::{self, BufRead};
+use std::time::Duration;
+
+use crate::{SbusBitDecoder, SbusFrameRate, TimestampedPacket};
+
+/// Decodes the SBUS frames of a logic analyzer capture, e.g. to debug a link in the field
+///
+/// Captures are read either as value change dumps (VCD), which most logic analyzer software
+/// such as Saleae Logic and sigrok can export, or as raw level samples at a known sample rate.
+/// The levels are decoded by an [`SbusBitDecoder`], so the capture may start mid-frame and
+/// frames with parity or framing errors are dropped.
+///
+/// Packets are stamped with the time their last byte was decoded, measured from the start of
+/// the capture.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{CaptureImporter, SbusFrameRate};
+///
+/// let vcd = "\
+/// $timescale 1us $end
+/// $var wire 1 ! sbus $end
+/// $enddefinitions $end
+/// #0
+/// 0!
+/// ";
+///
+/// let packets = CaptureImporter::new(SbusFrameRate::Analog)
+///     .import_vcd(vcd.as_bytes(), "sbus")
+///     .unwrap();
+/// assert!(packets.is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureImporter {
+    frame_rate: SbusFrameRate,
+    inverted: bool,
+}
+
+impl CaptureImporter {
+    /// Creates an importer for the baud rate of `frame_rate`, expecting the raw inverted signal
+    pub const fn new(frame_rate: SbusFrameRate) -> Self {
+        Self {
+            frame_rate,
+            inverted: true,
+        }
+    }
+
+    /// Sets whether the capture holds the raw inverted SBUS signal, `true` by default
+    pub const fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    /// Decodes the variable named `signal` of a VCD file
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the file is malformed or has no variable
+    /// named `signal`.
+    pub fn import_vcd<R>(&self, reader: R, signal: &str) -> io::Result<Vec<TimestampedPacket>>
+    where
+        R: BufRead,
+    {
+        let mut collector = Collector::new(self);
+        let mut timescale_fs: Option<u128> = None;
+        let mut id: Option<String> = None;
+        let mut now = Duration::ZERO;
+
+        let mut lines = reader.lines();
+        let mut tokens = Vec::new();
+        let mut next_token = |tokens: &mut Vec<String>| -> io::Result<Option<String>> {
+            while tokens.is_empty() {
+                let Some(line) = lines.next() else {
+                    return Ok(None);
+                };
+                tokens.extend(line?.split_whitespace().rev().map(String::from));
+            }
+            Ok(tokens.pop())
+        };
+
+        while let Some(token) = next_token(&mut tokens)? {
+            if let Some(keyword) = token.strip_prefix('$') {
+                let mut body = Vec::new();
+                if !matches!(
+                    keyword,
+                    "dumpvars" | "dumpon" | "dumpoff" | "dumpall" | "end"
+                ) {
+                    loop {
+                        match next_token(&mut tokens)? {
+                            Some(token) if token == "$end" => break,
+                            Some(token) => body.push(token),
+                            None => return Err(invalid("unterminated section")),
+                        }
+                    }
+                }
+                match keyword {
+                    "timescale" => timescale_fs = Some(parse_timescale(&body.concat())?),
+                    // $var type size id reference [index] $end
+                    "var" if body.get(3).is_some_and(|name| name == signal) => {
+                        id = body.get(2).cloned();
+                    }
+                    _ => {}
+                }
+            } else if let Some(time) = token.strip_prefix('#') {
+                let time: u128 = time.parse().map_err(|_| invalid("invalid timestamp"))?;
+                let femtos = time * timescale_fs.unwrap_or(1_000_000);
+                now = Duration::from_nanos((femtos / 1_000_000) as u64);
+            } else {
+                let id = id.as_deref().ok_or_else(|| invalid("signal not found"))?;
+                let (value, changed) = match token.as_bytes()[0] {
+                    b'b' | b'B' => {
+                        let changed =
+                            next_token(&mut tokens)?.ok_or_else(|| invalid("missing id"))?;
+                        (token.chars().last(), changed)
+                    }
+                    b'r' | b'R' => {
+                        next_token(&mut tokens)?;
+                        continue;
+                    }
+                    _ => {
+                        let first = token.chars().next().map_or(0, char::len_utf8);
+                        let (value, changed) = token.split_at(first);
+                        (value.chars().next(), changed.into())
+                    }
+                };
+                if changed != id {
+                    continue;
+                }
+                match value {
+                    Some('0') => collector.edge(now, false),
+                    Some('1') => collector.edge(now, true),
+                    _ => {}
+                }
+            }
+        }
+
+        if id.is_none() {
+            return Err(invalid("signal not found"));
+        }
+        Ok(collector.finish())
+    }
+
+    /// Decodes pin levels sampled at `sample_rate` Hz, the first of them at the start of the
+    /// capture
+    ///
+    /// Sample at least 4 times per bit, i.e. at 400 kHz or more for standard SBUS.
+    pub fn import_samples<I>(&self, samples: I, sample_rate: u32) -> Vec<TimestampedPacket>
+    where
+        I: IntoIterator<Item = bool>,
+    {
+        let mut collector = Collector::new(self);
+        for (i, level) in samples.into_iter().enumerate() {
+            let timestamp =
+                Duration::from_nanos((i as u128 * 1_000_000_000 / sample_rate as u128) as u64);
+            collector.sample(timestamp, level);
+        }
+        collector.finish()
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Parses a VCD timescale such as `10ns` into femtoseconds
+fn parse_timescale(timescale: &str) -> io::Result<u128> {
+    let split = timescale
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| invalid("invalid timescale"))?;
+    let (multiplier, unit) = timescale.split_at(split);
+    let multiplier: u128 = multiplier
+        .parse()
+        .map_err(|_| invalid("invalid timescale"))?;
+    let unit = match unit {
+        "s" => 1_000_000_000_000_000,
+        "ms" => 1_000_000_000_000,
+        "us" => 1_000_000_000,
+        "ns" => 1_000_000,
+        "ps" => 1_000,
+        "fs" => 1,
+        _ => return Err(invalid("invalid timescale")),
+    };
+    Ok(multiplier * unit)
+}
+
+/// Feeds a capture into a decoder and collects the packets with their timestamps
+struct Collector {
+    decoder: SbusBitDecoder,
+    /// How long the line must be quiet for the byte in progress to be complete
+    settle: Duration,
+    last: Option<Duration>,
+    packets: Vec<TimestampedPacket>,
+}
+
+impl Collector {
+    fn new(importer: &CaptureImporter) -> Self {
+        Self {
+            decoder: SbusBitDecoder::new(importer.frame_rate).with_inverted(importer.inverted),
+            settle: importer.frame_rate.byte_time() * 2,
+            last: None,
+            packets: Vec::new(),
+        }
+    }
+
+    fn edge(&mut self, timestamp: Duration, level: bool) {
+        // The last byte of a frame completes without an edge, so stamp its packet with the end
+        // of the byte rather than the start of the next frame
+        if let Some(last) = self.last {
+            if timestamp > last + self.settle {
+                self.decoder.poll(last + self.settle);
+                self.drain(last + self.settle);
+            }
+        }
+        self.decoder.push_edge(timestamp, level);
+        self.drain(timestamp);
+        self.last = Some(timestamp);
+    }
+
+    fn sample(&mut self, timestamp: Duration, level: bool) {
+        self.decoder.push_sample(timestamp, level);
+        self.drain(timestamp);
+        self.last = Some(timestamp);
+    }
+
+    fn drain(&mut self, timestamp: Duration) {
+        while let Some(packet) = self.decoder.try_parse() {
+            let gap = self
+                .packets
+                .last()
+                .map(|previous| timestamp.saturating_sub(previous.received_at));
+            self.packets.push(TimestampedPacket {
+                packet,
+                received_at: timestamp,
+                gap,
+            });
+        }
+    }
+
+    fn finish(mut self) -> Vec<TimestampedPacket> {
+        if let Some(last) = self.last {
+            self.decoder.poll(last + self.settle);
+            self.drain(last + self.settle);
+        }
+        self.packets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SbusPacket;
+    use std::fmt::Write;
+
+    /// Pin levels of the raw inverted SBUS signal for `bytes`, one entry per 10 µs bit
+    fn bits(bytes: &[u8]) -> Vec<bool> {
+        let mut levels = Vec::new();
+        for &byte in bytes {
+            let parity = byte.count_ones() % 2 == 1;
+            levels.push(false);
+            levels.extend((0..8).map(|i| byte & (1 << i) != 0));
+            levels.extend([parity, true, true]);
+        }
+        levels.into_iter().map(|level| !level).collect()
+    }
+
+    fn two_frames() -> (SbusPacket, SbusPacket, Vec<bool>) {
+        let first = SbusPacket::neutral_throttle_low(2);
+        let second = SbusPacket::failsafe_default();
+        let mut levels = bits(&first.to_array());
+        // 7 ms frame interval, idle low
+        levels.resize(700, false);
+        levels.extend(bits(&second.to_array()));
+        (first, second, levels)
+    }
+
+    #[test]
+    fn test_import_vcd() {
+        let (first, second, levels) = two_frames();
+        let mut vcd = String::from(
+            "$date today $end\n$timescale 100 ns $end\n$scope module top $end\n\
+             $var wire 1 \" other $end\n$var wire 1 # sbus $end\n$upscope $end\n\
+             $enddefinitions $end\n$dumpvars\n0#\n1\"\n$end\n",
+        );
+        let mut previous = false;
+        for (i, &level) in levels.iter().enumerate() {
+            if level != previous {
+                writeln!(vcd, "#{}\n{}#\n0\"", i * 100, level as u8).unwrap();
+                previous = level;
+            }
+        }
+
+        let importer = CaptureImporter::new(SbusFrameRate::Analog);
+        let packets = importer.import_vcd(vcd.as_bytes(), "sbus").unwrap();
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].packet, first);
+        assert_eq!(packets[1].packet, second);
+        assert_eq!(packets[0].gap, None);
+        assert_eq!(packets[1].gap, Some(Duration::from_millis(7)));
+
+        let err = importer.import_vcd(vcd.as_bytes(), "missing").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // A value that is not a single byte is skipped like any other unknown value
+        let vcd = "$var wire 1 ! sbus $end\n$enddefinitions $end\n#0\né!\n";
+        assert!(importer.import_vcd(vcd.as_bytes(), "sbus").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_import_samples() {
+        let (first, second, levels) = two_frames();
+        // 500 kHz, 5 samples per bit, through an inverter
+        let samples = levels.iter().flat_map(|&level| [!level; 5]);
+
+        let packets = CaptureImporter::new(SbusFrameRate::Analog)
+            .with_inverted(false)
+            .import_samples(samples, 500_000);
+        let packets: Vec<_> = packets.into_iter().map(|stamped| stamped.packet).collect();
+        assert_eq!(packets, [first, second]);
+    }
+
+    #[test]
+    fn test_timescale() {
+        assert_eq!(parse_timescale("1ns").unwrap(), 1_000_000);
+        assert_eq!(parse_timescale("100us").unwrap(), 100_000_000_000);
+        assert!(parse_timescale("1 parsec").is_err());
+        assert!(parse_timescale("ns").is_err());
+    }
+}
@@ -0,0 +1,317 @@
+use core::ops::{Index, IndexMut};
+
+use crate::{
+    channel_parsing, SbusPacket, CHANNEL_COUNT, CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MIN,
+    CHANNEL_MAX, SBUS_FRAME_LENGTH,
+};
+
+/// An SBUS channel, numbered from one as on transmitters and receivers
+///
+/// `Ch1` to `Ch16` are the proportional channels in [`SbusPacket::channels`], `Ch17` and `Ch18`
+/// the digital channels carried in the `d1` and `d2` [`Flags`](crate::Flags).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Channel {
+    Ch1,
+    Ch2,
+    Ch3,
+    Ch4,
+    Ch5,
+    Ch6,
+    Ch7,
+    Ch8,
+    Ch9,
+    Ch10,
+    Ch11,
+    Ch12,
+    Ch13,
+    Ch14,
+    Ch15,
+    Ch16,
+    /// Digital channel 17, the `d1` flag
+    Ch17,
+    /// Digital channel 18, the `d2` flag
+    Ch18,
+}
+
+impl Channel {
+    /// All channels in order, the proportional ones first
+    pub const ALL: [Channel; 18] = [
+        Channel::Ch1,
+        Channel::Ch2,
+        Channel::Ch3,
+        Channel::Ch4,
+        Channel::Ch5,
+        Channel::Ch6,
+        Channel::Ch7,
+        Channel::Ch8,
+        Channel::Ch9,
+        Channel::Ch10,
+        Channel::Ch11,
+        Channel::Ch12,
+        Channel::Ch13,
+        Channel::Ch14,
+        Channel::Ch15,
+        Channel::Ch16,
+        Channel::Ch17,
+        Channel::Ch18,
+    ];
+
+    /// The proportional channels `Ch1` to `Ch16`
+    pub const PROPORTIONAL: [Channel; CHANNEL_COUNT] = {
+        let mut channels = [Channel::Ch1; CHANNEL_COUNT];
+        let mut i = 0;
+        while i < CHANNEL_COUNT {
+            channels[i] = Self::ALL[i];
+            i += 1;
+        }
+        channels
+    };
+
+    /// The channel with the zero-based `index`, e.g. 0 for `Ch1` and 16 for `Ch17`
+    pub const fn from_index(index: usize) -> Option<Self> {
+        if index < Self::ALL.len() {
+            Some(Self::ALL[index])
+        } else {
+            None
+        }
+    }
+
+    /// The channel with the one-based `number`, e.g. 1 for `Ch1`
+    pub const fn from_number(number: u8) -> Option<Self> {
+        match number {
+            0 => None,
+            number => Self::from_index(number as usize - 1),
+        }
+    }
+
+    /// The zero-based index of the channel, `Ch1` to `Ch16` index [`SbusPacket::channels`]
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The one-based channel number
+    pub const fn number(self) -> u8 {
+        self as u8 + 1
+    }
+
+    /// Whether this is one of the digital channels `Ch17` and `Ch18`
+    pub const fn is_digital(self) -> bool {
+        matches!(self, Channel::Ch17 | Channel::Ch18)
+    }
+}
+
+impl SbusPacket {
+    /// The state of a digital channel, or `None` for the proportional channels
+    pub const fn digital(&self, channel: Channel) -> Option<bool> {
+        match channel {
+            Channel::Ch17 => Some(self.flags.d1),
+            Channel::Ch18 => Some(self.flags.d2),
+            _ => None,
+        }
+    }
+
+    /// Sets a digital channel, returning `false` without changes for the proportional channels
+    pub fn set_digital(&mut self, channel: Channel, value: bool) -> bool {
+        match channel {
+            Channel::Ch17 => self.flags.d1 = value,
+            Channel::Ch18 => self.flags.d2 = value,
+            _ => return false,
+        }
+        true
+    }
+
+    /// The value of any channel, digital channels read as [`CHANNEL_FUTABA_MAX`] when set and
+    /// [`CHANNEL_FUTABA_MIN`] otherwise
+    pub const fn value(&self, channel: Channel) -> u16 {
+        match self.digital(channel) {
+            Some(true) => CHANNEL_FUTABA_MAX,
+            Some(false) => CHANNEL_FUTABA_MIN,
+            None => self.channels[channel.index()],
+        }
+    }
+
+    /// The value of a single channel of a validated raw frame, like [`SbusPacket::value`] but
+    /// without decoding the whole frame
+    ///
+    /// Meant for latency critical paths, e.g. reading only the throttle in a motor cut-off
+    /// interrupt. The frame is not validated, see [`SbusPacket::validate_frame`].
+    pub const fn channel_from_frame(frame: &[u8; SBUS_FRAME_LENGTH], channel: Channel) -> u16 {
+        let flags = frame[SBUS_FRAME_LENGTH - 2];
+        let digital = match channel {
+            Channel::Ch17 => flags & 0x01 != 0,
+            Channel::Ch18 => flags & 0x02 != 0,
+            _ => return channel_parsing(frame, channel.index()),
+        };
+        if digital {
+            CHANNEL_FUTABA_MAX
+        } else {
+            CHANNEL_FUTABA_MIN
+        }
+    }
+
+    /// Iterates over the proportional channels along with their values
+    pub fn iter_channels(&self) -> impl Iterator<Item = (Channel, u16)> + '_ {
+        Channel::PROPORTIONAL
+            .iter()
+            .zip(self.channels.iter())
+            .map(|(&channel, &value)| (channel, value))
+    }
+}
+
+/// Pulse width offset of channel value 0 in the common SBUS to microseconds mapping
+const MICROS_OFFSET: u16 = 880;
+
+/// The servo pulse width in microseconds for a channel value
+///
+/// Uses the mapping common to flight controllers, `880 + value * 5 / 8` rounded, which puts
+/// [`CHANNEL_FUTABA_MIN`], the centre and [`CHANNEL_FUTABA_MAX`] at 988, 1500 and 2012 µs.
+pub const fn channel_to_micros(value: u16) -> u16 {
+    let value = if value > CHANNEL_MAX {
+        CHANNEL_MAX
+    } else {
+        value
+    };
+    MICROS_OFFSET + ((value as u32 * 5 + 4) / 8) as u16
+}
+
+/// The channel value for a servo pulse width in microseconds, the inverse of
+/// [`channel_to_micros`]
+///
+/// Pulse widths outside 880 to 2159 µs are clamped to the channel range.
+pub const fn micros_to_channel(micros: u16) -> u16 {
+    let above = micros.saturating_sub(MICROS_OFFSET) as u32;
+    let value = above * 8 / 5;
+    if value > CHANNEL_MAX as u32 {
+        CHANNEL_MAX
+    } else {
+        value as u16
+    }
+}
+
+/// Indexes the proportional channels
+///
+/// # Panics
+///
+/// Panics for the digital channels `Ch17` and `Ch18`, use [`SbusPacket::digital`] for those.
+impl Index<Channel> for SbusPacket {
+    type Output = u16;
+
+    fn index(&self, channel: Channel) -> &u16 {
+        assert!(
+            !channel.is_digital(),
+            "{channel:?} is digital, use SbusPacket::digital"
+        );
+        &self.channels[channel.index()]
+    }
+}
+
+/// Indexes the proportional channels
+///
+/// # Panics
+///
+/// Panics for the digital channels `Ch17` and `Ch18`, use [`SbusPacket::set_digital`] for
+/// those.
+impl IndexMut<Channel> for SbusPacket {
+    fn index_mut(&mut self, channel: Channel) -> &mut u16 {
+        assert!(
+            !channel.is_digital(),
+            "{channel:?} is digital, use SbusPacket::set_digital"
+        );
+        &mut self.channels[channel.index()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CHANNEL_FUTABA_MID;
+
+    #[test]
+    fn test_numbering() {
+        for (index, channel) in Channel::ALL.iter().enumerate() {
+            assert_eq!(channel.index(), index);
+            assert_eq!(channel.number() as usize, index + 1);
+            assert_eq!(Channel::from_index(index), Some(*channel));
+            assert_eq!(Channel::from_number(channel.number()), Some(*channel));
+        }
+        assert_eq!(Channel::from_index(18), None);
+        assert_eq!(Channel::from_number(0), None);
+        assert_eq!(Channel::from_number(19), None);
+        assert_eq!(Channel::PROPORTIONAL[15], Channel::Ch16);
+        assert!(Channel::PROPORTIONAL.iter().all(|ch| !ch.is_digital()));
+    }
+
+    #[test]
+    fn test_micros() {
+        assert_eq!(channel_to_micros(CHANNEL_FUTABA_MIN), 988);
+        assert_eq!(channel_to_micros(CHANNEL_FUTABA_MID), 1500);
+        assert_eq!(channel_to_micros(CHANNEL_FUTABA_MAX), 2012);
+        assert_eq!(channel_to_micros(u16::MAX), 2159);
+
+        for value in [
+            0,
+            CHANNEL_FUTABA_MIN,
+            CHANNEL_FUTABA_MID,
+            CHANNEL_FUTABA_MAX,
+        ] {
+            assert_eq!(micros_to_channel(channel_to_micros(value)), value);
+        }
+        assert_eq!(micros_to_channel(500), 0);
+        assert_eq!(micros_to_channel(3000), CHANNEL_MAX);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch1] = CHANNEL_FUTABA_MAX;
+
+        assert_eq!(packet.channels[0], CHANNEL_FUTABA_MAX);
+        assert_eq!(packet[Channel::Ch16], CHANNEL_FUTABA_MID);
+        assert_eq!(
+            packet.iter_channels().next(),
+            Some((Channel::Ch1, CHANNEL_FUTABA_MAX))
+        );
+        assert_eq!(packet.iter_channels().count(), CHANNEL_COUNT);
+    }
+
+    #[test]
+    fn test_digital() {
+        let mut packet = SbusPacket::neutral();
+        assert!(packet.set_digital(Channel::Ch18, true));
+        assert!(!packet.set_digital(Channel::Ch3, true));
+
+        assert!(packet.flags.d2);
+        assert_eq!(packet.digital(Channel::Ch17), Some(false));
+        assert_eq!(packet.digital(Channel::Ch18), Some(true));
+        assert_eq!(packet.digital(Channel::Ch3), None);
+
+        assert_eq!(packet.value(Channel::Ch17), CHANNEL_FUTABA_MIN);
+        assert_eq!(packet.value(Channel::Ch18), CHANNEL_FUTABA_MAX);
+        assert_eq!(packet.value(Channel::Ch3), packet.channels[2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_digital_panics() {
+        let _ = SbusPacket::neutral()[Channel::Ch17];
+    }
+
+    #[test]
+    fn test_channel_from_frame() {
+        let mut packet = SbusPacket::neutral();
+        for (channel, value) in packet.channels.iter_mut().enumerate() {
+            *value = channel as u16 * 127 + 5;
+        }
+        packet.flags.d1 = true;
+        let frame = packet.to_array();
+
+        for channel in Channel::ALL {
+            assert_eq!(
+                SbusPacket::channel_from_frame(&frame, channel),
+                packet.value(channel)
+            );
+        }
+    }
+}
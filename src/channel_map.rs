@@ -0,0 +1,165 @@
+use crate::{Channel, SbusPacket};
+
+/// Assigns the stick axes to channels, following the ordering of the transmitter
+///
+/// The channels not used by a stick are the aux channels, in ascending order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelMap {
+    roll: Channel,
+    pitch: Channel,
+    throttle: Channel,
+    yaw: Channel,
+}
+
+impl ChannelMap {
+    /// Aileron, elevator, throttle, rudder, the Futaba and FrSky default
+    pub const AETR: Self = Self::preset(Channel::Ch1, Channel::Ch2, Channel::Ch3, Channel::Ch4);
+    /// Throttle, aileron, elevator, rudder, the Spektrum and Graupner default
+    pub const TAER: Self = Self::preset(Channel::Ch2, Channel::Ch3, Channel::Ch1, Channel::Ch4);
+    /// Rudder, elevator, throttle, aileron
+    pub const RETA: Self = Self::preset(Channel::Ch4, Channel::Ch2, Channel::Ch3, Channel::Ch1);
+
+    const fn preset(roll: Channel, pitch: Channel, throttle: Channel, yaw: Channel) -> Self {
+        Self {
+            roll,
+            pitch,
+            throttle,
+            yaw,
+        }
+    }
+
+    /// A custom map, or `None` if any axis is assigned to a digital channel
+    pub const fn new(
+        roll: Channel,
+        pitch: Channel,
+        throttle: Channel,
+        yaw: Channel,
+    ) -> Option<Self> {
+        if roll.is_digital() || pitch.is_digital() || throttle.is_digital() || yaw.is_digital() {
+            None
+        } else {
+            Some(Self::preset(roll, pitch, throttle, yaw))
+        }
+    }
+
+    pub const fn roll(&self) -> Channel {
+        self.roll
+    }
+
+    pub const fn pitch(&self) -> Channel {
+        self.pitch
+    }
+
+    pub const fn throttle(&self) -> Channel {
+        self.throttle
+    }
+
+    pub const fn yaw(&self) -> Channel {
+        self.yaw
+    }
+
+    /// The channel of the zero-based aux `index`, i.e. `aux(0)` is AUX1
+    pub fn aux(&self, index: usize) -> Option<Channel> {
+        Channel::PROPORTIONAL
+            .into_iter()
+            .filter(|&channel| !self.is_stick(channel))
+            .nth(index)
+    }
+
+    fn is_stick(&self, channel: Channel) -> bool {
+        [self.roll, self.pitch, self.throttle, self.yaw].contains(&channel)
+    }
+}
+
+impl Default for ChannelMap {
+    fn default() -> Self {
+        Self::AETR
+    }
+}
+
+/// A packet viewed through a [`ChannelMap`], returned by [`SbusPacket::mapped`]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct MappedPacket<'a> {
+    packet: &'a SbusPacket,
+    map: &'a ChannelMap,
+}
+
+impl MappedPacket<'_> {
+    pub fn roll(&self) -> u16 {
+        self.packet[self.map.roll]
+    }
+
+    pub fn pitch(&self) -> u16 {
+        self.packet[self.map.pitch]
+    }
+
+    pub fn throttle(&self) -> u16 {
+        self.packet[self.map.throttle]
+    }
+
+    pub fn yaw(&self) -> u16 {
+        self.packet[self.map.yaw]
+    }
+
+    /// The value of the zero-based aux `index`, see [`ChannelMap::aux`]
+    pub fn aux(&self, index: usize) -> Option<u16> {
+        self.map.aux(index).map(|channel| self.packet[channel])
+    }
+}
+
+impl SbusPacket {
+    /// Accesses the stick axes and aux channels according to `map`
+    pub fn mapped<'a>(&'a self, map: &'a ChannelMap) -> MappedPacket<'a> {
+        MappedPacket { packet: self, map }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::numbered_packet;
+
+    #[test]
+    fn test_presets() {
+        let packet = numbered_packet();
+
+        let aetr = packet.mapped(&ChannelMap::AETR);
+        assert_eq!(
+            [aetr.roll(), aetr.pitch(), aetr.throttle(), aetr.yaw()],
+            [1000, 1001, 1002, 1003]
+        );
+
+        let taer = packet.mapped(&ChannelMap::TAER);
+        assert_eq!(
+            [taer.roll(), taer.pitch(), taer.throttle(), taer.yaw()],
+            [1001, 1002, 1000, 1003]
+        );
+
+        let reta = packet.mapped(&ChannelMap::RETA);
+        assert_eq!(
+            [reta.roll(), reta.pitch(), reta.throttle(), reta.yaw()],
+            [1003, 1001, 1002, 1000]
+        );
+        assert_eq!(reta.aux(0), Some(1004));
+        assert_eq!(reta.aux(11), Some(1015));
+        assert_eq!(reta.aux(12), None);
+    }
+
+    #[test]
+    fn test_custom_map() {
+        let map = ChannelMap::new(Channel::Ch5, Channel::Ch6, Channel::Ch7, Channel::Ch8).unwrap();
+        let packet = numbered_packet();
+        let mapped = packet.mapped(&map);
+
+        assert_eq!(mapped.throttle(), 1006);
+        assert_eq!(mapped.aux(0), Some(1000));
+        assert_eq!(mapped.aux(4), Some(1008));
+        assert_eq!(
+            ChannelMap::new(Channel::Ch1, Channel::Ch2, Channel::Ch17, Channel::Ch4),
+            None
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Conversions between SBUS packets and CRSF `RC_CHANNELS_PACKED` frames
+//!
+//! CRSF packs 16 channels of 11 bits in the same order as SBUS and uses the same scale, 172,
+//! 992 and 1811 being 988, 1500 and 2012 µs on both (see [`channel_to_micros`]), so channel
+//! values carry over unchanged. Receivers using another range, such as those centred on 1024,
+//! can be mapped onto it with a [`ChannelCalibration`](crate::ChannelCalibration) first.
+//!
+//! CRSF has no digital channels or flags: `d1`, `d2` and the flags are dropped on the way to
+//! CRSF, where a lost link is signalled by the frames stopping instead.
+//!
+//! [`channel_to_micros`]: crate::channel_to_micros
+use crate::{
+    channels_parsing, pack_channels, try_pack_channels, SbusError, SbusPacket, CHANNEL_COUNT,
+    SBUS_FRAME_LENGTH,
+};
+
+/// Address byte of the flight controller, starting the frames sent to it
+pub const CRSF_SYNC: u8 = 0xC8;
+/// Frame type of `RC_CHANNELS_PACKED`
+pub const CRSF_FRAME_TYPE_RC_CHANNELS_PACKED: u8 = 0x16;
+/// Length of the packed channels of an `RC_CHANNELS_PACKED` frame
+pub const CRSF_RC_PAYLOAD_LENGTH: usize = 22;
+/// Length of an `RC_CHANNELS_PACKED` frame: address, length, type, payload and CRC
+pub const CRSF_RC_FRAME_LENGTH: usize = CRSF_RC_PAYLOAD_LENGTH + 4;
+/// Lowest CRSF channel value sent by transmitters, 988 µs
+pub const CRSF_CHANNEL_MIN: u16 = 172;
+/// Centre CRSF channel value, 1500 µs
+pub const CRSF_CHANNEL_MID: u16 = 992;
+/// Highest CRSF channel value sent by transmitters, 2012 µs
+pub const CRSF_CHANNEL_MAX: u16 = 1811;
+
+/// The CRC-8 with polynomial 0xD5 closing CRSF frames, computed over the type and payload
+pub const fn crsf_crc8(bytes: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    let mut i = 0;
+    while i < bytes.len() {
+        crc ^= bytes[i];
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0xD5
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+impl SbusPacket {
+    /// Encodes channels 1 to 16 into an `RC_CHANNELS_PACKED` frame addressed to the flight
+    /// controller, truncating values above [`CHANNEL_MAX`](crate::CHANNEL_MAX) like
+    /// [`to_array`](Self::to_array)
+    pub fn to_crsf_frame(&self) -> [u8; CRSF_RC_FRAME_LENGTH] {
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        pack_channels(&mut buffer, &self.channels);
+        crsf_frame(&buffer)
+    }
+
+    /// Like [`to_crsf_frame`](Self::to_crsf_frame), but fails with [`SbusError::OutOfRange`]
+    /// instead of truncating
+    pub fn try_to_crsf_frame(&self) -> Result<[u8; CRSF_RC_FRAME_LENGTH], SbusError> {
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        try_pack_channels(&mut buffer, &self.channels)?;
+        Ok(crsf_frame(&buffer))
+    }
+
+    /// Decodes an `RC_CHANNELS_PACKED` frame into a packet without digital channels or flags
+    ///
+    /// The address byte is not checked, so frames sent to the transmitter module work as well.
+    /// Fails with [`SbusError::InvalidLength`] for frames of another length,
+    /// [`SbusError::InvalidHeader`] with the frame type for other frame types, and
+    /// [`SbusError::InvalidChecksum`] if the CRC doesn't match.
+    pub fn from_crsf_frame(frame: &[u8]) -> Result<Self, SbusError> {
+        if frame.len() != CRSF_RC_FRAME_LENGTH {
+            return Err(SbusError::InvalidLength(frame.len()));
+        }
+        if frame[1] as usize != CRSF_RC_FRAME_LENGTH - 2 {
+            return Err(SbusError::InvalidLength(frame[1] as usize + 2));
+        }
+        if frame[2] != CRSF_FRAME_TYPE_RC_CHANNELS_PACKED {
+            return Err(SbusError::InvalidHeader(frame[2]));
+        }
+        let expected = crsf_crc8(&frame[2..CRSF_RC_FRAME_LENGTH - 1]);
+        let received = frame[CRSF_RC_FRAME_LENGTH - 1];
+        if expected != received {
+            return Err(SbusError::InvalidChecksum {
+                expected: expected as u16,
+                received: received as u16,
+            });
+        }
+
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        buffer[1..=CRSF_RC_PAYLOAD_LENGTH].copy_from_slice(&frame[3..3 + CRSF_RC_PAYLOAD_LENGTH]);
+        let mut packet = Self::neutral();
+        packet.channels = channels_parsing(&buffer);
+        Ok(packet)
+    }
+}
+
+/// Wraps the channel bytes of an SBUS frame buffer into an `RC_CHANNELS_PACKED` frame
+fn crsf_frame(buffer: &[u8; SBUS_FRAME_LENGTH]) -> [u8; CRSF_RC_FRAME_LENGTH] {
+    let mut frame = [0u8; CRSF_RC_FRAME_LENGTH];
+    frame[0] = CRSF_SYNC;
+    frame[1] = (CRSF_RC_FRAME_LENGTH - 2) as u8;
+    frame[2] = CRSF_FRAME_TYPE_RC_CHANNELS_PACKED;
+    frame[3..3 + CRSF_RC_PAYLOAD_LENGTH].copy_from_slice(&buffer[1..=CRSF_RC_PAYLOAD_LENGTH]);
+    frame[CRSF_RC_FRAME_LENGTH - 1] = crsf_crc8(&frame[2..CRSF_RC_FRAME_LENGTH - 1]);
+    frame
+}
+
+const _: () = assert!(CRSF_RC_PAYLOAD_LENGTH * 8 == CHANNEL_COUNT * 11);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Channel, CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MIN, CHANNEL_MAX};
+
+    #[test]
+    fn test_crsf_round_trip() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch1] = CHANNEL_FUTABA_MIN;
+        packet[Channel::Ch2] = CHANNEL_FUTABA_MAX;
+        packet[Channel::Ch16] = 1234;
+
+        let frame = packet.to_crsf_frame();
+        assert_eq!(
+            frame[..3],
+            [CRSF_SYNC, 24, CRSF_FRAME_TYPE_RC_CHANNELS_PACKED]
+        );
+        let decoded = SbusPacket::from_crsf_frame(&frame).unwrap();
+        assert_eq!(decoded.channels, packet.channels);
+        assert_eq!(decoded[Channel::Ch1], CRSF_CHANNEL_MIN);
+        assert_eq!(decoded[Channel::Ch2], CRSF_CHANNEL_MAX);
+        assert_eq!(decoded[Channel::Ch3], CRSF_CHANNEL_MID);
+        assert_eq!(CHANNEL_FUTABA_MID, CRSF_CHANNEL_MID);
+    }
+
+    #[test]
+    fn test_crsf_crc() {
+        // All channels centred, as sent by most transmitters on startup
+        let frame = SbusPacket::neutral().to_crsf_frame();
+        assert_eq!(
+            frame[CRSF_RC_FRAME_LENGTH - 1],
+            crsf_crc8(&frame[2..CRSF_RC_FRAME_LENGTH - 1])
+        );
+        assert_eq!(crsf_crc8(&[]), 0);
+        assert_eq!(crsf_crc8(&[0x01]), 0xD5);
+    }
+
+    #[test]
+    fn test_crsf_errors() {
+        let frame = SbusPacket::neutral().to_crsf_frame();
+        assert_eq!(
+            SbusPacket::from_crsf_frame(&frame[..25]),
+            Err(SbusError::InvalidLength(25))
+        );
+
+        let mut bad_type = frame;
+        bad_type[2] = 0x14;
+        assert!(matches!(
+            SbusPacket::from_crsf_frame(&bad_type),
+            Err(SbusError::InvalidHeader(0x14))
+        ));
+
+        let mut bad_crc = frame;
+        bad_crc[5] ^= 0x01;
+        assert!(matches!(
+            SbusPacket::from_crsf_frame(&bad_crc),
+            Err(SbusError::InvalidChecksum { .. })
+        ));
+
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch4] = CHANNEL_MAX + 1;
+        assert!(matches!(
+            packet.try_to_crsf_frame(),
+            Err(SbusError::OutOfRange { .. })
+        ));
+    }
+}
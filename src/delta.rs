@@ -0,0 +1,365 @@
+use crate::{
+    FrameKind, SbusError, SbusPacket, CHANNEL_COUNT, CHANNEL_MAX, FLAG_MASK, SBUS_FRAME_LENGTH,
+    SBUS_HEADER,
+};
+
+/// Longest message produced by [`DeltaEncoder`], a keyframe
+pub const DELTA_MAX_MESSAGE_LENGTH: usize = SBUS_FRAME_LENGTH;
+/// Default number of messages from one keyframe to the next
+pub const DELTA_KEYFRAME_INTERVAL: u16 = 50;
+
+/// Set in the first byte of keyframes, the lower seven bits hold the sequence number
+const KEYFRAME_BIT: u8 = 0x80;
+const SEQUENCE_MASK: u8 = 0x7F;
+/// Sequence byte, channel mask, flags and footer
+const DELTA_HEADER_LENGTH: usize = 5;
+
+/// Encodes packets into messages carrying only the channels that changed
+///
+/// A keyframe carries the whole packet in 25 bytes: a sequence byte followed by the channel,
+/// flag and footer bytes of the SBUS frame. Every other message is a delta: the sequence byte,
+/// a little-endian mask of the changed channels, the flag and footer bytes, and the changed
+/// channels packed into 11 bits each like in an SBUS frame, 5 bytes if nothing changed.
+/// Keyframes are sent every [`with_keyframe_interval`](Self::with_keyframe_interval) messages,
+/// whenever a delta wouldn't be shorter, and after [`request_keyframe`](Self::request_keyframe),
+/// so a [`DeltaDecoder`] that lost a message recovers with the next keyframe.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{DeltaDecoder, DeltaEncoder, SbusPacket, DELTA_MAX_MESSAGE_LENGTH};
+///
+/// let mut encoder = DeltaEncoder::new();
+/// let mut decoder = DeltaDecoder::new();
+/// let mut message = [0u8; DELTA_MAX_MESSAGE_LENGTH];
+///
+/// let mut packet = SbusPacket::neutral();
+/// for value in [1000, 1000, 1010] {
+///     packet.channels[0] = value;
+///     let len = encoder.encode(&packet, &mut message);
+///     // Send `message[..len]` over the link, then on the other end
+///     assert_eq!(decoder.decode(&message[..len]), Ok(Some(packet)));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct DeltaEncoder {
+    last: Option<SbusPacket>,
+    sequence: u8,
+    keyframe_interval: u16,
+    since_keyframe: u16,
+}
+
+impl DeltaEncoder {
+    pub const fn new() -> Self {
+        Self {
+            last: None,
+            sequence: 0,
+            keyframe_interval: DELTA_KEYFRAME_INTERVAL,
+            since_keyframe: 0,
+        }
+    }
+
+    /// Sets the number of messages from one keyframe to the next, [`DELTA_KEYFRAME_INTERVAL`]
+    /// by default
+    ///
+    /// Lower intervals recover faster from lost messages at the cost of bandwidth, 1 sends
+    /// only keyframes.
+    pub const fn with_keyframe_interval(mut self, interval: u16) -> Self {
+        self.keyframe_interval = interval;
+        self
+    }
+
+    /// Sends a keyframe with the next packet, e.g. when the decoder reported
+    /// [`DeltaDecoder::needs_keyframe`] over a back channel
+    pub fn request_keyframe(&mut self) {
+        self.last = None;
+    }
+
+    /// Encodes `packet` into `message`, returning the length of the message
+    pub fn encode(
+        &mut self,
+        packet: &SbusPacket,
+        message: &mut [u8; DELTA_MAX_MESSAGE_LENGTH],
+    ) -> usize {
+        let sequence = self.sequence;
+        self.sequence = (self.sequence + 1) & SEQUENCE_MASK;
+
+        let frame = packet.to_array();
+        let mask = match self.last {
+            Some(last) if self.since_keyframe + 1 < self.keyframe_interval => {
+                last.diff(packet, 0).mask() as u16
+            }
+            _ => u16::MAX,
+        };
+        let len = DELTA_HEADER_LENGTH + packed_len(mask.count_ones() as usize);
+        self.last = Some(*packet);
+
+        if len >= DELTA_MAX_MESSAGE_LENGTH {
+            self.since_keyframe = 0;
+            message[0] = KEYFRAME_BIT | sequence;
+            message[1..].copy_from_slice(&frame[1..]);
+            return DELTA_MAX_MESSAGE_LENGTH;
+        }
+
+        self.since_keyframe += 1;
+        message[0] = sequence;
+        message[1..3].copy_from_slice(&mask.to_le_bytes());
+        message[3] = frame[23];
+        message[4] = frame[24];
+        message[DELTA_HEADER_LENGTH..len].fill(0);
+        let mut bit = 0;
+        for (index, &value) in packet.channels.iter().enumerate() {
+            if mask & (1 << index) != 0 {
+                write_bits(
+                    &mut message[DELTA_HEADER_LENGTH..len],
+                    bit,
+                    value & CHANNEL_MAX,
+                );
+                bit += 11;
+            }
+        }
+        len
+    }
+}
+
+impl Default for DeltaEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decodes the messages of a [`DeltaEncoder`] back into packets
+///
+/// Deltas only apply on top of the message right before them. After a lost message, seen as a
+/// gap in the sequence numbers, deltas are skipped until the next keyframe.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct DeltaDecoder {
+    last: Option<SbusPacket>,
+    next_sequence: u8,
+}
+
+impl DeltaDecoder {
+    pub const fn new() -> Self {
+        Self {
+            last: None,
+            next_sequence: 0,
+        }
+    }
+
+    /// Decodes a message, returning `None` for a delta skipped while waiting for a keyframe
+    ///
+    /// Fails with [`SbusError::InvalidLength`] for messages of the wrong length,
+    /// [`SbusError::InvalidFlagByte`] and [`SbusError::InvalidFooter`] for invalid flag and
+    /// footer bytes, leaving the decoder waiting for a keyframe.
+    pub fn decode(&mut self, message: &[u8]) -> Result<Option<SbusPacket>, SbusError> {
+        let result = self.apply(message);
+        match result {
+            Ok(Some(packet)) => self.last = Some(packet),
+            _ => self.last = None,
+        }
+        result
+    }
+
+    /// Whether deltas are skipped until the next keyframe
+    pub fn needs_keyframe(&self) -> bool {
+        self.last.is_none()
+    }
+
+    fn apply(&mut self, message: &[u8]) -> Result<Option<SbusPacket>, SbusError> {
+        let Some(&first) = message.first() else {
+            return Err(SbusError::InvalidLength(0));
+        };
+        let sequence = first & SEQUENCE_MASK;
+        let in_sequence = sequence == self.next_sequence;
+        self.next_sequence = (sequence + 1) & SEQUENCE_MASK;
+
+        let mut frame = [0u8; SBUS_FRAME_LENGTH];
+        frame[0] = SBUS_HEADER;
+        if first & KEYFRAME_BIT != 0 {
+            if message.len() != DELTA_MAX_MESSAGE_LENGTH {
+                return Err(SbusError::InvalidLength(message.len()));
+            }
+            frame[1..].copy_from_slice(&message[1..]);
+            return decode_frame(&frame).map(Some);
+        }
+
+        if message.len() < DELTA_HEADER_LENGTH {
+            return Err(SbusError::InvalidLength(message.len()));
+        }
+        let mask = u16::from_le_bytes([message[1], message[2]]);
+        let len = DELTA_HEADER_LENGTH + packed_len(mask.count_ones() as usize);
+        if message.len() != len {
+            return Err(SbusError::InvalidLength(message.len()));
+        }
+        let Some(last) = self.last.filter(|_| in_sequence) else {
+            return Ok(None);
+        };
+
+        let mut packet = last;
+        let mut bit = 0;
+        for (index, value) in packet.channels.iter_mut().enumerate() {
+            if mask & (1 << index) != 0 {
+                *value = read_bits(&message[DELTA_HEADER_LENGTH..], bit);
+                bit += 11;
+            }
+        }
+        frame[23] = message[3];
+        frame[24] = message[4];
+        packet.flags = decode_frame(&frame)?.flags;
+        Ok(Some(packet))
+    }
+}
+
+/// Decodes a frame, rejecting reserved flag bits that [`SbusPacket::from_array`] ignores
+fn decode_frame(frame: &[u8; SBUS_FRAME_LENGTH]) -> Result<SbusPacket, SbusError> {
+    if frame[23] & !FLAG_MASK != 0 {
+        return Err(SbusError::InvalidFlagByte(frame[23]));
+    }
+    if FrameKind::from_footer(frame[24]).is_none() {
+        return Err(SbusError::InvalidFooter(frame[24]));
+    }
+    SbusPacket::from_array(frame)
+}
+
+/// Bytes needed for `count` channels of 11 bits
+const fn packed_len(count: usize) -> usize {
+    (count * 11).div_ceil(8)
+}
+
+fn write_bits(bytes: &mut [u8], bit: usize, value: u16) {
+    for offset in 0..11 {
+        if value & (1 << offset) != 0 {
+            let position = bit + offset;
+            bytes[position / 8] |= 1 << (position % 8);
+        }
+    }
+}
+
+fn read_bits(bytes: &[u8], bit: usize) -> u16 {
+    (0..11).fold(0, |value, offset| {
+        let position = bit + offset;
+        let set = bytes[position / 8] & (1 << (position % 8)) != 0;
+        value | (set as u16) << offset
+    })
+}
+
+const _: () = assert!(DELTA_HEADER_LENGTH + (CHANNEL_COUNT * 11).div_ceil(8) > SBUS_FRAME_LENGTH);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Channel;
+
+    fn encode(encoder: &mut DeltaEncoder, packet: &SbusPacket) -> Vec<u8> {
+        let mut message = [0u8; DELTA_MAX_MESSAGE_LENGTH];
+        let len = encoder.encode(packet, &mut message);
+        message[..len].to_vec()
+    }
+
+    #[test]
+    fn test_deltas() {
+        let mut encoder = DeltaEncoder::new();
+        let mut decoder = DeltaDecoder::new();
+        let mut packet = SbusPacket::neutral();
+
+        let keyframe = encode(&mut encoder, &packet);
+        assert_eq!(keyframe.len(), DELTA_MAX_MESSAGE_LENGTH);
+        assert_eq!(keyframe[0], KEYFRAME_BIT);
+        assert_eq!(decoder.decode(&keyframe), Ok(Some(packet)));
+
+        let unchanged = encode(&mut encoder, &packet);
+        assert_eq!(unchanged, [1, 0, 0, 0, 0]);
+        assert_eq!(decoder.decode(&unchanged), Ok(Some(packet)));
+
+        packet[Channel::Ch2] = 2047;
+        packet[Channel::Ch16] = 5;
+        packet.flags.failsafe = true;
+        let delta = encode(&mut encoder, &packet);
+        assert_eq!(delta.len(), DELTA_HEADER_LENGTH + 3);
+        assert_eq!(delta[1..3], [0b10, 0x80]);
+        assert_eq!(decoder.decode(&delta), Ok(Some(packet)));
+
+        // Changing every channel is cheaper as a keyframe
+        packet.channels = [100; CHANNEL_COUNT];
+        let keyframe = encode(&mut encoder, &packet);
+        assert_eq!(keyframe[0], KEYFRAME_BIT | 3);
+        assert_eq!(decoder.decode(&keyframe), Ok(Some(packet)));
+    }
+
+    #[test]
+    fn test_resync_after_loss() {
+        let mut encoder = DeltaEncoder::new().with_keyframe_interval(4);
+        let mut decoder = DeltaDecoder::new();
+        let mut packet = SbusPacket::neutral();
+
+        let mut messages = Vec::new();
+        for value in 0..8 {
+            packet.channels[0] = value;
+            messages.push(encode(&mut encoder, &packet));
+        }
+        let keyframes: Vec<_> = messages
+            .iter()
+            .map(|message| message[0] & KEYFRAME_BIT != 0)
+            .collect();
+        assert_eq!(
+            keyframes,
+            [true, false, false, false, true, false, false, false]
+        );
+
+        assert!(decoder.needs_keyframe());
+        assert_eq!(decoder.decode(&messages[1]), Ok(None));
+        assert_eq!(
+            decoder.decode(&messages[0]).unwrap().unwrap().channels[0],
+            0
+        );
+        // Message 1 is lost
+        assert_eq!(decoder.decode(&messages[2]), Ok(None));
+        assert!(decoder.needs_keyframe());
+        assert_eq!(decoder.decode(&messages[3]), Ok(None));
+        assert_eq!(
+            decoder.decode(&messages[4]).unwrap().unwrap().channels[0],
+            4
+        );
+        assert_eq!(
+            decoder.decode(&messages[5]).unwrap().unwrap().channels[0],
+            5
+        );
+
+        encoder.request_keyframe();
+        assert_eq!(
+            encode(&mut encoder, &packet)[0] & KEYFRAME_BIT,
+            KEYFRAME_BIT
+        );
+    }
+
+    #[test]
+    fn test_invalid_messages() {
+        let mut decoder = DeltaDecoder::new();
+        assert_eq!(decoder.decode(&[]), Err(SbusError::InvalidLength(0)));
+        assert_eq!(
+            decoder.decode(&[KEYFRAME_BIT, 0, 0]),
+            Err(SbusError::InvalidLength(3))
+        );
+
+        let mut keyframe = encode(&mut DeltaEncoder::new(), &SbusPacket::neutral());
+        keyframe[23] = 0x10;
+        assert_eq!(
+            decoder.decode(&keyframe),
+            Err(SbusError::InvalidFlagByte(0x10))
+        );
+        keyframe[23] = 0;
+        keyframe[24] = 0xFF;
+        assert!(matches!(
+            decoder.decode(&keyframe),
+            Err(SbusError::InvalidFooter(0xFF))
+        ));
+
+        // A delta announcing two channels but carrying one
+        assert_eq!(
+            decoder.decode(&[1, 0b11, 0, 0, 0, 0, 0]),
+            Err(SbusError::InvalidLength(7))
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use core::time::Duration;
+
+use crate::{
+    ibus_checksum, FrameKind, IBUS_FRAME_LENGTH, IBUS_HEADER, SBUS_FRAME_LENGTH, SBUS_HEADER,
+};
+
+/// Number of consecutive frames of one protocol for full confidence
+const FRAMES_FOR_CERTAINTY: u8 = 5;
+/// Bytes without a recognised frame after which all protocols lose confidence
+const BYTES_WITHOUT_FRAME: usize = 2 * IBUS_FRAME_LENGTH;
+/// Pause between two bytes that marks the gap between frames, several byte times of either
+/// protocol
+const IDLE_GAP: Duration = Duration::from_micros(500);
+
+/// An RC input protocol told apart by [`ProtocolDetector`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum RcProtocol {
+    /// No protocol recognised yet
+    #[default]
+    Unknown,
+    /// SBUS frames with the footer `0x00`
+    Sbus,
+    /// SBUS frames with SBUS2 footers
+    Sbus2,
+    /// FlySky IBUS servo frames
+    Ibus,
+}
+
+/// The protocol a [`ProtocolDetector`] settled on, and how sure it is
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Detection {
+    pub protocol: RcProtocol,
+    /// Confidence in percent, 0 for [`RcProtocol::Unknown`]
+    pub confidence: u8,
+}
+
+/// Classifies a raw byte stream as SBUS, SBUS2 or IBUS
+///
+/// Every complete, well-formed frame at the end of the pushed bytes counts for its protocol and
+/// against the others, and confidence drops while no frames are recognised. Confidence reaches
+/// 100% after five consecutive frames of one protocol.
+///
+/// The protocols use different UART settings, SBUS 100000 baud 8E2 and usually inverted, IBUS
+/// 115200 baud 8N1, so a UART set up for one of them receives garbage from the other. Firmware
+/// can cycle through the settings, feeding each for a few frame intervals until a protocol is
+/// detected. Bytes pushed with timestamps are only counted as a frame if the frame started
+/// after an idle gap, which rules out frames that happen to appear in the middle of others.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{ProtocolDetector, RcProtocol, SbusPacket};
+///
+/// let mut detector = ProtocolDetector::new();
+/// for _ in 0..5 {
+///     detector.push_bytes(&SbusPacket::neutral().to_array());
+/// }
+/// let detection = detector.detection();
+/// assert_eq!(detection.protocol, RcProtocol::Sbus);
+/// assert_eq!(detection.confidence, 100);
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ProtocolDetector {
+    /// The last bytes received, oldest first
+    window: [u8; IBUS_FRAME_LENGTH],
+    /// Number of valid bytes at the end of `window`
+    len: usize,
+    /// Consecutive frames seen per protocol, indexed by [`ProtocolDetector::index`]
+    frames: [u8; 3],
+    bytes_without_frame: usize,
+    /// Time of the last byte, once bytes are pushed with timestamps
+    last_byte_at: Option<Duration>,
+    /// Bytes received since the last idle gap
+    bytes_since_gap: usize,
+}
+
+impl ProtocolDetector {
+    pub const fn new() -> Self {
+        Self {
+            window: [0; IBUS_FRAME_LENGTH],
+            len: 0,
+            frames: [0; 3],
+            bytes_without_frame: 0,
+            last_byte_at: None,
+            bytes_since_gap: 0,
+        }
+    }
+
+    /// Appends a received byte
+    pub fn push_byte(&mut self, byte: u8) {
+        self.window.copy_within(1.., 0);
+        self.window[IBUS_FRAME_LENGTH - 1] = byte;
+        self.len = (self.len + 1).min(IBUS_FRAME_LENGTH);
+        self.bytes_since_gap = self.bytes_since_gap.saturating_add(1);
+
+        match self.frame_at_end() {
+            Some(protocol) => self.count(protocol),
+            None => {
+                self.bytes_without_frame += 1;
+                if self.bytes_without_frame >= BYTES_WITHOUT_FRAME {
+                    self.bytes_without_frame = 0;
+                    self.frames = self.frames.map(|frames| frames.saturating_sub(1));
+                }
+            }
+        }
+    }
+
+    /// Appends received bytes
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.push_byte(byte);
+        }
+    }
+
+    /// Appends a byte received at `timestamp`, measured from any fixed epoch
+    ///
+    /// Once bytes are pushed with timestamps, frames only count if they started after an idle
+    /// gap.
+    pub fn push_byte_at(&mut self, byte: u8, timestamp: Duration) {
+        let gap = self
+            .last_byte_at
+            .is_none_or(|last| timestamp.saturating_sub(last) >= IDLE_GAP);
+        if gap {
+            self.bytes_since_gap = 0;
+        }
+        self.last_byte_at = Some(timestamp);
+        self.push_byte(byte);
+    }
+
+    /// The most likely protocol so far
+    pub fn detection(&self) -> Detection {
+        let (index, &frames) = self
+            .frames
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, &frames)| frames)
+            .unwrap_or((0, &0));
+        if frames == 0 {
+            return Detection::default();
+        }
+        Detection {
+            protocol: [RcProtocol::Sbus, RcProtocol::Sbus2, RcProtocol::Ibus][index],
+            confidence: (frames as u16 * 100 / FRAMES_FOR_CERTAINTY as u16) as u8,
+        }
+    }
+
+    /// Forgets everything seen so far, e.g. after switching the UART settings
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// The protocol of a frame ending with the last byte
+    fn frame_at_end(&self) -> Option<RcProtocol> {
+        let timed = self.last_byte_at.is_some();
+        let aligned = |len: usize| !timed || self.bytes_since_gap == len;
+
+        if self.len >= SBUS_FRAME_LENGTH && aligned(SBUS_FRAME_LENGTH) {
+            let frame = &self.window[IBUS_FRAME_LENGTH - SBUS_FRAME_LENGTH..];
+            // Only the lower four bits of the flags byte are used
+            if frame[0] == SBUS_HEADER && frame[23] & 0xF0 == 0 {
+                match FrameKind::from_footer(frame[SBUS_FRAME_LENGTH - 1]) {
+                    Some(FrameKind::Sbus1) => return Some(RcProtocol::Sbus),
+                    Some(FrameKind::Sbus2(_)) => return Some(RcProtocol::Sbus2),
+                    None => {}
+                }
+            }
+        }
+
+        if self.len == IBUS_FRAME_LENGTH && aligned(IBUS_FRAME_LENGTH) {
+            let frame = &self.window;
+            let checksum = u16::from_le_bytes([frame[30], frame[31]]);
+            if frame[..2] == IBUS_HEADER && ibus_checksum(&frame[..30]) == checksum {
+                return Some(RcProtocol::Ibus);
+            }
+        }
+        None
+    }
+
+    fn count(&mut self, protocol: RcProtocol) {
+        self.bytes_without_frame = 0;
+        for (index, frames) in self.frames.iter_mut().enumerate() {
+            if index == Self::index(protocol) {
+                *frames = (*frames + 1).min(FRAMES_FOR_CERTAINTY);
+            } else {
+                *frames = frames.saturating_sub(1);
+            }
+        }
+    }
+
+    const fn index(protocol: RcProtocol) -> usize {
+        match protocol {
+            RcProtocol::Sbus | RcProtocol::Unknown => 0,
+            RcProtocol::Sbus2 => 1,
+            RcProtocol::Ibus => 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SbusPacket;
+
+    fn sbus2() -> [u8; SBUS_FRAME_LENGTH] {
+        SbusPacket::neutral().to_array_with_kind(FrameKind::Sbus2(2))
+    }
+
+    #[test]
+    fn test_detect_protocols() {
+        let mut detector = ProtocolDetector::new();
+        assert_eq!(detector.detection(), Detection::default());
+
+        detector.push_bytes(&SbusPacket::neutral().to_ibus_frame());
+        detector.push_bytes(&SbusPacket::neutral().to_ibus_frame());
+        assert_eq!(
+            detector.detection(),
+            Detection {
+                protocol: RcProtocol::Ibus,
+                confidence: 40,
+            }
+        );
+
+        detector.reset();
+        for _ in 0..7 {
+            detector.push_bytes(&sbus2());
+        }
+        assert_eq!(
+            detector.detection(),
+            Detection {
+                protocol: RcProtocol::Sbus2,
+                confidence: 100,
+            }
+        );
+
+        // Plain SBUS frames take over
+        for _ in 0..4 {
+            detector.push_bytes(&SbusPacket::neutral().to_array());
+        }
+        assert_eq!(detector.detection().protocol, RcProtocol::Sbus);
+    }
+
+    #[test]
+    fn test_noise_lowers_confidence() {
+        let mut detector = ProtocolDetector::new();
+        for _ in 0..5 {
+            detector.push_bytes(&SbusPacket::neutral().to_array());
+        }
+        detector.push_bytes(&[0x55; BYTES_WITHOUT_FRAME * 2]);
+        assert_eq!(detector.detection().confidence, 60);
+        detector.push_bytes(&[0x55; BYTES_WITHOUT_FRAME * 3]);
+        assert_eq!(detector.detection(), Detection::default());
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let frame = SbusPacket::neutral().to_array();
+        let mut detector = ProtocolDetector::new();
+        let mut now = Duration::ZERO;
+
+        // A frame directly following other bytes doesn't count
+        for &byte in [0x11, 0x22].iter().chain(&frame) {
+            detector.push_byte_at(byte, now);
+            now += Duration::from_micros(120);
+        }
+        assert_eq!(detector.detection().protocol, RcProtocol::Unknown);
+
+        now += Duration::from_millis(3);
+        for &byte in &frame {
+            detector.push_byte_at(byte, now);
+            now += Duration::from_micros(120);
+        }
+        assert_eq!(detector.detection().protocol, RcProtocol::Sbus);
+    }
+}
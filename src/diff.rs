@@ -0,0 +1,144 @@
+use crate::{Channel, SbusPacket};
+
+/// A channel whose value changed between two packets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelChange {
+    pub channel: Channel,
+    pub old: u16,
+    pub new: u16,
+}
+
+/// The channels that changed between two packets, see [`SbusPacket::diff`]
+///
+/// Digital channels are reported with the values of [`SbusPacket::value`].
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{Channel, ChannelChange, SbusPacket};
+///
+/// let old = SbusPacket::neutral();
+/// let mut new = old;
+/// new.channels[0] += 3;
+/// new.channels[2] += 40;
+///
+/// let diff = old.diff(&new, 5);
+/// assert_eq!(diff.mask(), 0b100);
+/// assert_eq!(
+///     diff.iter().collect::<Vec<_>>(),
+///     [ChannelChange { channel: Channel::Ch3, old: 992, new: 1032 }]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct PacketDiff {
+    old: SbusPacket,
+    new: SbusPacket,
+    mask: u32,
+}
+
+impl PacketDiff {
+    /// The changed channels, bit `n` set for the channel with index `n`
+    pub const fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    /// Whether no channel changed
+    pub const fn is_empty(&self) -> bool {
+        self.mask == 0
+    }
+
+    /// The number of changed channels
+    pub const fn len(&self) -> usize {
+        self.mask.count_ones() as usize
+    }
+
+    /// Whether `channel` changed
+    pub const fn contains(&self, channel: Channel) -> bool {
+        self.mask & (1 << channel.index()) != 0
+    }
+
+    /// Iterates over the changed channels in order
+    pub fn iter(&self) -> impl Iterator<Item = ChannelChange> + '_ {
+        Channel::ALL
+            .iter()
+            .filter(|&&channel| self.contains(channel))
+            .map(|&channel| ChannelChange {
+                channel,
+                old: self.old.value(channel),
+                new: self.new.value(channel),
+            })
+    }
+}
+
+impl SbusPacket {
+    /// Compares against a `newer` packet, reporting the proportional channels that moved by
+    /// more than `tolerance` and the digital channels that toggled
+    ///
+    /// The failsafe and frame lost flags are not compared.
+    pub fn diff(&self, newer: &SbusPacket, tolerance: u16) -> PacketDiff {
+        let mut mask = 0;
+        for channel in Channel::ALL {
+            let changed = match (self.digital(channel), newer.digital(channel)) {
+                (Some(old), Some(new)) => old != new,
+                _ => self[channel].abs_diff(newer[channel]) > tolerance,
+            };
+            if changed {
+                mask |= 1 << channel.index();
+            }
+        }
+        PacketDiff {
+            old: *self,
+            new: *newer,
+            mask,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MIN};
+
+    #[test]
+    fn test_diff() {
+        let old = SbusPacket::neutral();
+        assert!(old.diff(&old, 0).is_empty());
+
+        let mut new = old;
+        new.channels[1] += 10;
+        new.channels[15] -= 11;
+        new.flags.d2 = true;
+        new.flags.failsafe = true;
+
+        let diff = old.diff(&new, 10);
+        assert_eq!(diff.len(), 2);
+        assert!(!diff.contains(Channel::Ch2));
+        assert!(diff.contains(Channel::Ch16));
+        assert!(!diff.contains(Channel::Ch17));
+        assert_eq!(diff.mask(), 1 << 15 | 1 << 17);
+
+        let mut changes = diff.iter();
+        assert_eq!(
+            changes.next(),
+            Some(ChannelChange {
+                channel: Channel::Ch16,
+                old: old.channels[15],
+                new: old.channels[15] - 11,
+            })
+        );
+        assert_eq!(
+            changes.next(),
+            Some(ChannelChange {
+                channel: Channel::Ch18,
+                old: CHANNEL_FUTABA_MIN,
+                new: CHANNEL_FUTABA_MAX,
+            })
+        );
+        assert_eq!(changes.next(), None);
+
+        assert_eq!(old.diff(&new, 0).len(), 3);
+    }
+}
@@ -0,0 +1,97 @@
+//! Glue for running the SBUS parser as an embassy task
+use core::convert::Infallible;
+
+use embassy_sync::{blocking_mutex::raw::RawMutex, channel::Channel, signal::Signal};
+use embedded_io_async::Read;
+
+use crate::{SbusError, SbusPacket, SbusParserAsync};
+
+/// Where [`run_sbus_receiver`] publishes decoded packets
+pub trait PacketSink {
+    /// Publishes a packet without waiting
+    fn publish(&self, packet: SbusPacket);
+}
+
+/// Replaces the pending packet, so waiters always get the latest one
+impl<M: RawMutex> PacketSink for Signal<M, SbusPacket> {
+    fn publish(&self, packet: SbusPacket) {
+        self.signal(packet);
+    }
+}
+
+/// Queues the packet, dropping it if the channel is full so the UART is never starved
+impl<M: RawMutex, const N: usize> PacketSink for Channel<M, SbusPacket, N> {
+    fn publish(&self, packet: SbusPacket) {
+        let _ = self.try_send(packet);
+    }
+}
+
+/// Reads SBUS frames from `rx` forever and publishes every valid packet to `sink`
+///
+/// `rx` is usually the RX half of a split embassy UART, e.g. a `BufferedUartRx`, configured
+/// with [`SbusUartConfig`](crate::SbusUartConfig). The parser resynchronises on its own after
+/// noise or dropped bytes. Only returns if reading fails, in which case the task can be
+/// restarted with the same reader.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+/// use sbus_rs::{run_sbus_receiver, SbusPacket};
+///
+/// static SBUS: Signal<CriticalSectionRawMutex, SbusPacket> = Signal::new();
+///
+/// // #[embassy_executor::task]
+/// async fn sbus_task<R: embedded_io_async::Read>(mut rx: R) {
+///     loop {
+///         let _ = run_sbus_receiver(&mut rx, &SBUS).await;
+///     }
+/// }
+///
+/// // In the control task
+/// async fn control() {
+///     let packet = SBUS.wait().await;
+/// }
+/// ```
+pub async fn run_sbus_receiver<R, S>(rx: R, sink: &S) -> Result<Infallible, SbusError<R::Error>>
+where
+    R: Read,
+    S: PacketSink + ?Sized,
+{
+    let mut parser = SbusParserAsync::new(rx);
+    loop {
+        let packet = parser.read_next_valid_frame().await?;
+        sink.publish(packet);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::frames;
+    use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+    use embedded_io_adapters::tokio_1::FromTokio;
+
+    #[tokio::test]
+    async fn test_signal_holds_latest() {
+        let signal = Signal::<NoopRawMutex, SbusPacket>::new();
+        let mut data = vec![0xAA, 0x55];
+        data.extend(frames(&[100, 200, 300]));
+
+        let result = run_sbus_receiver(FromTokio::new(data.as_slice()), &signal).await;
+        assert!(matches!(result, Err(SbusError::UnexpectedEof)));
+        assert_eq!(signal.try_take().unwrap().channels, [300; 16]);
+    }
+
+    #[tokio::test]
+    async fn test_channel_drops_when_full() {
+        let channel = Channel::<NoopRawMutex, SbusPacket, 2>::new();
+        let mut data = vec![0xAA, 0x55];
+        data.extend(frames(&[100, 200, 300]));
+
+        let _ = run_sbus_receiver(FromTokio::new(data.as_slice()), &channel).await;
+        assert_eq!(channel.try_receive().unwrap().channels, [100; 16]);
+        assert_eq!(channel.try_receive().unwrap().channels, [200; 16]);
+        assert!(channel.try_receive().is_err());
+    }
+}
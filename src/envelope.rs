@@ -0,0 +1,324 @@
+use crate::{SbusError, SbusPacket, SBUS_FRAME_LENGTH};
+
+/// The two bytes starting every envelope
+pub const ENVELOPE_SYNC: [u8; 2] = [0xA5, 0x5A];
+/// Longest payload an envelope can carry
+pub const ENVELOPE_MAX_PAYLOAD: usize = 64;
+/// Bytes an envelope adds to its payload: sync bytes, length and CRC
+pub const ENVELOPE_OVERHEAD: usize = 5;
+
+/// The CRC-16/CCITT-FALSE checksum, polynomial 0x1021 starting from 0xFFFF, closing envelopes
+pub const fn crc16_ccitt(bytes: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    let mut i = 0;
+    while i < bytes.len() {
+        crc ^= (bytes[i] as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+/// Wraps `payload` into an envelope in `out`, returning the length of the envelope
+///
+/// An envelope is [`ENVELOPE_SYNC`], the payload length, the payload and the little-endian
+/// [`crc16_ccitt`] of the length and payload. SBUS has no checksum of its own, so frames sent
+/// over UDP, LoRa, BLE or other lossy links can be wrapped to have corrupted ones dropped by the
+/// [`EnvelopeParser`] on the far side. Fails with [`SbusError::InvalidLength`] if the payload
+/// is longer than [`ENVELOPE_MAX_PAYLOAD`] or the envelope doesn't fit into `out`.
+pub fn wrap_envelope(payload: &[u8], out: &mut [u8]) -> Result<usize, SbusError> {
+    let len = payload.len() + ENVELOPE_OVERHEAD;
+    if payload.len() > ENVELOPE_MAX_PAYLOAD || out.len() < len {
+        return Err(SbusError::InvalidLength(payload.len()));
+    }
+    out[..2].copy_from_slice(&ENVELOPE_SYNC);
+    out[2] = payload.len() as u8;
+    out[3..len - 2].copy_from_slice(payload);
+    let crc = crc16_ccitt(&out[2..len - 2]);
+    out[len - 2..len].copy_from_slice(&crc.to_le_bytes());
+    Ok(len)
+}
+
+impl SbusPacket {
+    /// Wraps the raw frame of the packet into an envelope, see [`wrap_envelope`]
+    pub fn to_envelope(&self) -> [u8; SBUS_FRAME_LENGTH + ENVELOPE_OVERHEAD] {
+        let mut envelope = [0u8; SBUS_FRAME_LENGTH + ENVELOPE_OVERHEAD];
+        // A frame always fits
+        let _ = wrap_envelope(&self.to_array(), &mut envelope);
+        envelope
+    }
+}
+
+/// The payload of an envelope received by an [`EnvelopeParser`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Envelope {
+    payload: [u8; ENVELOPE_MAX_PAYLOAD],
+    len: u8,
+}
+
+impl Envelope {
+    pub fn payload(&self) -> &[u8] {
+        &self.payload[..self.len as usize]
+    }
+
+    /// Decodes the payload as a raw SBUS frame, failing with [`SbusError::InvalidLength`] if it
+    /// isn't [`SBUS_FRAME_LENGTH`] bytes long
+    pub fn packet(&self) -> Result<SbusPacket, SbusError> {
+        let frame: &[u8; SBUS_FRAME_LENGTH] = self
+            .payload()
+            .try_into()
+            .map_err(|_| SbusError::InvalidLength(self.len as usize))?;
+        SbusPacket::from_array(frame)
+    }
+}
+
+/// Unwraps envelopes from bytes pushed in by the caller
+///
+/// Bytes before the sync bytes are skipped. After an envelope with a bad CRC or an oversized
+/// length, the search for the next envelope restarts right after the rejected sync bytes, so
+/// an envelope following a corrupted one is not lost. Hand over whole datagrams with
+/// [`EnvelopeParser::push_bytes`] to get every envelope they complete.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{EnvelopeParser, SbusPacket};
+///
+/// let packet = SbusPacket::neutral();
+/// let mut parser = EnvelopeParser::new();
+/// let mut received = None;
+/// for &byte in &packet.to_envelope() {
+///     received = parser.push_byte(byte).or(received);
+/// }
+/// assert_eq!(received.unwrap().unwrap().packet(), Ok(packet));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct EnvelopeParser {
+    buffer: [u8; ENVELOPE_MAX_PAYLOAD + ENVELOPE_OVERHEAD],
+    len: usize,
+}
+
+impl EnvelopeParser {
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; ENVELOPE_MAX_PAYLOAD + ENVELOPE_OVERHEAD],
+            len: 0,
+        }
+    }
+
+    /// Appends a received byte, returning the envelope it completes
+    ///
+    /// Fails with [`SbusError::InvalidChecksum`] for envelopes with a bad CRC and
+    /// [`SbusError::InvalidLength`] for lengths above [`ENVELOPE_MAX_PAYLOAD`]. A result may
+    /// leave further complete envelopes buffered, e.g. those received while a corrupted length
+    /// was being waited for, so call [`EnvelopeParser::poll`] until it returns `None` after each
+    /// result, or use [`EnvelopeParser::push_bytes`].
+    pub fn push_byte(&mut self, byte: u8) -> Option<Result<Envelope, SbusError>> {
+        self.append(byte);
+        self.poll()
+    }
+
+    /// Appends received bytes, returning an iterator over every envelope and error they complete
+    ///
+    /// The buffered envelopes are drained before each byte is appended, so none is held back
+    /// until more bytes arrive. Bytes not consumed because the iterator was dropped early are
+    /// not appended.
+    pub fn push_bytes<'a>(
+        &'a mut self,
+        bytes: &'a [u8],
+    ) -> impl Iterator<Item = Result<Envelope, SbusError>> + 'a {
+        let mut bytes = bytes.iter();
+        core::iter::from_fn(move || loop {
+            if let Some(result) = self.poll() {
+                return Some(result);
+            }
+            self.append(*bytes.next()?);
+        })
+    }
+
+    /// The next envelope or error already complete in the buffered bytes, without appending a
+    /// byte
+    pub fn poll(&mut self) -> Option<Result<Envelope, SbusError>> {
+        // Skip to the next possible start of an envelope
+        let start = (0..self.len)
+            .find(|&i| {
+                self.buffer[i..self.len]
+                    .iter()
+                    .zip(&ENVELOPE_SYNC)
+                    .all(|(a, b)| a == b)
+            })
+            .unwrap_or(self.len);
+        self.discard(start);
+        if self.len < 3 {
+            return None;
+        }
+
+        let payload_len = self.buffer[2] as usize;
+        if payload_len > ENVELOPE_MAX_PAYLOAD {
+            self.discard(1);
+            return Some(Err(SbusError::InvalidLength(payload_len)));
+        }
+        let len = payload_len + ENVELOPE_OVERHEAD;
+        if self.len < len {
+            return None;
+        }
+
+        let expected = crc16_ccitt(&self.buffer[2..len - 2]);
+        let received = u16::from_le_bytes([self.buffer[len - 2], self.buffer[len - 1]]);
+        if expected != received {
+            self.discard(1);
+            return Some(Err(SbusError::InvalidChecksum { expected, received }));
+        }
+
+        let mut envelope = Envelope {
+            payload: [0; ENVELOPE_MAX_PAYLOAD],
+            len: payload_len as u8,
+        };
+        envelope.payload[..payload_len].copy_from_slice(&self.buffer[3..len - 2]);
+        self.discard(len);
+        Some(Ok(envelope))
+    }
+
+    /// Discards a partly received envelope
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Appends a byte, dropping the oldest buffered byte if the buffer is full
+    fn append(&mut self, byte: u8) {
+        if self.len == self.buffer.len() {
+            self.discard(1);
+        }
+        self.buffer[self.len] = byte;
+        self.len += 1;
+    }
+
+    fn discard(&mut self, count: usize) {
+        self.buffer.copy_within(count..self.len, 0);
+        self.len -= count;
+    }
+}
+
+impl Default for EnvelopeParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Channel;
+
+    fn parse(parser: &mut EnvelopeParser, bytes: &[u8]) -> Vec<Result<Envelope, SbusError>> {
+        bytes
+            .iter()
+            .filter_map(|&byte| parser.push_byte(byte))
+            .collect()
+    }
+
+    #[test]
+    fn test_crc() {
+        // The check value of CRC-16/CCITT-FALSE
+        assert_eq!(crc16_ccitt(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let mut out = [0u8; 16];
+        assert_eq!(wrap_envelope(&[1, 2, 3], &mut out), Ok(8));
+        assert_eq!(out[..3], [0xA5, 0x5A, 3]);
+        assert_eq!(
+            wrap_envelope(&[0; 12], &mut out),
+            Err(SbusError::InvalidLength(12))
+        );
+        assert_eq!(
+            wrap_envelope(&[0; 65], &mut [0; 128]),
+            Err(SbusError::InvalidLength(65))
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch5] = 1234;
+        let mut bytes = vec![0x00, 0xA5, 0x11];
+        bytes.extend_from_slice(&packet.to_envelope());
+        bytes.extend_from_slice(&SbusPacket::neutral().to_envelope());
+
+        let mut parser = EnvelopeParser::new();
+        let envelopes = parse(&mut parser, &bytes);
+        assert_eq!(envelopes.len(), 2);
+        assert_eq!(envelopes[0].as_ref().unwrap().packet(), Ok(packet));
+        assert_eq!(
+            envelopes[1].as_ref().unwrap().packet(),
+            Ok(SbusPacket::neutral())
+        );
+    }
+
+    #[test]
+    fn test_corruption() {
+        let mut corrupted = SbusPacket::neutral().to_envelope();
+        corrupted[10] ^= 0x40;
+        let mut out = [0u8; 16];
+        let len = wrap_envelope(&[7, 8], &mut out).unwrap();
+
+        let mut bytes = corrupted.to_vec();
+        bytes.extend_from_slice(&out[..len]);
+        let mut parser = EnvelopeParser::new();
+        let results = parse(&mut parser, &bytes);
+        assert!(matches!(results[0], Err(SbusError::InvalidChecksum { .. })));
+        assert_eq!(results[1].as_ref().unwrap().payload(), [7, 8]);
+        assert_eq!(
+            results[1].as_ref().unwrap().packet(),
+            Err(SbusError::InvalidLength(2))
+        );
+
+        // An oversized length is skipped as well
+        let results = parse(&mut parser, &[0xA5, 0x5A, 200, 0xA5, 0x5A, 0]);
+        assert_eq!(results, [Err(SbusError::InvalidLength(200))]);
+        let crc = crc16_ccitt(&[0]).to_le_bytes();
+        assert_eq!(parse(&mut parser, &crc)[0].as_ref().unwrap().payload(), [0u8; 0]);
+    }
+
+    #[test]
+    fn test_buffered_envelopes_after_error() {
+        // A corrupted length makes the parser wait for bytes that hold the following envelopes
+        let mut corrupted = SbusPacket::neutral().to_envelope();
+        corrupted[2] = ENVELOPE_MAX_PAYLOAD as u8;
+        let mut bytes = corrupted.to_vec();
+        for _ in 0..5 {
+            bytes.extend_from_slice(&SbusPacket::neutral().to_envelope());
+        }
+
+        // Byte by byte, the envelopes left buffered after the error are polled out
+        let mut parser = EnvelopeParser::new();
+        let mut results = Vec::new();
+        for &byte in &bytes {
+            if let Some(result) = parser.push_byte(byte) {
+                results.push(result);
+                results.extend(core::iter::from_fn(|| parser.poll()));
+            }
+        }
+        assert!(matches!(results[0], Err(SbusError::InvalidChecksum { .. })));
+        assert_eq!(results[1..].len(), 5);
+        assert!(results[1..]
+            .iter()
+            .all(|result| result.as_ref().unwrap().packet() == Ok(SbusPacket::neutral())));
+
+        let mut parser = EnvelopeParser::new();
+        let batched: Vec<_> = parser.push_bytes(&bytes).collect();
+        assert_eq!(batched, results);
+        assert_eq!(parser.poll(), None);
+    }
+}
@@ -1,11 +1,375 @@
+use core::fmt;
+
+#[cfg(feature = "error-context")]
+use crate::SBUS_FRAME_LENGTH;
+
 /// Error types for SBUS operations
+///
+/// `E` is the error type of the underlying reader. Errors that can only come from
+/// decoding a frame, such as those returned by [`SbusPacket::from_array`](crate::SbusPacket::from_array),
+/// use the default `()`.
+///
+/// New variants may be added, use [`is_fatal`](Self::is_fatal) and
+/// [`is_frame_error`](Self::is_frame_error) to decide how to recover from errors not matched
+/// explicitly.
 #[derive(Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
-pub enum SbusError {
+#[non_exhaustive]
+pub enum SbusError<E = ()> {
     /// Error reading from the reader
+    #[deprecated(note = "reader errors are reported as `SbusError::Read`")]
     ReadError,
+    /// Error returned by the underlying reader
+    Read(E),
+    /// Error returned by the underlying writer
+    Write(E),
+    /// Error returned by the underlying reader, or the writer if `write` is set, whose value has
+    /// no counterpart of the error type, as for errors read back from a log and converted with
+    /// [`SbusError::with_reader_error`]
+    Io { write: bool },
+    /// The reader reached end of stream before a complete frame was read
+    UnexpectedEof,
     /// Invalid header
+    ///
+    /// The parsers keep the rejected frame, see
+    /// [`Deframer::last_rejected_frame`](crate::Deframer::last_rejected_frame).
     InvalidHeader(u8),
     /// Invalid footer
+    ///
+    /// The parsers keep the rejected frame, see
+    /// [`Deframer::last_rejected_frame`](crate::Deframer::last_rejected_frame).
     InvalidFooter(u8),
+    /// No valid frame arrived within the timeout
+    Timeout,
+    /// Reserved bits of the flag byte are set, see [`FLAG_MASK`](crate::FLAG_MASK)
+    InvalidFlagByte(u8),
+    /// A channel value doesn't fit in the 11 bits of an SBUS channel
+    OutOfRange { channel: usize, value: u16 },
+    /// A channel index is not less than [`CHANNEL_COUNT`](crate::CHANNEL_COUNT)
+    InvalidChannel(usize),
+    /// A raw frame is not [`SBUS_FRAME_LENGTH`](crate::SBUS_FRAME_LENGTH) bytes long
+    InvalidLength(usize),
+    /// More than the allowed number of bytes were discarded while searching for a frame, as on
+    /// a dead link or one at the wrong baud rate
+    DesyncLimitExceeded(usize),
+    /// The checksum of a frame of a protocol that has one, such as CRSF or IBUS, doesn't match
+    /// its contents
+    InvalidChecksum { expected: u16, received: u16 },
+}
+
+/// The raw bytes of a rejected frame, kept to debug link corruption from logs
+///
+/// Frames are only captured with the `error-context` feature, otherwise captures are empty and
+/// take no space.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameCapture {
+    #[cfg(feature = "error-context")]
+    frame: [u8; SBUS_FRAME_LENGTH],
+    #[cfg(feature = "error-context")]
+    len: u8,
+}
+
+impl FrameCapture {
+    /// A capture without any bytes
+    pub const EMPTY: Self = Self {
+        #[cfg(feature = "error-context")]
+        frame: [0; SBUS_FRAME_LENGTH],
+        #[cfg(feature = "error-context")]
+        len: 0,
+    };
+
+    /// Captures up to [`SBUS_FRAME_LENGTH`](crate::SBUS_FRAME_LENGTH) bytes from the start of `frame`
+    #[cfg_attr(not(feature = "error-context"), allow(unused_variables))]
+    pub const fn new(frame: &[u8]) -> Self {
+        #[allow(unused_mut)]
+        let mut capture = Self::EMPTY;
+        #[cfg(feature = "error-context")]
+        {
+            while (capture.len as usize) < frame.len() && (capture.len as usize) < SBUS_FRAME_LENGTH
+            {
+                capture.frame[capture.len as usize] = frame[capture.len as usize];
+                capture.len += 1;
+            }
+        }
+        capture
+    }
+
+    /// The captured bytes, empty without the `error-context` feature
+    pub fn bytes(&self) -> &[u8] {
+        #[cfg(feature = "error-context")]
+        {
+            &self.frame[..self.len as usize]
+        }
+        #[cfg(not(feature = "error-context"))]
+        {
+            &[]
+        }
+    }
+
+    /// Whether no bytes were captured
+    pub fn is_empty(&self) -> bool {
+        self.bytes().is_empty()
+    }
+}
+
+impl Default for FrameCapture {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl fmt::Debug for FrameCapture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FrameCapture({:02X?})", self.bytes())
+    }
+}
+
+#[cfg(feature = "defmt-03")]
+impl defmt::Format for FrameCapture {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "FrameCapture({=[u8]:X})", self.bytes())
+    }
+}
+
+impl SbusError {
+    /// Converts a frame decoding error into one that can also carry reader errors of type `E`
+    ///
+    /// Reader and writer errors without a value, as read back from a log, have no counterpart
+    /// of type `E` and become [`SbusError::Io`].
+    #[allow(deprecated)]
+    pub fn with_reader_error<E>(self) -> SbusError<E> {
+        match self {
+            SbusError::ReadError | SbusError::Read(()) => SbusError::Io { write: false },
+            SbusError::Write(()) => SbusError::Io { write: true },
+            SbusError::Io { write } => SbusError::Io { write },
+            SbusError::UnexpectedEof => SbusError::UnexpectedEof,
+            SbusError::InvalidHeader(header) => SbusError::InvalidHeader(header),
+            SbusError::InvalidFooter(footer) => SbusError::InvalidFooter(footer),
+            SbusError::Timeout => SbusError::Timeout,
+            SbusError::InvalidFlagByte(flags) => SbusError::InvalidFlagByte(flags),
+            SbusError::OutOfRange { channel, value } => SbusError::OutOfRange { channel, value },
+            SbusError::InvalidChannel(channel) => SbusError::InvalidChannel(channel),
+            SbusError::InvalidLength(len) => SbusError::InvalidLength(len),
+            SbusError::DesyncLimitExceeded(skipped) => SbusError::DesyncLimitExceeded(skipped),
+            SbusError::InvalidChecksum { expected, received } => {
+                SbusError::InvalidChecksum { expected, received }
+            }
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl<E> fmt::Display for SbusError<E>
+where
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SbusError::ReadError => write!(f, "failed to read from the reader"),
+            SbusError::Read(err) => write!(f, "reader error: {err:?}"),
+            SbusError::Write(err) => write!(f, "writer error: {err:?}"),
+            SbusError::Io { write: false } => write!(f, "failed to read from the reader"),
+            SbusError::Io { write: true } => write!(f, "failed to write to the writer"),
+            SbusError::UnexpectedEof => write!(f, "stream ended before a complete frame"),
+            SbusError::InvalidHeader(header) => write!(
+                f,
+                "invalid frame header 0x{header:02X}, expected 0x{:02X}",
+                crate::SBUS_HEADER
+            ),
+            SbusError::InvalidFooter(footer) => write!(f, "invalid frame footer 0x{footer:02X}"),
+            SbusError::Timeout => write!(f, "no valid frame arrived within the timeout"),
+            SbusError::InvalidFlagByte(flags) => {
+                write!(f, "reserved bits set in flag byte 0x{flags:02X}")
+            }
+            SbusError::OutOfRange { channel, value } => write!(
+                f,
+                "value {value} of channel {channel} exceeds the maximum of {}",
+                crate::CHANNEL_MAX
+            ),
+            SbusError::InvalidChannel(channel) => write!(
+                f,
+                "channel index {channel} is out of range for {} channels",
+                crate::CHANNEL_COUNT
+            ),
+            SbusError::InvalidLength(len) => write!(
+                f,
+                "frame is {len} bytes long, expected {}",
+                crate::SBUS_FRAME_LENGTH
+            ),
+            SbusError::DesyncLimitExceeded(skipped) => {
+                write!(f, "no frame found after discarding {skipped} bytes")
+            }
+            SbusError::InvalidChecksum { expected, received } => write!(
+                f,
+                "frame checksum 0x{received:04X} doesn't match the expected 0x{expected:04X}"
+            ),
+        }
+    }
+}
+
+// Written out so the deprecated variant can be matched without a warning
+#[cfg(feature = "defmt-03")]
+#[allow(deprecated)]
+impl<E> defmt::Format for SbusError<E>
+where
+    E: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            SbusError::ReadError => defmt::write!(f, "ReadError"),
+            SbusError::Read(err) => defmt::write!(f, "Read({})", err),
+            SbusError::Write(err) => defmt::write!(f, "Write({})", err),
+            SbusError::Io { write } => defmt::write!(f, "Io {{ write: {} }}", write),
+            SbusError::UnexpectedEof => defmt::write!(f, "UnexpectedEof"),
+            SbusError::InvalidHeader(header) => defmt::write!(f, "InvalidHeader({=u8:#X})", header),
+            SbusError::InvalidFooter(footer) => defmt::write!(f, "InvalidFooter({=u8:#X})", footer),
+            SbusError::Timeout => defmt::write!(f, "Timeout"),
+            SbusError::InvalidFlagByte(flags) => {
+                defmt::write!(f, "InvalidFlagByte({=u8:#X})", flags)
+            }
+            SbusError::OutOfRange { channel, value } => {
+                defmt::write!(f, "OutOfRange {{ channel: {}, value: {} }}", channel, value)
+            }
+            SbusError::InvalidChannel(channel) => defmt::write!(f, "InvalidChannel({})", channel),
+            SbusError::InvalidLength(len) => defmt::write!(f, "InvalidLength({})", len),
+            SbusError::DesyncLimitExceeded(skipped) => {
+                defmt::write!(f, "DesyncLimitExceeded({})", skipped)
+            }
+            SbusError::InvalidChecksum { expected, received } => defmt::write!(
+                f,
+                "InvalidChecksum {{ expected: {=u16:#X}, received: {=u16:#X} }}",
+                expected,
+                received
+            ),
+        }
+    }
+}
+
+/// Also `std::error::Error`, which is the same trait
+impl<E> core::error::Error for SbusError<E> where E: fmt::Debug {}
+
+impl<E> SbusError<E> {
+    /// Whether reading can't continue after this error, as opposed to a single bad frame
+    ///
+    /// Fatal errors come from the reader, the writer or a link that stopped delivering frames,
+    /// the read loops of the proxies and of [`SbusPipeline`](crate::SbusPipeline) stop on them.
+    #[allow(deprecated)]
+    pub const fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            SbusError::ReadError
+                | SbusError::Read(_)
+                | SbusError::Write(_)
+                | SbusError::Io { .. }
+                | SbusError::UnexpectedEof
+                | SbusError::Timeout
+                | SbusError::DesyncLimitExceeded(_)
+        )
+    }
+
+    /// Whether the error rejects a single received frame, after which the next frame may be
+    /// read
+    pub const fn is_frame_error(&self) -> bool {
+        matches!(
+            self,
+            SbusError::InvalidHeader(_)
+                | SbusError::InvalidFooter(_)
+                | SbusError::InvalidFlagByte(_)
+                | SbusError::InvalidLength(_)
+                | SbusError::InvalidChecksum { .. }
+        )
+    }
+}
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+impl<E> From<embedded_io::ReadExactError<E>> for SbusError<E> {
+    fn from(err: embedded_io::ReadExactError<E>) -> Self {
+        match err {
+            embedded_io::ReadExactError::UnexpectedEof => SbusError::UnexpectedEof,
+            embedded_io::ReadExactError::Other(err) => SbusError::Read(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = SbusError::<()>::InvalidHeader(0x0E);
+        assert_eq!(err.to_string(), "invalid frame header 0x0E, expected 0x0F");
+        assert_eq!(
+            SbusError::<()>::OutOfRange {
+                channel: 3,
+                value: 4000
+            }
+            .to_string(),
+            "value 4000 of channel 3 exceeds the maximum of 2047"
+        );
+        assert_eq!(
+            SbusError::<()>::InvalidChecksum {
+                expected: 0x1234,
+                received: 0x0042
+            }
+            .to_string(),
+            "frame checksum 0x0042 doesn't match the expected 0x1234"
+        );
+        assert_eq!(
+            SbusError::Read(std::io::ErrorKind::TimedOut).to_string(),
+            "reader error: TimedOut"
+        );
+    }
+
+    #[test]
+    fn test_classification() {
+        let frame_errors = [
+            SbusError::<()>::InvalidFooter(0x55),
+            SbusError::InvalidFlagByte(0xF0),
+            SbusError::InvalidLength(24),
+        ];
+        for err in frame_errors {
+            assert!(err.is_frame_error() && !err.is_fatal(), "{err}");
+        }
+
+        let fatal = [
+            SbusError::<()>::UnexpectedEof,
+            SbusError::Timeout,
+            SbusError::DesyncLimitExceeded(1000),
+        ];
+        for err in fatal {
+            assert!(err.is_fatal() && !err.is_frame_error(), "{err}");
+        }
+
+        let usage = SbusError::<()>::InvalidChannel(16);
+        assert!(!usage.is_fatal() && !usage.is_frame_error());
+    }
+
+    #[test]
+    fn test_with_reader_error() {
+        assert_eq!(
+            SbusError::Write(()).with_reader_error::<std::io::ErrorKind>(),
+            SbusError::Io { write: true }
+        );
+        assert_eq!(
+            SbusError::Read(()).with_reader_error::<std::io::ErrorKind>(),
+            SbusError::Io { write: false }
+        );
+        assert_eq!(
+            SbusError::InvalidFlagByte(0xF0).with_reader_error::<std::io::ErrorKind>(),
+            SbusError::InvalidFlagByte(0xF0)
+        );
+        assert!(SbusError::<()>::Io { write: true }.is_fatal());
+    }
+
+    #[test]
+    fn test_error_trait() {
+        fn parse() -> Result<(), Box<dyn std::error::Error>> {
+            Err(SbusError::<()>::InvalidLength(3))?;
+            Ok(())
+        }
+        assert_eq!(
+            parse().unwrap_err().to_string(),
+            "frame is 3 bytes long, expected 25"
+        );
+    }
 }
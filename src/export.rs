@@ -0,0 +1,234 @@
+//! Exporting packet streams for plotting and analysis
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crate::{SbusPacket, TimestampedPacket};
+
+/// Writes packets as CSV, one row per packet
+///
+/// The first row holds the column names: `timestamp_us`, `ch1` to `ch16`, the digital channels
+/// `ch17` and `ch18`, then `frame_lost` and `failsafe`. Flags are written as `0` or `1`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use sbus_rs::{CsvExporter, SbusPacket};
+///
+/// let mut csv = CsvExporter::new(Vec::new());
+/// csv.write_packet(&SbusPacket::neutral(), Duration::from_millis(7)).unwrap();
+///
+/// let output = String::from_utf8(csv.finish().unwrap()).unwrap();
+/// assert!(output.starts_with("timestamp_us,ch1,ch2,"));
+/// assert!(output.ends_with("\n7000,992,992,992,992,992,992,992,992,992,992,992,992,992,992,992,992,0,0,0,0\n"));
+/// ```
+#[derive(Debug)]
+pub struct CsvExporter<W>
+where
+    W: Write,
+{
+    writer: W,
+    header_written: bool,
+}
+
+impl<W> CsvExporter<W>
+where
+    W: Write,
+{
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            header_written: false,
+        }
+    }
+
+    /// Writes the row of a packet received at `timestamp`, preceded by the header row on the
+    /// first call
+    pub fn write_packet(&mut self, packet: &SbusPacket, timestamp: Duration) -> io::Result<()> {
+        if !self.header_written {
+            write!(self.writer, "timestamp_us")?;
+            for channel in 1..=18 {
+                write!(self.writer, ",ch{channel}")?;
+            }
+            writeln!(self.writer, ",frame_lost,failsafe")?;
+            self.header_written = true;
+        }
+
+        write!(self.writer, "{}", timestamp.as_micros())?;
+        for value in packet.channels {
+            write!(self.writer, ",{value}")?;
+        }
+        let flags = packet.flags;
+        writeln!(
+            self.writer,
+            ",{},{},{},{}",
+            flags.d1 as u8, flags.d2 as u8, flags.frame_lost as u8, flags.failsafe as u8
+        )
+    }
+
+    /// Writes the row of a timestamped packet
+    pub fn write(&mut self, stamped: &TimestampedPacket) -> io::Result<()> {
+        self.write_packet(&stamped.packet, stamped.received_at)
+    }
+
+    /// Flushes the output and returns the writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Writes packets in the InfluxDB line protocol, one line per packet
+///
+/// Every line holds the integer fields `ch1` to `ch16` and the boolean fields `ch17`, `ch18`,
+/// `frame_lost` and `failsafe`, with the timestamp in nanoseconds. The measurement is named
+/// `sbus` unless changed with [`with_measurement`](Self::with_measurement).
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use sbus_rs::{LineProtocolExporter, SbusPacket};
+///
+/// let mut influx = LineProtocolExporter::new(Vec::new()).with_tag("model", "glider 2");
+/// influx.write_packet(&SbusPacket::neutral(), Duration::from_millis(7)).unwrap();
+///
+/// let output = String::from_utf8(influx.finish().unwrap()).unwrap();
+/// assert!(output.starts_with("sbus,model=glider\\ 2 ch1=992i,ch2=992i,"));
+/// assert!(output.ends_with(",failsafe=false 7000000\n"));
+/// ```
+#[derive(Debug)]
+pub struct LineProtocolExporter<W>
+where
+    W: Write,
+{
+    writer: W,
+    /// The measurement followed by the tags, escaped
+    series: String,
+}
+
+impl<W> LineProtocolExporter<W>
+where
+    W: Write,
+{
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            series: String::from("sbus"),
+        }
+    }
+
+    /// Sets the name of the measurement, resetting the tags
+    pub fn with_measurement(mut self, measurement: &str) -> Self {
+        self.series.clear();
+        escape_into(&mut self.series, measurement, &[',', ' ']);
+        self
+    }
+
+    /// Adds a tag to every line, e.g. to tell apart receivers or sessions
+    pub fn with_tag(mut self, key: &str, value: &str) -> Self {
+        self.series.push(',');
+        escape_into(&mut self.series, key, &[',', '=', ' ']);
+        self.series.push('=');
+        escape_into(&mut self.series, value, &[',', '=', ' ']);
+        self
+    }
+
+    /// Writes the line of a packet received at `timestamp`
+    pub fn write_packet(&mut self, packet: &SbusPacket, timestamp: Duration) -> io::Result<()> {
+        write!(self.writer, "{} ", self.series)?;
+        for (i, value) in packet.channels.iter().enumerate() {
+            write!(self.writer, "ch{}={value}i,", i + 1)?;
+        }
+        let flags = packet.flags;
+        writeln!(
+            self.writer,
+            "ch17={},ch18={},frame_lost={},failsafe={} {}",
+            flags.d1,
+            flags.d2,
+            flags.frame_lost,
+            flags.failsafe,
+            timestamp.as_nanos()
+        )
+    }
+
+    /// Writes the line of a timestamped packet
+    pub fn write(&mut self, stamped: &TimestampedPacket) -> io::Result<()> {
+        self.write_packet(&stamped.packet, stamped.received_at)
+    }
+
+    /// Flushes the output and returns the writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Appends `text` with a backslash before each of the `special` characters
+fn escape_into(output: &mut String, text: &str, special: &[char]) {
+    for c in text.chars() {
+        if special.contains(&c) {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+}
+
+/// Exports a whole stream, e.g. a replayed recording, as CSV
+pub fn export_csv<W, I>(writer: W, packets: I) -> io::Result<W>
+where
+    W: Write,
+    I: IntoIterator<Item = TimestampedPacket>,
+{
+    let mut csv = CsvExporter::new(writer);
+    for stamped in packets {
+        csv.write(&stamped)?;
+    }
+    csv.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamped(ms: u64) -> TimestampedPacket {
+        let mut packet = SbusPacket::neutral();
+        packet.channels[0] = 172;
+        packet.flags.d2 = true;
+        packet.flags.failsafe = true;
+        TimestampedPacket {
+            packet,
+            received_at: Duration::from_millis(ms),
+            gap: None,
+        }
+    }
+
+    #[test]
+    fn test_csv() {
+        let output = export_csv(Vec::new(), [stamped(1), stamped(8)]).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].split(',').count(), 21);
+        assert!(lines[0].ends_with(",ch18,frame_lost,failsafe"));
+        assert!(lines[1].starts_with("1000,172,992,"));
+        assert!(lines[2].starts_with("8000,"));
+        assert!(lines[2].ends_with(",992,0,1,0,1"));
+    }
+
+    #[test]
+    fn test_line_protocol() {
+        let mut influx = LineProtocolExporter::new(Vec::new())
+            .with_measurement("rc link")
+            .with_tag("rx", "a=b,c");
+        influx.write(&stamped(2)).unwrap();
+        let output = String::from_utf8(influx.finish().unwrap()).unwrap();
+
+        assert!(output.starts_with("rc\\ link,rx=a\\=b\\,c ch1=172i,ch2=992i,"));
+        assert!(output.contains(",ch16=992i,ch17=false,ch18=true,frame_lost=false,failsafe=true "));
+        assert!(output.ends_with(" 2000000\n"));
+        assert_eq!(output.lines().count(), 1);
+    }
+}
@@ -0,0 +1,223 @@
+use core::time::Duration;
+
+use crate::{SbusPacket, CHANNEL_COUNT};
+
+/// What a single channel should output while failsafe is active
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FailsafeChannel {
+    /// Keep the value from the last good packet
+    Hold,
+    /// Output a fixed value
+    Value(u16),
+}
+
+/// What the [`FailsafeHandler`] outputs while failsafe is active
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FailsafePolicy {
+    /// Keep all channels at their values from the last good packet
+    HoldLast,
+    /// Output fixed channel values
+    Preset([u16; CHANNEL_COUNT]),
+    /// Decide per channel whether to hold or output a fixed value
+    PerChannel([FailsafeChannel; CHANNEL_COUNT]),
+}
+
+impl FailsafePolicy {
+    /// Output all channels centred, see [`SbusPacket::neutral`]
+    pub const fn neutral() -> Self {
+        Self::Preset(SbusPacket::neutral().channels)
+    }
+
+    /// Output all channels centred with the throttle low, see
+    /// [`SbusPacket::neutral_throttle_low`]
+    pub const fn neutral_throttle_low(throttle: usize) -> Self {
+        Self::Preset(SbusPacket::neutral_throttle_low(throttle).channels)
+    }
+}
+
+/// Substitutes packets according to a [`FailsafePolicy`] when the link is lost
+///
+/// Failsafe becomes active when a packet arrives with the `failsafe` flag set, or when no packet
+/// has arrived within the configured timeout. Substituted packets always have the `failsafe`
+/// flag set so downstream consumers can tell them apart. Failsafe ends with the next packet that
+/// does not have the flag set.
+///
+/// Timestamps are measured from any fixed epoch, e.g. the boot time of the system.
+///
+/// # Example
+///
+/// ```rust
+/// use core::time::Duration;
+/// use sbus_rs::{FailsafeHandler, FailsafePolicy};
+///
+/// let mut failsafe = FailsafeHandler::new(FailsafePolicy::HoldLast, Duration::from_millis(100));
+///
+/// // In the control loop, when no frame was read:
+/// if let Some(packet) = failsafe.poll(Duration::from_millis(250)) {
+///     // use the substitute packet
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct FailsafeHandler {
+    policy: FailsafePolicy,
+    timeout: Duration,
+    last_good: Option<SbusPacket>,
+    last_received_at: Option<Duration>,
+    active: bool,
+}
+
+impl FailsafeHandler {
+    pub const fn new(policy: FailsafePolicy, timeout: Duration) -> Self {
+        Self {
+            policy,
+            timeout,
+            last_good: None,
+            last_received_at: None,
+            active: false,
+        }
+    }
+
+    /// Processes a packet received at `now` and returns the packet to act on
+    ///
+    /// If the packet has its `failsafe` flag set it is replaced according to the policy. With
+    /// [`FailsafePolicy::HoldLast`] and no good packet seen yet, the packet is passed through.
+    pub fn process(&mut self, packet: SbusPacket, now: Duration) -> SbusPacket {
+        self.last_received_at = Some(now);
+        if packet.flags.failsafe {
+            self.active = true;
+            self.substitute().unwrap_or(packet)
+        } else {
+            self.active = false;
+            self.last_good = Some(packet);
+            packet
+        }
+    }
+
+    /// Checks for a timeout at `now`, returning a substitute packet while failsafe is active
+    ///
+    /// Call this whenever no packet was received, e.g. on every control loop iteration.
+    pub fn poll(&mut self, now: Duration) -> Option<SbusPacket> {
+        let timed_out = match self.last_received_at {
+            Some(last) => now.saturating_sub(last) > self.timeout,
+            None => true,
+        };
+        if timed_out {
+            self.active = true;
+        }
+        if self.active {
+            self.substitute()
+        } else {
+            None
+        }
+    }
+
+    /// Whether failsafe is currently active
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// The last packet received without the `failsafe` flag
+    pub fn last_good(&self) -> Option<&SbusPacket> {
+        self.last_good.as_ref()
+    }
+
+    pub fn policy(&self) -> &FailsafePolicy {
+        &self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: FailsafePolicy) {
+        self.policy = policy;
+    }
+
+    /// The packet to output while failsafe is active, if the policy can produce one
+    pub fn substitute(&self) -> Option<SbusPacket> {
+        let mut packet = match &self.policy {
+            FailsafePolicy::HoldLast => self.last_good?,
+            FailsafePolicy::Preset(channels) => SbusPacket {
+                channels: *channels,
+                flags: self
+                    .last_good
+                    .map(|packet| packet.flags)
+                    .unwrap_or_default(),
+            },
+            FailsafePolicy::PerChannel(channels) => {
+                let mut packet = self.last_good?;
+                for (value, channel) in packet.channels.iter_mut().zip(channels) {
+                    if let FailsafeChannel::Value(preset) = channel {
+                        *value = *preset;
+                    }
+                }
+                packet
+            }
+        };
+        packet.flags.failsafe = true;
+        Some(packet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::packet_with_flags, Flags};
+
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    fn packet(value: u16, failsafe: bool) -> SbusPacket {
+        packet_with_flags(
+            value,
+            Flags {
+                failsafe,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_hold_last() {
+        let mut handler = FailsafeHandler::new(FailsafePolicy::HoldLast, TIMEOUT);
+        assert_eq!(handler.poll(Duration::ZERO), None);
+
+        let good = packet(1200, false);
+        assert_eq!(handler.process(good, Duration::ZERO), good);
+        assert!(!handler.is_active());
+
+        let output = handler.process(packet(0, true), Duration::from_millis(10));
+        assert!(handler.is_active());
+        assert_eq!(output.channels, good.channels);
+        assert!(output.flags.failsafe);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut handler = FailsafeHandler::new(FailsafePolicy::Preset([172; 16]), TIMEOUT);
+        handler.process(packet(1500, false), Duration::ZERO);
+
+        assert_eq!(handler.poll(Duration::from_millis(50)), None);
+        let output = handler.poll(Duration::from_millis(150)).unwrap();
+        assert_eq!(output.channels, [172; 16]);
+        assert!(output.flags.failsafe);
+
+        handler.process(packet(1500, false), Duration::from_millis(160));
+        assert!(!handler.is_active());
+        assert_eq!(handler.poll(Duration::from_millis(170)), None);
+    }
+
+    #[test]
+    fn test_per_channel() {
+        let mut channels = [FailsafeChannel::Hold; CHANNEL_COUNT];
+        channels[2] = FailsafeChannel::Value(172);
+        let mut handler = FailsafeHandler::new(FailsafePolicy::PerChannel(channels), TIMEOUT);
+
+        handler.process(packet(1500, false), Duration::ZERO);
+        let output = handler.process(packet(0, true), Duration::from_millis(10));
+
+        let mut expected = [1500; CHANNEL_COUNT];
+        expected[2] = 172;
+        assert_eq!(output.channels, expected);
+    }
+}
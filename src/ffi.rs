@@ -0,0 +1,307 @@
+//! `extern "C"` functions for C firmware adopting the parser
+//!
+//! The types are `#[repr(C)]` and the functions use plain pointers, so a header can be
+//! generated with `cbindgen` using the `cbindgen.toml` at the root of the repository. Link the
+//! crate as a static library, e.g. from a wrapper crate with `crate-type = ["staticlib"]` that
+//! provides the panic handler of the firmware.
+use core::mem::{align_of, size_of};
+use core::{ptr, slice};
+
+use crate::{Deframer, Flags, FrameKind, SbusError, SbusPacket, CHANNEL_COUNT, SBUS_FRAME_LENGTH};
+
+/// Size in 64-bit words of the storage of a [`SbusFfiDeframer`]
+pub const SBUS_FFI_DEFRAMER_WORDS: usize = 32;
+
+const _: () = assert!(size_of::<Deframer>() <= SBUS_FFI_DEFRAMER_WORDS * size_of::<u64>());
+const _: () = assert!(align_of::<Deframer>() <= align_of::<u64>());
+
+/// The result of an FFI call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum SbusFfiStatus {
+    Ok = 0,
+    /// No complete frame has been fed to the deframer yet
+    NoFrame = 1,
+    /// A pointer argument was null
+    NullPointer = -1,
+    InvalidHeader = -2,
+    InvalidFooter = -3,
+    InvalidFlagByte = -4,
+    /// A channel value doesn't fit in 11 bits
+    OutOfRange = -5,
+    /// Any other error
+    Other = -100,
+}
+
+impl<E> From<SbusError<E>> for SbusFfiStatus {
+    fn from(error: SbusError<E>) -> Self {
+        match error {
+            SbusError::InvalidHeader(_) => SbusFfiStatus::InvalidHeader,
+            SbusError::InvalidFooter(_) => SbusFfiStatus::InvalidFooter,
+            SbusError::InvalidFlagByte(_) => SbusFfiStatus::InvalidFlagByte,
+            SbusError::OutOfRange { .. } => SbusFfiStatus::OutOfRange,
+            _ => SbusFfiStatus::Other,
+        }
+    }
+}
+
+/// A decoded packet with a stable C layout
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SbusFfiPacket {
+    pub channels: [u16; CHANNEL_COUNT],
+    pub d1: bool,
+    pub d2: bool,
+    pub failsafe: bool,
+    pub frame_lost: bool,
+    /// The footer of the frame, `0x00` for SBUS and `0x04`, `0x14`, `0x24` or `0x34` for SBUS2
+    pub footer: u8,
+}
+
+impl SbusFfiPacket {
+    fn with_kind(packet: SbusPacket, kind: FrameKind) -> Self {
+        Self {
+            channels: packet.channels,
+            d1: packet.flags.d1,
+            d2: packet.flags.d2,
+            failsafe: packet.flags.failsafe,
+            frame_lost: packet.flags.frame_lost,
+            footer: kind.footer(),
+        }
+    }
+
+    /// Unknown footers are sent as plain SBUS frames
+    fn kind(&self) -> FrameKind {
+        FrameKind::from_footer(self.footer).unwrap_or_default()
+    }
+}
+
+impl From<SbusPacket> for SbusFfiPacket {
+    fn from(packet: SbusPacket) -> Self {
+        Self::with_kind(packet, FrameKind::Sbus1)
+    }
+}
+
+impl From<SbusFfiPacket> for SbusPacket {
+    fn from(packet: SbusFfiPacket) -> Self {
+        let flags = Flags {
+            d1: packet.d1,
+            d2: packet.d2,
+            failsafe: packet.failsafe,
+            frame_lost: packet.frame_lost,
+        };
+        Self::new(packet.channels, flags)
+    }
+}
+
+/// Storage for a [`Deframer`], allocated by C code and set up with [`sbus_deframer_init`]
+#[repr(C)]
+pub struct SbusFfiDeframer {
+    storage: [u64; SBUS_FFI_DEFRAMER_WORDS],
+}
+
+impl SbusFfiDeframer {
+    fn deframer(&mut self) -> &mut Deframer {
+        // Sizes and alignments are checked at compile time, and the storage holds a deframer
+        // once `sbus_deframer_init` was called
+        unsafe { &mut *(self.storage.as_mut_ptr() as *mut Deframer) }
+    }
+}
+
+/// Sets up a deframer, also discarding any bytes fed to it before
+///
+/// # Safety
+///
+/// `deframer` must be null or point to writable storage for a [`SbusFfiDeframer`].
+#[no_mangle]
+pub unsafe extern "C" fn sbus_deframer_init(deframer: *mut SbusFfiDeframer) -> SbusFfiStatus {
+    if deframer.is_null() {
+        return SbusFfiStatus::NullPointer;
+    }
+    ptr::write(
+        (*deframer).storage.as_mut_ptr() as *mut Deframer,
+        Deframer::new(),
+    );
+    SbusFfiStatus::Ok
+}
+
+/// Feeds `len` received bytes to a deframer, e.g. from a UART interrupt or DMA buffer
+///
+/// # Safety
+///
+/// `deframer` must be null or point to a deframer set up with [`sbus_deframer_init`], and
+/// `bytes` must be null or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sbus_deframer_feed(
+    deframer: *mut SbusFfiDeframer,
+    bytes: *const u8,
+    len: usize,
+) -> SbusFfiStatus {
+    if deframer.is_null() || (bytes.is_null() && len > 0) {
+        return SbusFfiStatus::NullPointer;
+    }
+    if len > 0 {
+        (*deframer)
+            .deframer()
+            .push_bytes(slice::from_raw_parts(bytes, len));
+    }
+    SbusFfiStatus::Ok
+}
+
+/// Takes the next valid frame from the bytes fed to a deframer
+///
+/// Returns [`SbusFfiStatus::NoFrame`], leaving `packet` untouched, until a complete frame
+/// was fed.
+///
+/// # Safety
+///
+/// `deframer` must be null or point to a deframer set up with [`sbus_deframer_init`], and
+/// `packet` must be null or point to a writable [`SbusFfiPacket`].
+#[no_mangle]
+pub unsafe extern "C" fn sbus_deframer_next(
+    deframer: *mut SbusFfiDeframer,
+    packet: *mut SbusFfiPacket,
+) -> SbusFfiStatus {
+    if deframer.is_null() || packet.is_null() {
+        return SbusFfiStatus::NullPointer;
+    }
+    let deframer = (*deframer).deframer();
+    match deframer.try_parse() {
+        Some(decoded) => {
+            packet.write(SbusFfiPacket::with_kind(
+                decoded,
+                deframer.last_frame_kind(),
+            ));
+            SbusFfiStatus::Ok
+        }
+        None => SbusFfiStatus::NoFrame,
+    }
+}
+
+/// Decodes a raw frame of [`SBUS_FRAME_LENGTH`] bytes
+///
+/// # Safety
+///
+/// `frame` must be null or point to [`SBUS_FRAME_LENGTH`] readable bytes, and `packet` must
+/// be null or point to a writable [`SbusFfiPacket`].
+#[no_mangle]
+pub unsafe extern "C" fn sbus_decode(
+    frame: *const u8,
+    packet: *mut SbusFfiPacket,
+) -> SbusFfiStatus {
+    if frame.is_null() || packet.is_null() {
+        return SbusFfiStatus::NullPointer;
+    }
+    let frame = &*(frame as *const [u8; SBUS_FRAME_LENGTH]);
+    match SbusPacket::from_array_with_kind(frame) {
+        Ok((decoded, kind)) => {
+            packet.write(SbusFfiPacket::with_kind(decoded, kind));
+            SbusFfiStatus::Ok
+        }
+        Err(error) => error.into(),
+    }
+}
+
+/// Encodes a packet into a raw frame of [`SBUS_FRAME_LENGTH`] bytes
+///
+/// Fails with [`SbusFfiStatus::OutOfRange`], leaving `frame` untouched, if a channel value
+/// doesn't fit in 11 bits.
+///
+/// # Safety
+///
+/// `packet` must be null or point to a readable [`SbusFfiPacket`], and `frame` must be null
+/// or point to [`SBUS_FRAME_LENGTH`] writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sbus_encode(
+    packet: *const SbusFfiPacket,
+    frame: *mut u8,
+) -> SbusFfiStatus {
+    if packet.is_null() || frame.is_null() {
+        return SbusFfiStatus::NullPointer;
+    }
+    match SbusPacket::from(*packet).try_to_array() {
+        Ok(mut encoded) => {
+            encoded[SBUS_FRAME_LENGTH - 1] = (*packet).kind().footer();
+            ptr::copy_nonoverlapping(encoded.as_ptr(), frame, SBUS_FRAME_LENGTH);
+            SbusFfiStatus::Ok
+        }
+        Err(error) => error.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::MaybeUninit;
+
+    #[test]
+    fn test_encode_decode() {
+        let mut packet = SbusFfiPacket::from(SbusPacket::neutral());
+        packet.channels[2] = 1811;
+        packet.failsafe = true;
+        packet.footer = 0x14;
+
+        let mut frame = [0u8; SBUS_FRAME_LENGTH];
+        let mut decoded = MaybeUninit::<SbusFfiPacket>::uninit();
+        unsafe {
+            assert_eq!(sbus_encode(&packet, frame.as_mut_ptr()), SbusFfiStatus::Ok);
+            assert_eq!(
+                sbus_decode(frame.as_ptr(), decoded.as_mut_ptr()),
+                SbusFfiStatus::Ok
+            );
+            assert_eq!(decoded.assume_init(), packet);
+
+            frame[0] = 0x00;
+            assert_eq!(
+                sbus_decode(frame.as_ptr(), decoded.as_mut_ptr()),
+                SbusFfiStatus::InvalidHeader
+            );
+            packet.channels[0] = 2048;
+            assert_eq!(
+                sbus_encode(&packet, frame.as_mut_ptr()),
+                SbusFfiStatus::OutOfRange
+            );
+            assert_eq!(
+                sbus_decode(ptr::null(), decoded.as_mut_ptr()),
+                SbusFfiStatus::NullPointer
+            );
+        }
+    }
+
+    #[test]
+    fn test_deframer() {
+        let mut packet = SbusPacket::neutral();
+        packet.channels[0] = 500;
+        let mut bytes = vec![0xAA, 0x0F];
+        bytes.extend_from_slice(&packet.to_array());
+
+        let mut deframer = MaybeUninit::<SbusFfiDeframer>::uninit();
+        let mut decoded = MaybeUninit::<SbusFfiPacket>::uninit();
+        unsafe {
+            let deframer = deframer.as_mut_ptr();
+            assert_eq!(sbus_deframer_init(deframer), SbusFfiStatus::Ok);
+            assert_eq!(
+                sbus_deframer_next(deframer, decoded.as_mut_ptr()),
+                SbusFfiStatus::NoFrame
+            );
+            let (first, rest) = bytes.split_at(10);
+            for chunk in [first, rest] {
+                assert_eq!(
+                    sbus_deframer_feed(deframer, chunk.as_ptr(), chunk.len()),
+                    SbusFfiStatus::Ok
+                );
+            }
+            assert_eq!(
+                sbus_deframer_next(deframer, decoded.as_mut_ptr()),
+                SbusFfiStatus::Ok
+            );
+            assert_eq!(SbusPacket::from(decoded.assume_init()), packet);
+            assert_eq!(
+                sbus_deframer_next(deframer, decoded.as_mut_ptr()),
+                SbusFfiStatus::NoFrame
+            );
+        }
+    }
+}
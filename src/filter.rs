@@ -0,0 +1,365 @@
+use crate::{Channel, PacketTransform, SbusPacket, CHANNEL_COUNT, CHANNEL_FUTABA_MID};
+
+/// A filter smoothing the values of a single channel from frame to frame
+///
+/// Tuples of filters apply their elements in order. Use a [`FilterBank`] to run filters on the
+/// channels of a packet.
+pub trait ChannelFilter {
+    /// Filters the next value of the channel
+    fn filter(&mut self, value: u16) -> u16;
+
+    /// Forgets the previous values, e.g. after a failsafe
+    fn reset(&mut self) {}
+}
+
+macro_rules! impl_tuple_filter {
+    ($($name:ident),+) => {
+        impl<$($name: ChannelFilter),+> ChannelFilter for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn filter(&mut self, value: u16) -> u16 {
+                let ($($name,)+) = self;
+                $(let value = $name.filter(value);)+
+                value
+            }
+
+            #[allow(non_snake_case)]
+            fn reset(&mut self) {
+                let ($($name,)+) = self;
+                $($name.reset();)+
+            }
+        }
+    };
+}
+
+impl_tuple_filter!(A, B);
+impl_tuple_filter!(A, B, C);
+impl_tuple_filter!(A, B, C, D);
+
+/// Runs an independent copy of a [`ChannelFilter`] on selected proportional channels
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{Channel, Deadband, FilterBank, PacketTransform, SbusPacket, SlewRateLimiter};
+///
+/// let mut bank = FilterBank::new(
+///     (Deadband::new(8), SlewRateLimiter::new(40)),
+///     &[Channel::Ch1, Channel::Ch2],
+/// );
+///
+/// let mut packet = SbusPacket::neutral();
+/// packet.channels[0] = 996;
+/// assert_eq!(bank.apply(&packet).channels[0], 992);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct FilterBank<F> {
+    filters: [F; CHANNEL_COUNT],
+    mask: u16,
+}
+
+impl<F> FilterBank<F>
+where
+    F: ChannelFilter + Clone,
+{
+    /// Filters `channels` with copies of `filter`, digital channels are ignored
+    pub fn new(filter: F, channels: &[Channel]) -> Self {
+        let mask = channels
+            .iter()
+            .filter(|channel| !channel.is_digital())
+            .fold(0, |mask, channel| mask | 1 << channel.index());
+        Self {
+            filters: core::array::from_fn(|_| filter.clone()),
+            mask,
+        }
+    }
+
+    /// Filters every proportional channel with copies of `filter`
+    pub fn all(filter: F) -> Self {
+        Self::new(filter, &Channel::PROPORTIONAL)
+    }
+}
+
+impl<F> FilterBank<F>
+where
+    F: ChannelFilter,
+{
+    /// Whether `channel` is filtered
+    pub fn is_filtered(&self, channel: Channel) -> bool {
+        !channel.is_digital() && self.mask & (1 << channel.index()) != 0
+    }
+
+    /// The filter of a proportional channel, or `None` for the digital channels
+    pub fn filter_mut(&mut self, channel: Channel) -> Option<&mut F> {
+        self.filters.get_mut(channel.index())
+    }
+
+    /// Resets the filters of all channels
+    pub fn reset(&mut self) {
+        self.filters.iter_mut().for_each(ChannelFilter::reset);
+    }
+}
+
+impl<F> PacketTransform for FilterBank<F>
+where
+    F: ChannelFilter,
+{
+    fn transform(&mut self, packet: &mut SbusPacket) {
+        for (index, (value, filter)) in packet
+            .channels
+            .iter_mut()
+            .zip(self.filters.iter_mut())
+            .enumerate()
+        {
+            if self.mask & (1 << index) != 0 {
+                *value = filter.filter(*value);
+            }
+        }
+    }
+}
+
+/// Snaps values within `width` of the centre to the centre
+///
+/// Keeps sticks that don't quite return to centre from slowly driving servos or ESCs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Deadband {
+    center: u16,
+    width: u16,
+}
+
+impl Deadband {
+    /// A deadband around [`CHANNEL_FUTABA_MID`]
+    pub const fn new(width: u16) -> Self {
+        Self::around(CHANNEL_FUTABA_MID, width)
+    }
+
+    /// A deadband around a custom `center`
+    pub const fn around(center: u16, width: u16) -> Self {
+        Self { center, width }
+    }
+}
+
+impl ChannelFilter for Deadband {
+    fn filter(&mut self, value: u16) -> u16 {
+        if value.abs_diff(self.center) <= self.width {
+            self.center
+        } else {
+            value
+        }
+    }
+}
+
+/// Limits how far a channel may move per frame
+///
+/// The first value after creation or [`reset`](ChannelFilter::reset) passes through unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlewRateLimiter {
+    max_step: u16,
+    last: Option<u16>,
+}
+
+impl SlewRateLimiter {
+    /// Allows a change of at most `max_step` channel units per frame
+    pub const fn new(max_step: u16) -> Self {
+        Self {
+            max_step,
+            last: None,
+        }
+    }
+}
+
+impl ChannelFilter for SlewRateLimiter {
+    fn filter(&mut self, value: u16) -> u16 {
+        let output = match self.last {
+            Some(last) if value > last => value.min(last.saturating_add(self.max_step)),
+            Some(last) => value.max(last.saturating_sub(self.max_step)),
+            None => value,
+        };
+        self.last = Some(output);
+        output
+    }
+
+    fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+/// Exponentially weighted moving average low-pass filter
+///
+/// Every frame moves the output `alpha / 256` of the way towards the input, smaller values
+/// smooth more. The state keeps 8 fractional bits, so small steps aren't lost to rounding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LowPass {
+    alpha: u8,
+    state: Option<u32>,
+}
+
+impl LowPass {
+    pub const fn new(alpha: u8) -> Self {
+        Self { alpha, state: None }
+    }
+}
+
+impl ChannelFilter for LowPass {
+    fn filter(&mut self, value: u16) -> u16 {
+        let target = (value as u32) << 8;
+        let state = match self.state {
+            Some(state) => {
+                let delta = (target as i32 - state as i32) * self.alpha as i32 / 256;
+                (state as i32 + delta) as u32
+            }
+            None => target,
+        };
+        self.state = Some(state);
+        ((state + 0x80) >> 8) as u16
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// Median of the last `N` values, removing single-frame spikes without smoothing steps
+///
+/// `N` must be odd. Until `N` values were seen, the median of the values so far is returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Median<const N: usize> {
+    window: [u16; N],
+    len: usize,
+    next: usize,
+}
+
+/// A 3-tap [`Median`] filter
+pub type Median3 = Median<3>;
+/// A 5-tap [`Median`] filter
+pub type Median5 = Median<5>;
+
+impl<const N: usize> Median<N> {
+    pub const fn new() -> Self {
+        const { assert!(N % 2 == 1, "median filters need an odd number of taps") };
+        Self {
+            window: [0; N],
+            len: 0,
+            next: 0,
+        }
+    }
+}
+
+impl<const N: usize> Default for Median<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> ChannelFilter for Median<N> {
+    fn filter(&mut self, value: u16) -> u16 {
+        self.window[self.next] = value;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+
+        let mut sorted = self.window;
+        let sorted = &mut sorted[..self.len];
+        sorted.sort_unstable();
+        sorted[(self.len - 1) / 2]
+    }
+
+    fn reset(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadband() {
+        let mut deadband = Deadband::new(10);
+        assert_eq!(deadband.filter(982), 992);
+        assert_eq!(deadband.filter(1002), 992);
+        assert_eq!(deadband.filter(1003), 1003);
+        assert_eq!(deadband.filter(981), 981);
+
+        let mut custom = Deadband::around(172, 5);
+        assert_eq!(custom.filter(176), 172);
+    }
+
+    #[test]
+    fn test_slew_rate_limiter() {
+        let mut limiter = SlewRateLimiter::new(100);
+        assert_eq!(limiter.filter(172), 172);
+        assert_eq!(limiter.filter(1811), 272);
+        assert_eq!(limiter.filter(1811), 372);
+        assert_eq!(limiter.filter(300), 300);
+        assert_eq!(limiter.filter(0), 200);
+
+        limiter.reset();
+        assert_eq!(limiter.filter(1811), 1811);
+    }
+
+    #[test]
+    fn test_low_pass() {
+        let mut low_pass = LowPass::new(64);
+        assert_eq!(low_pass.filter(1000), 1000);
+        assert_eq!(low_pass.filter(1400), 1100);
+        assert_eq!(low_pass.filter(1400), 1175);
+
+        // Settles on the input instead of stalling a few units short
+        let settled = (0..200).fold(0, |_, _| low_pass.filter(1401));
+        assert_eq!(settled, 1401);
+
+        low_pass.reset();
+        assert_eq!(low_pass.filter(172), 172);
+        assert_eq!(LowPass::new(0).filter(500), 500);
+    }
+
+    #[test]
+    fn test_median() {
+        let mut median = Median3::new();
+        assert_eq!(median.filter(1000), 1000);
+        assert_eq!(median.filter(1010), 1000);
+        assert_eq!(median.filter(1811), 1010);
+        assert_eq!(median.filter(1020), 1020);
+        assert_eq!(median.filter(1030), 1030);
+
+        let mut median = Median5::new();
+        for value in [1000, 1002, 0, 1004, 2047] {
+            median.filter(value);
+        }
+        assert_eq!(median.filter(1006), 1004);
+
+        median.reset();
+        assert_eq!(median.filter(172), 172);
+    }
+
+    #[test]
+    fn test_filter_bank() {
+        let mut bank = FilterBank::new(
+            (Deadband::new(5), SlewRateLimiter::new(50)),
+            &[Channel::Ch3, Channel::Ch17],
+        );
+        assert!(bank.is_filtered(Channel::Ch3));
+        assert!(!bank.is_filtered(Channel::Ch1));
+        assert!(!bank.is_filtered(Channel::Ch17));
+
+        let mut packet = SbusPacket::neutral();
+        bank.transform(&mut packet);
+
+        packet.channels[0] = 1811;
+        packet.channels[2] = 1811;
+        let output = bank.apply(&packet);
+        assert_eq!(output.channels[0], 1811);
+        assert_eq!(output.channels[2], 1042);
+
+        bank.reset();
+        assert_eq!(bank.apply(&packet).channels[2], 1811);
+    }
+}
@@ -0,0 +1,71 @@
+//! Frames shared by the unit tests and the `test_util` module
+use crate::{pack_channels, CHANNEL_COUNT, SBUS_FOOTER, SBUS_FRAME_LENGTH, SBUS_HEADER};
+#[cfg(test)]
+use crate::{Flags, SbusPacket};
+
+/// A valid SBUS frame with every channel set to `value` and no flags
+pub(crate) const fn frame(value: u16) -> [u8; SBUS_FRAME_LENGTH] {
+    frame_with_flags(value, 0)
+}
+
+/// Like [`frame`], with the raw flag byte `flags`
+pub(crate) const fn frame_with_flags(value: u16, flags: u8) -> [u8; SBUS_FRAME_LENGTH] {
+    frame_with_channels(&[value; CHANNEL_COUNT], flags)
+}
+
+/// A valid SBUS frame with the given channels and raw flag byte `flags`
+pub(crate) const fn frame_with_channels(
+    channels: &[u16; CHANNEL_COUNT],
+    flags: u8,
+) -> [u8; SBUS_FRAME_LENGTH] {
+    let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+    buffer[0] = SBUS_HEADER;
+    pack_channels(&mut buffer, channels);
+    buffer[SBUS_FRAME_LENGTH - 2] = flags;
+    buffer[SBUS_FRAME_LENGTH - 1] = SBUS_FOOTER;
+    buffer
+}
+
+/// A frame with all channels and flags zero, ending in `footer`
+#[cfg(test)]
+pub(crate) const fn frame_with_footer(footer: u8) -> [u8; SBUS_FRAME_LENGTH] {
+    let mut buffer = frame(0);
+    buffer[SBUS_FRAME_LENGTH - 1] = footer;
+    buffer
+}
+
+/// The frames of [`frame`] for each of `values`, back to back
+#[cfg(all(test, feature = "blocking"))]
+pub(crate) fn frames(values: &[u16]) -> Vec<u8> {
+    values.iter().flat_map(|&value| frame(value)).collect()
+}
+
+/// A packet with every channel set to `value` and no flags
+#[cfg(test)]
+pub(crate) const fn packet(value: u16) -> SbusPacket {
+    packet_with_flags(
+        value,
+        Flags {
+            d1: false,
+            d2: false,
+            failsafe: false,
+            frame_lost: false,
+        },
+    )
+}
+
+/// Like [`packet`], with `flags`
+#[cfg(test)]
+pub(crate) const fn packet_with_flags(value: u16, flags: Flags) -> SbusPacket {
+    SbusPacket::new([value; CHANNEL_COUNT], flags)
+}
+
+/// A neutral packet with channel `n` set to `1000 + n`, to tell channels apart
+#[cfg(test)]
+pub(crate) fn numbered_packet() -> SbusPacket {
+    let mut packet = SbusPacket::neutral();
+    for (index, value) in packet.channels.iter_mut().enumerate() {
+        *value = 1000 + index as u16;
+    }
+    packet
+}
@@ -0,0 +1,337 @@
+//! Decoding of FrSky FPort frames
+//!
+//! FPort carries the channels and flags of SBUS along with SmartPort telemetry over a single
+//! inverted UART at 115200 baud, 8N1. Frames are delimited by `0x7E`, with `0x7E` and `0x7D`
+//! inside a frame escaped as `0x7D` followed by the byte XORed with `0x20`.
+use crate::{channels_parsing, Flags, SbusError, SbusPacket, SBUS_FRAME_LENGTH};
+
+/// Byte starting and ending every FPort frame
+pub const FPORT_DELIMITER: u8 = 0x7E;
+/// Byte escaping a delimiter or escape byte inside a frame
+pub const FPORT_ESCAPE: u8 = 0x7D;
+/// Frame type of control frames, carrying the channels, flags and RSSI
+pub const FPORT_TYPE_CONTROL: u8 = 0x00;
+/// Frame type of telemetry requests from the receiver
+pub const FPORT_TYPE_DOWNLINK: u8 = 0x01;
+/// Frame type of telemetry responses to the receiver
+pub const FPORT_TYPE_UPLINK: u8 = 0x81;
+
+/// Length field of control frames: type, channels, flags and RSSI
+const CONTROL_LENGTH: u8 = 0x19;
+/// Length field of telemetry frames: type, primitive, application ID and data
+const TELEMETRY_LENGTH: u8 = 0x08;
+/// Longest unescaped frame without delimiters: length, type, payload and CRC
+const MAX_FRAME_LENGTH: usize = CONTROL_LENGTH as usize + 2;
+
+/// The CRC closing FPort frames, computed over the length, type and payload
+///
+/// The bytes are summed with the carry added back in, and the CRC is `0xFF` minus the sum.
+pub const fn fport_crc(bytes: &[u8]) -> u8 {
+    let mut sum = 0u16;
+    let mut i = 0;
+    while i < bytes.len() {
+        sum += bytes[i] as u16;
+        sum = (sum & 0xFF) + (sum >> 8);
+        i += 1;
+    }
+    0xFF - sum as u8
+}
+
+/// A SmartPort telemetry request or response carried in an FPort frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct FportTelemetry {
+    /// The frame primitive, e.g. `0x10` for a data frame
+    pub prim: u8,
+    /// The sensor value ID
+    pub app_id: u16,
+    pub data: u32,
+}
+
+/// A decoded FPort frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum FportFrame {
+    /// The channels and flags, with the RSSI the receiver reported for them
+    Control { packet: SbusPacket, rssi: u8 },
+    /// A telemetry request sent by the receiver
+    Downlink(FportTelemetry),
+    /// A telemetry response sent to the receiver
+    Uplink(FportTelemetry),
+}
+
+/// Decoder for FPort frames from bytes pushed in by the caller
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{FportDecoder, FportFrame};
+///
+/// let mut decoder = FportDecoder::new();
+/// # let received: &[u8] = &[];
+/// for &byte in received {
+///     if let Some(Ok(FportFrame::Control { packet, rssi })) = decoder.push_byte(byte) {
+///         // Use the packet
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct FportDecoder {
+    buffer: [u8; MAX_FRAME_LENGTH],
+    len: usize,
+    /// Whether a delimiter was seen and bytes are collected
+    in_frame: bool,
+    /// Whether the previous byte was an escape byte
+    escaped: bool,
+    /// Whether the frame outgrew the buffer and is skipped up to the next delimiter
+    overflowed: bool,
+    inverted: bool,
+}
+
+impl FportDecoder {
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; MAX_FRAME_LENGTH],
+            len: 0,
+            in_frame: false,
+            escaped: false,
+            overflowed: false,
+            inverted: false,
+        }
+    }
+
+    /// Flips every received byte, for UARTs capturing FPort without an inverter
+    pub const fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    /// Appends a received byte, returning the frame it completes
+    ///
+    /// Fails with [`SbusError::InvalidLength`] for frames of the wrong length,
+    /// [`SbusError::InvalidChecksum`] if the CRC doesn't match and [`SbusError::InvalidHeader`]
+    /// with the frame type for unknown frame types.
+    pub fn push_byte(&mut self, byte: u8) -> Option<Result<FportFrame, SbusError>> {
+        let byte = if self.inverted { !byte } else { byte };
+        if byte == FPORT_DELIMITER {
+            let result = (self.len > 0 || self.overflowed).then(|| self.decode());
+            self.len = 0;
+            self.in_frame = true;
+            self.escaped = false;
+            self.overflowed = false;
+            return result;
+        }
+        if !self.in_frame {
+            return None;
+        }
+        if byte == FPORT_ESCAPE {
+            self.escaped = true;
+            return None;
+        }
+        let byte = if self.escaped { byte ^ 0x20 } else { byte };
+        self.escaped = false;
+        if self.len == MAX_FRAME_LENGTH {
+            self.overflowed = true;
+        } else {
+            self.buffer[self.len] = byte;
+            self.len += 1;
+        }
+        None
+    }
+
+    /// Discards a partly received frame
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.in_frame = false;
+        self.escaped = false;
+        self.overflowed = false;
+    }
+
+    fn decode(&self) -> Result<FportFrame, SbusError> {
+        let frame = &self.buffer[..self.len];
+        let expected_len = frame[0] as usize + 2;
+        if self.overflowed || frame[0] < TELEMETRY_LENGTH || frame.len() != expected_len {
+            return Err(SbusError::InvalidLength(frame.len()));
+        }
+        let expected = fport_crc(&frame[..expected_len - 1]);
+        let received = frame[expected_len - 1];
+        if expected != received {
+            return Err(SbusError::InvalidChecksum {
+                expected: expected as u16,
+                received: received as u16,
+            });
+        }
+
+        let payload = &frame[2..expected_len - 1];
+        match (frame[1], frame[0]) {
+            (FPORT_TYPE_CONTROL, CONTROL_LENGTH) => {
+                let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+                buffer[1..=22].copy_from_slice(&payload[..22]);
+                let mut packet = SbusPacket::neutral();
+                packet.channels = channels_parsing(&buffer);
+                packet.flags = Flags::from_byte(payload[22]);
+                Ok(FportFrame::Control {
+                    packet,
+                    rssi: payload[23],
+                })
+            }
+            (FPORT_TYPE_DOWNLINK, TELEMETRY_LENGTH) => Ok(FportFrame::Downlink(telemetry(payload))),
+            (FPORT_TYPE_UPLINK, TELEMETRY_LENGTH) => Ok(FportFrame::Uplink(telemetry(payload))),
+            (FPORT_TYPE_CONTROL | FPORT_TYPE_DOWNLINK | FPORT_TYPE_UPLINK, _) => {
+                Err(SbusError::InvalidLength(frame.len()))
+            }
+            (kind, _) => Err(SbusError::InvalidHeader(kind)),
+        }
+    }
+}
+
+fn telemetry(payload: &[u8]) -> FportTelemetry {
+    FportTelemetry {
+        prim: payload[0],
+        app_id: u16::from_le_bytes([payload[1], payload[2]]),
+        data: u32::from_le_bytes([payload[3], payload[4], payload[5], payload[6]]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pack_channels, Channel, CHANNEL_FUTABA_MAX};
+
+    /// Adds the CRC, escapes and delimits an unescaped frame starting with its length
+    fn wire(body: &[u8]) -> Vec<u8> {
+        let mut frame = body.to_vec();
+        frame.push(fport_crc(body));
+        let mut wire = vec![FPORT_DELIMITER];
+        for byte in frame {
+            if byte == FPORT_DELIMITER || byte == FPORT_ESCAPE {
+                wire.extend_from_slice(&[FPORT_ESCAPE, byte ^ 0x20]);
+            } else {
+                wire.push(byte);
+            }
+        }
+        wire.push(FPORT_DELIMITER);
+        wire
+    }
+
+    fn control(packet: &SbusPacket, rssi: u8) -> Vec<u8> {
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        pack_channels(&mut buffer, &packet.channels);
+        let mut body = vec![CONTROL_LENGTH, FPORT_TYPE_CONTROL];
+        body.extend_from_slice(&buffer[1..=22]);
+        body.extend_from_slice(&[packet.flags.to_byte(), rssi]);
+        wire(&body)
+    }
+
+    fn decode(decoder: &mut FportDecoder, bytes: &[u8]) -> Vec<Result<FportFrame, SbusError>> {
+        bytes
+            .iter()
+            .filter_map(|&byte| decoder.push_byte(byte))
+            .collect()
+    }
+
+    #[test]
+    fn test_crc() {
+        assert_eq!(fport_crc(&[]), 0xFF);
+        // 0x80 + 0x90 carries into 0x11
+        assert_eq!(fport_crc(&[0x80, 0x90]), 0xFF - 0x11);
+    }
+
+    #[test]
+    fn test_control_frame() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch2] = CHANNEL_FUTABA_MAX;
+        // A 0x7E in the payload is escaped on the wire
+        packet[Channel::Ch1] = 0x7E | 0x500;
+        packet.flags.frame_lost = true;
+
+        let bytes = control(&packet, 70);
+        assert!(bytes.windows(2).any(|pair| pair == [FPORT_ESCAPE, 0x5E]));
+        let mut decoder = FportDecoder::new();
+        // Bytes before the first delimiter are skipped
+        let mut received = vec![0x12, 0x34];
+        received.extend_from_slice(&bytes);
+        assert_eq!(
+            decode(&mut decoder, &received),
+            [Ok(FportFrame::Control { packet, rssi: 70 })]
+        );
+
+        let inverted: Vec<u8> = bytes.iter().map(|byte| !byte).collect();
+        let mut decoder = FportDecoder::new().with_inverted(true);
+        assert_eq!(decode(&mut decoder, &inverted).len(), 1);
+    }
+
+    #[test]
+    fn test_telemetry_frames() {
+        let downlink = wire(&[0x08, FPORT_TYPE_DOWNLINK, 0x10, 0x00, 0x01, 1, 2, 3, 4]);
+        let uplink = wire(&[0x08, FPORT_TYPE_UPLINK, 0x32, 0x10, 0x02, 0, 0, 0, 0]);
+        let mut decoder = FportDecoder::new();
+        let mut received = downlink;
+        received.extend_from_slice(&uplink);
+        assert_eq!(
+            decode(&mut decoder, &received),
+            [
+                Ok(FportFrame::Downlink(FportTelemetry {
+                    prim: 0x10,
+                    app_id: 0x0100,
+                    data: 0x0403_0201,
+                })),
+                Ok(FportFrame::Uplink(FportTelemetry {
+                    prim: 0x32,
+                    app_id: 0x0210,
+                    data: 0,
+                })),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_frames() {
+        let mut decoder = FportDecoder::new();
+
+        let mut bad_crc = control(&SbusPacket::neutral(), 100);
+        bad_crc[4] ^= 0x01;
+        assert!(matches!(
+            decode(&mut decoder, &bad_crc)[..],
+            [Err(SbusError::InvalidChecksum { .. })]
+        ));
+
+        let unknown = wire(&[0x08, 0x42, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(matches!(
+            decode(&mut decoder, &unknown)[..],
+            [Err(SbusError::InvalidHeader(0x42))]
+        ));
+
+        let short = wire(&[0x08, FPORT_TYPE_CONTROL, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            decode(&mut decoder, &short),
+            [Err(SbusError::InvalidLength(10))]
+        );
+
+        // A length field below the shortest frame is rejected before its payload is read
+        assert_eq!(
+            decode(&mut decoder, &[0x7E, 0x00, 0xFF, 0x7E]),
+            [Err(SbusError::InvalidLength(2))]
+        );
+        assert_eq!(
+            decode(&mut decoder, &wire(&[0x01, FPORT_TYPE_CONTROL])),
+            [Err(SbusError::InvalidLength(3))]
+        );
+
+        let mut long = vec![FPORT_DELIMITER];
+        long.extend_from_slice(&[0x01; 40]);
+        long.push(FPORT_DELIMITER);
+        assert_eq!(
+            decode(&mut decoder, &long),
+            [Err(SbusError::InvalidLength(MAX_FRAME_LENGTH))]
+        );
+
+        // The decoder recovers for the next frame
+        let packet = SbusPacket::neutral();
+        assert_eq!(
+            decode(&mut decoder, &control(&packet, 1)),
+            [Ok(FportFrame::Control { packet, rssi: 1 })]
+        );
+    }
+}
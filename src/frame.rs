@@ -0,0 +1,121 @@
+use crate::{CalibrationRecorder, LinkQuality, SbusPacket};
+
+/// A consumer of packets, such as a logger, a recorder or a link monitor
+///
+/// Closures taking `&SbusPacket` are sinks, `()` discards all packets and tuples of sinks hand
+/// every packet to each of their elements in order. Writers don't implement this trait as
+/// writing can fail, use them as the [`Transmit`](crate::Transmit) stage of an
+/// [`SbusPipeline`](crate::SbusPipeline) instead.
+pub trait FrameSink {
+    fn on_frame(&mut self, packet: &SbusPacket);
+}
+
+/// A producer of packets, such as a parser, a player or a simulator
+///
+/// Closures returning `Option<SbusPacket>` are sources.
+pub trait FrameSource {
+    /// The next packet, or `None` once the source is exhausted
+    fn next_frame(&mut self) -> Option<SbusPacket>;
+}
+
+impl FrameSink for () {
+    fn on_frame(&mut self, _packet: &SbusPacket) {}
+}
+
+impl<F> FrameSink for F
+where
+    F: FnMut(&SbusPacket),
+{
+    fn on_frame(&mut self, packet: &SbusPacket) {
+        self(packet)
+    }
+}
+
+macro_rules! impl_tuple_sink {
+    ($($name:ident),+) => {
+        impl<$($name: FrameSink),+> FrameSink for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn on_frame(&mut self, packet: &SbusPacket) {
+                let ($($name,)+) = self;
+                $($name.on_frame(packet);)+
+            }
+        }
+    };
+}
+
+impl_tuple_sink!(A, B);
+impl_tuple_sink!(A, B, C);
+impl_tuple_sink!(A, B, C, D);
+
+impl<F> FrameSource for F
+where
+    F: FnMut() -> Option<SbusPacket>,
+{
+    fn next_frame(&mut self) -> Option<SbusPacket> {
+        self()
+    }
+}
+
+impl FrameSink for LinkQuality {
+    fn on_frame(&mut self, packet: &SbusPacket) {
+        self.update(packet);
+    }
+}
+
+impl FrameSink for CalibrationRecorder {
+    fn on_frame(&mut self, packet: &SbusPacket) {
+        self.observe(packet);
+    }
+}
+
+#[cfg(target_has_atomic = "32")]
+impl FrameSink for crate::SbusMailbox {
+    fn on_frame(&mut self, packet: &SbusPacket) {
+        self.publish(packet);
+    }
+}
+
+#[cfg(target_has_atomic = "32")]
+impl FrameSink for &crate::SbusMailbox {
+    fn on_frame(&mut self, packet: &SbusPacket) {
+        self.publish(packet);
+    }
+}
+
+#[cfg(feature = "std")]
+impl FrameSink for Vec<SbusPacket> {
+    fn on_frame(&mut self, packet: &SbusPacket) {
+        self.push(*packet);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(source: &mut impl FrameSource, sink: &mut impl FrameSink) -> usize {
+        let mut count = 0;
+        while let Some(packet) = source.next_frame() {
+            sink.on_frame(&packet);
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn test_source_to_sinks() {
+        let mut remaining = 3;
+        let mut source = || {
+            remaining -= 1;
+            (remaining > 0).then(SbusPacket::neutral)
+        };
+
+        let mut seen = 0;
+        let mut sinks = (|_: &SbusPacket| seen += 1, CalibrationRecorder::new(), ());
+        assert_eq!(drain(&mut source, &mut sinks), 2);
+        let (_, recorder, ()) = sinks;
+
+        assert_eq!(seen, 2);
+        assert_eq!(recorder.frames(), 2);
+    }
+}
@@ -0,0 +1,83 @@
+use core::time::Duration;
+
+use crate::SBUS_FRAME_LENGTH;
+
+/// The standard SBUS baud rate
+pub const SBUS_BAUD_RATE: u32 = 100_000;
+/// The baud rate of fast SBUS
+pub const SBUS_FAST_BAUD_RATE: u32 = 200_000;
+/// Bits on the wire per byte: start bit, 8 data bits, even parity and 2 stop bits (8E2)
+pub const SBUS_BITS_PER_BYTE: u32 = 12;
+
+/// The frame rate modes of SBUS transmitters and receivers
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SbusFrameRate {
+    /// Analog servo mode, one frame every 14 ms at 100000 baud
+    #[default]
+    Analog,
+    /// High-speed (digital servo) mode, one frame every 7 ms at 100000 baud
+    HighSpeed,
+    /// Fast SBUS, one frame every 7 ms at 200000 baud
+    Fast,
+}
+
+impl SbusFrameRate {
+    /// Time between the start of two consecutive frames
+    pub const fn interval(self) -> Duration {
+        match self {
+            SbusFrameRate::Analog => Duration::from_millis(14),
+            SbusFrameRate::HighSpeed | SbusFrameRate::Fast => Duration::from_millis(7),
+        }
+    }
+
+    /// The UART baud rate used in this mode
+    pub const fn baud_rate(self) -> u32 {
+        match self {
+            SbusFrameRate::Analog | SbusFrameRate::HighSpeed => SBUS_BAUD_RATE,
+            SbusFrameRate::Fast => SBUS_FAST_BAUD_RATE,
+        }
+    }
+
+    /// Time it takes to transmit one byte on the wire
+    pub const fn byte_time(self) -> Duration {
+        Duration::from_nanos(SBUS_BITS_PER_BYTE as u64 * 1_000_000_000 / self.baud_rate() as u64)
+    }
+
+    /// Time it takes to transmit one frame on the wire
+    pub const fn frame_time(self) -> Duration {
+        let bits = SBUS_FRAME_LENGTH as u64 * SBUS_BITS_PER_BYTE as u64;
+        Duration::from_micros(bits * 1_000_000 / self.baud_rate() as u64)
+    }
+
+    /// Idle time on the wire between the end of one frame and the start of the next
+    pub const fn idle_gap(self) -> Duration {
+        self.interval().saturating_sub(self.frame_time())
+    }
+
+    /// Number of frames sent per second
+    pub const fn frames_per_second(self) -> u32 {
+        (1_000_000 / self.interval().as_micros()) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing() {
+        assert_eq!(SbusFrameRate::Analog.frame_time(), Duration::from_millis(3));
+        assert_eq!(
+            SbusFrameRate::Fast.frame_time(),
+            Duration::from_micros(1500)
+        );
+        assert_eq!(
+            SbusFrameRate::HighSpeed.idle_gap(),
+            Duration::from_millis(4)
+        );
+        assert_eq!(SbusFrameRate::Analog.frames_per_second(), 71);
+        assert_eq!(SbusFrameRate::HighSpeed.frames_per_second(), 142);
+    }
+}
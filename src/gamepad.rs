@@ -0,0 +1,311 @@
+//! Turning normalized gamepad input into packets, e.g. from `gilrs`
+use crate::{Channel, SbusPacket, CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MIN};
+
+/// Channels a [`GamepadMapper`] drives, the proportional and the digital ones
+const MAPPED_CHANNELS: usize = Channel::ALL.len();
+
+/// The channel value of an axis deflection from -1.0 to 1.0, spanning the Futaba range
+///
+/// Deflections beyond the ends are clamped, NaN is centred.
+pub(crate) fn axis_to_channel(axis: f32) -> u16 {
+    let axis = if axis.is_nan() {
+        0.0
+    } else {
+        axis.clamp(-1.0, 1.0)
+    };
+    let value = CHANNEL_FUTABA_MID as f32 + axis * axis_span(axis);
+    (value.round() as u16).clamp(CHANNEL_FUTABA_MIN, CHANNEL_FUTABA_MAX)
+}
+
+/// The span of the Futaba range on the side of the centre `deflection` points to
+pub(crate) fn axis_span(deflection: f32) -> f32 {
+    if deflection < 0.0 {
+        (CHANNEL_FUTABA_MID - CHANNEL_FUTABA_MIN) as f32
+    } else {
+        (CHANNEL_FUTABA_MAX - CHANNEL_FUTABA_MID) as f32
+    }
+}
+
+/// How a gamepad axis drives a channel
+///
+/// The axis value is taken from -1.0 to 1.0, or from 0.0 to 1.0 for triggers. The dead zone
+/// is removed around the rest position, then the deflection is reversed if requested and
+/// scaled, and finally spread over the Futaba range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AxisMap {
+    axis: usize,
+    trigger: bool,
+    reverse: bool,
+    scale: f32,
+    deadzone: f32,
+}
+
+impl AxisMap {
+    /// Maps a stick axis from -1.0 to 1.0 onto the Futaba range
+    pub const fn new(axis: usize) -> Self {
+        Self {
+            axis,
+            trigger: false,
+            reverse: false,
+            scale: 1.0,
+            deadzone: 0.0,
+        }
+    }
+
+    /// Maps a trigger from 0.0 at rest to 1.0 pressed onto the whole Futaba range, e.g. for
+    /// throttle
+    pub const fn trigger(axis: usize) -> Self {
+        Self {
+            trigger: true,
+            ..Self::new(axis)
+        }
+    }
+
+    /// Mirrors the deflection around the centre
+    pub const fn with_reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
+    /// Multiplies the deflection, e.g. 0.5 for low rates
+    pub const fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Ignores deflections up to `deadzone` around the rest position, stretching the remaining
+    /// travel so full deflection is still reached
+    pub const fn with_deadzone(mut self, deadzone: f32) -> Self {
+        self.deadzone = deadzone;
+        self
+    }
+
+    /// The channel value for the axis values of a gamepad
+    ///
+    /// Missing axes are treated as being at rest.
+    pub fn map(&self, axes: &[f32]) -> u16 {
+        let raw = axes.get(self.axis).copied().unwrap_or(0.0);
+        let raw = if raw.is_nan() { 0.0 } else { raw };
+        let deflection = if self.trigger {
+            raw.clamp(0.0, 1.0)
+        } else {
+            raw.clamp(-1.0, 1.0)
+        };
+
+        let magnitude = deflection.abs();
+        let live = if magnitude <= self.deadzone || self.deadzone >= 1.0 {
+            0.0
+        } else {
+            (magnitude - self.deadzone) / (1.0 - self.deadzone.max(0.0))
+        };
+        let deflection = live.copysign(deflection);
+        // Triggers span the whole range, from the low end at rest
+        let deflection = if self.trigger {
+            deflection * 2.0 - 1.0
+        } else {
+            deflection
+        };
+        let deflection = if self.reverse {
+            -deflection
+        } else {
+            deflection
+        };
+        axis_to_channel(deflection * self.scale)
+    }
+}
+
+/// What drives a channel of a [`GamepadMapper`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadSource {
+    /// Follows an axis
+    Axis(AxisMap),
+    /// High while the button is held, low otherwise
+    Button(usize),
+    /// Flips between low and high on every press of the button
+    Toggle(usize),
+    /// A three-position switch, moved to low, middle and high by pressing one of the buttons
+    /// and staying there when released
+    Switch([usize; 3]),
+}
+
+/// Converts normalized gamepad axes and buttons into packets, turning a PC and a USB gamepad
+/// into an SBUS transmitter
+///
+/// Axes and buttons are addressed by their index in the slices passed to
+/// [`GamepadMapper::update`], so any gamepad library can be used, e.g. by collecting the
+/// `gilrs` axes and buttons of interest in a fixed order. Channels without a source stay
+/// centred, latched toggles and switches start low and in the middle.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{AxisMap, Channel, GamepadMapper, GamepadSource};
+///
+/// let mut mapper = GamepadMapper::new()
+///     .with_channel(Channel::Ch1, GamepadSource::Axis(AxisMap::new(0).with_deadzone(0.05)))
+///     .with_channel(Channel::Ch3, GamepadSource::Axis(AxisMap::trigger(1)))
+///     .with_channel(Channel::Ch5, GamepadSource::Switch([0, 1, 2]))
+///     .with_channel(Channel::Ch17, GamepadSource::Toggle(3));
+///
+/// // Stick centred, trigger released, first switch button held
+/// let packet = mapper.update(&[0.02, 0.0], &[true, false, false, false]);
+/// assert_eq!(packet[Channel::Ch1], 992);
+/// assert_eq!(packet[Channel::Ch3], 172);
+/// assert_eq!(packet[Channel::Ch5], 172);
+/// // Send it with an `SbusWriter`, once per frame
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GamepadMapper {
+    sources: [Option<GamepadSource>; MAPPED_CHANNELS],
+    /// The positions of toggles and switches
+    latched: [u16; MAPPED_CHANNELS],
+    /// Whether the button of a toggle was held during the previous update
+    held: [bool; MAPPED_CHANNELS],
+}
+
+impl GamepadMapper {
+    /// Creates a mapper leaving every channel centred
+    pub const fn new() -> Self {
+        Self {
+            sources: [None; MAPPED_CHANNELS],
+            latched: [CHANNEL_FUTABA_MID; MAPPED_CHANNELS],
+            held: [false; MAPPED_CHANNELS],
+        }
+    }
+
+    /// Drives `channel` from `source`
+    pub fn with_channel(mut self, channel: Channel, source: GamepadSource) -> Self {
+        let index = channel.index();
+        self.sources[index] = Some(source);
+        self.latched[index] = match source {
+            GamepadSource::Switch(_) => CHANNEL_FUTABA_MID,
+            _ => CHANNEL_FUTABA_MIN,
+        };
+        self.held[index] = false;
+        self
+    }
+
+    /// The packet for the current axes and buttons of the gamepad
+    ///
+    /// Call this once per frame, so toggles see every press. Missing axes are at rest and
+    /// missing buttons are released. Digital channels are set above the centre.
+    pub fn update(&mut self, axes: &[f32], buttons: &[bool]) -> SbusPacket {
+        let pressed = |button: usize| buttons.get(button).copied().unwrap_or(false);
+        let mut packet = SbusPacket::neutral();
+        for channel in Channel::ALL {
+            let index = channel.index();
+            let value = match self.sources[index] {
+                None => continue,
+                Some(GamepadSource::Axis(map)) => map.map(axes),
+                Some(GamepadSource::Button(button)) => {
+                    if pressed(button) {
+                        CHANNEL_FUTABA_MAX
+                    } else {
+                        CHANNEL_FUTABA_MIN
+                    }
+                }
+                Some(GamepadSource::Toggle(button)) => {
+                    let held = pressed(button);
+                    if held && !self.held[index] {
+                        self.latched[index] = if self.latched[index] == CHANNEL_FUTABA_MAX {
+                            CHANNEL_FUTABA_MIN
+                        } else {
+                            CHANNEL_FUTABA_MAX
+                        };
+                    }
+                    self.held[index] = held;
+                    self.latched[index]
+                }
+                Some(GamepadSource::Switch(positions)) => {
+                    let values = [CHANNEL_FUTABA_MIN, CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MAX];
+                    if let Some(position) = positions.iter().position(|&button| pressed(button)) {
+                        self.latched[index] = values[position];
+                    }
+                    self.latched[index]
+                }
+            };
+            if !packet.set_digital(channel, value > CHANNEL_FUTABA_MID) {
+                packet[channel] = value;
+            }
+        }
+        packet
+    }
+}
+
+impl Default for GamepadMapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_axis_map() {
+        assert_eq!(AxisMap::new(0).map(&[-1.0]), CHANNEL_FUTABA_MIN);
+        assert_eq!(AxisMap::new(0).map(&[0.0]), CHANNEL_FUTABA_MID);
+        assert_eq!(AxisMap::new(0).map(&[2.0]), CHANNEL_FUTABA_MAX);
+        assert_eq!(AxisMap::new(3).map(&[1.0]), CHANNEL_FUTABA_MID);
+        assert_eq!(AxisMap::new(0).map(&[f32::NAN]), CHANNEL_FUTABA_MID);
+
+        let reversed = AxisMap::new(0).with_reverse(true).with_scale(0.5);
+        assert_eq!(reversed.map(&[1.0]), CHANNEL_FUTABA_MID - 410);
+
+        let deadzone = AxisMap::new(0).with_deadzone(0.5);
+        assert_eq!(deadzone.map(&[-0.5]), CHANNEL_FUTABA_MID);
+        assert_eq!(deadzone.map(&[0.75]), axis_to_channel(0.5));
+        assert_eq!(deadzone.map(&[1.0]), CHANNEL_FUTABA_MAX);
+
+        let trigger = AxisMap::trigger(1);
+        assert_eq!(trigger.map(&[0.0, 0.0]), CHANNEL_FUTABA_MIN);
+        assert_eq!(trigger.map(&[0.0, 0.5]), CHANNEL_FUTABA_MID);
+        assert_eq!(trigger.map(&[0.0, 1.0]), CHANNEL_FUTABA_MAX);
+    }
+
+    #[test]
+    fn test_axis_to_channel() {
+        assert_eq!(axis_to_channel(-1.0), CHANNEL_FUTABA_MIN);
+        assert_eq!(axis_to_channel(-0.5), CHANNEL_FUTABA_MID - 410);
+        assert_eq!(axis_to_channel(1.5), CHANNEL_FUTABA_MAX);
+        assert_eq!(axis_to_channel(f32::NAN), CHANNEL_FUTABA_MID);
+    }
+
+    #[test]
+    fn test_buttons() {
+        let mut mapper = GamepadMapper::new()
+            .with_channel(Channel::Ch5, GamepadSource::Button(0))
+            .with_channel(Channel::Ch6, GamepadSource::Toggle(1))
+            .with_channel(Channel::Ch7, GamepadSource::Switch([2, 3, 4]))
+            .with_channel(Channel::Ch18, GamepadSource::Toggle(1));
+
+        let packet = mapper.update(&[], &[]);
+        assert_eq!(packet[Channel::Ch1], CHANNEL_FUTABA_MID);
+        assert_eq!(packet[Channel::Ch5], CHANNEL_FUTABA_MIN);
+        assert_eq!(packet[Channel::Ch6], CHANNEL_FUTABA_MIN);
+        assert_eq!(packet[Channel::Ch7], CHANNEL_FUTABA_MID);
+
+        let packet = mapper.update(&[], &[true, true, false, false, true]);
+        assert_eq!(packet[Channel::Ch5], CHANNEL_FUTABA_MAX);
+        assert_eq!(packet[Channel::Ch6], CHANNEL_FUTABA_MAX);
+        assert_eq!(packet[Channel::Ch7], CHANNEL_FUTABA_MAX);
+        assert!(packet.flags.d2);
+
+        // Holding the toggle doesn't flip it again, releasing the switch keeps its position
+        let packet = mapper.update(&[], &[false, true]);
+        assert_eq!(packet[Channel::Ch6], CHANNEL_FUTABA_MAX);
+        assert_eq!(packet[Channel::Ch7], CHANNEL_FUTABA_MAX);
+        mapper.update(&[], &[]);
+        let packet = mapper.update(&[], &[false, true, true]);
+        assert_eq!(packet[Channel::Ch6], CHANNEL_FUTABA_MIN);
+        assert_eq!(packet[Channel::Ch7], CHANNEL_FUTABA_MIN);
+        assert!(!packet.flags.d2);
+    }
+}
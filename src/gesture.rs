@@ -0,0 +1,258 @@
+use crate::{ChannelMap, SbusPacket, CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MIN};
+
+/// Where a stick axis must be for a [`Gesture`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StickZone {
+    /// The axis is ignored
+    #[default]
+    Any,
+    /// Near [`CHANNEL_FUTABA_MIN`], i.e. left or back
+    Low,
+    /// Near [`CHANNEL_FUTABA_MID`]
+    Centre,
+    /// Near [`CHANNEL_FUTABA_MAX`], i.e. right or forward
+    High,
+}
+
+impl StickZone {
+    /// Whether `value` lies within `margin` of this zone
+    pub const fn contains(self, value: u16, margin: u16) -> bool {
+        match self {
+            StickZone::Any => true,
+            StickZone::Low => value <= CHANNEL_FUTABA_MIN.saturating_add(margin),
+            StickZone::Centre => value.abs_diff(CHANNEL_FUTABA_MID) <= margin,
+            StickZone::High => value >= CHANNEL_FUTABA_MAX.saturating_sub(margin),
+        }
+    }
+}
+
+/// A combination of stick positions, e.g. throttle low and yaw right to arm
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gesture {
+    roll: StickZone,
+    pitch: StickZone,
+    throttle: StickZone,
+    yaw: StickZone,
+}
+
+impl Gesture {
+    /// Throttle low, yaw right
+    pub const ARM: Self = Self::new().throttle(StickZone::Low).yaw(StickZone::High);
+    /// Throttle low, yaw left
+    pub const DISARM: Self = Self::new().throttle(StickZone::Low).yaw(StickZone::Low);
+    /// Throttle low, yaw left, pitch back
+    pub const CALIBRATE_GYRO: Self = Self::DISARM.pitch(StickZone::Low);
+    /// Throttle high, yaw left, pitch back
+    pub const CALIBRATE_ACC: Self = Self::new()
+        .throttle(StickZone::High)
+        .yaw(StickZone::Low)
+        .pitch(StickZone::Low);
+
+    /// A gesture matching any stick positions, to be narrowed down per axis
+    pub const fn new() -> Self {
+        Self {
+            roll: StickZone::Any,
+            pitch: StickZone::Any,
+            throttle: StickZone::Any,
+            yaw: StickZone::Any,
+        }
+    }
+
+    pub const fn roll(mut self, zone: StickZone) -> Self {
+        self.roll = zone;
+        self
+    }
+
+    pub const fn pitch(mut self, zone: StickZone) -> Self {
+        self.pitch = zone;
+        self
+    }
+
+    pub const fn throttle(mut self, zone: StickZone) -> Self {
+        self.throttle = zone;
+        self
+    }
+
+    pub const fn yaw(mut self, zone: StickZone) -> Self {
+        self.yaw = zone;
+        self
+    }
+
+    /// Whether the sticks of `packet` are in position, reading the axes through `map`
+    pub fn matches(&self, packet: &SbusPacket, map: &ChannelMap, margin: u16) -> bool {
+        let sticks = packet.mapped(map);
+        self.roll.contains(sticks.roll(), margin)
+            && self.pitch.contains(sticks.pitch(), margin)
+            && self.throttle.contains(sticks.throttle(), margin)
+            && self.yaw.contains(sticks.yaw(), margin)
+    }
+}
+
+/// Reports gestures once their stick positions were held for a number of frames
+///
+/// Every gesture is reported once per hold, the sticks must leave the position before it is
+/// reported again. Failsafe and lost frames interrupt a hold. When several gestures match, the
+/// first in the list wins.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{ChannelMap, Gesture, GestureDetector, SbusPacket};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Command {
+///     Arm,
+///     Disarm,
+/// }
+///
+/// let mut detector = GestureDetector::new(
+///     ChannelMap::AETR,
+///     [(Command::Arm, Gesture::ARM), (Command::Disarm, Gesture::DISARM)],
+/// )
+/// .with_hold_frames(3);
+///
+/// let mut packet = SbusPacket::neutral();
+/// packet.channels[2] = 172;
+/// packet.channels[3] = 1811;
+///
+/// assert_eq!(detector.update(&packet), None);
+/// assert_eq!(detector.update(&packet), None);
+/// assert_eq!(detector.update(&packet), Some(Command::Arm));
+/// assert_eq!(detector.update(&packet), None);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct GestureDetector<G, const N: usize> {
+    map: ChannelMap,
+    gestures: [(G, Gesture); N],
+    hold_frames: u16,
+    margin: u16,
+    active: Option<usize>,
+    held: u16,
+}
+
+impl<G, const N: usize> GestureDetector<G, N>
+where
+    G: Copy,
+{
+    /// Creates a detector for the tagged `gestures`, requiring a hold of 50 frames and sticks
+    /// within 100 units of their zone
+    pub const fn new(map: ChannelMap, gestures: [(G, Gesture); N]) -> Self {
+        Self {
+            map,
+            gestures,
+            hold_frames: 50,
+            margin: 100,
+            active: None,
+            held: 0,
+        }
+    }
+
+    /// Sets for how many consecutive frames a gesture must be held, at least one
+    pub const fn with_hold_frames(mut self, frames: u16) -> Self {
+        self.hold_frames = if frames == 0 { 1 } else { frames };
+        self
+    }
+
+    /// Sets how close to its zone a stick must be
+    pub const fn with_margin(mut self, margin: u16) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Processes the next packet, returning a gesture on the frame its hold completes
+    pub fn update(&mut self, packet: &SbusPacket) -> Option<G> {
+        let matching = if packet.flags.failsafe || packet.flags.frame_lost {
+            None
+        } else {
+            self.gestures
+                .iter()
+                .position(|(_, gesture)| gesture.matches(packet, &self.map, self.margin))
+        };
+
+        if matching != self.active {
+            self.active = matching;
+            self.held = 0;
+        }
+
+        let index = matching?;
+        self.held = self.held.saturating_add(1);
+        (self.held == self.hold_frames).then(|| self.gestures[index].0)
+    }
+
+    /// Abandons the current hold
+    pub fn reset(&mut self) {
+        self.active = None;
+        self.held = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sticks(roll: u16, pitch: u16, throttle: u16, yaw: u16) -> SbusPacket {
+        let mut packet = SbusPacket::neutral();
+        packet.channels[..4].copy_from_slice(&[roll, pitch, throttle, yaw]);
+        packet
+    }
+
+    #[test]
+    fn test_zones() {
+        assert!(StickZone::Low.contains(CHANNEL_FUTABA_MIN, 0));
+        assert!(StickZone::Low.contains(250, 100));
+        assert!(!StickZone::Low.contains(300, 100));
+        assert!(StickZone::Centre.contains(1050, 100));
+        assert!(!StickZone::Centre.contains(1100, 100));
+        assert!(StickZone::High.contains(1750, 100));
+        assert!(StickZone::Any.contains(0, 0));
+    }
+
+    #[test]
+    fn test_gestures() {
+        let map = ChannelMap::TAER;
+        // TAER puts throttle on channel 1 and yaw on channel 4
+        let mut packet = SbusPacket::neutral();
+        packet.channels[0] = 172;
+        packet.channels[3] = 172;
+        assert!(Gesture::DISARM.matches(&packet, &map, 50));
+        assert!(!Gesture::ARM.matches(&packet, &map, 50));
+        assert!(!Gesture::CALIBRATE_GYRO.matches(&packet, &map, 50));
+    }
+
+    #[test]
+    fn test_detector_hold() {
+        let mut detector = GestureDetector::new(
+            ChannelMap::AETR,
+            [
+                ("gyro", Gesture::CALIBRATE_GYRO),
+                ("disarm", Gesture::DISARM),
+            ],
+        )
+        .with_hold_frames(2);
+
+        let disarm = sticks(992, 992, 172, 172);
+        let gyro = sticks(992, 172, 172, 172);
+
+        assert_eq!(detector.update(&disarm), None);
+        // Switching gestures restarts the hold
+        assert_eq!(detector.update(&gyro), None);
+        assert_eq!(detector.update(&gyro), Some("gyro"));
+        assert_eq!(detector.update(&gyro), None);
+
+        let mut lost = disarm;
+        lost.flags.frame_lost = true;
+        assert_eq!(detector.update(&disarm), None);
+        assert_eq!(detector.update(&lost), None);
+        assert_eq!(detector.update(&disarm), None);
+        assert_eq!(detector.update(&disarm), Some("disarm"));
+
+        detector.reset();
+        assert_eq!(detector.update(&disarm), None);
+        assert_eq!(detector.update(&disarm), Some("disarm"));
+    }
+}
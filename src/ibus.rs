@@ -0,0 +1,145 @@
+//! Conversions between SBUS packets and FlySky IBUS servo frames
+//!
+//! An IBUS servo frame carries 14 channels as pulse widths in microseconds, which are
+//! converted with [`channel_to_micros`] and [`micros_to_channel`]. Channels 15 and 16, the
+//! digital channels and the flags are not carried.
+use crate::{channel_to_micros, micros_to_channel, SbusError, SbusPacket, CHANNEL_COUNT};
+
+/// Length of an IBUS servo frame: header, channels and checksum
+pub const IBUS_FRAME_LENGTH: usize = 32;
+/// The length and command bytes starting an IBUS servo frame
+pub const IBUS_HEADER: [u8; 2] = [0x20, 0x40];
+/// Number of channels in an IBUS servo frame
+pub const IBUS_CHANNEL_COUNT: usize = 14;
+
+/// The checksum closing IBUS frames, `0xFFFF` minus the sum of the preceding bytes
+pub const fn ibus_checksum(bytes: &[u8]) -> u16 {
+    let mut sum = 0xFFFFu16;
+    let mut i = 0;
+    while i < bytes.len() {
+        sum = sum.wrapping_sub(bytes[i] as u16);
+        i += 1;
+    }
+    sum
+}
+
+impl SbusPacket {
+    /// Encodes channels 1 to 14 into an IBUS servo frame
+    pub fn to_ibus_frame(&self) -> [u8; IBUS_FRAME_LENGTH] {
+        let mut frame = [0u8; IBUS_FRAME_LENGTH];
+        frame[..2].copy_from_slice(&IBUS_HEADER);
+        for (bytes, &value) in frame[2..IBUS_FRAME_LENGTH - 2]
+            .chunks_exact_mut(2)
+            .zip(&self.channels)
+        {
+            bytes.copy_from_slice(&channel_to_micros(value).to_le_bytes());
+        }
+        let checksum = ibus_checksum(&frame[..IBUS_FRAME_LENGTH - 2]);
+        frame[IBUS_FRAME_LENGTH - 2..].copy_from_slice(&checksum.to_le_bytes());
+        frame
+    }
+
+    /// Decodes an IBUS servo frame into a packet with channels 15 and 16 centred and without
+    /// digital channels or flags
+    ///
+    /// Fails with [`SbusError::InvalidLength`] for frames of another length,
+    /// [`SbusError::InvalidHeader`] with the first mismatching byte for other headers, and
+    /// [`SbusError::InvalidChecksum`] if the checksum doesn't match.
+    pub fn from_ibus_frame(frame: &[u8]) -> Result<Self, SbusError> {
+        if frame.len() != IBUS_FRAME_LENGTH {
+            return Err(SbusError::InvalidLength(frame.len()));
+        }
+        if let Some((&byte, _)) = frame.iter().zip(&IBUS_HEADER).find(|(a, b)| a != b) {
+            return Err(SbusError::InvalidHeader(byte));
+        }
+        let expected = ibus_checksum(&frame[..IBUS_FRAME_LENGTH - 2]);
+        let received =
+            u16::from_le_bytes([frame[IBUS_FRAME_LENGTH - 2], frame[IBUS_FRAME_LENGTH - 1]]);
+        if expected != received {
+            return Err(SbusError::InvalidChecksum { expected, received });
+        }
+
+        let mut packet = Self::neutral();
+        for (value, bytes) in packet
+            .channels
+            .iter_mut()
+            .zip(frame[2..IBUS_FRAME_LENGTH - 2].chunks_exact(2))
+        {
+            *value = micros_to_channel(u16::from_le_bytes([bytes[0], bytes[1]]));
+        }
+        Ok(packet)
+    }
+}
+
+const _: () = assert!(IBUS_CHANNEL_COUNT < CHANNEL_COUNT);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Channel, CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MIN};
+
+    #[test]
+    fn test_ibus_round_trip() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch1] = CHANNEL_FUTABA_MIN;
+        packet[Channel::Ch14] = CHANNEL_FUTABA_MAX;
+        packet[Channel::Ch15] = CHANNEL_FUTABA_MAX;
+        packet.flags.d1 = true;
+
+        let frame = packet.to_ibus_frame();
+        assert_eq!(frame[..4], [0x20, 0x40, 0xDC, 0x03]);
+        assert_eq!(frame[4..6], 1500u16.to_le_bytes());
+        assert_eq!(frame[28..30], 2012u16.to_le_bytes());
+
+        let decoded = SbusPacket::from_ibus_frame(&frame).unwrap();
+        assert_eq!(
+            decoded.channels[..IBUS_CHANNEL_COUNT],
+            packet.channels[..IBUS_CHANNEL_COUNT]
+        );
+        assert_eq!(decoded[Channel::Ch15], CHANNEL_FUTABA_MID);
+        assert!(!decoded.flags.d1);
+    }
+
+    #[test]
+    fn test_ibus_checksum() {
+        // Sticks centred and channels 5 to 14 at 1000 µs
+        let mut frame = [0u8; IBUS_FRAME_LENGTH];
+        frame[..2].copy_from_slice(&IBUS_HEADER);
+        for (index, bytes) in frame[2..30].chunks_exact_mut(2).enumerate() {
+            let micros: u16 = if index < 4 { 1500 } else { 1000 };
+            bytes.copy_from_slice(&micros.to_le_bytes());
+        }
+        let checksum = ibus_checksum(&frame[..30]);
+        assert_eq!(
+            checksum,
+            0xFFFF - 0x60 - 4 * (0xDC + 0x05) - 10 * (0xE8 + 0x03)
+        );
+        frame[30..].copy_from_slice(&checksum.to_le_bytes());
+        let decoded = SbusPacket::from_ibus_frame(&frame).unwrap();
+        assert_eq!(decoded[Channel::Ch1], CHANNEL_FUTABA_MID);
+        assert_eq!(decoded[Channel::Ch5], micros_to_channel(1000));
+    }
+
+    #[test]
+    fn test_ibus_errors() {
+        let frame = SbusPacket::neutral().to_ibus_frame();
+        assert_eq!(
+            SbusPacket::from_ibus_frame(&frame[..31]),
+            Err(SbusError::InvalidLength(31))
+        );
+
+        let mut bad_header = frame;
+        bad_header[1] = 0x41;
+        assert!(matches!(
+            SbusPacket::from_ibus_frame(&bad_header),
+            Err(SbusError::InvalidHeader(0x41))
+        ));
+
+        let mut bad_checksum = frame;
+        bad_checksum[10] ^= 0x01;
+        assert!(matches!(
+            SbusPacket::from_ibus_frame(&bad_checksum),
+            Err(SbusError::InvalidChecksum { .. })
+        ));
+    }
+}
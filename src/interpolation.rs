@@ -0,0 +1,230 @@
+use core::time::Duration;
+
+use crate::{SbusPacket, TimestampedPacket, CHANNEL_MAX};
+
+impl SbusPacket {
+    /// Blends the channels of `self` and `other`, `t` running from 0 (`self`) to 1 (`other`)
+    ///
+    /// `t` is clamped to `0.0..=1.0`. Flags are taken from the nearer packet.
+    pub fn lerp(&self, other: &SbusPacket, t: f32) -> SbusPacket {
+        let t = t.clamp(0.0, 1.0);
+        let mut output = if t < 0.5 { *self } else { *other };
+        for ((value, &from), &to) in output
+            .channels
+            .iter_mut()
+            .zip(self.channels.iter())
+            .zip(other.channels.iter())
+        {
+            *value = blend(from, to, t);
+        }
+        output
+    }
+
+    /// Continues the motion from `previous` to `self` for `t` more frames
+    ///
+    /// `t` is clamped to `0.0..=1.0`, so the projection never reaches further than one frame
+    /// ahead, and the channels are clamped to `0..=CHANNEL_MAX`. Flags are taken from `self`.
+    pub fn extrapolate(&self, previous: &SbusPacket, t: f32) -> SbusPacket {
+        let t = t.clamp(0.0, 1.0);
+        let mut output = *self;
+        for (value, &from) in output.channels.iter_mut().zip(previous.channels.iter()) {
+            *value = blend(from, *value, 1.0 + t);
+        }
+        output
+    }
+}
+
+fn blend(from: u16, to: u16, t: f32) -> u16 {
+    let value = from as f32 + (to as f32 - from as f32) * t;
+    (value.clamp(0.0, CHANNEL_MAX as f32) + 0.5) as u16
+}
+
+/// Turns the packets of a slow SBUS stream into smooth setpoints for a faster control loop
+///
+/// By default the output trails the stream by one frame, moving linearly from the previous to
+/// the latest packet over the interval between them. With
+/// [`with_extrapolation`](Self::with_extrapolation) the output instead starts at the latest
+/// packet and continues its motion for at most the given horizon, which removes the delay at
+/// the cost of overshooting on sudden stops.
+///
+/// # Example
+///
+/// ```rust
+/// use core::time::Duration;
+/// use sbus_rs::{PacketInterpolator, SbusPacket, TimestampedPacket};
+///
+/// let stamped = |value, ms| {
+///     let mut packet = SbusPacket::neutral();
+///     packet.channels[0] = value;
+///     TimestampedPacket {
+///         packet,
+///         received_at: Duration::from_millis(ms),
+///         gap: None,
+///     }
+/// };
+///
+/// let mut interpolator = PacketInterpolator::new();
+/// interpolator.push(stamped(1000, 0));
+/// interpolator.push(stamped(1100, 14));
+///
+/// let setpoint = interpolator.sample(Duration::from_millis(21)).unwrap();
+/// assert_eq!(setpoint.channels[0], 1050);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct PacketInterpolator {
+    previous: Option<TimestampedPacket>,
+    latest: Option<TimestampedPacket>,
+    horizon: Option<Duration>,
+}
+
+impl PacketInterpolator {
+    pub const fn new() -> Self {
+        Self {
+            previous: None,
+            latest: None,
+            horizon: None,
+        }
+    }
+
+    /// Extrapolates ahead of the latest packet for at most `horizon` instead of interpolating
+    /// behind it
+    pub const fn with_extrapolation(mut self, horizon: Duration) -> Self {
+        self.horizon = Some(horizon);
+        self
+    }
+
+    /// Adds the next received packet
+    pub fn push(&mut self, packet: TimestampedPacket) {
+        self.previous = self.latest.replace(packet);
+    }
+
+    /// The setpoint at `now`, measured from the same epoch as the packet timestamps
+    ///
+    /// Returns the latest packet unchanged until two packets were pushed, and `None` before the
+    /// first one. Failsafe and lost frames are passed through without blending.
+    pub fn sample(&self, now: Duration) -> Option<SbusPacket> {
+        let latest = self.latest?;
+        let Some(previous) = self.previous else {
+            return Some(latest.packet);
+        };
+        if latest.packet.flags.failsafe || latest.packet.flags.frame_lost {
+            return Some(latest.packet);
+        }
+
+        let interval = latest.received_at.saturating_sub(previous.received_at);
+        if interval.is_zero() {
+            return Some(latest.packet);
+        }
+        let elapsed = now.saturating_sub(latest.received_at);
+
+        Some(match self.horizon {
+            None => {
+                let t = elapsed.as_secs_f32() / interval.as_secs_f32();
+                previous.packet.lerp(&latest.packet, t)
+            }
+            Some(horizon) => {
+                let t = elapsed.min(horizon).as_secs_f32() / interval.as_secs_f32();
+                latest.packet.extrapolate(&previous.packet, t)
+            }
+        })
+    }
+
+    /// Forgets the received packets, e.g. after a link loss
+    pub fn reset(&mut self) {
+        self.previous = None;
+        self.latest = None;
+    }
+}
+
+impl Default for PacketInterpolator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::packet;
+
+    fn stamped(value: u16, ms: u64) -> TimestampedPacket {
+        TimestampedPacket {
+            packet: packet(value),
+            received_at: Duration::from_millis(ms),
+            gap: None,
+        }
+    }
+
+    fn channel_at(interpolator: &PacketInterpolator, ms: u64) -> u16 {
+        interpolator
+            .sample(Duration::from_millis(ms))
+            .unwrap()
+            .channels[0]
+    }
+
+    #[test]
+    fn test_lerp() {
+        let from = packet(1000);
+        let mut to = packet(2000);
+        to.flags.d1 = true;
+
+        assert_eq!(from.lerp(&to, 0.0), from);
+        assert_eq!(from.lerp(&to, 1.0), to);
+        assert_eq!(from.lerp(&to, 0.25).channels[0], 1250);
+        assert!(!from.lerp(&to, 0.25).flags.d1);
+        assert!(from.lerp(&to, 0.75).flags.d1);
+        assert_eq!(from.lerp(&to, 7.0), to);
+        assert_eq!(to.lerp(&from, 0.5).channels[0], 1500);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        let previous = packet(1000);
+        let latest = packet(1100);
+        assert_eq!(latest.extrapolate(&previous, 0.0), latest);
+        assert_eq!(latest.extrapolate(&previous, 0.5).channels[0], 1150);
+        assert_eq!(latest.extrapolate(&previous, 3.0).channels[0], 1200);
+
+        let falling = packet(50);
+        assert_eq!(falling.extrapolate(&packet(200), 1.0).channels[0], 0);
+    }
+
+    #[test]
+    fn test_interpolator() {
+        let mut interpolator = PacketInterpolator::new();
+        assert_eq!(interpolator.sample(Duration::ZERO), None);
+
+        interpolator.push(stamped(1000, 0));
+        assert_eq!(
+            interpolator.sample(Duration::from_millis(5)),
+            Some(packet(1000))
+        );
+
+        interpolator.push(stamped(1140, 14));
+        assert_eq!(channel_at(&interpolator, 14), 1000);
+        assert_eq!(channel_at(&interpolator, 21), 1070);
+        assert_eq!(channel_at(&interpolator, 28), 1140);
+        assert_eq!(channel_at(&interpolator, 100), 1140);
+
+        interpolator.reset();
+        assert_eq!(interpolator.sample(Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_interpolator_extrapolation() {
+        let mut interpolator =
+            PacketInterpolator::new().with_extrapolation(Duration::from_millis(7));
+        interpolator.push(stamped(1000, 0));
+        interpolator.push(stamped(1140, 14));
+
+        assert_eq!(channel_at(&interpolator, 14), 1140);
+        assert_eq!(channel_at(&interpolator, 21), 1210);
+        assert_eq!(channel_at(&interpolator, 40), 1210);
+
+        let mut failsafe = stamped(1500, 28);
+        failsafe.packet.flags.failsafe = true;
+        interpolator.push(failsafe);
+        assert_eq!(channel_at(&interpolator, 30), 1500);
+    }
+}
@@ -8,7 +8,41 @@
 //!
 //! - `blocking`: Enables blocking I/O operations (enabled by default)
 //! - `async`: Enables async I/O operations
-//! - `std`: Enables standard library features
+//! - `embedded-hal`: Enables `ServoOutputs`, driving `embedded-hal` PWM pins from channels
+//! - `embassy`: Enables a ready-made receiver task publishing packets to `embassy-sync` primitives
+//! - `heapless`: Enables a parser draining bytes from a `heapless::spsc` queue
+//! - `nb`: Enables a parser for `embedded-hal-nb` non-blocking UART reads
+//! - `memchr`: Uses the `memchr` crate to search for frame headers while resynchronising
+//! - `stream`: Enables a `futures_core::Stream` adapter for the async parser
+//! - `serialport`: Enables opening parsers and writers on serial ports with the `serialport` crate,
+//!   and on Unix a pseudo-terminal acting as a fake receiver for simulators
+//! - `tokio`: Enables opening async parsers and writers on serial ports with the `tokio-serial` crate
+//! - `alloc`: Enables `Vec`-returning conveniences without `std`, such as `parse_all_vec` and
+//!   recording sessions into memory with `SbusLogBuffer`
+//! - `std`: Enables standard library features, such as recording sessions with `SbusRecorder`
+//!   exporting them as CSV or InfluxDB line protocol, importing logic analyzer captures, and
+//!   sending packets over UDP and TCP, and mapping gamepad input to channels
+//! - `cli`: Builds the `sbus-mon` binary, monitoring, recording and replaying SBUS links on
+//!   serial ports
+//! - `defmt-03`: Implements `defmt::Format` for packets, errors, stats and the other value types
+//! - `defmt-trace`: Logs parser events such as losing and regaining sync with `defmt`, e.g. over
+//!   RTT
+//! - `error-context`: Keeps the last rejected frame, see `Deframer::last_rejected_frame` and
+//!   the parsers' `last_rejected_frame`
+//! - `ffi`: Enables `extern "C"` functions feeding a deframer and decoding and encoding
+//!   frames, for C firmware
+//! - `fport`: Enables decoding FrSky FPort control and telemetry frames
+//! - `mavlink`: Enables conversions between packets and the channel fields of MAVLink
+//!   `RC_CHANNELS` and `RC_CHANNELS_OVERRIDE` messages
+//! - `ros2`: Enables conversions between packets and the fields of ROS 2 `sensor_msgs/Joy`
+//!   messages
+//! - `zerocopy`: Implements the `zerocopy` traits for `SbusRawFrame` and `SbusRawPacket`
+//! - `test-util`: Enables the `test_util` module with frame helpers and `MockSbusSource` for
+//!   testing code built on the parsers
+//! - `proptest`: Enables the `strategies` module with `proptest` strategies for packets and
+//!   frames
+//! - `spec-vectors`: Enables the `spec_vectors` module with frames built from the protocol
+//!   specification and their decoded values
 //!
 //! ## Example
 //!
@@ -40,13 +74,170 @@
 //! - 1 byte of flags
 //! - End byte (0x00)
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// Logs a parser event at the given `defmt` level with the `defmt-trace` feature
+macro_rules! defmt_event {
+    ($level:ident, $($arg:tt)*) => {
+        #[cfg(feature = "defmt-trace")]
+        defmt::$level!($($arg)*);
+    };
+}
+
+pub use bitbang::*;
+pub use calibration::*;
+#[cfg(feature = "std")]
+pub use capture::*;
+pub use channel::*;
+pub use channel_map::*;
+pub use crsf::*;
+pub use delta::*;
+pub use detect::*;
+pub use diff::*;
+#[cfg(feature = "embassy")]
+pub use embassy::*;
+pub use envelope::*;
 pub use error::*;
+#[cfg(feature = "std")]
+pub use export::*;
+pub use failsafe::*;
+#[cfg(feature = "ffi")]
+pub use ffi::*;
+pub use filter::*;
+#[cfg(feature = "fport")]
+pub use fport::*;
+pub use frame::*;
+pub use frame_rate::*;
+#[cfg(feature = "std")]
+pub use gamepad::*;
+pub use gesture::*;
+pub use ibus::*;
+pub use interpolation::*;
+pub use link_quality::*;
+#[cfg(feature = "alloc")]
+pub use log::*;
+#[cfg(target_has_atomic = "32")]
+pub use mailbox::*;
+#[cfg(feature = "mavlink")]
+pub use mavlink::*;
+pub use mixer::*;
+#[cfg(feature = "std")]
+pub use net::*;
 pub use packet::*;
 pub use parser::*;
-
+pub use pipeline::*;
+pub use ppm::*;
+pub use proxy::*;
+pub use raw::*;
+pub use receiver::*;
+#[cfg(feature = "std")]
+pub use recording::*;
+pub use redundancy::*;
+pub use remap::*;
+#[cfg(feature = "ros2")]
+pub use ros2::*;
+pub use rssi::*;
+pub use scheduler::*;
+#[cfg(any(feature = "serialport", feature = "tokio"))]
+pub use serial::*;
+#[cfg(feature = "embedded-hal")]
+pub use servo::*;
+pub use simulator::*;
+pub use stats::*;
+pub use switch::*;
+pub use timestamp::*;
+pub use trainer::*;
+#[cfg(feature = "async")]
+pub use transceiver::*;
+pub use transform::*;
+#[cfg(feature = "async")]
+pub use transmitter::*;
+pub use uart::*;
+pub use validation::*;
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub use writer::*;
+
+mod bitbang;
+mod calibration;
+#[cfg(feature = "std")]
+mod capture;
+mod channel;
+mod channel_map;
+mod crsf;
+mod delta;
+mod detect;
+mod diff;
+#[cfg(feature = "embassy")]
+mod embassy;
+mod envelope;
 mod error;
+#[cfg(feature = "std")]
+mod export;
+mod failsafe;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod filter;
+#[cfg(any(test, feature = "test-util"))]
+mod fixtures;
+#[cfg(feature = "fport")]
+mod fport;
+mod frame;
+mod frame_rate;
+#[cfg(feature = "std")]
+mod gamepad;
+mod gesture;
+mod ibus;
+mod interpolation;
+mod link_quality;
+#[cfg(feature = "alloc")]
+mod log;
+#[cfg(target_has_atomic = "32")]
+mod mailbox;
+#[cfg(feature = "mavlink")]
+mod mavlink;
+mod mixer;
+#[cfg(feature = "std")]
+mod net;
 mod packet;
 mod parser;
+mod pipeline;
+mod ppm;
+mod proxy;
+mod raw;
+mod receiver;
+#[cfg(feature = "std")]
+mod recording;
+mod redundancy;
+mod remap;
+#[cfg(feature = "ros2")]
+mod ros2;
+mod rssi;
+mod scheduler;
+#[cfg(any(feature = "serialport", feature = "tokio"))]
+mod serial;
+#[cfg(feature = "embedded-hal")]
+mod servo;
+mod simulator;
+mod stats;
+#[cfg(feature = "proptest")]
+pub mod strategies;
+mod switch;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+#[cfg(feature = "spec-vectors")]
+pub mod spec_vectors;
+mod timestamp;
+mod trainer;
+#[cfg(feature = "async")]
+mod transceiver;
+mod transform;
+#[cfg(feature = "async")]
+mod transmitter;
+mod uart;
+mod validation;
+#[cfg(any(feature = "blocking", feature = "async"))]
+mod writer;
 
 #[inline(always)]
 pub const fn channels_parsing(buffer: &[u8; SBUS_FRAME_LENGTH]) -> [u16; CHANNEL_COUNT] {
@@ -74,8 +265,68 @@ pub const fn channels_parsing(buffer: &[u8; SBUS_FRAME_LENGTH]) -> [u16; CHANNEL
     ]
 }
 
+/// Decodes a single proportional channel from the data bytes of `buffer`, with `channel` from
+/// 0 to 15
+///
+/// Only the two or three bytes holding the channel are read, for paths that need one channel
+/// as fast as possible. The frame is not validated. Panics if `channel` is not less than
+/// [`CHANNEL_COUNT`].
+#[inline(always)]
+pub const fn channel_parsing(buffer: &[u8; SBUS_FRAME_LENGTH], channel: usize) -> u16 {
+    assert!(channel < CHANNEL_COUNT, "channel index out of range");
+    let bit = channel * 11;
+    let byte = 1 + bit / 8;
+    // The third byte is at most the flag byte, and masked out when not needed
+    let bits =
+        (buffer[byte] as u32) | (buffer[byte + 1] as u32) << 8 | (buffer[byte + 2] as u32) << 16;
+    (bits >> (bit % 8)) as u16 & CHANNEL_MAX
+}
+
+/// Decodes only the proportional channels selected by `mask` from the data bytes of `buffer`,
+/// leaving the others at 0
+///
+/// Bit `n` of `mask` selects the channel with index `n`. Consumers using only a handful of
+/// channels save most of the decoding work, a full mask decodes like [`channels_parsing`].
+pub const fn channels_parsing_masked(
+    buffer: &[u8; SBUS_FRAME_LENGTH],
+    mask: u16,
+) -> [u16; CHANNEL_COUNT] {
+    if mask == CHANNEL_MASK_ALL {
+        return channels_parsing(buffer);
+    }
+    let mut channels = [0; CHANNEL_COUNT];
+    let mut remaining = mask;
+    while remaining != 0 {
+        let channel = remaining.trailing_zeros() as usize;
+        channels[channel] = channel_parsing(buffer, channel);
+        remaining &= remaining - 1;
+    }
+    channels
+}
+
+/// Decodes only the first `N` proportional channels from the data bytes of `buffer`
+///
+/// For small MCUs that use a few channels, which then skip decoding and storing the others.
+/// `N` above [`CHANNEL_COUNT`] fails to compile.
+pub const fn first_channels_parsing<const N: usize>(buffer: &[u8; SBUS_FRAME_LENGTH]) -> [u16; N] {
+    const { assert!(N <= CHANNEL_COUNT, "SBUS frames carry 16 channels") };
+    let mut channels = [0; N];
+    let mut channel = 0;
+    while channel < N {
+        channels[channel] = channel_parsing(buffer, channel);
+        channel += 1;
+    }
+    channels
+}
+
+/// Packs the channels into the data bytes of `buffer`, clearing the flag byte and leaving header
+/// and footer alone
+///
+/// Only the low 11 bits of each channel are encoded, larger values are truncated. Use
+/// [`try_pack_channels`] or [`pack_channels_saturating`] when values may exceed
+/// [`CHANNEL_MAX`].
 #[inline(always)]
-pub fn pack_channels(buffer: &mut [u8; SBUS_FRAME_LENGTH], channels: &[u16; CHANNEL_COUNT]) {
+pub const fn pack_channels(buffer: &mut [u8; SBUS_FRAME_LENGTH], channels: &[u16; CHANNEL_COUNT]) {
     // Clear the buffer first (except header and footer)
     let mut i = 1;
     while i < SBUS_FRAME_LENGTH - 1 {
@@ -155,6 +406,62 @@ pub fn pack_channels(buffer: &mut [u8; SBUS_FRAME_LENGTH], channels: &[u16; CHAN
     buffer[22] = ((ch[15] >> 3) & 0xFF) as u8;
 }
 
+/// Like [`pack_channels`], but fails with [`SbusError::OutOfRange`] on the first channel above
+/// [`CHANNEL_MAX`], leaving `buffer` untouched
+pub const fn try_pack_channels(
+    buffer: &mut [u8; SBUS_FRAME_LENGTH],
+    channels: &[u16; CHANNEL_COUNT],
+) -> Result<(), SbusError> {
+    let mut channel = 0;
+    while channel < CHANNEL_COUNT {
+        let value = channels[channel];
+        if value > CHANNEL_MAX {
+            return Err(SbusError::OutOfRange { channel, value });
+        }
+        channel += 1;
+    }
+    pack_channels(buffer, channels);
+    Ok(())
+}
+
+// Packing must be the exact inverse of `channels_parsing` and `channel_parsing`, checked at compile time for a few
+// reference patterns
+const _: () = {
+    const PATTERNS: [[u16; CHANNEL_COUNT]; 4] = [
+        [0; CHANNEL_COUNT],
+        [CHANNEL_MAX; CHANNEL_COUNT],
+        [
+            0, 2047, 0, 2047, 0, 2047, 0, 2047, 0, 2047, 0, 2047, 0, 2047, 0, 2047,
+        ],
+        [
+            1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 172, 992, 1811, 1365, 682,
+        ],
+    ];
+
+    let mut pattern = 0;
+    while pattern < PATTERNS.len() {
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        pack_channels(&mut buffer, &PATTERNS[pattern]);
+        let decoded = channels_parsing(&buffer);
+
+        let mut channel = 0;
+        while channel < CHANNEL_COUNT {
+            assert!(decoded[channel] == PATTERNS[pattern][channel]);
+            assert!(channel_parsing(&buffer, channel) == PATTERNS[pattern][channel]);
+            channel += 1;
+        }
+        pattern += 1;
+    }
+};
+
+/// Like [`pack_channels`], but clamps channels above [`CHANNEL_MAX`] instead of truncating them
+pub fn pack_channels_saturating(
+    buffer: &mut [u8; SBUS_FRAME_LENGTH],
+    channels: &[u16; CHANNEL_COUNT],
+) {
+    pack_channels(buffer, &channels.map(|value| value.min(CHANNEL_MAX)));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +499,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pack_keeps_header_and_footer() {
+        let mut buffer = [0xFF; SBUS_FRAME_LENGTH];
+        pack_channels(&mut buffer, &[0; CHANNEL_COUNT]);
+        assert_eq!(buffer[0], 0xFF);
+        assert_eq!(buffer[1..24], [0; 23]);
+        assert_eq!(buffer[24], 0xFF);
+    }
+
     #[test]
     fn test_parse_pack_inverse_property() {
         let test_patterns = [
@@ -271,4 +587,63 @@ mod tests {
                 });
         }
     }
+
+    #[test]
+    fn test_channels_parsing_masked() {
+        let channels: [u16; CHANNEL_COUNT] = core::array::from_fn(|i| i as u16 * 100 + 7);
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        pack_channels(&mut buffer, &channels);
+
+        assert_eq!(channels_parsing_masked(&buffer, CHANNEL_MASK_ALL), channels);
+        assert_eq!(channels_parsing_masked(&buffer, 0), [0; CHANNEL_COUNT]);
+        let decoded = channels_parsing_masked(&buffer, 0b0000_0000_0011_1010);
+        for (i, &value) in decoded.iter().enumerate() {
+            let selected = [1, 3, 4, 5].contains(&i);
+            assert_eq!(value, if selected { channels[i] } else { 0 });
+        }
+    }
+
+    #[test]
+    fn test_first_channels_parsing() {
+        let channels: [u16; CHANNEL_COUNT] = core::array::from_fn(|i| 2047 - i as u16 * 100);
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        pack_channels(&mut buffer, &channels);
+
+        assert_eq!(first_channels_parsing::<4>(&buffer), channels[..4]);
+        assert_eq!(first_channels_parsing::<16>(&buffer), channels);
+        assert_eq!(first_channels_parsing::<0>(&buffer), [0u16; 0]);
+    }
+
+    #[test]
+    fn test_try_pack_channels_out_of_range() {
+        let mut channels = [CHANNEL_MAX; CHANNEL_COUNT];
+        channels[5] = CHANNEL_MAX + 1;
+
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        assert_eq!(
+            try_pack_channels(&mut buffer, &channels),
+            Err(SbusError::OutOfRange {
+                channel: 5,
+                value: CHANNEL_MAX + 1
+            })
+        );
+        assert_eq!(buffer, [0u8; SBUS_FRAME_LENGTH]);
+
+        channels[5] = CHANNEL_MAX;
+        assert_eq!(try_pack_channels(&mut buffer, &channels), Ok(()));
+        assert_eq!(channels_parsing(&buffer), channels);
+    }
+
+    #[test]
+    fn test_pack_channels_saturating() {
+        let mut channels = [0u16; CHANNEL_COUNT];
+        channels[3] = u16::MAX;
+
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        pack_channels_saturating(&mut buffer, &channels);
+
+        let mut expected = [0u16; CHANNEL_COUNT];
+        expected[3] = CHANNEL_MAX;
+        assert_eq!(channels_parsing(&buffer), expected);
+    }
 }
@@ -0,0 +1,188 @@
+use core::time::Duration;
+
+use crate::SbusPacket;
+
+/// Fixed point scale used for the quality average
+const SCALE: i32 = 256;
+const MAX_QUALITY: i32 = 100 * SCALE;
+/// Missed frames inferred from a single gap are capped so one long outage doesn't stall updates
+const MAX_MISSED_PER_GAP: u32 = 64;
+
+/// Link quality estimator producing a 0-100% figure from a stream of packets
+///
+/// Every received packet counts as a good sample unless its `frame_lost` or `failsafe` flag is
+/// set. When an expected frame interval is configured and packets are timestamped, gaps larger
+/// than the interval count as missed frames. Samples are combined with an exponentially
+/// weighted moving average, so the quality starts at 0% and ramps up as good frames arrive.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{LinkQuality, SbusPacket};
+///
+/// let mut lq = LinkQuality::new();
+/// let packet = SbusPacket::from_array(&[
+///     0x0F, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+/// ])
+/// .unwrap();
+///
+/// for _ in 0..100 {
+///     lq.update(&packet);
+/// }
+/// assert!(lq.quality() > 95);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct LinkQuality {
+    quality: i32,
+    smoothing_shift: u8,
+    expected_interval: Option<Duration>,
+    last_timestamp: Option<Duration>,
+}
+
+impl Default for LinkQuality {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinkQuality {
+    /// Smoothing used by [`LinkQuality::new`], each sample has a weight of 1/8
+    pub const DEFAULT_SMOOTHING_SHIFT: u8 = 3;
+
+    pub const fn new() -> Self {
+        Self {
+            quality: 0,
+            smoothing_shift: Self::DEFAULT_SMOOTHING_SHIFT,
+            expected_interval: None,
+            last_timestamp: None,
+        }
+    }
+
+    /// Creates an estimator that treats gaps between timestamped packets larger than
+    /// `interval` as missed frames
+    ///
+    /// The interval usually is the [`SbusFrameRate::interval`](crate::SbusFrameRate::interval)
+    /// of the link.
+    pub const fn with_expected_interval(interval: Duration) -> Self {
+        let mut lq = Self::new();
+        lq.expected_interval = Some(interval);
+        lq
+    }
+
+    /// Sets the weight of each sample to `1 / 2^shift`; larger values smooth more
+    pub const fn with_smoothing_shift(mut self, shift: u8) -> Self {
+        self.smoothing_shift = if shift > 15 { 15 } else { shift };
+        self
+    }
+
+    /// Records a received packet and returns the updated quality
+    pub fn update(&mut self, packet: &SbusPacket) -> u8 {
+        let good = !packet.flags.frame_lost && !packet.flags.failsafe;
+        self.sample(good);
+        self.quality()
+    }
+
+    /// Records a packet received at `timestamp`, measured from any fixed epoch, and returns the
+    /// updated quality
+    ///
+    /// Frames missing between this and the previous timestamped packet are counted first.
+    pub fn update_at(&mut self, packet: &SbusPacket, timestamp: Duration) -> u8 {
+        if let (Some(interval), Some(last)) = (self.expected_interval, self.last_timestamp) {
+            let gap = timestamp.saturating_sub(last);
+            if !interval.is_zero() && gap > interval {
+                // A frame is only missed once the gap reaches 1.5 intervals, to tolerate jitter
+                let missed = (gap + interval / 2).as_nanos() / interval.as_nanos() - 1;
+                self.record_missed(u32::try_from(missed).unwrap_or(u32::MAX));
+            }
+        }
+        self.last_timestamp = Some(timestamp);
+        self.update(packet)
+    }
+
+    /// Records frames known to be missing, e.g. after a read timeout
+    pub fn record_missed(&mut self, count: u32) {
+        for _ in 0..count.min(MAX_MISSED_PER_GAP) {
+            self.sample(false);
+        }
+    }
+
+    /// The current link quality in percent
+    pub fn quality(&self) -> u8 {
+        ((self.quality + SCALE / 2) / SCALE) as u8
+    }
+
+    /// Resets the estimator to 0% and forgets the last timestamp
+    pub fn reset(&mut self) {
+        self.quality = 0;
+        self.last_timestamp = None;
+    }
+
+    fn sample(&mut self, good: bool) {
+        let target = if good { MAX_QUALITY } else { 0 };
+        self.quality += (target - self.quality) >> self.smoothing_shift;
+        // The shift truncates towards negative infinity, so make sure 100% stays reachable
+        if good && MAX_QUALITY - self.quality < (1 << self.smoothing_shift) {
+            self.quality = MAX_QUALITY;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::packet_with_flags, Flags};
+
+    fn packet(frame_lost: bool, failsafe: bool) -> SbusPacket {
+        packet_with_flags(
+            1000,
+            Flags {
+                failsafe,
+                frame_lost,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_quality_converges() {
+        let mut lq = LinkQuality::new();
+        assert_eq!(lq.quality(), 0);
+
+        for _ in 0..200 {
+            lq.update(&packet(false, false));
+        }
+        assert_eq!(lq.quality(), 100);
+
+        for _ in 0..200 {
+            lq.update(&packet(true, false));
+        }
+        assert_eq!(lq.quality(), 0);
+    }
+
+    #[test]
+    fn test_failsafe_counts_as_bad() {
+        let mut lq = LinkQuality::new();
+        for _ in 0..200 {
+            lq.update(&packet(false, false));
+        }
+        let quality = lq.update(&packet(false, true));
+        assert!(quality < 100);
+    }
+
+    #[test]
+    fn test_gaps_count_as_missed_frames() {
+        let interval = Duration::from_millis(14);
+        let mut on_time = LinkQuality::with_expected_interval(interval);
+        let mut gappy = LinkQuality::with_expected_interval(interval);
+
+        for i in 0..200 {
+            on_time.update_at(&packet(false, false), interval * i);
+            // Every other frame is missing
+            gappy.update_at(&packet(false, false), interval * 2 * i);
+        }
+
+        assert_eq!(on_time.quality(), 100);
+        assert!((45..=55).contains(&gappy.quality()));
+    }
+}
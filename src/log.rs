@@ -0,0 +1,349 @@
+//! Log structures shared by the recorders, available without `std`
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::{SbusError, SbusPacket, SBUS_FRAME_LENGTH, SBUS_HEADER};
+
+/// First bytes of every log, followed by the format version
+pub(crate) const MAGIC: &[u8; 7] = b"SBUSLOG";
+pub(crate) const VERSION: u8 = 1;
+
+pub(crate) const RECORD_FRAME: u8 = 0;
+pub(crate) const RECORD_ERROR: u8 = 1;
+
+/// Length of an encoded error, see [`encode_error`]
+pub(crate) const ERROR_LENGTH: usize = 5;
+
+/// A record of a log, read back by `SbusPlayer::next_record` with the `std` feature
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum LogRecord {
+    /// A raw frame, which may not decode
+    Frame {
+        frame: [u8; SBUS_FRAME_LENGTH],
+        timestamp: Duration,
+    },
+    Error(ErrorEvent),
+}
+
+/// An error recorded by [`SbusLogBuffer::record_error`] or `SbusRecorder::record_error`
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct ErrorEvent {
+    /// Number of frames recorded before the error
+    pub frame: u64,
+    pub timestamp: Duration,
+    /// The error, reader and writer errors are recorded without their value, as
+    /// `SbusError::Read(())` and `SbusError::Write(())`
+    pub error: SbusError,
+}
+
+/// The kind byte, timestamp in microseconds and payload length starting every record
+pub(crate) fn record_header(kind: u8, timestamp: Duration, len: usize) -> [u8; 11] {
+    let micros = u64::try_from(timestamp.as_micros()).unwrap_or(u64::MAX);
+    let mut header = [0; 11];
+    header[0] = kind;
+    header[1..9].copy_from_slice(&micros.to_le_bytes());
+    header[9..].copy_from_slice(&(len as u16).to_le_bytes());
+    header
+}
+
+/// Records timestamped raw frames and errors into memory, in the log format of
+/// `SbusRecorder`
+///
+/// For targets with an allocator but without `std`, e.g. to record a flight on the device and
+/// replay the bytes of [`SbusLogBuffer::as_bytes`] with `SbusPlayer` on a PC.
+///
+/// # Example
+///
+/// ```rust
+/// use core::time::Duration;
+/// use sbus_rs::{SbusLogBuffer, SbusPacket};
+///
+/// let mut log = SbusLogBuffer::new();
+/// log.record_packet(&SbusPacket::neutral(), Duration::from_millis(7));
+/// assert_eq!(log.frames(), 1);
+/// assert_eq!(log.as_bytes().len(), 8 + 11 + 25);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct SbusLogBuffer {
+    bytes: Vec<u8>,
+    frames: u64,
+    errors: Vec<ErrorEvent>,
+}
+
+impl SbusLogBuffer {
+    /// Starts a log with its header
+    pub fn new() -> Self {
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 1);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        Self {
+            bytes,
+            frames: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Appends a raw frame received at `timestamp`
+    pub fn record_frame(&mut self, frame: &[u8; SBUS_FRAME_LENGTH], timestamp: Duration) {
+        self.push_record(RECORD_FRAME, timestamp, frame);
+        self.frames += 1;
+    }
+
+    /// Appends the frame encoding `packet`, with channels truncated to 11 bits
+    pub fn record_packet(&mut self, packet: &SbusPacket, timestamp: Duration) {
+        self.record_frame(&packet.to_array(), timestamp);
+    }
+
+    /// Appends an error that occurred at `timestamp` and adds it to the index
+    pub fn record_error<E>(&mut self, error: &SbusError<E>, timestamp: Duration) {
+        let error = without_reader_error(error);
+        self.push_record(RECORD_ERROR, timestamp, &encode_error(&error));
+        self.errors.push(ErrorEvent {
+            frame: self.frames,
+            timestamp,
+            error,
+        });
+    }
+
+    /// Appends the result of reading a frame
+    pub fn record_result<E>(
+        &mut self,
+        result: &Result<SbusPacket, SbusError<E>>,
+        timestamp: Duration,
+    ) {
+        match result {
+            Ok(packet) => self.record_packet(packet, timestamp),
+            Err(error) => self.record_error(error, timestamp),
+        }
+    }
+
+    /// Number of frames recorded so far
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// The errors recorded so far
+    pub fn error_index(&self) -> &[ErrorEvent] {
+        &self.errors
+    }
+
+    /// The log recorded so far
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    fn push_record(&mut self, kind: u8, timestamp: Duration, payload: &[u8]) {
+        self.bytes
+            .extend_from_slice(&record_header(kind, timestamp, payload.len()));
+        self.bytes.extend_from_slice(payload);
+    }
+}
+
+impl Default for SbusLogBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decodes every frame in a capture of back to back frames
+///
+/// Bytes before the first header are skipped. A frame that fails to decode is reported once,
+/// then the bytes are searched for the next frame that decodes, so header bytes within the
+/// rejected frame don't add errors. An incomplete frame at the end of a synchronised capture is
+/// reported as [`SbusError::UnexpectedEof`]. Meant for captures and logs; use a streaming
+/// parser for live links.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{parse_all_vec, SbusError, SbusPacket};
+///
+/// let mut bytes = vec![0xAA];
+/// bytes.extend_from_slice(&SbusPacket::neutral().to_array());
+/// bytes.extend_from_slice(&[0x0F, 0x00]);
+///
+/// let results = parse_all_vec(&bytes);
+/// assert_eq!(results, [Ok(SbusPacket::neutral()), Err(SbusError::UnexpectedEof)]);
+/// ```
+pub fn parse_all_vec(bytes: &[u8]) -> Vec<Result<SbusPacket, SbusError>> {
+    let next_header = |from: usize| {
+        bytes[from..]
+            .iter()
+            .position(|&byte| byte == SBUS_HEADER)
+            .map_or(bytes.len(), |offset| from + offset)
+    };
+
+    let mut results = Vec::with_capacity(bytes.len() / SBUS_FRAME_LENGTH);
+    let mut start = next_header(0);
+    let mut in_sync = true;
+    while start < bytes.len() {
+        let Some(frame) = bytes[start..].first_chunk::<SBUS_FRAME_LENGTH>() else {
+            if in_sync {
+                results.push(Err(SbusError::UnexpectedEof));
+            }
+            break;
+        };
+        match SbusPacket::from_array(frame) {
+            Ok(packet) => {
+                results.push(Ok(packet));
+                in_sync = true;
+                start = next_header(start + SBUS_FRAME_LENGTH);
+            }
+            Err(error) => {
+                if in_sync {
+                    results.push(Err(error));
+                }
+                in_sync = false;
+                start = next_header(start + 1);
+            }
+        }
+    }
+    results
+}
+
+#[allow(deprecated)]
+pub(crate) fn without_reader_error<E>(error: &SbusError<E>) -> SbusError {
+    match *error {
+        SbusError::ReadError | SbusError::Read(_) | SbusError::Io { write: false } => {
+            SbusError::Read(())
+        }
+        SbusError::Write(_) | SbusError::Io { write: true } => SbusError::Write(()),
+        SbusError::UnexpectedEof => SbusError::UnexpectedEof,
+        SbusError::InvalidHeader(header) => SbusError::InvalidHeader(header),
+        SbusError::InvalidFooter(footer) => SbusError::InvalidFooter(footer),
+        SbusError::Timeout => SbusError::Timeout,
+        SbusError::InvalidFlagByte(flags) => SbusError::InvalidFlagByte(flags),
+        SbusError::OutOfRange { channel, value } => SbusError::OutOfRange { channel, value },
+        SbusError::InvalidChannel(channel) => SbusError::InvalidChannel(channel),
+        SbusError::InvalidLength(len) => SbusError::InvalidLength(len),
+        SbusError::DesyncLimitExceeded(skipped) => SbusError::DesyncLimitExceeded(skipped),
+        SbusError::InvalidChecksum { expected, received } => {
+            SbusError::InvalidChecksum { expected, received }
+        }
+    }
+}
+
+/// Encodes an error as a code byte followed by two little-endian `u16` arguments
+#[allow(deprecated)]
+pub(crate) fn encode_error(error: &SbusError) -> [u8; ERROR_LENGTH] {
+    let (code, first, second) = match *error {
+        SbusError::ReadError | SbusError::Read(()) | SbusError::Io { write: false } => (0, 0, 0),
+        SbusError::Write(()) | SbusError::Io { write: true } => (11, 0, 0),
+        SbusError::UnexpectedEof => (1, 0, 0),
+        SbusError::InvalidHeader(header) => (2, header as u16, 0),
+        SbusError::InvalidFooter(footer) => (3, footer as u16, 0),
+        SbusError::Timeout => (4, 0, 0),
+        SbusError::InvalidFlagByte(flags) => (5, flags as u16, 0),
+        SbusError::OutOfRange { channel, value } => (6, channel as u16, value),
+        SbusError::InvalidChannel(channel) => (7, channel as u16, 0),
+        SbusError::InvalidLength(len) => (8, len as u16, 0),
+        SbusError::DesyncLimitExceeded(skipped) => {
+            let skipped = u32::try_from(skipped).unwrap_or(u32::MAX);
+            (9, skipped as u16, (skipped >> 16) as u16)
+        }
+        SbusError::InvalidChecksum { expected, received } => (10, expected, received),
+    };
+    let [a, b] = u16::to_le_bytes(first);
+    let [c, d] = u16::to_le_bytes(second);
+    [code, a, b, c, d]
+}
+
+// Logs are only read back by `SbusPlayer`
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+pub(crate) fn decode_error(payload: &[u8; ERROR_LENGTH]) -> Option<SbusError> {
+    let [code, a, b, c, d] = *payload;
+    let first = u16::from_le_bytes([a, b]);
+    let second = u16::from_le_bytes([c, d]);
+    Some(match code {
+        0 => SbusError::Read(()),
+        1 => SbusError::UnexpectedEof,
+        2 => SbusError::InvalidHeader(first as u8),
+        3 => SbusError::InvalidFooter(first as u8),
+        4 => SbusError::Timeout,
+        5 => SbusError::InvalidFlagByte(first as u8),
+        6 => SbusError::OutOfRange {
+            channel: first as usize,
+            value: second,
+        },
+        7 => SbusError::InvalidChannel(first as usize),
+        8 => SbusError::InvalidLength(first as usize),
+        9 => SbusError::DesyncLimitExceeded((second as usize) << 16 | first as usize),
+        10 => SbusError::InvalidChecksum {
+            expected: first,
+            received: second,
+        },
+        11 => SbusError::Write(()),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Channel;
+
+    #[test]
+    fn test_error_codes() {
+        for error in [
+            SbusError::InvalidHeader(0x0E),
+            SbusError::OutOfRange {
+                channel: 3,
+                value: 4000,
+            },
+            SbusError::DesyncLimitExceeded(70_000),
+            SbusError::Write(()),
+            SbusError::InvalidChecksum {
+                expected: 0x1234,
+                received: 0x4321,
+            },
+        ] {
+            assert_eq!(decode_error(&encode_error(&error)), Some(error));
+        }
+        assert_eq!(decode_error(&[12, 0, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn test_log_buffer() {
+        let mut log = SbusLogBuffer::new();
+        log.record_packet(&SbusPacket::neutral(), Duration::from_micros(0x0102));
+        log.record_result(
+            &Err(SbusError::<()>::InvalidFlagByte(0xF0)),
+            Duration::from_millis(1),
+        );
+
+        assert_eq!(log.frames(), 1);
+        assert_eq!(log.error_index()[0].frame, 1);
+        let bytes = log.into_bytes();
+        assert_eq!(bytes[..8], *b"SBUSLOG\x01");
+        assert_eq!(bytes[8], RECORD_FRAME);
+        assert_eq!(bytes[9..17], 0x0102u64.to_le_bytes());
+        assert_eq!(bytes[17..19], [25, 0]);
+        assert_eq!(bytes[bytes.len() - ERROR_LENGTH..], [5, 0xF0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_parse_all_vec() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch1] = 1500;
+        let mut corrupted = packet.to_array();
+        corrupted[SBUS_FRAME_LENGTH - 1] = 0xFF;
+
+        let mut bytes = vec![0xAA, 0x55];
+        bytes.extend_from_slice(&packet.to_array());
+        bytes.extend_from_slice(&corrupted);
+        bytes.extend_from_slice(&SbusPacket::neutral().to_array());
+
+        let results = parse_all_vec(&bytes);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], Ok(packet));
+        assert!(matches!(results[1], Err(SbusError::InvalidFooter(0xFF))));
+        assert_eq!(results[2], Ok(SbusPacket::neutral()));
+        assert!(parse_all_vec(&[0xAA; 40]).is_empty());
+    }
+}
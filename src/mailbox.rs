@@ -0,0 +1,179 @@
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+
+use crate::{channels_parsing, Flags, SbusPacket, SBUS_FRAME_LENGTH};
+
+/// Words needed to hold the 23 payload bytes of a frame
+const WORDS: usize = (SBUS_FRAME_LENGTH - 2).div_ceil(4);
+
+/// A packet read from an [`SbusMailbox`] along with its sequence number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SbusMailboxEntry {
+    pub packet: SbusPacket,
+    /// Number of packets published up to and including this one, wrapping at `u32::MAX / 2`
+    pub sequence: u32,
+}
+
+impl SbusMailboxEntry {
+    /// Number of packets published between the entry with sequence `previous` and this one
+    pub fn missed_since(&self, previous: u32) -> u32 {
+        self.sequence.wrapping_sub(previous).saturating_sub(1) & (u32::MAX >> 1)
+    }
+}
+
+/// Lock-free cell holding the latest packet, shared between an interrupt and the main loop
+///
+/// The mailbox is a seqlock: publishing never blocks, and reading retries if it raced with a
+/// publish, so neither side needs a critical section or allocation. Only one context may publish;
+/// any number may read. The packet is stored packed in atomics, so the mailbox can live in a
+/// `static`.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{SbusMailbox, SbusPacket};
+///
+/// static MAILBOX: SbusMailbox = SbusMailbox::new();
+///
+/// // In the UART interrupt, after decoding a packet
+/// MAILBOX.publish(&SbusPacket::neutral());
+///
+/// // In the control loop
+/// let mut last_sequence = 0;
+/// if let Some(entry) = MAILBOX.read() {
+///     let missed = entry.missed_since(last_sequence);
+///     last_sequence = entry.sequence;
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct SbusMailbox {
+    /// Twice the number of publishes, odd while a publish is in progress
+    seq: AtomicU32,
+    words: [AtomicU32; WORDS],
+}
+
+impl SbusMailbox {
+    pub const fn new() -> Self {
+        Self {
+            seq: AtomicU32::new(0),
+            words: [const { AtomicU32::new(0) }; WORDS],
+        }
+    }
+
+    /// Stores `packet` as the latest packet
+    ///
+    /// Must not be called from more than one context at a time.
+    pub fn publish(&self, packet: &SbusPacket) {
+        let frame = packet.to_array();
+        let mut payload = [0u8; WORDS * 4];
+        payload[..SBUS_FRAME_LENGTH - 2].copy_from_slice(&frame[1..SBUS_FRAME_LENGTH - 1]);
+
+        let seq = self.seq.load(Ordering::Relaxed);
+        self.seq.store(seq.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+        for (word, bytes) in self.words.iter().zip(payload.chunks_exact(4)) {
+            word.store(
+                u32::from_le_bytes(bytes.try_into().unwrap()),
+                Ordering::Relaxed,
+            );
+        }
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+    }
+
+    /// The latest packet, or `None` if nothing was published yet
+    ///
+    /// Spins while a publish is in progress, which only happens when reading from a context that
+    /// can run concurrently with the publisher.
+    pub fn read(&self) -> Option<SbusMailboxEntry> {
+        loop {
+            let seq = self.seq.load(Ordering::Acquire);
+            if seq & 1 == 1 {
+                core::hint::spin_loop();
+                continue;
+            }
+            if seq == 0 {
+                return None;
+            }
+
+            let mut payload = [0u8; WORDS * 4];
+            for (word, bytes) in self.words.iter().zip(payload.chunks_exact_mut(4)) {
+                bytes.copy_from_slice(&word.load(Ordering::Relaxed).to_le_bytes());
+            }
+
+            fence(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == seq {
+                let mut frame = [0u8; SBUS_FRAME_LENGTH];
+                frame[1..SBUS_FRAME_LENGTH - 1].copy_from_slice(&payload[..SBUS_FRAME_LENGTH - 2]);
+                let packet = SbusPacket {
+                    channels: channels_parsing(&frame),
+                    flags: Flags::from_byte(frame[SBUS_FRAME_LENGTH - 2]),
+                };
+                return Some(SbusMailboxEntry {
+                    packet,
+                    sequence: seq / 2,
+                });
+            }
+        }
+    }
+
+    /// Number of packets published so far, wrapping at `u32::MAX / 2`
+    pub fn sequence(&self) -> u32 {
+        self.seq.load(Ordering::Acquire) / 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_and_read() {
+        let mailbox = SbusMailbox::new();
+        assert_eq!(mailbox.read(), None);
+
+        let mut packet = SbusPacket::neutral();
+        packet.channels[2] = 172;
+        packet.flags.frame_lost = true;
+        mailbox.publish(&packet);
+
+        let entry = mailbox.read().unwrap();
+        assert_eq!(entry.packet, packet);
+        assert_eq!(entry.sequence, 1);
+    }
+
+    #[test]
+    fn test_missed_updates() {
+        let mailbox = SbusMailbox::new();
+        mailbox.publish(&SbusPacket::neutral());
+        let first = mailbox.read().unwrap();
+        assert_eq!(first.missed_since(0), 0);
+
+        for _ in 0..3 {
+            mailbox.publish(&SbusPacket::failsafe_default());
+        }
+        let latest = mailbox.read().unwrap();
+        assert_eq!(latest.packet, SbusPacket::failsafe_default());
+        assert_eq!(latest.missed_since(first.sequence), 2);
+        assert_eq!(mailbox.sequence(), 4);
+    }
+
+    #[test]
+    fn test_concurrent_reads_are_consistent() {
+        let mailbox = SbusMailbox::new();
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                for value in 0..2000u16 {
+                    let mut packet = SbusPacket::neutral();
+                    packet.channels = [value; 16];
+                    mailbox.publish(&packet);
+                }
+            });
+            for _ in 0..2000 {
+                if let Some(entry) = mailbox.read() {
+                    let first = entry.packet.channels[0];
+                    assert!(entry.packet.channels.iter().all(|&c| c == first));
+                }
+            }
+        });
+    }
+}
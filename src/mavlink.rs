@@ -0,0 +1,174 @@
+//! Conversions to and from the channel fields of MAVLink RC messages
+//!
+//! The structs mirror the fields of `RC_CHANNELS` (#65) and `RC_CHANNELS_OVERRIDE` (#70), so
+//! they can be copied into the message types of whichever MAVLink crate is in use. Channel
+//! values are pulse widths in microseconds, see [`channel_to_micros`].
+use crate::{channel_to_micros, micros_to_channel, Channel, SbusPacket, CHANNEL_FUTABA_MID};
+
+/// Number of channel fields in the MAVLink RC messages, covering all 18 SBUS channels
+pub const MAVLINK_CHANNEL_COUNT: usize = 18;
+/// `UINT16_MAX`, marking an unused channel field
+pub const MAVLINK_CHANNEL_UNUSED: u16 = u16::MAX;
+/// `UINT8_MAX`, marking an unknown RSSI
+pub const MAVLINK_RSSI_UNKNOWN: u8 = u8::MAX;
+
+/// The fields of a MAVLink `RC_CHANNELS` message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MavlinkRcChannels {
+    pub time_boot_ms: u32,
+    /// Number of channels received
+    pub chancount: u8,
+    /// `chan1_raw` to `chan18_raw` in microseconds
+    pub chan_raw: [u16; MAVLINK_CHANNEL_COUNT],
+    /// Receive signal strength from 0 to 254, [`MAVLINK_RSSI_UNKNOWN`] if unknown
+    pub rssi: u8,
+}
+
+impl MavlinkRcChannels {
+    /// Reports all 18 channels of `packet`, digital channels as 988 or 2012 µs
+    pub fn from_packet(packet: &SbusPacket, time_boot_ms: u32, rssi: Option<u8>) -> Self {
+        Self {
+            time_boot_ms,
+            chancount: MAVLINK_CHANNEL_COUNT as u8,
+            chan_raw: Channel::ALL.map(|channel| channel_to_micros(packet.value(channel))),
+            rssi: rssi.map_or(MAVLINK_RSSI_UNKNOWN, |rssi| {
+                rssi.min(MAVLINK_RSSI_UNKNOWN - 1)
+            }),
+        }
+    }
+
+    /// The packet carrying the reported channels
+    ///
+    /// Unused channels and those beyond `chancount` are centred, digital channels are set
+    /// above 1500 µs.
+    pub fn to_packet(&self) -> SbusPacket {
+        let mut packet = SbusPacket::neutral();
+        for (index, (channel, &micros)) in Channel::ALL.iter().zip(&self.chan_raw).enumerate() {
+            let used = index < self.chancount as usize && micros != MAVLINK_CHANNEL_UNUSED;
+            let value = if used {
+                micros_to_channel(micros)
+            } else {
+                CHANNEL_FUTABA_MID
+            };
+            if !packet.set_digital(*channel, value > CHANNEL_FUTABA_MID) {
+                packet[*channel] = value;
+            }
+        }
+        packet
+    }
+}
+
+/// The fields of a MAVLink `RC_CHANNELS_OVERRIDE` message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MavlinkRcChannelsOverride {
+    pub target_system: u8,
+    pub target_component: u8,
+    /// `chan1_raw` to `chan18_raw` in microseconds
+    ///
+    /// [`MAVLINK_CHANNEL_UNUSED`] leaves a channel alone. 0 releases channels 1 to 8 back to
+    /// the RC radio and leaves channels 9 to 18 alone, `UINT16_MAX - 1` releases channels 9
+    /// to 18.
+    pub chan_raw: [u16; MAVLINK_CHANNEL_COUNT],
+}
+
+impl MavlinkRcChannelsOverride {
+    /// Overrides all 18 channels with the values of `packet`
+    pub fn from_packet(packet: &SbusPacket, target_system: u8, target_component: u8) -> Self {
+        Self {
+            target_system,
+            target_component,
+            chan_raw: Channel::ALL.map(|channel| channel_to_micros(packet.value(channel))),
+        }
+    }
+
+    /// Overrides only the given channels, leaving the others alone
+    pub fn from_channels(
+        packet: &SbusPacket,
+        channels: &[Channel],
+        target_system: u8,
+        target_component: u8,
+    ) -> Self {
+        let mut message = Self::from_packet(packet, target_system, target_component);
+        for (channel, micros) in Channel::ALL.iter().zip(&mut message.chan_raw) {
+            if !channels.contains(channel) {
+                *micros = MAVLINK_CHANNEL_UNUSED;
+            }
+        }
+        message
+    }
+
+    /// Applies the override to `packet`, the packet received from the RC radio
+    ///
+    /// Fields that leave a channel alone or release it keep the value of `packet`.
+    pub fn apply(&self, packet: &SbusPacket) -> SbusPacket {
+        let mut packet = *packet;
+        for (channel, &micros) in Channel::ALL.iter().zip(&self.chan_raw) {
+            let ignored = micros == 0 || micros >= MAVLINK_CHANNEL_UNUSED - 1;
+            if ignored {
+                continue;
+            }
+            let value = micros_to_channel(micros);
+            if !packet.set_digital(*channel, value > CHANNEL_FUTABA_MID) {
+                packet[*channel] = value;
+            }
+        }
+        packet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MIN};
+
+    #[test]
+    fn test_rc_channels() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch1] = CHANNEL_FUTABA_MIN;
+        packet[Channel::Ch3] = CHANNEL_FUTABA_MAX;
+        packet.flags.d2 = true;
+
+        let message = MavlinkRcChannels::from_packet(&packet, 1234, None);
+        assert_eq!(message.chancount, 18);
+        assert_eq!(message.chan_raw[..3], [988, 1500, 2012]);
+        assert_eq!(message.chan_raw[16..], [988, 2012]);
+        assert_eq!(message.rssi, MAVLINK_RSSI_UNKNOWN);
+        assert_eq!(message.to_packet(), packet);
+
+        let mut partial = message;
+        partial.chancount = 2;
+        partial.chan_raw[1] = MAVLINK_CHANNEL_UNUSED;
+        let decoded = partial.to_packet();
+        assert_eq!(decoded[Channel::Ch1], CHANNEL_FUTABA_MIN);
+        assert_eq!(decoded[Channel::Ch2], CHANNEL_FUTABA_MID);
+        assert_eq!(decoded[Channel::Ch3], CHANNEL_FUTABA_MID);
+        assert!(!decoded.flags.d2);
+    }
+
+    #[test]
+    fn test_rc_channels_override() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch3] = CHANNEL_FUTABA_MAX;
+        packet.flags.d1 = true;
+
+        let message =
+            MavlinkRcChannelsOverride::from_channels(&packet, &[Channel::Ch3, Channel::Ch17], 1, 1);
+        assert_eq!(message.chan_raw[2], 2012);
+        assert_eq!(message.chan_raw[0], MAVLINK_CHANNEL_UNUSED);
+
+        let mut radio = SbusPacket::neutral();
+        radio[Channel::Ch1] = 500;
+        let applied = message.apply(&radio);
+        assert_eq!(applied[Channel::Ch1], 500);
+        assert_eq!(applied[Channel::Ch3], CHANNEL_FUTABA_MAX);
+        assert!(applied.flags.d1);
+
+        let mut release = message;
+        release.chan_raw[2] = 0;
+        assert_eq!(release.apply(&radio)[Channel::Ch3], CHANNEL_FUTABA_MID);
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    Channel, PacketTransform, SbusPacket, CHANNEL_COUNT, CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MID,
+    CHANNEL_FUTABA_MIN, CHANNEL_MAX,
+};
+
+/// The transform applied to a single channel by a [`Mixer`]
+///
+/// The value is taken as a deflection from [`CHANNEL_FUTABA_MID`], where full deflection is
+/// the distance to [`CHANNEL_FUTABA_MIN`] or [`CHANNEL_FUTABA_MAX`]. The deflection is curved
+/// by expo, scaled by the endpoint of its side, optionally reversed, and finally offset by the
+/// subtrim. The result is clamped to `0..=CHANNEL_MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelMix {
+    reverse: bool,
+    low: u8,
+    high: u8,
+    subtrim: i16,
+    expo: u8,
+}
+
+impl ChannelMix {
+    /// Passes the channel through unchanged
+    pub const IDENTITY: Self = Self {
+        reverse: false,
+        low: 100,
+        high: 100,
+        subtrim: 0,
+        expo: 0,
+    };
+
+    pub const fn new() -> Self {
+        Self::IDENTITY
+    }
+
+    /// Mirrors the channel around the centre
+    pub const fn with_reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
+    /// Scales the deflection below and above the centre, in percent of full deflection
+    pub const fn with_endpoints(mut self, low: u8, high: u8) -> Self {
+        self.low = low;
+        self.high = high;
+        self
+    }
+
+    /// Shifts the output by `subtrim` channel units
+    pub const fn with_subtrim(mut self, subtrim: i16) -> Self {
+        self.subtrim = subtrim;
+        self
+    }
+
+    /// Softens the response around the centre, from 0 (linear) to 100 percent (cubic)
+    ///
+    /// Values above 100 are treated as 100.
+    pub const fn with_expo(mut self, expo: u8) -> Self {
+        self.expo = if expo > 100 { 100 } else { expo };
+        self
+    }
+
+    /// Applies the transform to a channel value
+    pub const fn mix(&self, value: u16) -> u16 {
+        let mid = CHANNEL_FUTABA_MID as i64;
+        let deflection = value as i64 - mid;
+        let (full, endpoint) = if deflection < 0 {
+            (
+                (CHANNEL_FUTABA_MID - CHANNEL_FUTABA_MIN) as i64,
+                self.low as i64,
+            )
+        } else {
+            (
+                (CHANNEL_FUTABA_MAX - CHANNEL_FUTABA_MID) as i64,
+                self.high as i64,
+            )
+        };
+
+        // Blend of linear and cubic response: d * ((100 - k) + k * (d / full)^2) / 100
+        let expo = self.expo as i64;
+        let curved = deflection * ((100 - expo) * full * full + expo * deflection * deflection)
+            / (100 * full * full);
+
+        let scaled = curved * endpoint / 100;
+        let directed = if self.reverse { -scaled } else { scaled };
+        let output = mid + directed + self.subtrim as i64;
+
+        if output < 0 {
+            0
+        } else if output > CHANNEL_MAX as i64 {
+            CHANNEL_MAX
+        } else {
+            output as u16
+        }
+    }
+}
+
+impl Default for ChannelMix {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// Applies a [`ChannelMix`] to every proportional channel of a packet
+///
+/// Flags are kept. Use [`Remap`](crate::Remap) to move channels around.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{Channel, ChannelMix, Mixer, SbusPacket};
+///
+/// let mixer = Mixer::new()
+///     .with_channel(Channel::Ch1, ChannelMix::new().with_expo(30))
+///     .with_channel(Channel::Ch2, ChannelMix::new().with_reverse(true).with_subtrim(-8));
+///
+/// let output = mixer.apply(&SbusPacket::neutral());
+/// assert_eq!(output[Channel::Ch2], 984);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mixer {
+    channels: [ChannelMix; CHANNEL_COUNT],
+}
+
+impl Mixer {
+    /// Creates a mixer passing every channel through unchanged
+    pub const fn new() -> Self {
+        Self {
+            channels: [ChannelMix::IDENTITY; CHANNEL_COUNT],
+        }
+    }
+
+    /// Sets the transform of a proportional channel, digital channels are ignored
+    pub const fn with_channel(mut self, channel: Channel, mix: ChannelMix) -> Self {
+        if !channel.is_digital() {
+            self.channels[channel.index()] = mix;
+        }
+        self
+    }
+
+    /// The transform of a proportional channel, or `None` for the digital channels
+    pub fn channel_mut(&mut self, channel: Channel) -> Option<&mut ChannelMix> {
+        self.channels.get_mut(channel.index())
+    }
+
+    /// Returns the mixed copy of `packet`
+    pub fn apply(&self, packet: &SbusPacket) -> SbusPacket {
+        let mut output = *packet;
+        for (value, mix) in output.channels.iter_mut().zip(self.channels.iter()) {
+            *value = mix.mix(*value);
+        }
+        output
+    }
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PacketTransform for Mixer {
+    fn transform(&mut self, packet: &mut SbusPacket) {
+        *packet = Mixer::apply(self, packet);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: u16 = CHANNEL_FUTABA_MIN;
+    const MID: u16 = CHANNEL_FUTABA_MID;
+    const MAX: u16 = CHANNEL_FUTABA_MAX;
+
+    #[test]
+    fn test_identity() {
+        for value in [0, MIN, 500, MID, 1500, MAX, CHANNEL_MAX] {
+            assert_eq!(ChannelMix::IDENTITY.mix(value), value);
+        }
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mix = ChannelMix::new().with_reverse(true);
+        assert_eq!(mix.mix(MIN), MAX + 1);
+        assert_eq!(mix.mix(MID), MID);
+        assert_eq!(mix.mix(MAX), MIN + 1);
+    }
+
+    #[test]
+    fn test_endpoints_and_subtrim() {
+        let mix = ChannelMix::new().with_endpoints(50, 120).with_subtrim(10);
+        assert_eq!(mix.mix(MIN), MID - 410 + 10);
+        assert_eq!(mix.mix(MID), MID + 10);
+        assert_eq!(mix.mix(MAX), MID + 982 + 10);
+
+        let clamped = ChannelMix::new().with_endpoints(100, 150);
+        assert_eq!(clamped.mix(CHANNEL_MAX), CHANNEL_MAX);
+        assert_eq!(ChannelMix::new().with_subtrim(-100).mix(0), 0);
+    }
+
+    #[test]
+    fn test_expo() {
+        let mix = ChannelMix::new().with_expo(100);
+        assert_eq!(mix.mix(MIN), MIN);
+        assert_eq!(mix.mix(MID), MID);
+        assert_eq!(mix.mix(MAX), MAX);
+        // Half deflection is an eighth of full deflection with pure cubic response
+        assert_eq!(mix.mix(MID + 410), MID + 102);
+
+        let half = ChannelMix::new().with_expo(50);
+        let linear = 410;
+        let response = half.mix(MID + linear) - MID;
+        assert!(response > 102 && response < linear);
+    }
+
+    #[test]
+    fn test_mixer() {
+        let mut mixer =
+            Mixer::new().with_channel(Channel::Ch3, ChannelMix::new().with_reverse(true));
+        *mixer.channel_mut(Channel::Ch4).unwrap() = ChannelMix::new().with_subtrim(5);
+        assert!(mixer.channel_mut(Channel::Ch17).is_none());
+
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch3] = MIN;
+        packet.flags.failsafe = true;
+
+        let output = mixer.apply(&packet);
+        assert_eq!(output[Channel::Ch3], MAX + 1);
+        assert_eq!(output[Channel::Ch4], MID + 5);
+        assert_eq!(output[Channel::Ch1], MID);
+        assert!(output.flags.failsafe);
+    }
+}
@@ -0,0 +1,198 @@
+//! Sending and receiving packets over UDP and TCP sockets
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+
+use crate::{EnvelopeParser, SbusError, SbusPacket, SBUS_FRAME_LENGTH};
+
+/// A UDP socket carrying one raw SBUS frame per datagram
+///
+/// Datagrams are checksummed by UDP already, so frames are sent as they are, e.g. to and from
+/// a simulator running software in the loop.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use sbus_rs::{SbusPacket, SbusUdpSocket};
+///
+/// let socket = SbusUdpSocket::bind("0.0.0.0:0")?;
+/// socket.send_to(&SbusPacket::neutral(), "192.168.1.10:5760")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SbusUdpSocket {
+    socket: UdpSocket,
+}
+
+impl SbusUdpSocket {
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        UdpSocket::bind(addr).map(Self::from)
+    }
+
+    /// Sends `packet` to the address the socket is connected to
+    pub fn send(&self, packet: &SbusPacket) -> io::Result<()> {
+        self.socket.send(&packet.to_array()).map(drop)
+    }
+
+    pub fn send_to(&self, packet: &SbusPacket, addr: impl ToSocketAddrs) -> io::Result<()> {
+        self.socket.send_to(&packet.to_array(), addr).map(drop)
+    }
+
+    /// Receives the next datagram and decodes it as a frame
+    ///
+    /// Fails with [`SbusError::InvalidLength`] for datagrams that are not
+    /// [`SBUS_FRAME_LENGTH`] bytes long.
+    pub fn recv(&self) -> Result<SbusPacket, SbusError<io::Error>> {
+        self.recv_from().map(|(packet, _)| packet)
+    }
+
+    /// Like [`recv`](Self::recv), also returning the address of the sender
+    pub fn recv_from(&self) -> Result<(SbusPacket, SocketAddr), SbusError<io::Error>> {
+        // One byte more to tell oversized datagrams from frames
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH + 1];
+        let (len, addr) = self
+            .socket
+            .recv_from(&mut buffer)
+            .map_err(SbusError::Read)?;
+        let frame: &[u8; SBUS_FRAME_LENGTH] = buffer[..len]
+            .try_into()
+            .map_err(|_| SbusError::InvalidLength(len))?;
+        let packet = SbusPacket::from_array(frame).map_err(SbusError::with_reader_error)?;
+        Ok((packet, addr))
+    }
+
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    pub fn into_inner(self) -> UdpSocket {
+        self.socket
+    }
+}
+
+impl From<UdpSocket> for SbusUdpSocket {
+    fn from(socket: UdpSocket) -> Self {
+        Self { socket }
+    }
+}
+
+/// A TCP stream carrying frames wrapped into envelopes, see [`wrap_envelope`](crate::wrap_envelope)
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use sbus_rs::SbusTcpStream;
+///
+/// let mut stream = SbusTcpStream::connect("192.168.1.10:5761")?;
+/// while let Ok(packet) = stream.recv() {
+///     // Use the packet
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct SbusTcpStream {
+    stream: TcpStream,
+    parser: EnvelopeParser,
+    buffer: [u8; 64],
+    pos: usize,
+    len: usize,
+}
+
+impl SbusTcpStream {
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        TcpStream::connect(addr).map(Self::from)
+    }
+
+    pub fn send(&mut self, packet: &SbusPacket) -> io::Result<()> {
+        self.stream.write_all(&packet.to_envelope())
+    }
+
+    /// Reads up to the next envelope and decodes its payload as a frame
+    ///
+    /// Fails with [`SbusError::UnexpectedEof`] once the peer closed the connection, and with
+    /// the errors of [`EnvelopeParser::push_byte`] and [`Envelope::packet`](crate::Envelope::packet)
+    /// for rejected envelopes, after which the next call continues with the following bytes.
+    pub fn recv(&mut self) -> Result<SbusPacket, SbusError<io::Error>> {
+        loop {
+            while self.pos < self.len {
+                let byte = self.buffer[self.pos];
+                self.pos += 1;
+                if let Some(result) = self.parser.push_byte(byte) {
+                    return result
+                        .and_then(|envelope| envelope.packet())
+                        .map_err(SbusError::with_reader_error);
+                }
+            }
+            self.len = self
+                .stream
+                .read(&mut self.buffer)
+                .map_err(SbusError::Read)?;
+            self.pos = 0;
+            if self.len == 0 {
+                return Err(SbusError::UnexpectedEof);
+            }
+        }
+    }
+
+    pub fn stream(&self) -> &TcpStream {
+        &self.stream
+    }
+
+    pub fn into_inner(self) -> TcpStream {
+        self.stream
+    }
+}
+
+impl From<TcpStream> for SbusTcpStream {
+    fn from(stream: TcpStream) -> Self {
+        Self {
+            stream,
+            parser: EnvelopeParser::new(),
+            buffer: [0; 64],
+            pos: 0,
+            len: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Channel;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_udp() {
+        let receiver = SbusUdpSocket::bind("127.0.0.1:0").unwrap();
+        let sender = SbusUdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = receiver.socket().local_addr().unwrap();
+
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch3] = 1500;
+        sender.send_to(&packet, addr).unwrap();
+        let (received, from) = receiver.recv_from().unwrap();
+        assert_eq!(received, packet);
+        assert_eq!(from, sender.socket().local_addr().unwrap());
+
+        sender.socket().send_to(&[0x0F; 26], addr).unwrap();
+        assert!(matches!(receiver.recv(), Err(SbusError::InvalidLength(26))));
+    }
+
+    #[test]
+    fn test_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = SbusTcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let mut server = SbusTcpStream::from(listener.accept().unwrap().0);
+
+        let mut packet = SbusPacket::neutral();
+        for value in [100, 200, 300] {
+            packet[Channel::Ch1] = value;
+            client.send(&packet).unwrap();
+        }
+        for value in [100, 200, 300] {
+            assert_eq!(server.recv().unwrap()[Channel::Ch1], value);
+        }
+
+        drop(client);
+        assert!(matches!(server.recv(), Err(SbusError::UnexpectedEof)));
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{channels_parsing, SbusError, SBUS_FOOTER, SBUS_FOOTER_2, SBUS_FRAME_LENGTH, SBUS_HEADER};
+use crate::{
+    channels_parsing_masked, pack_channels, try_pack_channels, SbusError, SyncBytes,
+    ValidationPolicy, CHANNEL_COUNT, CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MIN, CHANNEL_MASK_ALL,
+    CHANNEL_MAX, SBUS_FOOTER, SBUS_FOOTER_2, SBUS_FRAME_LENGTH, SBUS_HEADER,
+};
 
 /// Represents a complete SBUS packet with channel data and flags
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -10,6 +14,48 @@ pub struct SbusPacket {
 }
 
 impl SbusPacket {
+    /// The [`neutral`](Self::neutral) packet, also returned by [`Default`]
+    pub const DEFAULT: Self = Self::neutral();
+
+    /// Creates a plain SBUS packet from its channels and flags
+    pub const fn new(channels: [u16; CHANNEL_COUNT], flags: Flags) -> Self {
+        Self { channels, flags }
+    }
+
+    /// A packet with all channels centred at [`CHANNEL_FUTABA_MID`] and no flags set
+    pub const fn neutral() -> Self {
+        Self::new(
+            [CHANNEL_FUTABA_MID; CHANNEL_COUNT],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )
+    }
+
+    /// A neutral packet with the throttle channel at [`CHANNEL_FUTABA_MIN`]
+    ///
+    /// `throttle` is the zero-based index of the throttle channel, e.g. 2 for AETR ordering and
+    /// 0 for TAER ordering.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `throttle` is not less than [`CHANNEL_COUNT`].
+    pub const fn neutral_throttle_low(throttle: usize) -> Self {
+        let mut packet = Self::neutral();
+        packet.channels[throttle] = CHANNEL_FUTABA_MIN;
+        packet
+    }
+
+    /// A neutral packet with the `failsafe` flag set, as a receiver would output on signal loss
+    pub const fn failsafe_default() -> Self {
+        let mut packet = Self::neutral();
+        packet.flags.failsafe = true;
+        packet
+    }
+
     /// Creates a new SbusPacket from a raw 25-byte SBUS frame
     ///
     /// # Arguments
@@ -21,32 +67,206 @@ impl SbusPacket {
     /// * `Ok(SbusPacket)` if the frame is valid
     /// * `Err(SbusError)` if the frame has invalid header or footer
     pub fn from_array(buffer: &[u8; SBUS_FRAME_LENGTH]) -> Result<Self, SbusError> {
-        SbusPacket::validate_frame(buffer)?;
+        Self::from_array_with(buffer, ValidationPolicy::Standard)
+    }
+
+    /// Like [`from_array`](Self::from_array), but also returns the [`FrameKind`] told apart by
+    /// the footer
+    pub fn from_array_with_kind(
+        buffer: &[u8; SBUS_FRAME_LENGTH],
+    ) -> Result<(Self, FrameKind), SbusError> {
+        let packet = Self::from_array(buffer)?;
+        Ok((packet, FrameKind::of_frame(buffer, SyncBytes::SBUS)))
+    }
+
+    /// Parses a raw frame, checking it according to `policy`
+    pub fn from_array_with(
+        buffer: &[u8; SBUS_FRAME_LENGTH],
+        policy: ValidationPolicy,
+    ) -> Result<Self, SbusError> {
+        Self::from_array_with_sync(buffer, policy, SyncBytes::SBUS)
+    }
+
+    /// Parses a raw frame framed by `sync` instead of the standard header and footer, checking
+    /// it according to `policy`
+    pub fn from_array_with_sync(
+        buffer: &[u8; SBUS_FRAME_LENGTH],
+        policy: ValidationPolicy,
+        sync: SyncBytes,
+    ) -> Result<Self, SbusError> {
+        Self::from_array_masked(buffer, policy, sync, CHANNEL_MASK_ALL)
+    }
+
+    /// Like [`from_array_with_sync`](Self::from_array_with_sync), but decodes only the
+    /// channels selected by `mask` and leaves the others at 0, see [`channels_parsing_masked`]
+    pub fn from_array_masked(
+        buffer: &[u8; SBUS_FRAME_LENGTH],
+        policy: ValidationPolicy,
+        sync: SyncBytes,
+        mask: u16,
+    ) -> Result<Self, SbusError> {
+        policy.validate_with_sync(buffer, sync)?;
 
         // Parse channels and flags
-        let channels = channels_parsing(buffer);
+        let channels = channels_parsing_masked(buffer, mask);
         let flags = Flags::from_byte(buffer[23]);
 
         Ok(Self { channels, flags })
     }
+
+    /// The first `N` proportional channels, for applications that only use those
+    ///
+    /// `N` above [`CHANNEL_COUNT`] fails to compile. To skip decoding the other channels as
+    /// well, use [`first_channels_parsing`](crate::first_channels_parsing) on the raw frame.
+    pub const fn first_channels<const N: usize>(&self) -> [u16; N] {
+        const { assert!(N <= CHANNEL_COUNT, "SBUS frames carry 16 channels") };
+        let mut channels = [0; N];
+        let mut channel = 0;
+        while channel < N {
+            channels[channel] = self.channels[channel];
+            channel += 1;
+        }
+        channels
+    }
+
+    /// Encodes the packet into a raw 25-byte SBUS frame with the standard header and footer
+    pub fn to_array(&self) -> [u8; SBUS_FRAME_LENGTH] {
+        self.to_array_with_kind(FrameKind::Sbus1)
+    }
+
+    /// Encodes the packet into a raw frame of `kind`, e.g. an SBUS2 frame of a telemetry slot
+    /// group
+    pub fn to_array_with_kind(&self, kind: FrameKind) -> [u8; SBUS_FRAME_LENGTH] {
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        buffer[0] = SBUS_HEADER;
+        pack_channels(&mut buffer, &self.channels);
+        buffer[23] = self.flags.to_byte();
+        buffer[SBUS_FRAME_LENGTH - 1] = kind.footer();
+        buffer
+    }
+
+    /// Like [`to_array`](Self::to_array), but fails with [`SbusError::OutOfRange`] instead of
+    /// truncating channels above [`CHANNEL_MAX`](crate::CHANNEL_MAX)
+    pub fn try_to_array(&self) -> Result<[u8; SBUS_FRAME_LENGTH], SbusError> {
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        buffer[0] = SBUS_HEADER;
+        try_pack_channels(&mut buffer, &self.channels)?;
+        buffer[23] = self.flags.to_byte();
+        buffer[SBUS_FRAME_LENGTH - 1] = SBUS_FOOTER;
+        Ok(buffer)
+    }
+
+    /// Sets the channel at the zero-based index `channel`
+    ///
+    /// Fails with [`SbusError::InvalidChannel`] if `channel` is not less than [`CHANNEL_COUNT`]
+    /// and with [`SbusError::OutOfRange`] if `value` exceeds [`CHANNEL_MAX`], leaving the packet
+    /// unchanged.
+    pub fn set_channel(&mut self, channel: usize, value: u16) -> Result<(), SbusError> {
+        let slot = self
+            .channels
+            .get_mut(channel)
+            .ok_or(SbusError::InvalidChannel(channel))?;
+        if value > CHANNEL_MAX {
+            return Err(SbusError::OutOfRange { channel, value });
+        }
+        *slot = value;
+        Ok(())
+    }
+
+    /// Like [`set_channel`](Self::set_channel), but clamps `value` to [`CHANNEL_MAX`]
+    pub fn set_channel_clamped(&mut self, channel: usize, value: u16) -> Result<(), SbusError> {
+        self.set_channel(channel, value.min(CHANNEL_MAX))
+    }
+
     /// Validates if header and footer and set correctly
     pub fn validate_frame(frame_buf: &[u8; SBUS_FRAME_LENGTH]) -> Result<(), SbusError> {
-        let header = frame_buf[0];
-        let footer = frame_buf[SBUS_FRAME_LENGTH - 1];
-
-        // Check header and footer
-        if header != SBUS_HEADER {
-            Err(SbusError::InvalidHeader(header))
-        } else if footer != SBUS_FOOTER &&  footer & 0x0F != SBUS_FOOTER_2 {
-            Err(SbusError::InvalidFooter(footer))
+        ValidationPolicy::Standard.validate(frame_buf)
+    }
+}
+
+impl Default for SbusPacket {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl TryFrom<&[u8]> for SbusPacket {
+    type Error = SbusError;
+
+    /// Parses a raw frame like [`SbusPacket::from_array`], failing with
+    /// [`SbusError::InvalidLength`] unless the slice is exactly [`SBUS_FRAME_LENGTH`] bytes long
+    fn try_from(buffer: &[u8]) -> Result<Self, Self::Error> {
+        let buffer: &[u8; SBUS_FRAME_LENGTH] = buffer
+            .try_into()
+            .map_err(|_| SbusError::InvalidLength(buffer.len()))?;
+        Self::from_array(buffer)
+    }
+}
+
+impl From<SbusPacket> for [u8; SBUS_FRAME_LENGTH] {
+    fn from(packet: SbusPacket) -> Self {
+        packet.to_array()
+    }
+}
+
+impl From<&SbusPacket> for [u8; SBUS_FRAME_LENGTH] {
+    fn from(packet: &SbusPacket) -> Self {
+        packet.to_array()
+    }
+}
+
+/// The variant of an SBUS frame, as told by its footer
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrameKind {
+    /// A plain SBUS frame with the footer `0x00`
+    #[default]
+    Sbus1,
+    /// An SBUS2 frame, followed by the telemetry slots of the given slot group
+    ///
+    /// Standard SBUS2 transmitters cycle through slot groups 0 to 3, with the footers `0x04`,
+    /// `0x14`, `0x24` and `0x34`.
+    Sbus2(u8),
+}
+
+impl FrameKind {
+    /// Classifies a footer byte, returning `None` if it is neither an SBUS nor an SBUS2 footer
+    pub const fn from_footer(footer: u8) -> Option<Self> {
+        if footer == SBUS_FOOTER {
+            Some(FrameKind::Sbus1)
+        } else if footer & 0x0F == SBUS_FOOTER_2 {
+            Some(FrameKind::Sbus2(footer >> 4))
         } else {
-            Ok(())
+            None
+        }
+    }
+
+    /// The kind of a frame framed by `sync`
+    ///
+    /// Only lenient validation lets unknown footers through, those count as plain SBUS.
+    pub(crate) const fn of_frame(frame: &[u8; SBUS_FRAME_LENGTH], sync: SyncBytes) -> Self {
+        let footer = frame[SBUS_FRAME_LENGTH - 1];
+        if footer == sync.footer {
+            return FrameKind::Sbus1;
+        }
+        match FrameKind::from_footer(footer) {
+            Some(kind) => kind,
+            None => FrameKind::Sbus1,
+        }
+    }
+
+    /// The footer byte of frames of this kind
+    pub const fn footer(self) -> u8 {
+        match self {
+            FrameKind::Sbus1 => SBUS_FOOTER,
+            FrameKind::Sbus2(slot_group) => (slot_group << 4) | SBUS_FOOTER_2,
         }
     }
 }
 
 /// Status flags contained in an SBUS frame
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Flags {
@@ -60,6 +280,20 @@ impl Flags {
     pub fn from_byte(flag_byte: u8) -> Self {
         Flags::from(flag_byte)
     }
+
+    /// Encodes the flags into an SBUS flag byte
+    pub const fn to_byte(&self) -> u8 {
+        (self.d1 as u8)
+            | (self.d2 as u8) << 1
+            | (self.frame_lost as u8) << 2
+            | (self.failsafe as u8) << 3
+    }
+}
+
+impl From<Flags> for u8 {
+    fn from(flags: Flags) -> Self {
+        flags.to_byte()
+    }
 }
 
 impl From<u8> for Flags {
@@ -72,3 +306,157 @@ impl From<u8> for Flags {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neutral_constructors() {
+        let neutral = SbusPacket::neutral();
+        assert_eq!(neutral.channels, [CHANNEL_FUTABA_MID; CHANNEL_COUNT]);
+        assert_eq!(neutral.flags, Flags::default());
+
+        let throttle_low = SbusPacket::neutral_throttle_low(2);
+        assert_eq!(throttle_low.channels[2], CHANNEL_FUTABA_MIN);
+        assert_eq!(throttle_low.channels[0], CHANNEL_FUTABA_MID);
+
+        let failsafe = SbusPacket::failsafe_default();
+        assert_eq!(failsafe.channels, neutral.channels);
+        assert!(failsafe.flags.failsafe);
+    }
+
+    #[test]
+    fn test_neutral_round_trips() {
+        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
+        buffer[0] = SBUS_HEADER;
+        buffer[SBUS_FRAME_LENGTH - 1] = SBUS_FOOTER;
+        pack_channels(&mut buffer, &SbusPacket::neutral().channels);
+
+        assert_eq!(
+            SbusPacket::from_array(&buffer).unwrap(),
+            SbusPacket::neutral()
+        );
+    }
+
+    #[test]
+    fn test_to_array_round_trips() {
+        for flag_byte in 0..=0x0F {
+            let mut packet = SbusPacket::neutral_throttle_low(2);
+            packet.flags = Flags::from_byte(flag_byte);
+
+            let frame = packet.to_array();
+            assert_eq!(frame[23], flag_byte);
+            assert_eq!(SbusPacket::from_array(&frame).unwrap(), packet);
+        }
+    }
+
+    #[test]
+    fn test_set_channel() {
+        let mut packet = SbusPacket::neutral();
+
+        assert_eq!(packet.set_channel(4, 1811), Ok(()));
+        assert_eq!(packet.channels[4], 1811);
+
+        assert_eq!(
+            packet.set_channel(4, 2048),
+            Err(SbusError::OutOfRange {
+                channel: 4,
+                value: 2048
+            })
+        );
+        assert_eq!(packet.channels[4], 1811);
+        assert_eq!(
+            packet.set_channel(16, 0),
+            Err(SbusError::InvalidChannel(16))
+        );
+
+        assert_eq!(packet.set_channel_clamped(4, u16::MAX), Ok(()));
+        assert_eq!(packet.channels[4], CHANNEL_MAX);
+        assert_eq!(
+            packet.set_channel_clamped(16, 0),
+            Err(SbusError::InvalidChannel(16))
+        );
+    }
+
+    #[test]
+    fn test_first_channels() {
+        let mut packet = SbusPacket::neutral();
+        packet.channels[2] = CHANNEL_FUTABA_MIN;
+        assert_eq!(
+            packet.first_channels::<3>(),
+            [CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MIN]
+        );
+        assert_eq!(packet.first_channels::<16>(), packet.channels);
+    }
+
+    #[test]
+    fn test_const_constructors() {
+        const FAILSAFE: SbusPacket = SbusPacket::new(
+            [CHANNEL_FUTABA_MIN; CHANNEL_COUNT],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: true,
+                frame_lost: true,
+            },
+        );
+        assert_eq!(FAILSAFE.channels, [CHANNEL_FUTABA_MIN; CHANNEL_COUNT]);
+        assert_eq!(FAILSAFE.flags.to_byte(), 0x0C);
+
+        assert_eq!(SbusPacket::default(), SbusPacket::DEFAULT);
+        assert_eq!(SbusPacket::DEFAULT, SbusPacket::neutral());
+    }
+
+    #[test]
+    fn test_into_array() {
+        let packet = SbusPacket::failsafe_default();
+
+        let from_ref: [u8; SBUS_FRAME_LENGTH] = (&packet).into();
+        let from_value: [u8; SBUS_FRAME_LENGTH] = packet.into();
+        assert_eq!(from_ref, packet.to_array());
+        assert_eq!(from_value, packet.to_array());
+    }
+
+    #[test]
+    fn test_try_from_slice() {
+        let packet = SbusPacket::neutral_throttle_low(2);
+        let frame = packet.to_array();
+
+        assert_eq!(SbusPacket::try_from(&frame[..]), Ok(packet));
+        assert_eq!(
+            SbusPacket::try_from(&frame[..24]),
+            Err(SbusError::InvalidLength(24))
+        );
+        assert_eq!(
+            SbusPacket::try_from(&[0u8; 26][..]),
+            Err(SbusError::InvalidLength(26))
+        );
+
+        let mut bad_header = frame;
+        bad_header[0] = 0xAA;
+        assert_eq!(
+            SbusPacket::try_from(&bad_header[..]),
+            Err(SbusError::InvalidHeader(0xAA))
+        );
+    }
+
+    #[test]
+    fn test_frame_kind() {
+        let mut buffer = SbusPacket::neutral().to_array();
+        assert_eq!(
+            SbusPacket::from_array_with_kind(&buffer),
+            Ok((SbusPacket::neutral(), FrameKind::Sbus1))
+        );
+
+        for slot_group in 0..4 {
+            buffer[SBUS_FRAME_LENGTH - 1] = (slot_group << 4) | SBUS_FOOTER_2;
+            let (packet, kind) = SbusPacket::from_array_with_kind(&buffer).unwrap();
+            assert_eq!(kind, FrameKind::Sbus2(slot_group));
+            assert_eq!(packet, SbusPacket::neutral());
+            assert_eq!(packet.to_array_with_kind(kind), buffer);
+        }
+
+        assert_eq!(FrameKind::from_footer(0x55), None);
+    }
+}
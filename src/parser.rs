@@ -1,8 +1,65 @@
 //! SBus Frame parser
 use core::marker::PhantomData;
 
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::packet::SbusPacket;
+use crate::{
+    stats::SbusStats,
+    validation::{SyncBytes, ValidationPolicy},
+};
+
+/// Implements the builder methods shared by the parsers keeping a [`Deframer`] in a `deframer`
+/// field, as `const fn`s rebuilding `Self` when invoked with `const`
+macro_rules! deframer_builders {
+    ($($const:tt)?) => {
+        deframer_builders! { @methods [$($const)?]
+            /// Flips every received byte before framing, for UARTs capturing SBUS without an
+            /// inverter
+            with_inverted set_inverted(inverted: bool);
+            /// Sets the header and footer marking frames, [`SyncBytes::SBUS`] by default
+            with_sync_bytes set_sync_bytes(sync: SyncBytes);
+            /// Sets how strictly frames are checked, [`ValidationPolicy::Standard`] by default
+            with_validation_policy set_validation_policy(policy: ValidationPolicy);
+            /// Rejects frames with reserved flag bits set, see [`Deframer::with_flag_check`]
+            with_flag_check set_flag_check(enabled: bool);
+            /// Decodes only the channels selected by `mask`, see [`Deframer::with_channel_mask`]
+            with_channel_mask set_channel_mask(mask: u16);
+            /// Hands out only every `n`th valid frame, see [`Deframer::with_every_nth`]
+            with_every_nth set_every_nth(n: u16);
+        }
+    };
+    (@methods [] $($(#[$doc:meta])* $with:ident $set:ident($arg:ident: $ty:ty);)*) => {
+        $(
+            $(#[$doc])*
+            pub fn $with(mut self, $arg: $ty) -> Self {
+                self.deframer.$set($arg);
+                self
+            }
+        )*
+    };
+    (@methods [const] $($(#[$doc:meta])* $with:ident $set:ident($arg:ident: $ty:ty);)*) => {
+        $(
+            $(#[$doc])*
+            pub const fn $with(self, $arg: $ty) -> Self {
+                Self {
+                    deframer: self.deframer.$with($arg),
+                }
+            }
+        )*
+    };
+}
+
 #[cfg(feature = "async")]
 mod asynch;
+#[cfg(any(feature = "blocking", feature = "async"))]
+mod buffered;
+mod deframer;
+#[cfg(feature = "nb")]
+mod nonblocking;
+mod push;
+mod scan;
+#[cfg(feature = "heapless")]
+mod spsc;
 
 #[cfg(feature = "async")]
 pub use asynch::SbusParserAsync;
@@ -11,13 +68,70 @@ pub use asynch::SbusParserAsync;
 pub mod blocking;
 #[cfg(feature = "blocking")]
 pub use blocking::SbusParser;
+#[cfg(feature = "blocking")]
+pub use buffered::SbusBufParser;
+#[cfg(feature = "async")]
+pub use buffered::SbusBufParserAsync;
+pub use deframer::{Deframer, DEFRAMER_BUFFER_SIZE};
+#[cfg(feature = "nb")]
+pub use nonblocking::SbusParserNb;
+pub use push::SBusPacketParser;
+#[cfg(feature = "heapless")]
+pub use spsc::SbusSpscParser;
 
+/// Parser reading SBUS frames back to back, without searching for a header
+///
+/// Settings and statistics are those of a [`Deframer`], like the other parsers.
 pub struct Parser<R, M: Mode> {
-    #[allow(dead_code)]
     reader: R,
+    /// Keeps the bytes of a partially read frame across cancelled async reads
+    deframer: Deframer,
     _mode: PhantomData<M>,
 }
 
+impl<R, M: Mode> Parser<R, M> {
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    fn with_reader(reader: R) -> Self {
+        Self {
+            reader,
+            deframer: Deframer::new(),
+            _mode: PhantomData,
+        }
+    }
+
+    deframer_builders!();
+
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// The reader, see [`SbusParser::reader_mut`](crate::SbusParser::reader_mut)
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the reader, dropping a partially read frame
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Statistics about the frames read so far
+    pub fn stats(&self) -> &SbusStats {
+        self.deframer.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.deframer.reset_stats();
+    }
+}
+
+/// Whether `packet` differs from the last handed out packet by more than `threshold` on any
+/// channel, or in its flags
+#[cfg(any(feature = "blocking", feature = "async"))]
+fn is_changed(last: Option<&SbusPacket>, packet: &SbusPacket, threshold: u16) -> bool {
+    last.is_none_or(|last| last.flags != packet.flags || !last.diff(packet, threshold).is_empty())
+}
+
 #[allow(private_bounds)]
 pub trait Mode: Sealed {}
 
@@ -28,9 +142,20 @@ pub const SBUS_HEADER: u8 = 0x0F;
 /// The SBus Frame footer should end with a zero byte `0x00` (0 decimal).
 pub const SBUS_FOOTER: u8 = 0x00;
 pub const SBUS_FOOTER_2: u8 = 0x04;
+/// The bits of the flag byte that carry flags, the remaining bits are reserved
+pub const FLAG_MASK: u8 = 0x0F;
 /// The SBus Frame length
 pub const SBUS_FRAME_LENGTH: usize = 25;
 /// The number of channels in a SBus Frame.
 pub const CHANNEL_COUNT: usize = 16;
+/// A channel mask selecting all 16 channels, see
+/// [`channels_parsing_masked`](crate::channels_parsing_masked).
+pub const CHANNEL_MASK_ALL: u16 = u16::MAX;
 /// The maximum value of a channel.
 pub const CHANNEL_MAX: u16 = 0x07FF; // 11 bits max = 2047
+/// The channel value of a Futaba transmitter at -100% stick travel.
+pub const CHANNEL_FUTABA_MIN: u16 = 172;
+/// The channel value of a Futaba transmitter with a centred stick.
+pub const CHANNEL_FUTABA_MID: u16 = 992;
+/// The channel value of a Futaba transmitter at +100% stick travel.
+pub const CHANNEL_FUTABA_MAX: u16 = 1811;
@@ -1,9 +1,20 @@
+use core::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use embedded_hal_async::delay::DelayNs;
 use embedded_io_async::Read;
 
 use crate::{
-    error::SbusError,
-    packet::SbusPacket,
-    parser::{Parser, SBUS_FRAME_LENGTH},
+    error::{FrameCapture, SbusError},
+    packet::{FrameKind, SbusPacket},
+    parser::{Deframer, Parser, SBUS_FRAME_LENGTH},
+    stats::SbusStats,
+    validation::{SyncBytes, ValidationPolicy},
+    ReceiveAsync,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -17,10 +28,7 @@ where
     M: super::Mode,
 {
     pub fn new<R1: Read>(reader: R1) -> Parser<R1, Async> {
-        Parser {
-            reader,
-            _mode: Default::default(),
-        }
+        Parser::with_reader(reader)
     }
 }
 
@@ -31,15 +39,40 @@ impl<R: Read> Parser<R, Async> {
     ///
     /// * `Ok(SbusPacket)` if a valid frame was read
     /// * `Err(SbusError)` if an error occurred or the frame was invalid
-    pub async fn read_frame(&mut self) -> Result<SbusPacket, SbusError> {
-        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
-        self.reader
-            .read_exact(&mut buffer)
-            .await
-            .map_err(|_| SbusError::ReadError)?;
+    ///
+    /// # Cancellation safety
+    ///
+    /// This method is cancellation safe as long as the reader's `read` is. The partially read
+    /// frame is kept in the parser, so if the future is dropped, e.g. by a timeout, the next call
+    /// resumes where it left off.
+    pub async fn read_frame(&mut self) -> Result<SbusPacket, SbusError<R::Error>> {
+        loop {
+            if let Some(result) = self.deframer.try_take_frame() {
+                return result.map_err(SbusError::with_reader_error);
+            }
+            fill_frame(&mut self.reader, &mut self.deframer).await?;
+        }
+    }
+}
 
-        SbusPacket::from_array(&buffer)
+/// Reads at most the bytes missing from the frame at the head of `deframer`, so the reader is
+/// left at the end of the frame
+async fn fill_frame<R: Read>(
+    reader: &mut R,
+    deframer: &mut Deframer,
+) -> Result<(), SbusError<R::Error>> {
+    let missing = SBUS_FRAME_LENGTH.saturating_sub(deframer.len()).max(1);
+    let slot = deframer.write_slot();
+    let count = missing.min(slot.len());
+    let read = reader
+        .read(&mut slot[..count])
+        .await
+        .map_err(SbusError::Read)?;
+    if read == 0 {
+        return Err(SbusError::UnexpectedEof);
     }
+    deframer.commit(read);
+    Ok(())
 }
 
 pub struct SbusParserAsync<R>
@@ -47,6 +80,8 @@ where
     R: Read,
 {
     reader: R,
+    deframer: Deframer,
+    last_changed: Option<SbusPacket>,
 }
 
 impl<R> SbusParserAsync<R>
@@ -54,28 +89,287 @@ where
     R: Read,
 {
     pub fn new(reader: R) -> Self {
-        Self { reader }
+        Self {
+            reader,
+            deframer: Deframer::new(),
+            last_changed: None,
+        }
+    }
+
+    deframer_builders!();
+
+    /// Limits the bytes discarded while searching for a frame, see
+    /// [`Deframer::with_resync_limit`]
+    ///
+    /// Applies to [`SbusParserAsync::read_next_valid_frame`] and the methods built on it.
+    pub fn with_resync_limit(mut self, limit: usize) -> Self {
+        self.deframer.set_resync_limit(Some(limit));
+        self
     }
 
     /// Asynchronously reads the next complete SBUS frame
     ///
+    /// The bytes are read into the internal buffer shared by all read methods, but not searched
+    /// for a header: the next 25 bytes are checked as a frame and an invalid frame is returned
+    /// as an error.
+    ///
     /// # Returns
     ///
     /// * `Ok(SbusPacket)` if a valid frame was read
     /// * `Err(SbusError)` if an error occurred or the frame was invalid
-    pub async fn read_frame(&mut self) -> Result<SbusPacket, SbusError> {
-        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
-        self.reader
-            .read_exact(&mut buffer)
+    ///
+    /// # Cancellation safety
+    ///
+    /// This method is cancellation safe as long as the reader's `read` is. The bytes of a
+    /// partial frame are kept in the internal buffer, so the next call completes the frame.
+    pub async fn read_frame(&mut self) -> Result<SbusPacket, SbusError<R::Error>> {
+        loop {
+            if let Some(result) = self.deframer.try_take_frame() {
+                return result.map_err(SbusError::with_reader_error);
+            }
+            fill_frame(&mut self.reader, &mut self.deframer).await?;
+        }
+    }
+
+    /// Asynchronously reads the next valid SBUS frame, resynchronising on the stream if needed
+    ///
+    /// Bytes are read in chunks as large as the free space in the internal buffer allows and
+    /// are then scanned for a frame with a valid header and footer, followed by the header of
+    /// the next frame if it was already received. Bytes that cannot start a valid frame are
+    /// discarded, so a stream that starts mid-frame or contains garbage will lock onto the next
+    /// valid frame.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SbusPacket)` once a valid frame was found
+    /// * `Err(SbusError::DesyncLimitExceeded(_))` if more bytes than the
+    ///   [resync limit](SbusParserAsync::with_resync_limit) were discarded
+    /// * `Err(SbusError::Read(_))` if the reader failed
+    /// * `Err(SbusError::UnexpectedEof)` if the reader reached end of stream
+    ///
+    /// # Cancellation safety
+    ///
+    /// This method is cancellation safe as long as the reader's `read` is. Bytes are only moved
+    /// into the internal buffer once a read completes, so dropping the future, e.g. when another
+    /// branch of a `select!` wins, loses no bytes and the next call resumes the partial frame.
+    pub async fn read_next_valid_frame(&mut self) -> Result<SbusPacket, SbusError<R::Error>> {
+        loop {
+            let packet = self
+                .deframer
+                .try_parse_checked()
+                .map_err(SbusError::with_reader_error)?;
+            if let Some(packet) = packet {
+                return Ok(packet);
+            }
+            self.fill().await?;
+        }
+    }
+
+    /// Fills as much of the contiguous free space in the internal buffer as the reader will
+    /// give us
+    async fn fill(&mut self) -> Result<(), SbusError<R::Error>> {
+        let read = self
+            .reader
+            .read(self.deframer.write_slot())
             .await
-            .map_err(|_| SbusError::ReadError)?;
+            .map_err(SbusError::Read)?;
+        if read == 0 {
+            return Err(SbusError::UnexpectedEof);
+        }
+        self.deframer.commit(read);
+        Ok(())
+    }
+
+    /// Asynchronously reads valid frames into `packets`, returning how many were read
+    ///
+    /// Waits for the first frame like [`SbusParserAsync::read_next_valid_frame`], then fills the
+    /// rest of `packets` with the frames that are already buffered, without waiting for more.
+    /// Returns `Ok(0)` only for an empty slice. Cancellation safe as long as the reader's `read`
+    /// is.
+    pub async fn read_frames(
+        &mut self,
+        packets: &mut [SbusPacket],
+    ) -> Result<usize, SbusError<R::Error>> {
+        let Some((first, rest)) = packets.split_first_mut() else {
+            return Ok(0);
+        };
+        *first = self.read_next_valid_frame().await?;
+        Ok(1 + self.deframer.try_parse_into(rest))
+    }
+
+    /// Reads valid frames until one differs from the last frame returned by this method by more
+    /// than `threshold` on any channel, or in its flags
+    ///
+    /// The first call returns the first valid frame. Like
+    /// [`SbusParserAsync::read_next_valid_frame`] this resynchronises on the stream, and it is
+    /// cancellation safe as long as the reader's `read` is.
+    pub async fn read_changed_frame(
+        &mut self,
+        threshold: u16,
+    ) -> Result<SbusPacket, SbusError<R::Error>> {
+        loop {
+            let packet = self.read_next_valid_frame().await?;
+            if super::is_changed(self.last_changed.as_ref(), &packet, threshold) {
+                self.last_changed = Some(packet);
+                return Ok(packet);
+            }
+        }
+    }
+
+    /// Reads the next valid SBUS frame, giving up once `timeout` has elapsed on `delay`
+    ///
+    /// `delay` is any [`DelayNs`] implementation, such as `embassy_time::Delay` or a HAL timer.
+    /// Like [`SbusParserAsync::read_next_valid_frame`], bytes of a partial frame are kept for the
+    /// next call when the timeout hits.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SbusPacket)` once a valid frame was found
+    /// * `Err(SbusError::Timeout)` if no valid frame arrived within `timeout`
+    /// * `Err(SbusError::DesyncLimitExceeded(_))` if more bytes than the resync limit were
+    ///   discarded
+    /// * `Err(SbusError::Read(_))` if the reader failed
+    /// * `Err(SbusError::UnexpectedEof)` if the reader reached end of stream
+    pub async fn read_frame_timeout<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout: Duration,
+    ) -> Result<SbusPacket, SbusError<R::Error>> {
+        let mut read = pin!(self.read_next_valid_frame());
+        let mut sleep = pin!(async {
+            // `delay_us` takes a `u32`, so longer timeouts are waited out in chunks
+            let mut remaining = timeout;
+            while !remaining.is_zero() {
+                let chunk = remaining.min(Duration::from_micros(u32::MAX.into()));
+                delay.delay_us(chunk.as_micros() as u32).await;
+                remaining -= chunk;
+            }
+        });
+        poll_fn(|cx| {
+            if let Poll::Ready(result) = read.as_mut().poll(cx) {
+                return Poll::Ready(result);
+            }
+            sleep.as_mut().poll(cx).map(|()| Err(SbusError::Timeout))
+        })
+        .await
+    }
 
-        SbusPacket::from_array(&buffer)
+    /// Polls for the next valid SBUS frame, resynchronising on the stream if needed
+    ///
+    /// This is the poll-based counterpart of [`SbusParserAsync::read_next_valid_frame`] for
+    /// custom executors and hand-written futures. Each call polls a fresh `read` future once
+    /// and drops it if it is pending, so the reader's `read` must be cancellation safe, as
+    /// `embedded-io-async` recommends, and must register the waker when it returns pending.
+    /// The parser's own buffer state is always kept across calls.
+    pub fn poll_read_frame(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<SbusPacket, SbusError<R::Error>>> {
+        loop {
+            match self.deframer.try_parse_checked() {
+                Ok(Some(packet)) => return Poll::Ready(Ok(packet)),
+                Ok(None) => {}
+                Err(err) => return Poll::Ready(Err(err.with_reader_error())),
+            }
+
+            let read = {
+                let future = pin!(self.reader.read(self.deframer.write_slot()));
+                ready!(future.poll(cx)).map_err(SbusError::Read)?
+            };
+            if read == 0 {
+                return Poll::Ready(Err(SbusError::UnexpectedEof));
+            }
+            self.deframer.commit(read);
+        }
     }
 
+    #[deprecated(note = "use reader_mut")]
     pub fn inner_mut(&mut self) -> &mut R {
         &mut self.reader
     }
+
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// The reader, see [`SbusParser::reader_mut`](crate::SbusParser::reader_mut)
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the reader, dropping the bytes buffered by the parser
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// The next valid frame in the internal buffer, without consuming it, see
+    /// [`Deframer::peek_frame`]
+    ///
+    /// Every read method takes its frames from this buffer. This is the next frame the
+    /// resynchronising methods, such as [`SbusParserAsync::read_next_valid_frame`], return, unless
+    /// [`SbusParserAsync::with_every_nth`] drops it; [`SbusParserAsync::read_frame`] fails instead on any bytes
+    /// buffered before it.
+    pub fn peek_frame(&self) -> Option<SbusPacket> {
+        self.deframer.peek_frame()
+    }
+
+    /// The last frame that was rejected, see [`Deframer::last_rejected_frame`]
+    pub fn last_rejected_frame(&self) -> &FrameCapture {
+        self.deframer.last_rejected_frame()
+    }
+
+    /// The kind of the last frame returned, see [`Deframer::last_frame_kind`]
+    pub fn last_frame_kind(&self) -> FrameKind {
+        self.deframer.last_frame_kind()
+    }
+
+    /// Number of received bytes waiting in the internal buffer, which the next read method
+    /// consumes before reading again
+    pub fn buffered_len(&self) -> usize {
+        self.deframer.len()
+    }
+
+    /// Statistics about the frames read and bytes discarded so far
+    pub fn stats(&self) -> &SbusStats {
+        self.deframer.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.deframer.reset_stats();
+    }
+
+    /// Converts the parser into a stream of packets
+    ///
+    /// Packets are read with [`SbusParserAsync::read_next_valid_frame`], so invalid frames are
+    /// skipped. The stream ends after yielding the first error from the reader.
+    #[cfg(feature = "stream")]
+    pub fn into_stream(
+        self,
+    ) -> impl futures_core::Stream<Item = Result<SbusPacket, SbusError<R::Error>>> {
+        futures_util::stream::unfold(Some(self), |parser| async move {
+            let mut parser = parser?;
+            match parser.read_next_valid_frame().await {
+                Ok(packet) => Some((Ok(packet), Some(parser))),
+                Err(err) => Some((Err(err), None)),
+            }
+        })
+    }
+}
+
+/// Receives with [`SbusParserAsync::read_next_valid_frame`], resynchronising on the stream
+impl<R> ReceiveAsync for SbusParserAsync<R>
+where
+    R: Read,
+{
+    type Error = SbusError<R::Error>;
+
+    async fn receive(&mut self) -> Result<SbusPacket, Self::Error> {
+        self.read_next_valid_frame().await
+    }
+
+    fn is_fatal(err: &Self::Error) -> bool {
+        err.is_fatal()
+    }
 }
 
 #[cfg(test)]
@@ -164,4 +458,269 @@ mod tests {
         let result = parser.read_frame().await;
         assert!(matches!(result, Err(SbusError::InvalidHeader(0x00))));
     }
+
+    #[tokio::test]
+    async fn test_resync_after_garbage_async() {
+        let expected = SbusPacket::from_array(&TEST_PACKET).unwrap();
+        let mut data = vec![0xAA, 0x0F, 0x55];
+        data.extend_from_slice(&TEST_PACKET);
+        data.extend_from_slice(&TEST_PACKET);
+
+        let cursor = Cursor::new(data);
+        let mut parser = SbusParserAsync::new(FromTokio::new(cursor));
+
+        for _ in 0..2 {
+            let packet = parser
+                .read_next_valid_frame()
+                .await
+                .expect("Should resync onto a valid frame");
+            assert_eq!(packet, expected);
+        }
+        assert_eq!(parser.stats().frames_ok, 2);
+        assert_eq!(parser.stats().bytes_discarded, 3);
+        let result = parser.read_next_valid_frame().await;
+        assert!(matches!(result, Err(SbusError::UnexpectedEof)));
+    }
+
+    #[tokio::test]
+    async fn test_resync_limit_async() {
+        let mut data = vec![0xAA; 200];
+        data.extend_from_slice(&TEST_PACKET);
+
+        let cursor = Cursor::new(data);
+        let mut parser = SbusParserAsync::new(FromTokio::new(cursor)).with_resync_limit(100);
+
+        let result = parser.read_next_valid_frame().await;
+        assert!(matches!(result, Err(SbusError::DesyncLimitExceeded(skipped)) if skipped > 100));
+        assert!(parser.read_next_valid_frame().await.is_ok());
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_into_stream_async() {
+        use futures_util::StreamExt;
+
+        let expected = SbusPacket::from_array(&TEST_PACKET).unwrap();
+        let data = TEST_PACKET.repeat(3);
+
+        let cursor = Cursor::new(data);
+        let stream = SbusParserAsync::new(FromTokio::new(cursor)).into_stream();
+        let items: Vec<_> = stream.collect().await;
+
+        assert_eq!(items.len(), 4);
+        assert!(items[..3]
+            .iter()
+            .all(|item| matches!(item, Ok(packet) if *packet == expected)));
+        assert!(matches!(items[3], Err(SbusError::UnexpectedEof)));
+    }
+
+    #[tokio::test]
+    async fn test_poll_read_frame_async() {
+        use core::future::poll_fn;
+        use tokio::io::AsyncWriteExt;
+
+        let (mut tx, rx) = tokio::io::duplex(64);
+        let mut parser = SbusParserAsync::new(FromTokio::new(rx));
+
+        tx.write_all(&TEST_PACKET[..10]).await.unwrap();
+        let first = poll_fn(|cx| Poll::Ready(parser.poll_read_frame(cx))).await;
+        assert!(first.is_pending());
+
+        tx.write_all(&TEST_PACKET[10..]).await.unwrap();
+        let packet = poll_fn(|cx| parser.poll_read_frame(cx)).await.unwrap();
+        assert_eq!(packet, SbusPacket::from_array(&TEST_PACKET).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_read_next_valid_frame_cancellation_async() {
+        use core::time::Duration;
+        use tokio::io::AsyncWriteExt;
+
+        let (mut tx, rx) = tokio::io::duplex(64);
+        let mut parser = SbusParserAsync::new(FromTokio::new(rx));
+
+        tx.write_all(&TEST_PACKET[..10]).await.unwrap();
+        let timed_out =
+            tokio::time::timeout(Duration::from_millis(10), parser.read_next_valid_frame()).await;
+        assert!(timed_out.is_err());
+
+        tx.write_all(&TEST_PACKET[10..]).await.unwrap();
+        let packet = parser.read_next_valid_frame().await.unwrap();
+        assert_eq!(packet, SbusPacket::from_array(&TEST_PACKET).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_peek_frame_async() {
+        let mut data = vec![0xAA];
+        for value in [1, 2, 3] {
+            data.extend_from_slice(&crate::fixtures::frame(value));
+        }
+        let mut parser = SbusParserAsync::new(FromTokio::new(Cursor::new(data)));
+
+        assert_eq!(parser.peek_frame(), None);
+        assert_eq!(parser.read_next_valid_frame().await.unwrap().channels[0], 1);
+        let peeked = parser.peek_frame().unwrap();
+        assert_eq!(peeked.channels[0], 2);
+        assert_eq!(parser.read_frame().await.unwrap(), peeked);
+        assert_eq!(parser.read_frame().await.unwrap().channels[0], 3);
+        assert_eq!(parser.buffered_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_read_frame_cancellation_async() {
+        use core::time::Duration;
+        use tokio::io::AsyncWriteExt;
+
+        let (mut tx, rx) = tokio::io::duplex(64);
+        let mut parser = SbusParserAsync::new(FromTokio::new(rx));
+
+        tx.write_all(&TEST_PACKET[..10]).await.unwrap();
+        let timed_out = tokio::time::timeout(Duration::from_millis(10), parser.read_frame()).await;
+        assert!(timed_out.is_err());
+        assert_eq!(parser.buffered_len(), 10);
+
+        tx.write_all(&TEST_PACKET[10..]).await.unwrap();
+        tx.write_all(&TEST_PACKET).await.unwrap();
+        let expected = SbusPacket::from_array(&TEST_PACKET).unwrap();
+        assert_eq!(parser.read_frame().await.unwrap(), expected);
+        assert_eq!(parser.read_frame().await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_parser_read_frame_cancellation_async() {
+        use crate::parser::Parser;
+        use core::time::Duration;
+        use tokio::io::AsyncWriteExt;
+
+        let (mut tx, rx) = tokio::io::duplex(64);
+        let mut parser = Parser::<(), Async>::new(FromTokio::new(rx));
+
+        tx.write_all(&TEST_PACKET[..10]).await.unwrap();
+        let timed_out = tokio::time::timeout(Duration::from_millis(10), parser.read_frame()).await;
+        assert!(timed_out.is_err());
+
+        tx.write_all(&TEST_PACKET[10..]).await.unwrap();
+        tx.write_all(&TEST_PACKET).await.unwrap();
+        let expected = SbusPacket::from_array(&TEST_PACKET).unwrap();
+        assert_eq!(parser.read_frame().await.unwrap(), expected);
+        assert_eq!(parser.read_frame().await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_read_frame_timeout_async() {
+        use tokio::io::AsyncWriteExt;
+
+        struct TokioDelay;
+
+        impl DelayNs for TokioDelay {
+            async fn delay_ns(&mut self, ns: u32) {
+                tokio::time::sleep(Duration::from_nanos(ns.into())).await;
+            }
+        }
+
+        let (mut tx, rx) = tokio::io::duplex(64);
+        let mut parser = SbusParserAsync::new(FromTokio::new(rx));
+
+        tx.write_all(&TEST_PACKET[..10]).await.unwrap();
+        let result = parser
+            .read_frame_timeout(&mut TokioDelay, Duration::from_millis(10))
+            .await;
+        assert!(matches!(result, Err(SbusError::Timeout)));
+
+        tx.write_all(&TEST_PACKET[10..]).await.unwrap();
+        let packet = parser
+            .read_frame_timeout(&mut TokioDelay, Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert_eq!(packet, SbusPacket::from_array(&TEST_PACKET).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_read_frame_timeout_long() {
+        /// Returns at once, adding up the time it was asked to wait
+        struct CountingDelay(u64);
+
+        impl DelayNs for CountingDelay {
+            async fn delay_ns(&mut self, ns: u32) {
+                self.0 += u64::from(ns);
+            }
+        }
+
+        let (_tx, rx) = tokio::io::duplex(64);
+        let mut parser = SbusParserAsync::new(FromTokio::new(rx));
+        let mut delay = CountingDelay(0);
+
+        // Longer than the `u32::MAX` microseconds a single `delay_us` can wait
+        let timeout = Duration::from_secs(3 * 3600) + Duration::from_micros(7);
+        let result = parser.read_frame_timeout(&mut delay, timeout).await;
+        assert!(matches!(result, Err(SbusError::Timeout)));
+        assert_eq!(u128::from(delay.0), timeout.as_nanos());
+    }
+
+    #[tokio::test]
+    async fn test_read_frames_async() {
+        let expected = SbusPacket::from_array(&TEST_PACKET).unwrap();
+        let data = TEST_PACKET.repeat(3);
+
+        let cursor = Cursor::new(data);
+        let mut parser = SbusParserAsync::new(FromTokio::new(cursor));
+
+        // Only as many frames as fit into the internal buffer are read at once
+        let mut packets = [SbusPacket::neutral(); 4];
+        let mut total = 0;
+        let result = loop {
+            match parser.read_frames(&mut packets).await {
+                Ok(count) => {
+                    assert!(packets[..count].iter().all(|packet| *packet == expected));
+                    total += count;
+                }
+                Err(err) => break err,
+            }
+        };
+        assert_eq!(total, 3);
+        assert!(matches!(result, SbusError::UnexpectedEof));
+    }
+
+    #[tokio::test]
+    async fn test_resync_across_buffer_wrap_async() {
+        let expected = SbusPacket::from_array(&TEST_PACKET).unwrap();
+        // Enough frames, offset by a stray byte, to wrap the circular buffer several times
+        let mut data = vec![0x42];
+        for _ in 0..10 {
+            data.extend_from_slice(&TEST_PACKET);
+        }
+
+        let cursor = Cursor::new(data);
+        let mut parser = SbusParserAsync::new(FromTokio::new(cursor));
+
+        for _ in 0..10 {
+            let packet = parser.read_next_valid_frame().await.unwrap();
+            assert_eq!(packet, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_changed_frame_async() {
+        let mut data = Vec::new();
+        for value in [1000, 1000, 1000, 1200] {
+            let mut packet = SbusPacket::neutral();
+            packet.channels[4] = value;
+            data.extend_from_slice(&packet.to_array());
+        }
+        let mut failsafe = SbusPacket::neutral();
+        failsafe.channels[4] = 1200;
+        failsafe.flags.failsafe = true;
+        data.extend_from_slice(&failsafe.to_array());
+
+        let mut parser = SbusParserAsync::new(FromTokio::new(Cursor::new(data)));
+        assert_eq!(
+            parser.read_changed_frame(0).await.unwrap().channels[4],
+            1000
+        );
+        assert_eq!(
+            parser.read_changed_frame(0).await.unwrap().channels[4],
+            1200
+        );
+        assert!(parser.read_changed_frame(0).await.unwrap().flags.failsafe);
+    }
 }
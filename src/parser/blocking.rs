@@ -1,4 +1,11 @@
-use crate::{error::SbusError, packet::SbusPacket, parser::SBUS_FRAME_LENGTH, Parser};
+use crate::{
+    error::{FrameCapture, SbusError},
+    packet::{FrameKind, SbusPacket},
+    parser::{Deframer, SBUS_FRAME_LENGTH},
+    stats::SbusStats,
+    validation::{SyncBytes, ValidationPolicy},
+    FrameSource, Parser, Receive,
+};
 use embedded_io::Read;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -12,28 +19,39 @@ where
     M: super::Mode,
 {
     pub fn new_blocking<R1: Read>(reader: R1) -> Parser<R1, Blocking> {
-        Parser {
-            reader,
-            _mode: Default::default(),
-        }
+        Parser::with_reader(reader)
     }
 }
 
 impl<R: Read> Parser<R, Blocking> {
-    /// Asynchronously reads the next complete SBUS frame
+    /// Reads the next complete SBUS frame
     ///
     /// # Returns
     ///
     /// * `Ok(SbusPacket)` if a valid frame was read
     /// * `Err(SbusError)` if an error occurred or the frame was invalid
-    pub fn read_frame(&mut self) -> Result<SbusPacket, SbusError> {
-        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
-        self.reader
-            .read_exact(&mut buffer)
-            .map_err(|_| SbusError::ReadError)?;
+    pub fn read_frame(&mut self) -> Result<SbusPacket, SbusError<R::Error>> {
+        loop {
+            if let Some(result) = self.deframer.try_take_frame() {
+                return result.map_err(SbusError::with_reader_error);
+            }
+            fill_frame(&mut self.reader, &mut self.deframer)?;
+        }
+    }
+}
 
-        SbusPacket::from_array(&buffer)
+/// Reads at most the bytes missing from the frame at the head of `deframer`, so the reader is
+/// left at the end of the frame like with `read_exact`
+fn fill_frame<R: Read>(reader: &mut R, deframer: &mut Deframer) -> Result<(), SbusError<R::Error>> {
+    let missing = SBUS_FRAME_LENGTH.saturating_sub(deframer.len()).max(1);
+    let slot = deframer.write_slot();
+    let count = missing.min(slot.len());
+    let read = reader.read(&mut slot[..count]).map_err(SbusError::Read)?;
+    if read == 0 {
+        return Err(SbusError::UnexpectedEof);
     }
+    deframer.commit(read);
+    Ok(())
 }
 
 /// Parser for reading SBUS frames from a blocking I/O source
@@ -42,6 +60,10 @@ where
     R: Read,
 {
     reader: R,
+    deframer: Deframer,
+    last_changed: Option<SbusPacket>,
+    /// Set once the iterator yielded a fatal error
+    finished: bool,
 }
 
 impl<R> SbusParser<R>
@@ -49,33 +71,308 @@ where
     R: Read,
 {
     pub fn new(reader: R) -> Self {
-        Self { reader }
+        Self {
+            reader,
+            deframer: Deframer::new(),
+            last_changed: None,
+            finished: false,
+        }
+    }
+
+    deframer_builders!();
+
+    /// Limits the bytes the resynchronising read methods, such as [`SbusParser::read_frames`],
+    /// discard while searching for a frame, see [`Deframer::with_resync_limit`]
+    pub fn with_resync_limit(mut self, limit: usize) -> Self {
+        self.deframer.set_resync_limit(Some(limit));
+        self
     }
 
     /// Reads the next complete SBUS frame
     ///
+    /// The bytes are read into the internal buffer shared by all read methods, but not searched
+    /// for a header: the next 25 bytes are checked as a frame and an invalid frame is returned
+    /// as an error.
+    ///
     /// # Returns
     ///
     /// * `Ok(SbusPacket)` if a valid frame was read
     /// * `Err(SbusError)` if an error occurred or the frame was invalid
-    pub fn read_frame(&mut self) -> Result<SbusPacket, SbusError> {
-        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
-        self.reader
-            .read_exact(&mut buffer)
-            .map_err(|_| SbusError::ReadError)?;
+    pub fn read_frame(&mut self) -> Result<SbusPacket, SbusError<R::Error>> {
+        loop {
+            if let Some(result) = self.deframer.try_take_frame() {
+                return result.map_err(SbusError::with_reader_error);
+            }
+            fill_frame(&mut self.reader, &mut self.deframer)?;
+        }
+    }
 
-        SbusPacket::from_array(&buffer)
+    /// Reads the next valid SBUS frame, resynchronising on the stream if needed
+    ///
+    /// Bytes are read in chunks as large as the free space in the internal buffer allows and
+    /// are then scanned for a frame with a valid header and footer, like the async parser's
+    /// `read_next_valid_frame`. Bytes that cannot start a valid frame are discarded, so a stream
+    /// that starts mid-frame or contains garbage will lock onto the next valid frame.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SbusPacket)` once a valid frame was found
+    /// * `Err(SbusError::DesyncLimitExceeded(_))` if more bytes than the
+    ///   [resync limit](SbusParser::with_resync_limit) were discarded
+    /// * `Err(SbusError::Read(_))` if the reader failed
+    /// * `Err(SbusError::UnexpectedEof)` if the reader reached end of stream
+    pub fn read_next_valid_frame(&mut self) -> Result<SbusPacket, SbusError<R::Error>> {
+        loop {
+            let packet = self
+                .deframer
+                .try_parse_checked()
+                .map_err(SbusError::with_reader_error)?;
+            if let Some(packet) = packet {
+                return Ok(packet);
+            }
+            self.fill()?;
+        }
     }
 
+    /// Reads valid frames until one differs from the last frame returned by this method by more
+    /// than `threshold` on any channel, or in its flags
+    ///
+    /// The first call returns the first valid frame. Like [`SbusParser::read_next_valid_frame`]
+    /// this resynchronises on the stream, and errors are returned like from that method.
+    pub fn read_changed_frame(
+        &mut self,
+        threshold: u16,
+    ) -> Result<SbusPacket, SbusError<R::Error>> {
+        loop {
+            let packet = self.read_next_valid_frame()?;
+            if super::is_changed(self.last_changed.as_ref(), &packet, threshold) {
+                self.last_changed = Some(packet);
+                return Ok(packet);
+            }
+        }
+    }
+
+    /// Reads valid frames into `packets`, returning how many were read
+    ///
+    /// Blocks until the first valid frame arrives, resynchronising on the stream like
+    /// [`SbusParser::read_next_valid_frame`], then fills the rest of `packets` with the frames that
+    /// were received along with it, without reading again. Returns `Ok(0)` only for an empty
+    /// slice.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(count)` once at least one valid frame was found
+    /// * `Err(SbusError::DesyncLimitExceeded(_))` if more bytes than the resync limit were
+    ///   discarded
+    /// * `Err(SbusError::Read(_))` if the reader failed
+    /// * `Err(SbusError::UnexpectedEof)` if the reader reached end of stream
+    pub fn read_frames(
+        &mut self,
+        packets: &mut [SbusPacket],
+    ) -> Result<usize, SbusError<R::Error>> {
+        let Some((first, rest)) = packets.split_first_mut() else {
+            return Ok(0);
+        };
+        *first = self.read_next_valid_frame()?;
+        Ok(1 + self.deframer.try_parse_into(rest))
+    }
+
+    /// Reads the next valid SBUS frame, giving up once `timeout` has elapsed
+    ///
+    /// Like [`SbusParser::read_next_valid_frame`], this resynchronises on the stream and
+    /// skips invalid frames. A blocking read cannot be interrupted, so the timeout is only checked
+    /// whenever the reader returns. Use a reader with its own, shorter read timeout, such as a
+    /// serial port; its `TimedOut` errors are retried until the deadline. Bytes of a partial frame
+    /// are kept for the next call.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SbusPacket)` once a valid frame was found
+    /// * `Err(SbusError::Timeout)` if no valid frame arrived within `timeout`
+    /// * `Err(SbusError::DesyncLimitExceeded(_))` if more bytes than the resync limit were
+    ///   discarded
+    /// * `Err(SbusError::Read(_))` if the reader failed
+    /// * `Err(SbusError::UnexpectedEof)` if the reader reached end of stream
+    #[cfg(feature = "std")]
+    pub fn read_frame_timeout(
+        &mut self,
+        timeout: core::time::Duration,
+    ) -> Result<SbusPacket, SbusError<R::Error>> {
+        use embedded_io::{Error, ErrorKind};
+
+        // A timeout too long to represent, such as `Duration::MAX`, never expires
+        let deadline = std::time::Instant::now().checked_add(timeout);
+        loop {
+            let packet = self
+                .deframer
+                .try_parse_checked()
+                .map_err(SbusError::with_reader_error)?;
+            if let Some(packet) = packet {
+                return Ok(packet);
+            }
+            if deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) {
+                return Err(SbusError::Timeout);
+            }
+
+            match self.reader.read(self.deframer.write_slot()) {
+                Ok(0) => return Err(SbusError::UnexpectedEof),
+                Ok(read) => self.deframer.commit(read),
+                Err(err) if matches!(err.kind(), ErrorKind::TimedOut | ErrorKind::Interrupted) => {}
+                Err(err) => return Err(SbusError::Read(err)),
+            }
+        }
+    }
+
+    #[deprecated(note = "use reader_mut")]
     pub fn inner_mut(&mut self) -> &mut R {
         &mut self.reader
     }
+
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// The reader, e.g. to change the baud rate of a UART, keeping the parser's state and
+    /// statistics
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the reader, dropping the bytes buffered by the parser
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the bytes the reader has ready into the internal buffer
+    fn fill(&mut self) -> Result<(), SbusError<R::Error>> {
+        let read = self
+            .reader
+            .read(self.deframer.write_slot())
+            .map_err(SbusError::Read)?;
+        if read == 0 {
+            return Err(SbusError::UnexpectedEof);
+        }
+        self.deframer.commit(read);
+        Ok(())
+    }
+
+    /// The next valid frame in the internal buffer, without consuming it, see
+    /// [`Deframer::peek_frame`]
+    ///
+    /// Every read method takes its frames from this buffer. This is the next frame the
+    /// resynchronising methods, such as [`SbusParser::read_frames`], return, unless
+    /// [`SbusParser::with_every_nth`] drops it; [`SbusParser::read_frame`] fails instead on any bytes
+    /// buffered before it.
+    pub fn peek_frame(&self) -> Option<SbusPacket> {
+        self.deframer.peek_frame()
+    }
+
+    /// The last frame that was rejected, see [`Deframer::last_rejected_frame`]
+    pub fn last_rejected_frame(&self) -> &FrameCapture {
+        self.deframer.last_rejected_frame()
+    }
+
+    /// The kind of the last frame returned, see [`Deframer::last_frame_kind`]
+    pub fn last_frame_kind(&self) -> FrameKind {
+        self.deframer.last_frame_kind()
+    }
+
+    /// Number of received bytes waiting in the internal buffer, which the next read method
+    /// consumes before reading again
+    pub fn buffered_len(&self) -> usize {
+        self.deframer.len()
+    }
+
+    /// Statistics about the frames read so far
+    pub fn stats(&self) -> &SbusStats {
+        self.deframer.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.deframer.reset_stats();
+    }
+}
+
+/// Iterates over the frames of the reader until it reaches end of stream
+///
+/// Frames and errors are yielded like from [`SbusParser::read_frame`]. A frame cut short by the
+/// end of stream is yielded as `Err(SbusError::UnexpectedEof)` before iteration ends. Iteration
+/// also ends after an error from the reader itself, yielded as `Err(SbusError::Read(_))`, or any
+/// other [fatal](SbusError::is_fatal) error, like the stream of the async parser does.
+///
+/// ```rust
+/// use sbus_rs::{SbusParser, SBUS_FOOTER, SBUS_FRAME_LENGTH, SBUS_HEADER};
+/// use embedded_io_adapters::std::FromStd;
+/// use std::io::Cursor;
+///
+/// let mut frame = [0u8; SBUS_FRAME_LENGTH];
+/// frame[0] = SBUS_HEADER;
+/// frame[SBUS_FRAME_LENGTH - 1] = SBUS_FOOTER;
+///
+/// let parser = SbusParser::new(FromStd::new(Cursor::new(frame.repeat(3))));
+/// assert_eq!(parser.filter_map(Result::ok).count(), 3);
+/// ```
+impl<R> Iterator for SbusParser<R>
+where
+    R: Read,
+{
+    type Item = Result<SbusPacket, SbusError<R::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let result = loop {
+            if let Some(result) = self.deframer.try_take_frame() {
+                break result.map_err(SbusError::with_reader_error);
+            }
+
+            match fill_frame(&mut self.reader, &mut self.deframer) {
+                Ok(()) => {}
+                Err(SbusError::UnexpectedEof) if self.deframer.is_empty() => return None,
+                Err(SbusError::UnexpectedEof) => {
+                    self.deframer.clear();
+                    break Err(SbusError::UnexpectedEof);
+                }
+                Err(err) => break Err(err),
+            }
+        };
+        self.finished = matches!(&result, Err(err) if err.is_fatal());
+        Some(result)
+    }
+}
+
+/// Receives with [`SbusParser::read_next_valid_frame`], resynchronising on the stream
+impl<R> Receive for SbusParser<R>
+where
+    R: Read,
+{
+    type Error = SbusError<R::Error>;
+
+    fn receive(&mut self) -> Result<SbusPacket, Self::Error> {
+        self.read_next_valid_frame()
+    }
+
+    fn is_fatal(err: &Self::Error) -> bool {
+        err.is_fatal()
+    }
+}
+
+/// Yields the valid frames, resynchronising on the stream, until reading fails, e.g. at end of
+/// stream
+impl<R> FrameSource for SbusParser<R>
+where
+    R: Read,
+{
+    fn next_frame(&mut self) -> Option<SbusPacket> {
+        self.read_next_valid_frame().ok()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::CHANNEL_MAX;
+    use crate::{fixtures::frames, CHANNEL_MAX, SBUS_HEADER};
     use embedded_io_adapters::std::FromStd;
     use std::io::Cursor;
 
@@ -175,6 +472,27 @@ mod tests {
         assert!(packet.flags.failsafe);
     }
 
+    #[test]
+    fn test_stats() {
+        let mut data = TEST_PACKET.repeat(4);
+        data[25] = 0x00; // Invalid header
+        data[74] = 0xFF; // Invalid footer
+
+        let cursor = Cursor::new(data);
+        let mut parser = SbusParser::new(FromStd::new(cursor));
+        for _ in 0..4 {
+            let _ = parser.read_frame();
+        }
+
+        let stats = parser.stats();
+        assert_eq!(stats.frames_ok, 2);
+        assert_eq!(stats.invalid_header, 1);
+        assert_eq!(stats.invalid_footer, 1);
+
+        parser.reset_stats();
+        assert_eq!(parser.stats().frames_ok, 0);
+    }
+
     #[test]
     fn test_partial_frame() {
         let data = &TEST_PACKET[..20]; // Cut off the last few bytes
@@ -183,7 +501,61 @@ mod tests {
         let mut parser = SbusParser::new(FromStd::new(cursor));
 
         let result = parser.read_frame();
-        assert!(matches!(result, Err(SbusError::ReadError)));
+        assert!(matches!(result, Err(SbusError::UnexpectedEof)));
+    }
+
+    #[test]
+    fn test_reader_error_is_propagated() {
+        struct TimedOutReader;
+
+        impl std::io::Read for TimedOutReader {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::TimedOut.into())
+            }
+        }
+
+        let mut parser = SbusParser::new(FromStd::new(TimedOutReader));
+
+        let result = parser.read_frame();
+        assert!(
+            matches!(result, Err(SbusError::Read(err)) if err.kind() == std::io::ErrorKind::TimedOut)
+        );
+    }
+
+    #[test]
+    fn test_iterator_until_eof() {
+        let mut data = TEST_PACKET.repeat(2);
+        data.extend_from_slice(&TEST_PACKET[..10]);
+
+        let cursor = Cursor::new(data);
+        let mut parser = SbusParser::new(FromStd::new(cursor));
+
+        assert!(matches!(parser.next(), Some(Ok(_))));
+        assert!(matches!(parser.next(), Some(Ok(_))));
+        assert!(matches!(parser.next(), Some(Err(SbusError::UnexpectedEof))));
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn test_iterator_ends_on_reader_error() {
+        struct FlakyReader(bool);
+
+        impl std::io::Read for FlakyReader {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.0 = !self.0;
+                if self.0 {
+                    return Err(std::io::ErrorKind::TimedOut.into());
+                }
+                let len = buf.len().min(TEST_PACKET.len());
+                buf[..len].copy_from_slice(&TEST_PACKET[..len]);
+                Ok(len)
+            }
+        }
+
+        let mut parser = SbusParser::new(FromStd::new(FlakyReader(false)));
+        assert!(matches!(parser.next(), Some(Err(SbusError::Read(_)))));
+        assert!(parser.next().is_none());
+        assert!(parser.read_frame().is_ok());
     }
 
     #[test]
@@ -208,4 +580,184 @@ mod tests {
         assert_eq!(packet.channels[0], 0); // Channel 1 should be 0
         assert_eq!(packet.channels[1], CHANNEL_MAX); // Channel 2 should be 2047
     }
+
+    /// Hands out the bytes in chunks and times out in between, like a serial port
+    #[cfg(feature = "std")]
+    struct TimingOutReader {
+        chunks: std::collections::VecDeque<Vec<u8>>,
+    }
+
+    #[cfg(feature = "std")]
+    impl std::io::Read for TimingOutReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            match self.chunks.front_mut() {
+                Some(chunk) if !chunk.is_empty() => {
+                    let len = chunk.len().min(buf.len());
+                    buf[..len].copy_from_slice(&chunk[..len]);
+                    chunk.drain(..len);
+                    Ok(len)
+                }
+                Some(_) => {
+                    self.chunks.pop_front();
+                    Err(std::io::ErrorKind::TimedOut.into())
+                }
+                None => Err(std::io::ErrorKind::TimedOut.into()),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_frame_timeout() {
+        use core::time::Duration;
+
+        let reader = TimingOutReader {
+            chunks: [
+                vec![0xAA],
+                TEST_PACKET[..10].to_vec(),
+                TEST_PACKET[10..].to_vec(),
+            ]
+            .into(),
+        };
+        let mut parser = SbusParser::new(FromStd::new(reader));
+
+        let packet = parser.read_frame_timeout(Duration::MAX).unwrap();
+        assert_eq!(packet, SbusPacket::from_array(&TEST_PACKET).unwrap());
+        assert_eq!(parser.stats().bytes_discarded, 1);
+
+        let result = parser.read_frame_timeout(Duration::from_millis(20));
+        assert!(matches!(result, Err(SbusError::Timeout)));
+    }
+
+    #[test]
+    fn test_read_changed_frame() {
+        let mut data = frames(&[1000, 1003]);
+        data.extend_from_slice(&[0xAA; SBUS_FRAME_LENGTH]);
+        data.extend(frames(&[1006, 1010, 1010, 1500]));
+        let mut parser = SbusParser::new(FromStd::new(Cursor::new(data)));
+
+        assert_eq!(parser.read_changed_frame(5).unwrap().channels[0], 1000);
+        assert_eq!(parser.read_changed_frame(5).unwrap().channels[0], 1006);
+        assert_eq!(parser.read_changed_frame(5).unwrap().channels[0], 1500);
+        assert!(matches!(
+            parser.read_changed_frame(5),
+            Err(SbusError::UnexpectedEof)
+        ));
+    }
+
+    #[test]
+    fn test_parser_settings() {
+        let mut data: Vec<_> = frames(&[1, 2, 3]).iter().map(|byte| !byte).collect();
+        data.extend([0xAA; SBUS_FRAME_LENGTH]);
+        let mut parser = Parser::<(), Blocking>::new_blocking(FromStd::new(Cursor::new(data)))
+            .with_inverted(true)
+            .with_channel_mask(0b1)
+            .with_every_nth(2);
+
+        let packet = parser.read_frame().unwrap();
+        assert_eq!((packet.channels[0], packet.channels[1]), (1, 0));
+        assert_eq!(parser.read_frame().unwrap().channels[0], 3);
+        assert!(matches!(
+            parser.read_frame(),
+            Err(SbusError::InvalidHeader(0x55))
+        ));
+        assert!(matches!(parser.read_frame(), Err(SbusError::UnexpectedEof)));
+        assert_eq!(parser.stats().frames_ok, 3);
+    }
+
+    #[test]
+    fn test_every_nth() {
+        let data = frames(&[1, 2, 3, 4, 5, 6]);
+        let mut parser = SbusParser::new(FromStd::new(Cursor::new(data.clone()))).with_every_nth(2);
+        assert_eq!(parser.read_frame().unwrap().channels[0], 1);
+        assert_eq!(parser.read_frame().unwrap().channels[0], 3);
+
+        let parser = SbusParser::new(FromStd::new(Cursor::new(data))).with_every_nth(4);
+        let values: Vec<_> = parser.map(|packet| packet.unwrap().channels[0]).collect();
+        assert_eq!(values, [1, 5]);
+    }
+
+    #[test]
+    fn test_read_frames() {
+        let mut data = vec![0xAA];
+        data.extend(frames(&[1, 2, 3]));
+        let mut parser = SbusParser::new(FromStd::new(Cursor::new(data)));
+
+        let mut packets = [SbusPacket::neutral(); 2];
+        assert!(matches!(parser.read_frames(&mut packets), Ok(2)));
+        assert_eq!(packets.map(|packet| packet.channels[0]), [1, 2]);
+        assert!(matches!(parser.read_frames(&mut packets), Ok(1)));
+        assert_eq!(packets[0].channels[0], 3);
+        assert!(matches!(parser.read_frames(&mut []), Ok(0)));
+        assert!(matches!(
+            parser.read_frames(&mut packets),
+            Err(SbusError::UnexpectedEof)
+        ));
+    }
+
+    #[test]
+    fn test_mixed_reads_share_the_buffer() {
+        let mut data = vec![0xAA];
+        data.extend(frames(&[1, 2, 3, 4]));
+        let mut parser = SbusParser::new(FromStd::new(Cursor::new(data)));
+
+        let mut packets = [SbusPacket::neutral(); 1];
+        assert!(matches!(parser.read_frames(&mut packets), Ok(1)));
+        assert_eq!(packets[0].channels[0], 1);
+        assert_eq!(parser.peek_frame().unwrap().channels[0], 2);
+        assert_eq!(parser.read_frame().unwrap().channels[0], 2);
+        let values: Vec<_> = parser.map(|packet| packet.unwrap().channels[0]).collect();
+        assert_eq!(values, [3, 4]);
+    }
+
+    #[test]
+    fn test_reader_access() {
+        let data = frames(&[1, 2]);
+        let mut parser = SbusParser::new(FromStd::new(Cursor::new(data)));
+
+        assert_eq!(parser.read_frame().unwrap().channels[0], 1);
+        assert_eq!(parser.reader().inner().position(), 25);
+        parser.reader_mut().inner_mut().set_position(0);
+        assert_eq!(parser.read_frame().unwrap().channels[0], 1);
+        assert_eq!(parser.stats().frames_ok, 2);
+        assert_eq!(parser.into_inner().into_inner().position(), 25);
+    }
+
+    #[test]
+    fn test_read_next_valid_frame() {
+        let mut data = vec![0xAA, SBUS_HEADER, 0x55];
+        data.extend(frames(&[1, 2]));
+        let mut parser = SbusParser::new(FromStd::new(Cursor::new(data)));
+
+        assert_eq!(parser.read_next_valid_frame().unwrap().channels[0], 1);
+        assert_eq!(parser.read_next_valid_frame().unwrap().channels[0], 2);
+        assert_eq!(parser.stats().bytes_discarded, 3);
+        assert!(matches!(
+            parser.read_next_valid_frame(),
+            Err(SbusError::UnexpectedEof)
+        ));
+    }
+
+    #[test]
+    fn test_frame_source() {
+        let mut data = frames(&[1, 2]);
+        data.extend_from_slice(&[0xAA; SBUS_FRAME_LENGTH]);
+        data.extend(frames(&[3]));
+        let mut parser = SbusParser::new(FromStd::new(Cursor::new(data)));
+
+        assert_eq!(parser.next_frame().unwrap().channels[0], 1);
+        assert_eq!(parser.next_frame().unwrap().channels[0], 2);
+        assert_eq!(parser.next_frame().unwrap().channels[0], 3);
+        assert_eq!(parser.next_frame(), None);
+
+        // A stream starting mid-frame locks onto the next frame
+        let mut data = vec![0xAA];
+        data.extend(frames(&[1, 2, 3, 4]));
+        let mut parser = SbusParser::new(FromStd::new(Cursor::new(data)));
+        let values: Vec<_> = core::iter::from_fn(|| parser.next_frame())
+            .map(|packet| packet.channels[0])
+            .collect();
+        assert_eq!(values, [1, 2, 3, 4]);
+    }
 }
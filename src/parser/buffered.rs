@@ -0,0 +1,376 @@
+//! Frame scanning directly in the buffer of a buffered reader
+use crate::{
+    error::{FrameCapture, SbusError},
+    packet::{FrameKind, SbusPacket},
+    parser::Deframer,
+    stats::SbusStats,
+    validation::{SyncBytes, ValidationPolicy},
+};
+
+/// Parser scanning the buffer of an [`embedded_io::BufRead`] reader for SBUS frames
+///
+/// Where [`SbusParser`](crate::SbusParser) copies every byte into its own ring buffer, this
+/// parser checks frames in place in the reader's buffer, such as the one of a std
+/// `BufReader` behind `embedded_io_adapters::std::FromStd`. Only a frame split across two
+/// fills of that buffer is copied. It resynchronises on the stream like
+/// [`SbusParser::read_next_valid_frame`](crate::SbusParser::read_next_valid_frame).
+///
+/// ```rust
+/// use embedded_io_adapters::std::FromStd;
+/// use sbus_rs::{SbusBufParser, SbusPacket};
+/// use std::io::{BufReader, Cursor};
+///
+/// let mut data = vec![0xAA, 0x55];
+/// data.extend_from_slice(&SbusPacket::neutral().to_array());
+///
+/// let mut parser = SbusBufParser::new(FromStd::new(BufReader::new(Cursor::new(data))));
+/// assert_eq!(parser.read_next_valid_frame().unwrap(), SbusPacket::neutral());
+/// assert_eq!(parser.stats().bytes_discarded, 2);
+/// ```
+#[cfg(feature = "blocking")]
+pub struct SbusBufParser<R>
+where
+    R: embedded_io::BufRead,
+{
+    reader: R,
+    deframer: Deframer,
+}
+
+#[cfg(feature = "blocking")]
+impl<R> SbusBufParser<R>
+where
+    R: embedded_io::BufRead,
+{
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            deframer: Deframer::new(),
+        }
+    }
+
+    deframer_builders!();
+
+    /// Limits the bytes discarded while searching for a frame, see
+    /// [`Deframer::with_resync_limit`]
+    pub fn with_resync_limit(mut self, limit: usize) -> Self {
+        self.deframer.set_resync_limit(Some(limit));
+        self
+    }
+
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// The reader, see [`SbusParser::reader_mut`](crate::SbusParser::reader_mut)
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the reader, dropping the start of a frame split across two fills of its buffer
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Statistics about the frames read and bytes discarded so far
+    pub fn stats(&self) -> &SbusStats {
+        self.deframer.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.deframer.reset_stats();
+    }
+
+    /// The last frame that was rejected, see [`Deframer::last_rejected_frame`]
+    pub fn last_rejected_frame(&self) -> &FrameCapture {
+        self.deframer.last_rejected_frame()
+    }
+
+    /// The kind of the last frame returned, see [`Deframer::last_frame_kind`]
+    pub fn last_frame_kind(&self) -> FrameKind {
+        self.deframer.last_frame_kind()
+    }
+
+    /// Reads the next valid SBUS frame, resynchronising on the stream if needed
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SbusPacket)` once a valid frame was found
+    /// * `Err(SbusError::DesyncLimitExceeded(_))` if more bytes than the
+    ///   [resync limit](SbusBufParser::with_resync_limit) were discarded
+    /// * `Err(SbusError::Read(_))` if the reader failed
+    /// * `Err(SbusError::UnexpectedEof)` if the reader reached end of stream
+    pub fn read_next_valid_frame(&mut self) -> Result<SbusPacket, SbusError<R::Error>> {
+        loop {
+            let buf = self.reader.fill_buf().map_err(SbusError::Read)?;
+            if buf.is_empty() {
+                return Err(SbusError::UnexpectedEof);
+            }
+            let (used, result) = self.deframer.try_parse_in_place(buf);
+            self.reader.consume(used);
+            if let Some(packet) = result.map_err(SbusError::with_reader_error)? {
+                return Ok(packet);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<R> crate::Receive for SbusBufParser<R>
+where
+    R: embedded_io::BufRead,
+{
+    type Error = SbusError<R::Error>;
+
+    fn receive(&mut self) -> Result<SbusPacket, Self::Error> {
+        self.read_next_valid_frame()
+    }
+
+    fn is_fatal(err: &Self::Error) -> bool {
+        err.is_fatal()
+    }
+}
+
+/// Parser scanning the buffer of an [`embedded_io_async::BufRead`] reader for SBUS frames
+///
+/// The async counterpart of `SbusBufParser`: frames are checked in place in the reader's buffer
+/// and only a frame split across two fills of that buffer is copied.
+#[cfg(feature = "async")]
+pub struct SbusBufParserAsync<R>
+where
+    R: embedded_io_async::BufRead,
+{
+    reader: R,
+    deframer: Deframer,
+}
+
+#[cfg(feature = "async")]
+impl<R> SbusBufParserAsync<R>
+where
+    R: embedded_io_async::BufRead,
+{
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            deframer: Deframer::new(),
+        }
+    }
+
+    deframer_builders!();
+
+    /// Limits the bytes discarded while searching for a frame, see
+    /// [`Deframer::with_resync_limit`]
+    pub fn with_resync_limit(mut self, limit: usize) -> Self {
+        self.deframer.set_resync_limit(Some(limit));
+        self
+    }
+
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// The reader, see [`SbusParser::reader_mut`](crate::SbusParser::reader_mut)
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the reader, dropping the start of a frame split across two fills of its buffer
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Statistics about the frames read and bytes discarded so far
+    pub fn stats(&self) -> &SbusStats {
+        self.deframer.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.deframer.reset_stats();
+    }
+
+    /// The last frame that was rejected, see [`Deframer::last_rejected_frame`]
+    pub fn last_rejected_frame(&self) -> &FrameCapture {
+        self.deframer.last_rejected_frame()
+    }
+
+    /// The kind of the last frame returned, see [`Deframer::last_frame_kind`]
+    pub fn last_frame_kind(&self) -> FrameKind {
+        self.deframer.last_frame_kind()
+    }
+
+    /// Asynchronously reads the next valid SBUS frame, resynchronising on the stream if needed
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SbusPacket)` once a valid frame was found
+    /// * `Err(SbusError::DesyncLimitExceeded(_))` if more bytes than the
+    ///   [resync limit](SbusBufParserAsync::with_resync_limit) were discarded
+    /// * `Err(SbusError::Read(_))` if the reader failed
+    /// * `Err(SbusError::UnexpectedEof)` if the reader reached end of stream
+    ///
+    /// # Cancellation safety
+    ///
+    /// This method is cancellation safe as long as the reader's `fill_buf` is. Bytes are only
+    /// consumed once they were scanned, together with any partial frame copied out.
+    pub async fn read_next_valid_frame(&mut self) -> Result<SbusPacket, SbusError<R::Error>> {
+        loop {
+            let buf = self.reader.fill_buf().await.map_err(SbusError::Read)?;
+            if buf.is_empty() {
+                return Err(SbusError::UnexpectedEof);
+            }
+            let (used, result) = self.deframer.try_parse_in_place(buf);
+            self.reader.consume(used);
+            if let Some(packet) = result.map_err(SbusError::with_reader_error)? {
+                return Ok(packet);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R> crate::ReceiveAsync for SbusBufParserAsync<R>
+where
+    R: embedded_io_async::BufRead,
+{
+    type Error = SbusError<R::Error>;
+
+    async fn receive(&mut self) -> Result<SbusPacket, Self::Error> {
+        self.read_next_valid_frame().await
+    }
+
+    fn is_fatal(err: &Self::Error) -> bool {
+        err.is_fatal()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::frame;
+    use crate::{SBUS_FOOTER, SBUS_FRAME_LENGTH, SBUS_HEADER};
+
+    /// Scans `chunks` one after the other, as successive fills of a reader's buffer
+    fn scan_chunks(deframer: &mut Deframer, chunks: &[&[u8]]) -> Vec<u16> {
+        let mut values = Vec::new();
+        for chunk in chunks {
+            let mut chunk = *chunk;
+            while !chunk.is_empty() {
+                let (used, result) = deframer.try_parse_in_place(chunk);
+                if let Some(packet) = result.unwrap() {
+                    values.push(packet.channels[0]);
+                }
+                chunk = &chunk[used..];
+            }
+        }
+        values
+    }
+
+    #[test]
+    fn test_split_frames() {
+        let mut data = vec![0xAA];
+        for value in 1..=4 {
+            data.extend_from_slice(&frame(value));
+        }
+
+        for split in [1, 7, 24, 25, 26, 60] {
+            let chunks: Vec<_> = data.chunks(split).collect();
+            let mut deframer = Deframer::new();
+            assert_eq!(
+                scan_chunks(&mut deframer, &chunks),
+                [1, 2, 3, 4],
+                "split {split}"
+            );
+            assert_eq!(deframer.stats().bytes_discarded, 1);
+            assert_eq!(deframer.stats().frames_ok, 4);
+        }
+    }
+
+    #[test]
+    fn test_resync_within_partial_frame() {
+        // A header in the payload of a broken frame starts the next candidate
+        let mut broken = [0u8; SBUS_FRAME_LENGTH];
+        broken[0] = SBUS_HEADER;
+        broken[24] = 0x55;
+        let mut data = broken[..20].to_vec();
+        data.extend_from_slice(&broken[20..]);
+        data.extend_from_slice(&frame(7));
+
+        let mut deframer = Deframer::new();
+        assert_eq!(scan_chunks(&mut deframer, &[&data[..20], &data[20..]]), [7]);
+        assert_eq!(deframer.stats().bytes_discarded, SBUS_FRAME_LENGTH as u32);
+    }
+
+    #[test]
+    fn test_inverted_and_limit() {
+        let mut data = vec![!SBUS_FOOTER; 40];
+        data.extend(frame(9).map(|byte| !byte));
+
+        let mut deframer = Deframer::new().with_inverted(true).with_resync_limit(10);
+        let (used, result) = deframer.try_parse_in_place(&data[..30]);
+        assert_eq!(used, 30);
+        assert_eq!(result, Err(SbusError::DesyncLimitExceeded(30)));
+        assert_eq!(scan_chunks(&mut deframer, &[&data[30..]]), [9]);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_buf_parser() {
+        use embedded_io_adapters::std::FromStd;
+        use std::io::{BufReader, Cursor};
+
+        let mut data = vec![0x0F, 0x00];
+        data.extend_from_slice(&frame(1));
+        data.extend_from_slice(&frame(2));
+        let reader = BufReader::with_capacity(16, Cursor::new(data));
+        let mut parser = SbusBufParser::new(FromStd::new(reader));
+
+        assert_eq!(parser.read_next_valid_frame().unwrap().channels[0], 1);
+        assert_eq!(parser.read_next_valid_frame().unwrap().channels[0], 2);
+        assert!(matches!(
+            parser.read_next_valid_frame(),
+            Err(SbusError::UnexpectedEof)
+        ));
+        assert_eq!(parser.stats().bytes_discarded, 2);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_buf_parser_settings() {
+        use embedded_io_adapters::std::FromStd;
+        use std::io::{BufReader, Cursor};
+
+        let data: Vec<_> = (1..=5)
+            .flat_map(|value| frame(value).map(|byte| !byte))
+            .collect();
+        let reader = BufReader::with_capacity(16, Cursor::new(data));
+        let mut parser = SbusBufParser::new(FromStd::new(reader))
+            .with_inverted(true)
+            .with_channel_mask(0b1)
+            .with_every_nth(2);
+
+        for value in [1, 3, 5] {
+            let packet = parser.read_next_valid_frame().unwrap();
+            assert_eq!(packet.channels[0], value);
+            assert_eq!(packet.channels[1], 0);
+        }
+        assert_eq!(parser.stats().frames_ok, 5);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_buf_parser_async() {
+        use embedded_io_adapters::tokio_1::FromTokio;
+        use tokio::io::BufReader;
+
+        let mut data = frame(1).to_vec();
+        data.extend_from_slice(&frame(2));
+        let reader = BufReader::with_capacity(10, std::io::Cursor::new(data));
+        let mut parser = SbusBufParserAsync::new(FromTokio::new(reader));
+
+        assert_eq!(parser.read_next_valid_frame().await.unwrap().channels[0], 1);
+        assert_eq!(parser.read_next_valid_frame().await.unwrap().channels[0], 2);
+        assert!(matches!(
+            parser.read_next_valid_frame().await,
+            Err(SbusError::UnexpectedEof)
+        ));
+    }
+}
@@ -0,0 +1,1065 @@
+//! Byte stream to SBUS frame synchronisation shared by the streaming parsers
+use core::time::Duration;
+
+use crate::{
+    error::{FrameCapture, SbusError},
+    frame_rate::SbusFrameRate,
+    packet::{FrameKind, SbusPacket},
+    parser::{scan, CHANNEL_MASK_ALL, SBUS_FRAME_LENGTH},
+    stats::SbusStats,
+    validation::{validate_flag_byte, SyncBytes, ValidationPolicy},
+};
+
+/// Size of the deframer buffer, large enough to hold two complete frames
+///
+/// Must be a power of two so that indices can wrap with a mask instead of a modulo.
+pub const DEFRAMER_BUFFER_SIZE: usize = (SBUS_FRAME_LENGTH * 2).next_power_of_two();
+const DEFRAMER_BUFFER_MASK: usize = DEFRAMER_BUFFER_SIZE - 1;
+const _: () = assert!(DEFRAMER_BUFFER_SIZE.is_power_of_two());
+// Each slot of the buffer has a bit in the error mask
+const _: () = assert!(DEFRAMER_BUFFER_SIZE == u64::BITS as usize);
+
+/// Circular buffer that accumulates raw bytes and extracts valid SBUS frames from them
+///
+/// Bytes that cannot start a valid frame are discarded, so a stream that starts mid-frame or
+/// contains garbage will lock onto the next valid frame. Runs of bytes without a header are
+/// skipped in one go, a word at a time, or with `memchr` when the `memchr` feature is enabled.
+///
+/// With [`Deframer::with_idle_gap_alignment`], bytes fed with timestamps additionally realign
+/// on the idle gap between frames, which locks on faster and cannot be fooled by header bytes
+/// inside the payload.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct Deframer {
+    buffer: [u8; DEFRAMER_BUFFER_SIZE],
+    head: usize,
+    len: usize,
+    /// Buffer slots holding a byte received with an error, one bit per slot
+    errors: u64,
+    stats: SbusStats,
+    policy: ValidationPolicy,
+    sync: SyncBytes,
+    inverted: bool,
+    idle_gap: Option<IdleGap>,
+    last_byte_at: Option<Duration>,
+    channel_mask: u16,
+    every_nth: u16,
+    /// Valid frames to drop before the next one is handed out
+    decimation_skip: u16,
+    resync_limit: Option<usize>,
+    /// Bytes discarded since the last valid frame
+    resync_discarded: usize,
+    next_header_check: bool,
+    flag_check: bool,
+    /// Whether the head of the buffer directly follows a valid frame
+    locked: bool,
+    last_rejected: FrameCapture,
+    last_kind: FrameKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+struct IdleGap {
+    threshold: Duration,
+    byte_time: Duration,
+}
+
+impl Default for Deframer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deframer {
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; DEFRAMER_BUFFER_SIZE],
+            head: 0,
+            len: 0,
+            errors: 0,
+            stats: SbusStats::new(),
+            policy: ValidationPolicy::Standard,
+            sync: SyncBytes::SBUS,
+            inverted: false,
+            idle_gap: None,
+            last_byte_at: None,
+            channel_mask: CHANNEL_MASK_ALL,
+            every_nth: 1,
+            decimation_skip: 0,
+            resync_limit: None,
+            resync_discarded: 0,
+            next_header_check: true,
+            flag_check: false,
+            locked: false,
+            last_rejected: FrameCapture::EMPTY,
+            last_kind: FrameKind::Sbus1,
+        }
+    }
+
+    /// Sets how strictly frames are checked, [`ValidationPolicy::Standard`] by default
+    pub const fn with_validation_policy(mut self, policy: ValidationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn set_validation_policy(&mut self, policy: ValidationPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn validation_policy(&self) -> ValidationPolicy {
+        self.policy
+    }
+
+    /// Sets whether frames with reserved bits of the flag byte set are rejected with
+    /// [`SbusError::InvalidFlagByte`], `false` by default, see [`validate_flag_byte`]
+    ///
+    /// Independent of the validation policy, so SBUS2 frames can be checked as well.
+    pub const fn with_flag_check(mut self, enabled: bool) -> Self {
+        self.flag_check = enabled;
+        self
+    }
+
+    pub fn set_flag_check(&mut self, enabled: bool) {
+        self.flag_check = enabled;
+    }
+
+    pub fn flag_check(&self) -> bool {
+        self.flag_check
+    }
+
+    /// Sets the header and footer marking frames, [`SyncBytes::SBUS`] by default
+    pub const fn with_sync_bytes(mut self, sync: SyncBytes) -> Self {
+        self.sync = sync;
+        self
+    }
+
+    pub fn set_sync_bytes(&mut self, sync: SyncBytes) {
+        self.sync = sync;
+    }
+
+    pub fn sync_bytes(&self) -> SyncBytes {
+        self.sync
+    }
+
+    /// Flips every received byte before framing, for UARTs capturing SBUS without an inverter
+    pub const fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    pub fn set_inverted(&mut self, inverted: bool) {
+        self.inverted = inverted;
+    }
+
+    pub fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
+    /// Decodes only the channels selected by `mask`, leaving the others at 0, see
+    /// [`channels_parsing_masked`](crate::channels_parsing_masked)
+    ///
+    /// Frames are still checked in full, only the decoding of unused channels is skipped.
+    /// [`CHANNEL_MASK_ALL`] by default.
+    pub const fn with_channel_mask(mut self, mask: u16) -> Self {
+        self.channel_mask = mask;
+        self
+    }
+
+    pub fn set_channel_mask(&mut self, mask: u16) {
+        self.channel_mask = mask;
+    }
+
+    pub fn channel_mask(&self) -> u16 {
+        self.channel_mask
+    }
+
+    /// Hands out only every `n`th valid frame, starting with the first, and drops the others
+    ///
+    /// For consumers that don't need the full frame rate, e.g. loggers. Dropped frames still
+    /// count as valid in the statistics. `n` of 0 is treated as 1, which hands out every frame.
+    pub const fn with_every_nth(mut self, n: u16) -> Self {
+        self.every_nth = if n == 0 { 1 } else { n };
+        self.decimation_skip = 0;
+        self
+    }
+
+    pub fn set_every_nth(&mut self, n: u16) {
+        self.every_nth = n.max(1);
+        self.decimation_skip = 0;
+    }
+
+    pub fn every_nth(&self) -> u16 {
+        self.every_nth
+    }
+
+    /// Sets whether a frame found while resynchronising is only accepted if the byte after it
+    /// is a header, `true` by default
+    ///
+    /// Header bytes are common in channel data, so a header and a footer 24 bytes apart can
+    /// occur by chance. The frame is accepted right away if the byte after it hasn't been
+    /// received yet, so the check never delays a frame. SBUS2 frames, which are followed by
+    /// telemetry, are always accepted, as is every frame once the deframer is locked onto the
+    /// stream.
+    pub const fn with_next_header_check(mut self, enabled: bool) -> Self {
+        self.next_header_check = enabled;
+        self
+    }
+
+    pub fn set_next_header_check(&mut self, enabled: bool) {
+        self.next_header_check = enabled;
+    }
+
+    pub fn next_header_check(&self) -> bool {
+        self.next_header_check
+    }
+
+    /// Makes [`Deframer::try_parse_checked`] fail once more than `limit` bytes were discarded
+    /// without finding a valid frame, unlimited by default
+    ///
+    /// A full SBUS frame is 25 bytes, so a limit of a few frames catches a dead or mis-bauded
+    /// link quickly without tripping on a burst of corruption.
+    pub const fn with_resync_limit(mut self, limit: usize) -> Self {
+        self.resync_limit = Some(limit);
+        self
+    }
+
+    pub fn set_resync_limit(&mut self, limit: Option<usize>) {
+        self.resync_limit = limit;
+    }
+
+    pub fn resync_limit(&self) -> Option<usize> {
+        self.resync_limit
+    }
+
+    /// Number of bytes discarded since the last valid frame
+    pub fn resync_discarded(&self) -> usize {
+        self.resync_discarded
+    }
+
+    /// Enables realigning on the idle gap between frames of `frame_rate` for bytes fed through
+    /// [`Deframer::push_byte_at`] and [`Deframer::push_bytes_at`]
+    ///
+    /// Any silence longer than half the idle gap ends a frame, so buffered bytes are dropped and
+    /// the next byte is treated as the start of a frame.
+    pub const fn with_idle_gap_alignment(mut self, frame_rate: SbusFrameRate) -> Self {
+        self.idle_gap = Some(IdleGap {
+            threshold: Duration::from_nanos(frame_rate.idle_gap().as_nanos() as u64 / 2),
+            byte_time: frame_rate.byte_time(),
+        });
+        self
+    }
+
+    /// Appends a byte received at `timestamp`, measured from any fixed epoch
+    pub fn push_byte_at(&mut self, byte: u8, timestamp: Duration) {
+        self.align(timestamp);
+        self.push_byte(byte);
+        self.last_byte_at = Some(timestamp);
+    }
+
+    /// Appends bytes received back to back, the last of them at `timestamp`
+    ///
+    /// This fits chunks handed over by an RX interrupt or a DMA idle line interrupt. The arrival
+    /// of the first byte is worked out from the byte time of the configured frame rate.
+    pub fn push_bytes_at(&mut self, bytes: &[u8], timestamp: Duration) {
+        let Some(idle_gap) = self.idle_gap else {
+            self.push_bytes(bytes);
+            return;
+        };
+        if bytes.is_empty() {
+            return;
+        }
+
+        let transfer_time = idle_gap.byte_time * (bytes.len() as u32 - 1);
+        self.align(timestamp.saturating_sub(transfer_time));
+        self.push_bytes(bytes);
+        self.last_byte_at = Some(timestamp);
+    }
+
+    /// Appends a byte, overwriting the oldest buffered byte if the buffer is full
+    pub fn push_byte(&mut self, byte: u8) {
+        if self.len == DEFRAMER_BUFFER_SIZE {
+            self.skip(1);
+        }
+        self.buffer[(self.head + self.len) & DEFRAMER_BUFFER_MASK] = self.wire(byte);
+        self.len += 1;
+    }
+
+    /// Appends a byte along with whether the UART reported a parity, framing or noise error for it
+    ///
+    /// No frame containing an errored byte is decoded, so the deframer resynchronises right away
+    /// instead of waiting for a footer mismatch.
+    pub fn push_byte_with_error(&mut self, byte: u8, error: bool) {
+        self.push_byte(byte);
+        if error {
+            defmt_event!(trace, "sbus: UART error on a received byte");
+            self.stats.record_byte_error();
+            self.errors |= self.slots(self.len - 1, 1);
+        }
+    }
+
+    /// Records a byte the UART reported an error for without handing out its value
+    pub fn push_error(&mut self) {
+        self.push_byte_with_error(0, true);
+    }
+
+    /// Appends bytes, overwriting the oldest buffered bytes if the buffer overflows
+    pub fn push_bytes(&mut self, mut bytes: &[u8]) {
+        if bytes.len() >= DEFRAMER_BUFFER_SIZE {
+            let dropped = bytes.len() - DEFRAMER_BUFFER_SIZE;
+            defmt_event!(
+                trace,
+                "sbus: buffer overflow, dropped {=usize} bytes",
+                self.len + dropped
+            );
+            self.stats.record_discarded(self.len + dropped);
+            self.resync_discarded = self.resync_discarded.saturating_add(self.len + dropped);
+            bytes = &bytes[dropped..];
+            self.clear();
+        }
+        let overflow = (self.len + bytes.len()).saturating_sub(DEFRAMER_BUFFER_SIZE);
+        self.skip(overflow);
+
+        while !bytes.is_empty() {
+            let slot = self.write_slot();
+            let count = slot.len().min(bytes.len());
+            slot[..count].copy_from_slice(&bytes[..count]);
+            self.commit(count);
+            bytes = &bytes[count..];
+        }
+    }
+
+    /// Extracts the next valid frame from the buffered bytes, if there is one
+    ///
+    /// Bytes preceding the frame that cannot start a valid frame are discarded.
+    pub fn try_parse(&mut self) -> Option<SbusPacket> {
+        self.parse(&[]).1
+    }
+
+    /// The next valid frame in the buffered bytes, without consuming it or the bytes before it
+    ///
+    /// This is the frame [`Deframer::try_parse`] would find next, but frames to be dropped by
+    /// [`Deframer::with_every_nth`] are returned too and the statistics are left untouched.
+    pub fn peek_frame(&self) -> Option<SbusPacket> {
+        (0..(self.len + 1).saturating_sub(SBUS_FRAME_LENGTH)).find_map(|offset| {
+            let errored = self.errors & self.slots(offset, SBUS_FRAME_LENGTH) != 0;
+            if errored || self.byte(&[], offset) != self.sync.header {
+                return None;
+            }
+            self.decode(&self.frame_at(&[], offset))
+                .ok()
+                .filter(|_| self.is_aligned_at(&[], offset))
+        })
+    }
+
+    /// Like [`Deframer::try_parse`], but fails with [`SbusError::DesyncLimitExceeded`] once more
+    /// bytes than the [resync limit](Deframer::with_resync_limit) were discarded since the last
+    /// valid frame
+    ///
+    /// The count starts over after the error, so the caller may keep trying.
+    pub fn try_parse_checked(&mut self) -> Result<Option<SbusPacket>, SbusError> {
+        match self.try_parse() {
+            Some(packet) => Ok(Some(packet)),
+            None => self.check_resync_limit().map(|()| None),
+        }
+    }
+
+    /// Like [`Deframer::try_parse_checked`], but scans `bytes` received after the buffered ones
+    /// where they are, returning how many of them were used
+    ///
+    /// Only the bytes of an incomplete frame at the end of `bytes` are copied into the buffer,
+    /// for readers that already buffer the stream themselves.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn try_parse_in_place(
+        &mut self,
+        bytes: &[u8],
+    ) -> (usize, Result<Option<SbusPacket>, SbusError>) {
+        match self.parse(bytes) {
+            (used, Some(packet)) => (used, Ok(Some(packet))),
+            (used, None) => {
+                self.push_bytes(&bytes[used..]);
+                (bytes.len(), self.check_resync_limit().map(|()| None))
+            }
+        }
+    }
+
+    /// Takes the frame at the head of the buffered bytes as it is, without searching for a
+    /// header, once a whole frame is buffered
+    ///
+    /// An invalid frame is consumed along with its error, like a frame read with `read_exact`.
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn try_take_frame(&mut self) -> Option<Result<SbusPacket, SbusError>> {
+        while self.len >= SBUS_FRAME_LENGTH {
+            let frame = self.frame_at(&[], 0);
+            let result = self.decode(&frame);
+            self.stats.record(&result);
+            self.discard(SBUS_FRAME_LENGTH);
+            match result {
+                Ok(packet) => {
+                    self.resync_discarded = 0;
+                    self.locked = true;
+                    if self.decimate() {
+                        self.last_kind = FrameKind::of_frame(&frame, self.sync);
+                        return Some(Ok(packet));
+                    }
+                }
+                Err(err) => {
+                    self.last_rejected = FrameCapture::new(&frame);
+                    self.locked = false;
+                    return Some(Err(err));
+                }
+            }
+        }
+        None
+    }
+
+    /// Fills `packets` with the valid frames already buffered, returning how many were found
+    ///
+    /// Stops at the first gap without checking the resync limit, so a pending
+    /// [`SbusError::DesyncLimitExceeded`] is reported by the next [`Deframer::try_parse_checked`].
+    #[cfg(any(feature = "blocking", feature = "async"))]
+    pub(crate) fn try_parse_into(&mut self, packets: &mut [SbusPacket]) -> usize {
+        let mut count = 0;
+        for slot in packets.iter_mut() {
+            match self.try_parse() {
+                Some(packet) => *slot = packet,
+                None => break,
+            }
+            count += 1;
+        }
+        count
+    }
+
+    /// Discards all buffered bytes
+    pub fn clear(&mut self) {
+        self.locked = false;
+        self.head = 0;
+        self.len = 0;
+        self.errors = 0;
+        self.last_byte_at = None;
+    }
+
+    /// Statistics about the frames and bytes seen so far
+    pub fn stats(&self) -> &SbusStats {
+        &self.stats
+    }
+
+    /// The last frame candidate that was rejected, such as the frame of an
+    /// [`SbusError::InvalidFooter`] just returned, including those skipped while resynchronising
+    ///
+    /// Empty if no frame was rejected yet, or without the `error-context` feature.
+    pub fn last_rejected_frame(&self) -> &FrameCapture {
+        &self.last_rejected
+    }
+
+    /// The kind of the last frame handed out, told apart by its footer
+    ///
+    /// SBUS2-aware code reads this after each frame to answer in the telemetry slots of its
+    /// slot group. [`FrameKind::Sbus1`] until the first frame.
+    pub fn last_frame_kind(&self) -> FrameKind {
+        self.last_kind
+    }
+
+    /// Resets the statistics without touching the buffered bytes
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
+    /// Checks and decodes a frame with the settings of the deframer, without touching the
+    /// statistics
+    pub(crate) fn decode(&self, frame: &[u8; SBUS_FRAME_LENGTH]) -> Result<SbusPacket, SbusError> {
+        let packet =
+            SbusPacket::from_array_masked(frame, self.policy, self.sync, self.channel_mask)?;
+        if self.flag_check {
+            validate_flag_byte(frame)?;
+        }
+        Ok(packet)
+    }
+
+    /// Counts a valid frame towards the decimation, returning whether to hand it out
+    fn decimate(&mut self) -> bool {
+        if self.decimation_skip > 0 {
+            self.decimation_skip -= 1;
+            false
+        } else {
+            self.decimation_skip = self.every_nth - 1;
+            true
+        }
+    }
+
+    /// Number of bytes currently buffered
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The contiguous free space following the buffered bytes, for readers to fill directly
+    ///
+    /// Bytes written here become part of the buffer once passed to [`Deframer::commit`].
+    pub(crate) fn write_slot(&mut self) -> &mut [u8] {
+        let tail = (self.head + self.len) & DEFRAMER_BUFFER_MASK;
+        let free = DEFRAMER_BUFFER_SIZE - self.len;
+        let end = tail + free.min(DEFRAMER_BUFFER_SIZE - tail);
+        &mut self.buffer[tail..end]
+    }
+
+    /// Marks `count` bytes of the [`Deframer::write_slot`] as buffered
+    pub(crate) fn commit(&mut self, count: usize) {
+        debug_assert!(self.len + count <= DEFRAMER_BUFFER_SIZE);
+        if self.inverted {
+            let tail = (self.head + self.len) & DEFRAMER_BUFFER_MASK;
+            for byte in &mut self.buffer[tail..tail + count] {
+                *byte = !*byte;
+            }
+        }
+        self.len += count;
+    }
+
+    /// Extracts the next valid frame
+    ///
+    /// `rest` are bytes as received following the buffered ones, scanned in place. Returns how
+    /// many of them were used up, with the bytes of an incomplete frame at the end left unused.
+    fn parse(&mut self, rest: &[u8]) -> (usize, Option<SbusPacket>) {
+        let mut used = 0;
+        loop {
+            let rest = &rest[used..];
+            let available = self.len + rest.len();
+            if available == 0 {
+                return (used, None);
+            }
+            if self.errors & self.slots(0, SBUS_FRAME_LENGTH) != 0 {
+                // The frame candidate at the head contains an errored byte
+                used += self.skip(1);
+                continue;
+            }
+            if self.byte(rest, 0) != self.sync.header {
+                // Skip straight to the next header in the contiguous part of the bytes
+                let skipped = if self.len > 0 {
+                    let contiguous = self.len.min(DEFRAMER_BUFFER_SIZE - self.head);
+                    let region = &self.buffer[self.head..self.head + contiguous];
+                    scan::find_byte(self.sync.header, region).unwrap_or(contiguous)
+                } else {
+                    scan::find_byte(self.wire(self.sync.header), rest).unwrap_or(rest.len())
+                };
+                used += self.skip(skipped);
+                continue;
+            }
+            if available < SBUS_FRAME_LENGTH {
+                return (used, None);
+            }
+
+            let frame = self.frame_at(rest, 0);
+            let result = self.decode(&frame);
+            if result.is_ok() && !self.is_aligned_at(rest, 0) {
+                // A header and footer in the right places by chance, not a frame boundary
+                used += self.skip(1);
+                continue;
+            }
+            self.stats.record(&result);
+            match result {
+                Ok(packet) => {
+                    if !self.locked {
+                        defmt_event!(
+                            debug,
+                            "sbus: synchronised after discarding {=usize} bytes",
+                            self.resync_discarded
+                        );
+                    }
+                    used += self.discard(SBUS_FRAME_LENGTH);
+                    self.resync_discarded = 0;
+                    self.locked = true;
+                    if self.decimate() {
+                        self.last_kind = FrameKind::of_frame(&frame, self.sync);
+                        return (used, Some(packet));
+                    }
+                }
+                Err(_) => {
+                    self.last_rejected = FrameCapture::new(&frame);
+                    used += self.skip(1);
+                }
+            }
+        }
+    }
+
+    /// Fails with [`SbusError::DesyncLimitExceeded`] if more bytes than the resync limit were
+    /// discarded, starting the count over
+    fn check_resync_limit(&mut self) -> Result<(), SbusError> {
+        match self.resync_limit {
+            Some(limit) if self.resync_discarded > limit => {
+                defmt_event!(
+                    warn,
+                    "sbus: desync limit exceeded after {=usize} bytes",
+                    self.resync_discarded
+                );
+                Err(SbusError::DesyncLimitExceeded(core::mem::take(
+                    &mut self.resync_discarded,
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Copies the frame candidate `offset` bytes from the head out, with at most two copies
+    /// unless it continues into `rest`
+    fn frame_at(&self, rest: &[u8], offset: usize) -> [u8; SBUS_FRAME_LENGTH] {
+        let mut frame = [0u8; SBUS_FRAME_LENGTH];
+        if offset + SBUS_FRAME_LENGTH <= self.len {
+            let start = (self.head + offset) & DEFRAMER_BUFFER_MASK;
+            let first = SBUS_FRAME_LENGTH.min(DEFRAMER_BUFFER_SIZE - start);
+            frame[..first].copy_from_slice(&self.buffer[start..start + first]);
+            frame[first..].copy_from_slice(&self.buffer[..SBUS_FRAME_LENGTH - first]);
+        } else {
+            for (index, byte) in frame.iter_mut().enumerate() {
+                *byte = self.byte(rest, offset + index);
+            }
+        }
+        frame
+    }
+
+    /// Whether the valid frame candidate `offset` bytes from the head sits on a frame boundary
+    fn is_aligned_at(&self, rest: &[u8], offset: usize) -> bool {
+        let locked = self.locked && offset == 0;
+        let available = self.len + rest.len();
+        if locked || !self.next_header_check || available <= offset + SBUS_FRAME_LENGTH {
+            return true;
+        }
+        let footer = self.byte(rest, offset + SBUS_FRAME_LENGTH - 1);
+        if footer != self.sync.footer
+            && matches!(FrameKind::from_footer(footer), Some(FrameKind::Sbus2(_)))
+        {
+            return true;
+        }
+        self.byte(rest, offset + SBUS_FRAME_LENGTH) == self.sync.header
+    }
+
+    /// The byte at `index` from the head, continuing into the received bytes `rest` after the
+    /// buffered ones
+    fn byte(&self, rest: &[u8], index: usize) -> u8 {
+        match index.checked_sub(self.len) {
+            None => self.buffer[(self.head + index) & DEFRAMER_BUFFER_MASK],
+            Some(index) => self.wire(rest[index]),
+        }
+    }
+
+    /// Converts between a byte as received and as buffered
+    fn wire(&self, byte: u8) -> u8 {
+        if self.inverted {
+            !byte
+        } else {
+            byte
+        }
+    }
+
+    /// Drops the buffered bytes if the line was idle long enough before `first_byte_at`
+    fn align(&mut self, first_byte_at: Duration) {
+        let (Some(idle_gap), Some(last)) = (self.idle_gap, self.last_byte_at) else {
+            return;
+        };
+        if first_byte_at.saturating_sub(last) > idle_gap.threshold && self.len > 0 {
+            // A frame never spans an idle gap, so whatever is buffered is an incomplete frame
+            self.skip(self.len);
+        }
+    }
+
+    /// The error mask bits of the `count` buffer slots starting `offset` bytes from the head
+    fn slots(&self, offset: usize, count: usize) -> u64 {
+        let bits = match count {
+            0 => 0,
+            DEFRAMER_BUFFER_SIZE.. => u64::MAX,
+            _ => ((1u64 << count) - 1) << offset,
+        };
+        bits.rotate_left(self.head as u32)
+    }
+
+    /// Drops bytes that could not be part of a valid frame, see [`Deframer::discard`]
+    fn skip(&mut self, count: usize) -> usize {
+        if self.locked && count > 0 {
+            defmt_event!(debug, "sbus: lost sync");
+        }
+        self.locked = false;
+        self.stats.record_discarded(count);
+        self.resync_discarded = self.resync_discarded.saturating_add(count);
+        self.discard(count)
+    }
+
+    /// Drops `count` bytes, the buffered ones first, returning how many bytes following them
+    /// were dropped
+    fn discard(&mut self, count: usize) -> usize {
+        let buffered = count.min(self.len);
+        self.errors &= !self.slots(0, buffered);
+        self.head = (self.head + buffered) & DEFRAMER_BUFFER_MASK;
+        self.len -= buffered;
+        count - buffered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{frame, frame_with_footer};
+    use crate::{SBUS_FOOTER, SBUS_HEADER};
+
+    #[test]
+    fn test_last_rejected_frame() {
+        let mut deframer = Deframer::new();
+        let corrupted = frame_with_footer(0x55);
+        assert!(deframer.last_rejected_frame().is_empty());
+
+        deframer.push_bytes(&frame(1));
+        deframer.push_bytes(&corrupted);
+        assert_eq!(deframer.try_parse().unwrap().channels[0], 1);
+        assert_eq!(deframer.try_parse(), None);
+        let capture = deframer.last_rejected_frame();
+        assert_eq!(*capture, FrameCapture::new(&corrupted));
+        if cfg!(feature = "error-context") {
+            assert_eq!(capture.bytes(), corrupted);
+            assert_ne!(*capture, FrameCapture::EMPTY);
+            assert!(format!("{capture:?}").starts_with("FrameCapture([0F, 00,"));
+        } else {
+            assert!(capture.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_last_frame_kind() {
+        let mut deframer = Deframer::new();
+        deframer.push_bytes(&frame_with_footer(0x24));
+        deframer.push_bytes(&frame(1));
+
+        assert!(deframer.try_parse().is_some());
+        assert_eq!(deframer.last_frame_kind(), FrameKind::Sbus2(2));
+        assert!(deframer.try_parse().is_some());
+        assert_eq!(deframer.last_frame_kind(), FrameKind::Sbus1);
+    }
+
+    #[test]
+    fn test_partial_frame_waits_for_more_bytes() {
+        let mut deframer = Deframer::new();
+        let data = frame(1000);
+
+        deframer.push_bytes(&data[..10]);
+        assert_eq!(deframer.try_parse(), None);
+        assert_eq!(deframer.len(), 10);
+
+        deframer.push_bytes(&data[10..]);
+        let packet = deframer
+            .try_parse()
+            .expect("Should parse the completed frame");
+        assert_eq!(packet.channels, [1000; 16]);
+        assert!(deframer.is_empty());
+    }
+
+    #[test]
+    fn test_garbage_is_discarded() {
+        let mut deframer = Deframer::new();
+        deframer.push_bytes(&[0xAA, 0x0F, 0x55]);
+        deframer.push_bytes(&frame(1500));
+
+        let packet = deframer.try_parse().expect("Should resync onto the frame");
+        assert_eq!(packet.channels, [1500; 16]);
+        assert_eq!(deframer.try_parse(), None);
+
+        let stats = deframer.stats();
+        assert_eq!(stats.frames_ok, 1);
+        assert_eq!(stats.invalid_footer, 1);
+        assert_eq!(stats.bytes_discarded, 3);
+
+        deframer.reset_stats();
+        assert_eq!(deframer.stats(), &SbusStats::default());
+    }
+
+    #[test]
+    fn test_resync_limit() {
+        let mut deframer = Deframer::new().with_resync_limit(40);
+        deframer.push_bytes(&[0xAA; 30]);
+        assert_eq!(deframer.try_parse_checked(), Ok(None));
+        assert_eq!(deframer.resync_discarded(), 30);
+
+        deframer.push_bytes(&[0xAA; 30]);
+        assert_eq!(
+            deframer.try_parse_checked(),
+            Err(SbusError::DesyncLimitExceeded(60))
+        );
+        assert_eq!(deframer.resync_discarded(), 0);
+
+        deframer.push_bytes(&[0xAA; 30]);
+        deframer.push_bytes(&frame(1000));
+        assert!(deframer.try_parse_checked().unwrap().is_some());
+        assert_eq!(deframer.resync_discarded(), 0);
+
+        deframer.set_resync_limit(None);
+        deframer.push_bytes(&[0xAA; 60]);
+        assert_eq!(deframer.try_parse_checked(), Ok(None));
+    }
+
+    #[test]
+    fn test_next_header_check() {
+        // A header and footer 24 bytes apart inside garbage, followed by a real frame
+        let mut data = vec![SBUS_HEADER];
+        data.extend_from_slice(&[0x11; 23]);
+        data.extend_from_slice(&[SBUS_FOOTER, 0x22]);
+        data.extend_from_slice(&frame(1000));
+
+        let mut deframer = Deframer::new();
+        deframer.push_bytes(&data);
+        assert_eq!(deframer.try_parse().unwrap().channels, [1000; 16]);
+        assert_eq!(deframer.stats().frames_ok, 1);
+        assert_eq!(deframer.stats().bytes_discarded, 26);
+
+        let mut deframer = Deframer::new().with_next_header_check(false);
+        deframer.push_bytes(&data);
+        assert_ne!(deframer.try_parse().unwrap().channels, [1000; 16]);
+
+        // Without a following byte, and for SBUS2 frames followed by telemetry, the frame is
+        // accepted
+        let mut deframer = Deframer::new();
+        deframer.push_bytes(&data[..SBUS_FRAME_LENGTH]);
+        assert!(deframer.try_parse().is_some());
+
+        let mut sbus2 = frame(1000);
+        sbus2[SBUS_FRAME_LENGTH - 1] = 0x14;
+        deframer.push_bytes(&[0xAA]);
+        deframer.push_bytes(&sbus2);
+        deframer.push_bytes(&[0x03, 0x00, 0x00]);
+        assert_eq!(deframer.try_parse().unwrap().channels, [1000; 16]);
+    }
+
+    #[test]
+    fn test_peek_frame() {
+        let mut deframer = Deframer::new().with_every_nth(2);
+        let mut data = vec![0xAA, SBUS_HEADER];
+        data.extend_from_slice(&frame(1000));
+        deframer.push_bytes(&data[..20]);
+        assert_eq!(deframer.peek_frame(), None);
+
+        deframer.push_bytes(&data[20..]);
+        let peeked = deframer.peek_frame().unwrap();
+        assert_eq!(peeked.channels, [1000; 16]);
+        assert_eq!(deframer.len(), data.len());
+        assert_eq!(deframer.stats(), &SbusStats::default());
+
+        assert_eq!(deframer.try_parse(), Some(peeked));
+        deframer.push_bytes(&frame(1200));
+        assert_eq!(deframer.peek_frame().unwrap().channels, [1200; 16]);
+        // Dropped by the decimation
+        assert_eq!(deframer.try_parse(), None);
+        assert_eq!(deframer.peek_frame(), None);
+
+        deframer.push_bytes(&frame(1400));
+        deframer.push_error();
+        assert_eq!(deframer.peek_frame(), None);
+    }
+
+    #[test]
+    fn test_consecutive_frame_lost() {
+        let mut deframer = Deframer::new();
+        let mut lost = frame(0);
+        lost[23] = 0b0100;
+
+        for expected in 1..=3 {
+            deframer.push_bytes(&lost);
+            assert!(deframer.try_parse().unwrap().flags.frame_lost);
+            assert_eq!(deframer.stats().consecutive_frame_lost, expected);
+        }
+        deframer.push_bytes(&frame(0));
+        deframer.try_parse().unwrap();
+        assert_eq!(deframer.stats().consecutive_frame_lost, 0);
+    }
+
+    #[test]
+    fn test_overflow_keeps_newest_bytes() {
+        let mut deframer = Deframer::new();
+        deframer.push_bytes(&[0xFF; DEFRAMER_BUFFER_SIZE]);
+        deframer.push_bytes(&frame(42));
+
+        assert_eq!(deframer.len(), DEFRAMER_BUFFER_SIZE);
+        deframer.push_byte(0xFF);
+        assert_eq!(deframer.len(), DEFRAMER_BUFFER_SIZE);
+        deframer.push_bytes(&[0xFF; 2 * DEFRAMER_BUFFER_SIZE]);
+        deframer.push_bytes(&frame(42));
+        assert_eq!(
+            deframer.stats().bytes_discarded as usize,
+            2 * DEFRAMER_BUFFER_SIZE + 2 * SBUS_FRAME_LENGTH + 1
+        );
+
+        let packet = deframer.try_parse().expect("Newest frame should survive");
+        assert_eq!(packet.channels, [42; 16]);
+    }
+
+    #[test]
+    fn test_write_slot_wraps() {
+        let mut deframer = Deframer::new();
+        let data = frame(7);
+
+        for _ in 0..10 {
+            let mut written = 0;
+            while written < data.len() {
+                let slot = deframer.write_slot();
+                let count = slot.len().min(data.len() - written);
+                slot[..count].copy_from_slice(&data[written..written + count]);
+                deframer.commit(count);
+                written += count;
+            }
+            assert_eq!(deframer.try_parse().map(|p| p.channels), Some([7; 16]));
+        }
+    }
+
+    #[test]
+    fn test_idle_gap_realigns() {
+        let mut deframer = Deframer::new().with_idle_gap_alignment(SbusFrameRate::Analog);
+        let byte_time = SbusFrameRate::Analog.byte_time();
+
+        // A truncated frame whose payload holds a header byte, followed by an idle gap
+        let mut truncated = frame(0x0F);
+        truncated[1] = SBUS_HEADER;
+        deframer.push_bytes_at(&truncated[..20], Duration::from_millis(2));
+        assert_eq!(deframer.try_parse(), None);
+
+        let data = frame(1234);
+        let start = Duration::from_millis(10);
+        for (i, &byte) in data.iter().enumerate() {
+            deframer.push_byte_at(byte, start + byte_time * i as u32);
+        }
+        assert_eq!(deframer.try_parse().map(|p| p.channels), Some([1234; 16]));
+        assert_eq!(deframer.stats().bytes_discarded, 20);
+        assert_eq!(deframer.stats().frames_ok, 1);
+    }
+
+    #[test]
+    fn test_chunks_without_gap_are_kept() {
+        let mut deframer = Deframer::new().with_idle_gap_alignment(SbusFrameRate::Analog);
+        let byte_time = SbusFrameRate::Analog.byte_time();
+        let data = frame(99);
+
+        deframer.push_bytes_at(&data[..10], byte_time * 9);
+        deframer.push_bytes_at(&data[10..], byte_time * 24);
+        assert_eq!(deframer.try_parse().map(|p| p.channels), Some([99; 16]));
+        assert_eq!(deframer.stats().bytes_discarded, 0);
+    }
+
+    #[test]
+    fn test_validation_policy() {
+        let mut sbus2 = frame(500);
+        sbus2[SBUS_FRAME_LENGTH - 1] = 0x14;
+
+        let mut deframer = Deframer::new();
+        deframer.push_bytes(&sbus2);
+        assert!(deframer.try_parse().is_some());
+
+        let mut deframer = Deframer::new().with_validation_policy(ValidationPolicy::Strict);
+        deframer.push_bytes(&sbus2);
+        assert_eq!(deframer.try_parse(), None);
+        assert_eq!(deframer.stats().invalid_footer, 1);
+    }
+
+    #[test]
+    fn test_flag_check() {
+        let mut reserved = frame(500);
+        reserved[SBUS_FRAME_LENGTH - 2] = 0x10;
+        reserved[SBUS_FRAME_LENGTH - 1] = 0x14;
+
+        let mut deframer = Deframer::new();
+        deframer.push_bytes(&reserved);
+        assert!(deframer.try_parse().is_some());
+
+        let mut deframer = Deframer::new().with_flag_check(true);
+        deframer.push_bytes(&reserved);
+        deframer.push_bytes(&frame(1));
+        assert_eq!(deframer.try_parse().map(|p| p.channels), Some([1; 16]));
+        assert_eq!(deframer.stats().invalid_flag_byte, 1);
+    }
+
+    #[test]
+    fn test_custom_sync_bytes() {
+        let sync = SyncBytes::new(0xF0, 0xFF);
+        let mut custom = frame(321);
+        custom[0] = sync.header;
+        custom[SBUS_FRAME_LENGTH - 1] = sync.footer;
+
+        let mut deframer = Deframer::new().with_sync_bytes(sync);
+        deframer.push_bytes(&frame(1));
+        deframer.push_bytes(&custom);
+        assert_eq!(deframer.try_parse().map(|p| p.channels), Some([321; 16]));
+        assert_eq!(deframer.stats().bytes_discarded, SBUS_FRAME_LENGTH as u32);
+    }
+
+    #[test]
+    fn test_inverted_bytes() {
+        let inverted = frame(777).map(|byte| !byte);
+        let mut deframer = Deframer::new().with_inverted(true);
+
+        deframer.push_byte(inverted[0]);
+        deframer.push_bytes(&inverted[1..]);
+        assert_eq!(deframer.try_parse().map(|p| p.channels), Some([777; 16]));
+    }
+
+    #[test]
+    fn test_byte_error_drops_frame() {
+        let mut deframer = Deframer::new();
+        let data = frame(600);
+
+        for (i, &byte) in data.iter().enumerate() {
+            deframer.push_byte_with_error(byte, i == 5);
+        }
+        assert_eq!(deframer.try_parse(), None);
+
+        deframer.push_bytes(&data);
+        assert_eq!(deframer.try_parse().map(|p| p.channels), Some([600; 16]));
+        assert_eq!(deframer.try_parse(), None);
+        assert_eq!(deframer.stats().frames_ok, 1);
+        assert_eq!(deframer.stats().byte_errors, 1);
+        assert_eq!(deframer.stats().bytes_discarded, SBUS_FRAME_LENGTH as u32);
+        assert_eq!(deframer.stats().invalid_footer, 0);
+    }
+
+    #[test]
+    fn test_byte_errors_in_several_frames() {
+        let mut deframer = Deframer::new();
+        for value in [600, 700] {
+            for (i, &byte) in frame(value).iter().enumerate() {
+                deframer.push_byte_with_error(byte, i == 5);
+            }
+        }
+        // The error in the second frame does not clear the one in the first
+        assert_eq!(deframer.try_parse(), None);
+        assert_eq!(deframer.stats().byte_errors, 2);
+
+        deframer.push_bytes(&frame(800));
+        assert_eq!(deframer.try_parse().map(|p| p.channels), Some([800; 16]));
+        assert_eq!(deframer.stats().frames_ok, 1);
+    }
+
+    #[test]
+    fn test_channel_mask() {
+        let mut deframer = Deframer::new().with_channel_mask(0b1000_0000_0000_0101);
+        deframer.push_bytes(&frame(600));
+        let mut expected = [0; 16];
+        expected[0] = 600;
+        expected[2] = 600;
+        expected[15] = 600;
+        assert_eq!(deframer.try_parse().map(|p| p.channels), Some(expected));
+    }
+
+    #[test]
+    fn test_every_nth() {
+        let mut deframer = Deframer::new().with_every_nth(3);
+        let mut delivered = [0u16; 3];
+        let mut count = 0;
+        for value in 0..7 {
+            deframer.push_bytes(&frame(value));
+            if let Some(packet) = deframer.try_parse() {
+                delivered[count] = packet.channels[0];
+                count += 1;
+            }
+        }
+        assert_eq!(delivered[..count], [0, 3, 6]);
+        assert_eq!(deframer.stats().frames_ok, 7);
+        assert_eq!(deframer.every_nth(), 3);
+
+        deframer.set_every_nth(0);
+        deframer.push_bytes(&frame(7));
+        assert_eq!(deframer.try_parse().map(|p| p.channels[0]), Some(7));
+    }
+}
@@ -0,0 +1,215 @@
+use embedded_hal_nb::serial::{Error, ErrorKind, Read};
+
+use crate::{
+    error::{FrameCapture, SbusError},
+    packet::{FrameKind, SbusPacket},
+    parser::Deframer,
+    stats::SbusStats,
+    validation::{SyncBytes, ValidationPolicy},
+};
+
+/// Parser for HALs exposing non-blocking single byte UART reads
+///
+/// Received bytes are accumulated internally, so [`SbusParserNb::read_frame_nb`] can be called
+/// from a superloop and returns [`nb::Error::WouldBlock`] until a complete valid frame has
+/// arrived.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # fn example<U: embedded_hal_nb::serial::Read<u8>>(uart: U) {
+/// use sbus_rs::SbusParserNb;
+///
+/// let mut parser = SbusParserNb::new(uart);
+/// loop {
+///     match parser.read_frame_nb() {
+///         Ok(packet) => { /* use the packet */ }
+///         Err(nb::Error::WouldBlock) => { /* do other work */ }
+///         Err(nb::Error::Other(e)) => { /* handle the UART error */ }
+///     }
+/// }
+/// # }
+/// ```
+pub struct SbusParserNb<R>
+where
+    R: Read<u8>,
+{
+    reader: R,
+    deframer: Deframer,
+}
+
+impl<R> SbusParserNb<R>
+where
+    R: Read<u8>,
+{
+    pub const fn new(reader: R) -> Self {
+        Self {
+            reader,
+            deframer: Deframer::new(),
+        }
+    }
+
+    deframer_builders!();
+
+    /// Limits the bytes discarded while searching for a frame, see
+    /// [`Deframer::with_resync_limit`]
+    pub fn with_resync_limit(mut self, limit: usize) -> Self {
+        self.deframer.set_resync_limit(Some(limit));
+        self
+    }
+
+    /// Reads all available bytes and returns the next valid packet once one is complete
+    ///
+    /// Bytes following the packet stay in the UART until the next call. Bytes read before a
+    /// UART error are kept, so the error can be ignored and the call repeated. Parity, framing and
+    /// noise errors drop the frame they occurred in.
+    pub fn read_frame_nb(&mut self) -> nb::Result<SbusPacket, SbusError<R::Error>> {
+        loop {
+            match self.deframer.try_parse_checked() {
+                Ok(Some(packet)) => return Ok(packet),
+                Ok(None) => {}
+                Err(err) => return Err(nb::Error::Other(err.with_reader_error())),
+            }
+            match self.reader.read() {
+                Ok(byte) => self.deframer.push_byte(byte),
+                Err(nb::Error::Other(err)) => {
+                    if matches!(
+                        err.kind(),
+                        ErrorKind::Parity | ErrorKind::FrameFormat | ErrorKind::Noise
+                    ) {
+                        // The byte was received but is corrupt, so the frame around it is too
+                        self.deframer.push_error();
+                    }
+                    return Err(nb::Error::Other(SbusError::Read(err)));
+                }
+                Err(nb::Error::WouldBlock) => return Err(nb::Error::WouldBlock),
+            }
+        }
+    }
+
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// The reader, see [`SbusParser::reader_mut`](crate::SbusParser::reader_mut)
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the reader, dropping the bytes buffered by the parser
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// The next valid frame in the internal buffer, without consuming it, see
+    /// [`Deframer::peek_frame`]
+    pub fn peek_frame(&self) -> Option<SbusPacket> {
+        self.deframer.peek_frame()
+    }
+
+    /// Number of received bytes waiting in the internal buffer
+    pub fn buffered_len(&self) -> usize {
+        self.deframer.len()
+    }
+
+    /// Statistics about the frames and bytes seen so far
+    pub fn stats(&self) -> &SbusStats {
+        self.deframer.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.deframer.reset_stats();
+    }
+
+    /// The last frame that was rejected, see [`Deframer::last_rejected_frame`]
+    pub fn last_rejected_frame(&self) -> &FrameCapture {
+        self.deframer.last_rejected_frame()
+    }
+
+    /// The kind of the last frame returned, see [`Deframer::last_frame_kind`]
+    pub fn last_frame_kind(&self) -> FrameKind {
+        self.deframer.last_frame_kind()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{frame, frame_with_footer};
+    use embedded_hal_nb::serial::ErrorType;
+    use std::collections::VecDeque;
+
+    /// Returns `WouldBlock` whenever it runs out of bytes, like an idle UART
+    struct MockUart(VecDeque<Result<u8, ErrorKind>>);
+
+    impl ErrorType for MockUart {
+        type Error = ErrorKind;
+    }
+
+    impl Read<u8> for MockUart {
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            match self.0.pop_front() {
+                Some(result) => result.map_err(nb::Error::Other),
+                None => Err(nb::Error::WouldBlock),
+            }
+        }
+    }
+
+    #[test]
+    fn test_would_block_until_complete() {
+        let data = frame(1000);
+        let mut parser = SbusParserNb::new(MockUart(data[..10].iter().map(|&b| Ok(b)).collect()));
+        assert_eq!(parser.read_frame_nb(), Err(nb::Error::WouldBlock));
+
+        parser
+            .reader_mut()
+            .0
+            .extend(data[10..].iter().map(|&b| Ok(b)));
+        assert_eq!(parser.read_frame_nb().unwrap().channels, [1000; 16]);
+        assert_eq!(parser.read_frame_nb(), Err(nb::Error::WouldBlock));
+    }
+
+    #[test]
+    fn test_error_keeps_partial_frame() {
+        let data = frame(500);
+        let mut bytes: VecDeque<_> = data[..5].iter().map(|&b| Ok(b)).collect();
+        bytes.push_back(Err(ErrorKind::Overrun));
+        bytes.extend(data[5..].iter().map(|&b| Ok(b)));
+        let mut parser = SbusParserNb::new(MockUart(bytes));
+
+        assert_eq!(
+            parser.read_frame_nb(),
+            Err(nb::Error::Other(SbusError::Read(ErrorKind::Overrun)))
+        );
+        assert_eq!(parser.read_frame_nb().unwrap().channels, [500; 16]);
+    }
+
+    #[test]
+    fn test_parity_error_drops_frame() {
+        let data = frame(700);
+        let mut bytes: VecDeque<_> = data[..5].iter().map(|&b| Ok(b)).collect();
+        bytes.push_back(Err(ErrorKind::Parity));
+        bytes.extend(data[6..].iter().map(|&b| Ok(b)));
+        bytes.extend(data.iter().map(|&b| Ok(b)));
+        let mut parser = SbusParserNb::new(MockUart(bytes));
+
+        assert_eq!(
+            parser.read_frame_nb(),
+            Err(nb::Error::Other(SbusError::Read(ErrorKind::Parity)))
+        );
+        assert_eq!(parser.read_frame_nb().unwrap().channels, [700; 16]);
+        assert_eq!(parser.stats().frames_ok, 1);
+        assert_eq!(parser.stats().byte_errors, 1);
+    }
+
+    #[test]
+    fn test_last_rejected_frame() {
+        let corrupted = frame_with_footer(0x55);
+        let bytes = frame(1).into_iter().chain(corrupted).map(Ok).collect();
+        let mut parser = SbusParserNb::new(MockUart(bytes));
+
+        assert_eq!(parser.read_frame_nb().unwrap().channels[0], 1);
+        assert_eq!(parser.read_frame_nb(), Err(nb::Error::WouldBlock));
+        assert_eq!(*parser.last_rejected_frame(), FrameCapture::new(&corrupted));
+    }
+}
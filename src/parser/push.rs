@@ -0,0 +1,138 @@
+use core::time::Duration;
+
+use crate::{
+    error::{FrameCapture, SbusError},
+    frame_rate::SbusFrameRate,
+    packet::{FrameKind, SbusPacket},
+    parser::Deframer,
+    stats::SbusStats,
+    validation::{SyncBytes, ValidationPolicy},
+};
+
+/// Parser for SBUS frames from bytes pushed in by the caller
+///
+/// Unlike [`SbusParser`](crate::SbusParser), this parser does not own a reader, which makes it a
+/// good fit for bytes arriving from an interrupt handler, a DMA buffer or a capture file.
+/// Bytes are accumulated until a complete frame with a valid header and footer is available;
+/// anything preceding it is discarded.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{SBusPacketParser, SBUS_FOOTER, SBUS_FRAME_LENGTH, SBUS_HEADER};
+///
+/// let mut frame = [0u8; SBUS_FRAME_LENGTH];
+/// frame[0] = SBUS_HEADER;
+/// frame[SBUS_FRAME_LENGTH - 1] = SBUS_FOOTER;
+///
+/// let mut parser = SBusPacketParser::new();
+/// parser.push_bytes(&frame[..10]);
+/// assert!(parser.try_parse().is_none());
+///
+/// parser.push_bytes(&frame[10..]);
+/// assert!(parser.try_parse().is_some());
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SBusPacketParser {
+    deframer: Deframer,
+}
+
+impl SBusPacketParser {
+    pub const fn new() -> Self {
+        Self {
+            deframer: Deframer::new(),
+        }
+    }
+
+    deframer_builders!(const);
+
+    /// Enables realigning on the idle gap between frames for bytes pushed with timestamps, see
+    /// [`Deframer::with_idle_gap_alignment`]
+    pub const fn with_idle_gap_alignment(self, frame_rate: SbusFrameRate) -> Self {
+        Self {
+            deframer: self.deframer.with_idle_gap_alignment(frame_rate),
+        }
+    }
+
+    /// Limits the bytes discarded while searching for a frame, see
+    /// [`Deframer::with_resync_limit`]
+    ///
+    /// Applies to [`SBusPacketParser::try_parse_checked`].
+    pub const fn with_resync_limit(self, limit: usize) -> Self {
+        Self {
+            deframer: self.deframer.with_resync_limit(limit),
+        }
+    }
+
+    /// Appends a byte received at `timestamp`, measured from any fixed epoch
+    pub fn push_byte_at(&mut self, byte: u8, timestamp: Duration) {
+        self.deframer.push_byte_at(byte, timestamp);
+    }
+
+    /// Appends bytes received back to back, the last of them at `timestamp`
+    pub fn push_bytes_at(&mut self, bytes: &[u8], timestamp: Duration) {
+        self.deframer.push_bytes_at(bytes, timestamp);
+    }
+
+    /// Appends a byte along with whether the UART reported a parity, framing or noise error for
+    /// it, see [`Deframer::push_byte_with_error`]
+    pub fn push_byte_with_error(&mut self, byte: u8, error: bool) {
+        self.deframer.push_byte_with_error(byte, error);
+    }
+
+    /// Appends received bytes to the internal buffer
+    ///
+    /// If more bytes are pushed than the buffer can hold without calling
+    /// [`SBusPacketParser::try_parse`], the oldest bytes are dropped.
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        self.deframer.push_bytes(bytes);
+    }
+
+    /// Returns the next valid packet from the buffered bytes, if a complete one is available
+    pub fn try_parse(&mut self) -> Option<SbusPacket> {
+        self.deframer.try_parse()
+    }
+
+    /// Like [`SBusPacketParser::try_parse`], but fails with [`SbusError::DesyncLimitExceeded`]
+    /// once more bytes than the [resync limit](SBusPacketParser::with_resync_limit) were
+    /// discarded since the last valid frame, see [`Deframer::try_parse_checked`]
+    pub fn try_parse_checked(&mut self) -> Result<Option<SbusPacket>, SbusError> {
+        self.deframer.try_parse_checked()
+    }
+
+    /// Discards all buffered bytes, e.g. after the link was reconfigured
+    pub fn clear_buffer(&mut self) {
+        self.deframer.clear();
+    }
+
+    /// The next valid frame in the internal buffer, without consuming it, see
+    /// [`Deframer::peek_frame`]
+    pub fn peek_frame(&self) -> Option<SbusPacket> {
+        self.deframer.peek_frame()
+    }
+
+    /// Number of received bytes waiting in the internal buffer
+    pub fn buffered_len(&self) -> usize {
+        self.deframer.len()
+    }
+
+    /// Statistics about the frames and bytes seen so far
+    pub fn stats(&self) -> &SbusStats {
+        self.deframer.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.deframer.reset_stats();
+    }
+
+    /// The last frame that was rejected, see [`Deframer::last_rejected_frame`]
+    pub fn last_rejected_frame(&self) -> &FrameCapture {
+        self.deframer.last_rejected_frame()
+    }
+
+    /// The kind of the last frame returned, see [`Deframer::last_frame_kind`]
+    pub fn last_frame_kind(&self) -> FrameKind {
+        self.deframer.last_frame_kind()
+    }
+}
@@ -0,0 +1,57 @@
+//! Searching buffered bytes for frame headers
+
+/// Position of the first `needle` in `haystack`
+#[cfg(feature = "memchr")]
+pub(crate) fn find_byte(needle: u8, haystack: &[u8]) -> Option<usize> {
+    memchr::memchr(needle, haystack)
+}
+
+/// Position of the first `needle` in `haystack`
+///
+/// Compares a machine word at a time, then finds the byte within the matching word.
+#[cfg(not(feature = "memchr"))]
+pub(crate) fn find_byte(needle: u8, haystack: &[u8]) -> Option<usize> {
+    const WORD: usize = core::mem::size_of::<usize>();
+    const LOW_BITS: usize = usize::from_ne_bytes([0x01; WORD]);
+    const HIGH_BITS: usize = usize::from_ne_bytes([0x80; WORD]);
+
+    let repeated = usize::from_ne_bytes([needle; WORD]);
+    let mut offset = 0;
+    for chunk in haystack.chunks_exact(WORD) {
+        let mut word = [0; WORD];
+        word.copy_from_slice(chunk);
+        // Zero bytes where the needle is, found by the classic has-zero-byte test
+        let diff = usize::from_ne_bytes(word) ^ repeated;
+        if diff.wrapping_sub(LOW_BITS) & !diff & HIGH_BITS != 0 {
+            break;
+        }
+        offset += WORD;
+    }
+    haystack[offset..]
+        .iter()
+        .position(|&byte| byte == needle)
+        .map(|position| offset + position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_byte() {
+        let mut haystack = [0xAAu8; 67];
+        assert_eq!(find_byte(0x0F, &haystack), None);
+        assert_eq!(find_byte(0x0F, &[]), None);
+
+        for position in [0, 1, 7, 8, 9, 31, 63, 66] {
+            haystack[position] = 0x0F;
+            assert_eq!(find_byte(0x0F, &haystack), Some(position));
+            assert_eq!(find_byte(0x0F, &haystack[..position]), None);
+            haystack[position] = 0xAA;
+        }
+
+        // Bytes next to the needle must not trip the word test
+        let near = [0x0E, 0x10, 0x8F, 0x0F];
+        assert_eq!(find_byte(0x0F, &near), Some(3));
+    }
+}
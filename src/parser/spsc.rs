@@ -0,0 +1,207 @@
+use heapless::spsc::Consumer;
+
+use crate::{
+    error::{FrameCapture, SbusError},
+    packet::{FrameKind, SbusPacket},
+    parser::Deframer,
+    stats::SbusStats,
+    validation::{SyncBytes, ValidationPolicy},
+};
+
+/// Parser draining bytes from a `heapless::spsc` queue
+///
+/// The usual bare-metal pattern is a UART RX interrupt enqueueing every received byte through
+/// the [`Producer`](heapless::spsc::Producer) half, while the main loop or a lower priority task
+/// polls this parser with the [`Consumer`] half.
+///
+/// # Example
+///
+/// ```rust
+/// use heapless::spsc::Queue;
+/// use sbus_rs::{SbusSpscParser, SBUS_FOOTER, SBUS_FRAME_LENGTH, SBUS_HEADER};
+///
+/// let mut queue: Queue<u8, 64> = Queue::new();
+/// let (mut producer, consumer) = queue.split();
+/// let mut parser = SbusSpscParser::new(consumer);
+///
+/// // In the UART interrupt
+/// let mut frame = [0u8; SBUS_FRAME_LENGTH];
+/// frame[0] = SBUS_HEADER;
+/// frame[SBUS_FRAME_LENGTH - 1] = SBUS_FOOTER;
+/// for byte in frame {
+///     producer.enqueue(byte).unwrap();
+/// }
+///
+/// // In the main loop
+/// assert!(parser.poll().is_some());
+/// ```
+pub struct SbusSpscParser<'a, const N: usize> {
+    consumer: Consumer<'a, u8, N>,
+    deframer: Deframer,
+}
+
+impl<'a, const N: usize> SbusSpscParser<'a, N> {
+    pub const fn new(consumer: Consumer<'a, u8, N>) -> Self {
+        Self {
+            consumer,
+            deframer: Deframer::new(),
+        }
+    }
+
+    deframer_builders!();
+
+    /// Limits the bytes discarded while searching for a frame, see
+    /// [`Deframer::with_resync_limit`]
+    ///
+    /// Applies to [`SbusSpscParser::poll_checked`].
+    pub fn with_resync_limit(mut self, limit: usize) -> Self {
+        self.deframer.set_resync_limit(Some(limit));
+        self
+    }
+
+    /// Drains queued bytes until the next valid packet is complete
+    ///
+    /// Bytes following the packet stay queued for the next call.
+    pub fn poll(&mut self) -> Option<SbusPacket> {
+        loop {
+            if let Some(packet) = self.deframer.try_parse() {
+                return Some(packet);
+            }
+            self.deframer.push_byte(self.consumer.dequeue()?);
+        }
+    }
+
+    /// Like [`SbusSpscParser::poll`], but fails with [`SbusError::DesyncLimitExceeded`] once
+    /// more bytes than the [resync limit](SbusSpscParser::with_resync_limit) were discarded since
+    /// the last valid frame
+    ///
+    /// The count starts over after the error, so polling can continue.
+    pub fn poll_checked(&mut self) -> Result<Option<SbusPacket>, SbusError> {
+        loop {
+            if let Some(packet) = self.deframer.try_parse_checked()? {
+                return Ok(Some(packet));
+            }
+            let Some(byte) = self.consumer.dequeue() else {
+                return Ok(None);
+            };
+            self.deframer.push_byte(byte);
+        }
+    }
+
+    /// Drains all queued bytes and returns the newest valid packet among them
+    pub fn poll_latest(&mut self) -> Option<SbusPacket> {
+        let mut latest = None;
+        while let Some(packet) = self.poll() {
+            latest = Some(packet);
+        }
+        latest
+    }
+
+    pub fn consumer_mut(&mut self) -> &mut Consumer<'a, u8, N> {
+        &mut self.consumer
+    }
+
+    /// The next valid frame in the internal buffer, without consuming it, see
+    /// [`Deframer::peek_frame`]
+    pub fn peek_frame(&self) -> Option<SbusPacket> {
+        self.deframer.peek_frame()
+    }
+
+    /// Number of received bytes waiting in the internal buffer
+    pub fn buffered_len(&self) -> usize {
+        self.deframer.len()
+    }
+
+    /// Statistics about the frames and bytes seen so far
+    pub fn stats(&self) -> &SbusStats {
+        self.deframer.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.deframer.reset_stats();
+    }
+
+    /// The last frame that was rejected, see [`Deframer::last_rejected_frame`]
+    pub fn last_rejected_frame(&self) -> &FrameCapture {
+        self.deframer.last_rejected_frame()
+    }
+
+    /// The kind of the last frame returned, see [`Deframer::last_frame_kind`]
+    pub fn last_frame_kind(&self) -> FrameKind {
+        self.deframer.last_frame_kind()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{frame, frame_with_footer};
+    use heapless::spsc::Queue;
+
+    #[test]
+    fn test_poll_across_partial_frames() {
+        let mut queue: Queue<u8, 128> = Queue::new();
+        let (mut producer, consumer) = queue.split();
+        let mut parser = SbusSpscParser::new(consumer);
+
+        let data = frame(1000);
+        for &byte in [0xAA, 0x55].iter().chain(&data[..10]) {
+            producer.enqueue(byte).unwrap();
+        }
+        assert_eq!(parser.poll(), None);
+
+        for &byte in &data[10..] {
+            producer.enqueue(byte).unwrap();
+        }
+        assert_eq!(parser.poll().unwrap().channels, [1000; 16]);
+        assert_eq!(parser.stats().bytes_discarded, 2);
+    }
+
+    #[test]
+    fn test_poll_latest() {
+        let mut queue: Queue<u8, 128> = Queue::new();
+        let (mut producer, consumer) = queue.split();
+        let mut parser = SbusSpscParser::new(consumer);
+
+        for value in [100, 200, 300] {
+            for byte in frame(value) {
+                producer.enqueue(byte).unwrap();
+            }
+        }
+        assert_eq!(parser.poll_latest().unwrap().channels, [300; 16]);
+        assert_eq!(parser.stats().frames_ok, 3);
+        assert_eq!(parser.poll_latest(), None);
+    }
+
+    #[test]
+    fn test_last_frame_kind() {
+        let mut queue: Queue<u8, 128> = Queue::new();
+        let (mut producer, consumer) = queue.split();
+        let mut parser = SbusSpscParser::new(consumer);
+
+        for byte in frame(0).into_iter().chain(frame_with_footer(0x14)) {
+            producer.enqueue(byte).unwrap();
+        }
+        assert!(parser.poll().is_some());
+        assert_eq!(parser.last_frame_kind(), FrameKind::Sbus1);
+        assert!(parser.poll().is_some());
+        assert_eq!(parser.last_frame_kind(), FrameKind::Sbus2(1));
+    }
+
+    #[test]
+    fn test_poll_checked() {
+        let mut queue: Queue<u8, 128> = Queue::new();
+        let (mut producer, consumer) = queue.split();
+        let mut parser = SbusSpscParser::new(consumer).with_resync_limit(30);
+
+        for &byte in [0xAA; 40].iter().chain(&frame(800)) {
+            producer.enqueue(byte).unwrap();
+        }
+        assert_eq!(
+            parser.poll_checked(),
+            Err(SbusError::DesyncLimitExceeded(31))
+        );
+        assert_eq!(parser.poll_checked().unwrap().unwrap().channels, [800; 16]);
+        assert_eq!(parser.poll_checked(), Ok(None));
+    }
+}
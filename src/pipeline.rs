@@ -0,0 +1,356 @@
+use core::convert::Infallible;
+
+use crate::{FrameSink, PacketTransform, SbusPacket};
+
+/// The receiving stage of an [`SbusPipeline`], producing packets from a blocking source
+pub trait Receive {
+    type Error;
+
+    /// Waits for the next packet
+    fn receive(&mut self) -> Result<SbusPacket, Self::Error>;
+
+    /// Whether [`SbusPipeline::run`] stops after `err` instead of dropping the frame, by
+    /// default every error stops it
+    fn is_fatal(err: &Self::Error) -> bool {
+        let _ = err;
+        true
+    }
+}
+
+/// The transmitting stage of an [`SbusPipeline`], consuming packets with blocking I/O
+///
+/// Closures taking `&SbusPacket` transmit without failing, and `()` discards all packets.
+pub trait Transmit {
+    type Error;
+
+    fn transmit(&mut self, packet: &SbusPacket) -> Result<(), Self::Error>;
+}
+
+impl Transmit for () {
+    type Error = Infallible;
+
+    fn transmit(&mut self, _packet: &SbusPacket) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+impl<F> Transmit for F
+where
+    F: FnMut(&SbusPacket),
+{
+    type Error = Infallible;
+
+    fn transmit(&mut self, packet: &SbusPacket) -> Result<(), Infallible> {
+        self(packet);
+        Ok(())
+    }
+}
+
+/// The async counterpart of [`Receive`]
+#[allow(async_fn_in_trait)]
+pub trait ReceiveAsync {
+    type Error;
+
+    /// Waits for the next packet
+    async fn receive(&mut self) -> Result<SbusPacket, Self::Error>;
+
+    /// Whether [`SbusPipeline::run_async`] stops after `err` instead of dropping the frame, by
+    /// default every error stops it
+    fn is_fatal(err: &Self::Error) -> bool {
+        let _ = err;
+        true
+    }
+}
+
+/// The async counterpart of [`Transmit`]
+#[allow(async_fn_in_trait)]
+pub trait TransmitAsync {
+    type Error;
+
+    async fn transmit(&mut self, packet: &SbusPacket) -> Result<(), Self::Error>;
+}
+
+impl TransmitAsync for () {
+    type Error = Infallible;
+
+    async fn transmit(&mut self, _packet: &SbusPacket) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// Error of a pipeline, telling apart the receiving and the transmitting stage
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum PipelineError<R, W> {
+    Receive(R),
+    Transmit(W),
+}
+
+/// Receives packets, passes them through a chain of [`PacketTransform`]s and transmits them
+///
+/// Assemble a pipeline from a receiving stage, such as a parser, any number of transforms and
+/// a transmitting stage, such as a writer. Every transmitted packet is also handed to the
+/// [`FrameSink`]s added with [`tap`](Self::tap), e.g. to log or monitor the output. Without a transmitting stage the packets are
+/// returned by [`forward`](Self::forward) only. Blocking stages run with
+/// [`forward`](Self::forward) and [`run`](Self::run), async ones with
+/// [`forward_async`](Self::forward_async) and [`run_async`](Self::run_async).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # fn example<R: embedded_io::Read, W: embedded_io::Write>(receiver: R, servos: W) {
+/// use sbus_rs::{Deadband, FilterBank, Mixer, SbusParser, SbusPipeline, SbusWriter};
+///
+/// let mut pipeline = SbusPipeline::new(SbusParser::new(receiver))
+///     .transform(FilterBank::all(Deadband::new(8)))
+///     .transform(Mixer::new())
+///     .transmit(SbusWriter::new(servos));
+/// let _ = pipeline.run();
+/// # }
+/// ```
+pub struct SbusPipeline<R, T = (), W = (), K = ()> {
+    receive: R,
+    transform: T,
+    transmit: W,
+    taps: K,
+}
+
+impl<R> SbusPipeline<R> {
+    /// Starts a pipeline receiving from `receive`
+    pub fn new(receive: R) -> Self {
+        Self {
+            receive,
+            transform: (),
+            transmit: (),
+            taps: (),
+        }
+    }
+}
+
+impl<R, T, W, K> SbusPipeline<R, T, W, K>
+where
+    T: PacketTransform,
+    K: FrameSink,
+{
+    /// Appends a transform, applied after the ones added before
+    pub fn transform<U>(self, transform: U) -> SbusPipeline<R, (T, U), W, K>
+    where
+        U: PacketTransform,
+    {
+        SbusPipeline {
+            receive: self.receive,
+            transform: (self.transform, transform),
+            transmit: self.transmit,
+            taps: self.taps,
+        }
+    }
+
+    /// Sets the transmitting stage
+    pub fn transmit<X>(self, transmit: X) -> SbusPipeline<R, T, X, K> {
+        SbusPipeline {
+            receive: self.receive,
+            transform: self.transform,
+            transmit,
+            taps: self.taps,
+        }
+    }
+
+    /// Adds a sink observing the transmitted packets
+    pub fn tap<S>(self, sink: S) -> SbusPipeline<R, T, W, (K, S)>
+    where
+        S: FrameSink,
+    {
+        SbusPipeline {
+            receive: self.receive,
+            transform: self.transform,
+            transmit: self.transmit,
+            taps: (self.taps, sink),
+        }
+    }
+
+    pub fn receive_mut(&mut self) -> &mut R {
+        &mut self.receive
+    }
+
+    pub fn transform_mut(&mut self) -> &mut T {
+        &mut self.transform
+    }
+
+    pub fn transmit_mut(&mut self) -> &mut W {
+        &mut self.transmit
+    }
+
+    pub fn taps_mut(&mut self) -> &mut K {
+        &mut self.taps
+    }
+
+    pub fn into_parts(self) -> (R, T, W, K) {
+        (self.receive, self.transform, self.transmit, self.taps)
+    }
+}
+
+impl<R, T, W, K> SbusPipeline<R, T, W, K>
+where
+    R: Receive,
+    T: PacketTransform,
+    W: Transmit,
+    K: FrameSink,
+{
+    /// Passes one packet through all stages, returning the packet that was transmitted
+    pub fn forward(&mut self) -> Result<SbusPacket, PipelineError<R::Error, W::Error>> {
+        let mut packet = self.receive.receive().map_err(PipelineError::Receive)?;
+        self.transform.transform(&mut packet);
+        self.transmit
+            .transmit(&packet)
+            .map_err(PipelineError::Transmit)?;
+        self.taps.on_frame(&packet);
+        Ok(packet)
+    }
+
+    /// Passes packets through all stages forever
+    ///
+    /// Only returns on transmit errors and receive errors that are fatal according to
+    /// [`Receive::is_fatal`].
+    pub fn run(&mut self) -> Result<Infallible, PipelineError<R::Error, W::Error>> {
+        loop {
+            match self.forward() {
+                Ok(_) => {}
+                Err(PipelineError::Receive(err)) if !R::is_fatal(&err) => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+impl<R, T, W, K> SbusPipeline<R, T, W, K>
+where
+    R: ReceiveAsync,
+    T: PacketTransform,
+    W: TransmitAsync,
+    K: FrameSink,
+{
+    /// Passes one packet through all stages, returning the packet that was transmitted
+    pub async fn forward_async(&mut self) -> Result<SbusPacket, PipelineError<R::Error, W::Error>> {
+        let mut packet = self
+            .receive
+            .receive()
+            .await
+            .map_err(PipelineError::Receive)?;
+        self.transform.transform(&mut packet);
+        self.transmit
+            .transmit(&packet)
+            .await
+            .map_err(PipelineError::Transmit)?;
+        self.taps.on_frame(&packet);
+        Ok(packet)
+    }
+
+    /// Passes packets through all stages forever
+    ///
+    /// Only returns on transmit errors and receive errors that are fatal according to
+    /// [`ReceiveAsync::is_fatal`].
+    pub async fn run_async(&mut self) -> Result<Infallible, PipelineError<R::Error, W::Error>> {
+        loop {
+            match self.forward_async().await {
+                Ok(_) => {}
+                Err(PipelineError::Receive(err)) if !R::is_fatal(&err) => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_forward() {
+        use crate::{SbusError, SbusParser};
+        use embedded_io_adapters::std::FromStd;
+
+        let mut stream = SbusPacket::neutral().to_array().repeat(2);
+        stream.extend_from_slice(&[0xAA; 25]);
+        stream.extend_from_slice(&SbusPacket::failsafe_default().to_array());
+
+        let mut received = Vec::new();
+        let mut tapped = 0;
+        let mut pipeline = SbusPipeline::new(SbusParser::new(FromStd::new(stream.as_slice())))
+            .transform(|packet: &mut SbusPacket| packet.channels[0] = 100)
+            .transform(|packet: &mut SbusPacket| packet.channels[0] *= 2)
+            .transmit(|packet: &SbusPacket| received.push(*packet))
+            .tap(|_: &SbusPacket| tapped += 1);
+
+        assert_eq!(pipeline.forward().unwrap().channels[0], 200);
+        assert!(matches!(
+            pipeline.run(),
+            Err(PipelineError::Receive(SbusError::UnexpectedEof))
+        ));
+
+        assert_eq!(received.len(), 3);
+        assert_eq!(tapped, 3);
+        assert!(received.iter().all(|packet| packet.channels[0] == 200));
+        assert!(received[2].flags.failsafe);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_forward_mid_frame() {
+        use crate::{SbusError, SbusParser};
+        use embedded_io_adapters::std::FromStd;
+
+        // Started in the middle of a frame, as a pass-through on a live link usually is
+        let frame = SbusPacket::neutral().to_array();
+        let mut stream = frame[10..].to_vec();
+        stream.extend_from_slice(&frame.repeat(3));
+
+        let mut received = 0;
+        let mut pipeline = SbusPipeline::new(SbusParser::new(FromStd::new(stream.as_slice())))
+            .transmit(|_: &SbusPacket| received += 1);
+        assert!(matches!(
+            pipeline.run(),
+            Err(PipelineError::Receive(SbusError::UnexpectedEof))
+        ));
+        assert_eq!(received, 3);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_writer_stage() {
+        use crate::{SbusParser, SbusWriter};
+        use embedded_io_adapters::std::FromStd;
+
+        let stream = SbusPacket::neutral().to_array();
+        let mut pipeline = SbusPipeline::new(SbusParser::new(FromStd::new(&stream[..])))
+            .transmit(SbusWriter::new(FromStd::new(Vec::new())));
+
+        let packet = pipeline.forward().unwrap();
+        let (_, (), mut writer, ()) = pipeline.into_parts();
+        assert_eq!(writer.inner_mut().inner()[..], packet.to_array());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_run_async() {
+        use crate::{SbusParserAsync, SbusWriterAsync};
+        use embedded_io_adapters::tokio_1::FromTokio;
+
+        // Starts mid-frame, with garbage between the frames
+        let mut stream = SbusPacket::neutral().to_array()[20..].to_vec();
+        stream.extend_from_slice(&SbusPacket::neutral().to_array().repeat(2));
+        stream.extend_from_slice(&[0xAA; 25]);
+        stream.extend_from_slice(&SbusPacket::neutral().to_array());
+
+        let mut pipeline =
+            SbusPipeline::new(SbusParserAsync::new(FromTokio::new(stream.as_slice())))
+                .transform(|packet: &mut SbusPacket| packet.flags.d2 = true)
+                .transmit(SbusWriterAsync::new(FromTokio::new(Vec::new())));
+
+        assert!(pipeline.run_async().await.is_err());
+        let written = pipeline.transmit_mut().inner_mut().inner();
+        let mut expected = SbusPacket::neutral();
+        expected.flags.d2 = true;
+        assert_eq!(written[..], [expected.to_array(); 3].concat());
+    }
+}
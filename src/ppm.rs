@@ -0,0 +1,220 @@
+use crate::{channel_to_micros, Channel, SbusPacket, CHANNEL_COUNT};
+
+/// Default CPPM frame length in microseconds
+pub const PPM_FRAME_LENGTH: u32 = 22_500;
+/// Default width of the separator pulse starting every channel in microseconds
+pub const PPM_PULSE_WIDTH: u16 = 300;
+/// Shortest sync gap in microseconds, above the 2700 µs receivers commonly require to find the
+/// start of a frame
+pub const PPM_MIN_SYNC_GAP: u32 = 3_000;
+
+/// Converts packets into CPPM pulse trains
+///
+/// A CPPM frame is a separator pulse before every channel and one more before the sync gap.
+/// Each channel lasts from the start of its pulse to the start of the next, its pulse width in
+/// [`channel_to_micros`], and the sync gap fills up the rest of the frame. If the channels
+/// leave less than [`PPM_MIN_SYNC_GAP`], the frame is stretched instead.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{PpmEncoder, SbusPacket};
+///
+/// let encoder = PpmEncoder::new().with_channel_count(8);
+/// let frame = encoder.encode(&SbusPacket::neutral());
+/// assert_eq!(frame.channels(), [1500; 8]);
+/// assert_eq!(frame.sync_gap(), 22_500 - 8 * 1500);
+///
+/// // Periods for a timer counting at 2 MHz, channels first and the sync gap last
+/// for ticks in frame.ticks(2_000_000) {
+///     // Load `ticks` as the period of the next pulse
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PpmEncoder {
+    channel_count: u8,
+    frame_length: u32,
+    pulse_width: u16,
+}
+
+impl PpmEncoder {
+    /// An encoder for 8 channels in [`PPM_FRAME_LENGTH`] frames
+    pub const fn new() -> Self {
+        Self {
+            channel_count: 8,
+            frame_length: PPM_FRAME_LENGTH,
+            pulse_width: PPM_PULSE_WIDTH,
+        }
+    }
+
+    /// Sends channels 1 to `count`, clamped to 1 to 16; channels 17 and 18 are digital and not
+    /// sent
+    pub const fn with_channel_count(mut self, count: u8) -> Self {
+        self.channel_count = if count == 0 {
+            1
+        } else if count as usize > CHANNEL_COUNT {
+            CHANNEL_COUNT as u8
+        } else {
+            count
+        };
+        self
+    }
+
+    /// Sets the frame length in microseconds
+    pub const fn with_frame_length(mut self, micros: u32) -> Self {
+        self.frame_length = micros;
+        self
+    }
+
+    /// Sets the width of the separator pulses in microseconds
+    pub const fn with_pulse_width(mut self, micros: u16) -> Self {
+        self.pulse_width = micros;
+        self
+    }
+
+    pub const fn channel_count(&self) -> u8 {
+        self.channel_count
+    }
+
+    pub const fn pulse_width(&self) -> u16 {
+        self.pulse_width
+    }
+
+    /// The pulse train for `packet`
+    pub fn encode(&self, packet: &SbusPacket) -> PpmFrame {
+        let mut channels = [0u16; CHANNEL_COUNT];
+        for (micros, channel) in channels
+            .iter_mut()
+            .zip(Channel::ALL)
+            .take(self.channel_count as usize)
+        {
+            *micros = channel_to_micros(packet[channel]);
+        }
+        let total: u32 = channels.iter().map(|&micros| micros as u32).sum();
+        PpmFrame {
+            channels,
+            channel_count: self.channel_count,
+            sync_gap: self
+                .frame_length
+                .saturating_sub(total)
+                .max(PPM_MIN_SYNC_GAP),
+            pulse_width: self.pulse_width,
+        }
+    }
+}
+
+impl Default for PpmEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One CPPM frame, as the periods between the starts of consecutive separator pulses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct PpmFrame {
+    channels: [u16; CHANNEL_COUNT],
+    channel_count: u8,
+    sync_gap: u32,
+    pulse_width: u16,
+}
+
+impl PpmFrame {
+    /// The channel periods in microseconds, in channel order
+    pub fn channels(&self) -> &[u16] {
+        &self.channels[..self.channel_count as usize]
+    }
+
+    /// The period from the pulse after the last channel to the next frame in microseconds
+    pub const fn sync_gap(&self) -> u32 {
+        self.sync_gap
+    }
+
+    /// The width of every separator pulse in microseconds
+    pub const fn pulse_width(&self) -> u16 {
+        self.pulse_width
+    }
+
+    /// The length of the whole frame in microseconds
+    pub fn frame_length(&self) -> u32 {
+        self.periods().sum()
+    }
+
+    /// The channel periods followed by the sync gap, in microseconds
+    pub fn periods(&self) -> impl Iterator<Item = u32> + '_ {
+        self.channels()
+            .iter()
+            .map(|&micros| micros as u32)
+            .chain(core::iter::once(self.sync_gap))
+    }
+
+    /// Like [`periods`](Self::periods), in ticks of a timer counting at `timer_hz`, rounded to
+    /// the nearest tick
+    pub fn ticks(&self, timer_hz: u32) -> impl Iterator<Item = u32> + '_ {
+        self.periods()
+            .map(move |micros| micros_to_ticks(micros, timer_hz))
+    }
+
+    /// The separator pulse width in ticks of a timer counting at `timer_hz`
+    pub const fn pulse_ticks(&self, timer_hz: u32) -> u32 {
+        micros_to_ticks(self.pulse_width as u32, timer_hz)
+    }
+}
+
+const fn micros_to_ticks(micros: u32, timer_hz: u32) -> u32 {
+    ((micros as u64 * timer_hz as u64 + 500_000) / 1_000_000) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MIN};
+
+    #[test]
+    fn test_encode() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch1] = CHANNEL_FUTABA_MIN;
+        packet[Channel::Ch2] = CHANNEL_FUTABA_MAX;
+
+        let frame = PpmEncoder::new().with_channel_count(4).encode(&packet);
+        assert_eq!(frame.channels(), [988, 2012, 1500, 1500]);
+        assert_eq!(frame.sync_gap(), 22_500 - 6000);
+        assert_eq!(frame.frame_length(), 22_500);
+        assert_eq!(frame.pulse_width(), PPM_PULSE_WIDTH);
+        assert_eq!(
+            frame.periods().collect::<Vec<_>>(),
+            [988, 2012, 1500, 1500, 16_500]
+        );
+    }
+
+    #[test]
+    fn test_stretched_frame() {
+        let mut packet = SbusPacket::neutral();
+        packet.channels = [CHANNEL_FUTABA_MAX; CHANNEL_COUNT];
+
+        let encoder = PpmEncoder::new().with_channel_count(20);
+        assert_eq!(encoder.channel_count(), 16);
+        let frame = encoder.encode(&packet);
+        assert_eq!(frame.sync_gap(), PPM_MIN_SYNC_GAP);
+        assert_eq!(frame.frame_length(), 16 * 2012 + PPM_MIN_SYNC_GAP);
+        assert_eq!(PpmEncoder::new().with_channel_count(0).channel_count(), 1);
+    }
+
+    #[test]
+    fn test_ticks() {
+        let frame = PpmEncoder::new()
+            .with_channel_count(2)
+            .with_frame_length(20_000)
+            .with_pulse_width(400)
+            .encode(&SbusPacket::neutral());
+        assert_eq!(
+            frame.ticks(2_000_000).collect::<Vec<_>>(),
+            [3000, 3000, 34_000]
+        );
+        // 1500 µs at 32768 Hz are 49.152 ticks
+        assert_eq!(frame.ticks(32_768).next(), Some(49));
+        assert_eq!(frame.pulse_ticks(1_000_000), 400);
+    }
+}
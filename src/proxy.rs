@@ -0,0 +1,154 @@
+//! Forwarding SBUS frames from a receiver to a servo bus or flight controller
+
+#[cfg(feature = "async")]
+mod asynch;
+#[cfg(feature = "blocking")]
+mod blocking;
+
+#[cfg(feature = "async")]
+pub use asynch::SbusProxyAsync;
+#[cfg(feature = "blocking")]
+pub use blocking::SbusProxy;
+
+use crate::{Channel, PacketTransform, SbusError, SbusPacket, CHANNEL_COUNT};
+
+/// Error of a proxy or a transceiver, telling apart the reading and the writing side
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ProxyError<R, W> {
+    /// Reading or decoding the incoming frame failed
+    Read(SbusError<R>),
+    /// Encoding or writing the outgoing frame failed
+    Write(SbusError<W>),
+}
+
+/// What an [`Overrides`] table does with one channel
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChannelOverride {
+    /// Forward the received value
+    #[default]
+    Pass,
+    /// Replace the received value
+    Value(u16),
+    /// Limit the received value to `min..=max`
+    Clamp { min: u16, max: u16 },
+}
+
+impl ChannelOverride {
+    const fn apply(self, value: u16) -> u16 {
+        match self {
+            ChannelOverride::Pass => value,
+            ChannelOverride::Value(value) => value,
+            ChannelOverride::Clamp { min, .. } if value < min => min,
+            ChannelOverride::Clamp { max, .. } if value > max => max,
+            ChannelOverride::Clamp { .. } => value,
+        }
+    }
+}
+
+/// A table of per-channel overrides, e.g. for a companion computer taking over a model
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{Channel, ChannelOverride, Overrides, PacketTransform, SbusPacket};
+///
+/// let mut overrides = Overrides::new()
+///     .with_channel(Channel::Ch5, ChannelOverride::Value(1811))
+///     .with_channel(Channel::Ch3, ChannelOverride::Clamp { min: 172, max: 1400 });
+///
+/// let mut packet = SbusPacket::neutral();
+/// packet.channels[2] = 1811;
+/// overrides.transform(&mut packet);
+/// assert_eq!(packet.channels[2], 1400);
+/// assert_eq!(packet.channels[4], 1811);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Overrides {
+    channels: [ChannelOverride; CHANNEL_COUNT],
+}
+
+impl Overrides {
+    /// A table forwarding all channels unchanged
+    pub const fn new() -> Self {
+        Self {
+            channels: [ChannelOverride::Pass; CHANNEL_COUNT],
+        }
+    }
+
+    /// Sets the override of a proportional channel, the digital channels are ignored
+    pub const fn with_channel(mut self, channel: Channel, value: ChannelOverride) -> Self {
+        if !channel.is_digital() {
+            self.channels[channel.index()] = value;
+        }
+        self
+    }
+
+    /// The override of a proportional channel, or `None` for the digital channels
+    pub fn channel(&self, channel: Channel) -> Option<ChannelOverride> {
+        self.channels.get(channel.index()).copied()
+    }
+
+    /// Sets the override of a proportional channel, the digital channels are ignored
+    pub fn set(&mut self, channel: Channel, value: ChannelOverride) {
+        *self = self.with_channel(channel, value);
+    }
+
+    /// Forwards all channels unchanged again
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Whether any channel is overridden
+    pub fn is_active(&self) -> bool {
+        self.channels
+            .iter()
+            .any(|channel| *channel != ChannelOverride::Pass)
+    }
+}
+
+impl Default for Overrides {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PacketTransform for Overrides {
+    fn transform(&mut self, packet: &mut SbusPacket) {
+        for (value, channel) in packet.channels.iter_mut().zip(self.channels.iter()) {
+            *value = channel.apply(*value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides() {
+        let mut overrides = Overrides::new();
+        assert!(!overrides.is_active());
+
+        overrides.set(Channel::Ch1, ChannelOverride::Value(172));
+        overrides.set(Channel::Ch2, ChannelOverride::Clamp { min: 500, max: 600 });
+        overrides.set(Channel::Ch17, ChannelOverride::Value(172));
+        assert!(overrides.is_active());
+        assert_eq!(overrides.channel(Channel::Ch17), None);
+
+        let mut packet = SbusPacket::neutral();
+        packet.flags.d1 = true;
+        let output = overrides.apply(&packet);
+        assert_eq!(output.channels[0], 172);
+        assert_eq!(output.channels[1], 600);
+        assert_eq!(output.channels[2], packet.channels[2]);
+        assert_eq!(output.flags, packet.flags);
+
+        overrides.clear();
+        assert_eq!(overrides.apply(&packet), packet);
+    }
+}
@@ -0,0 +1,112 @@
+use core::convert::Infallible;
+
+use embedded_io_async::{Read, Write};
+
+use super::ProxyError;
+use crate::{PacketTransform, SbusPacket, SbusParserAsync, SbusWriterAsync};
+
+/// Forwards SBUS frames from an async reader to an async writer, passing them through a
+/// [`PacketTransform`]
+///
+/// The async counterpart of [`SbusProxy`](crate::SbusProxy).
+pub struct SbusProxyAsync<R, W, T>
+where
+    R: Read,
+    W: Write,
+    T: PacketTransform,
+{
+    parser: SbusParserAsync<R>,
+    writer: SbusWriterAsync<W>,
+    transform: T,
+}
+
+impl<R, W, T> SbusProxyAsync<R, W, T>
+where
+    R: Read,
+    W: Write,
+    T: PacketTransform,
+{
+    pub fn new(parser: SbusParserAsync<R>, writer: SbusWriterAsync<W>, transform: T) -> Self {
+        Self {
+            parser,
+            writer,
+            transform,
+        }
+    }
+
+    /// Reads the next valid frame with [`SbusParserAsync::read_next_valid_frame`], transforms
+    /// and writes it, returning the packet that was written
+    pub async fn forward(&mut self) -> Result<SbusPacket, ProxyError<R::Error, W::Error>> {
+        let mut packet = self
+            .parser
+            .read_next_valid_frame()
+            .await
+            .map_err(ProxyError::Read)?;
+        self.transform.transform(&mut packet);
+        self.writer
+            .write_frame(&packet)
+            .await
+            .map_err(ProxyError::Write)?;
+        Ok(packet)
+    }
+
+    /// Forwards frames forever
+    ///
+    /// The incoming stream is resynchronised on, so the proxy may start mid-frame and invalid
+    /// incoming frames are dropped. Only returns if reading or writing fails, or the transform
+    /// produces an out-of-range channel.
+    pub async fn run(&mut self) -> Result<Infallible, ProxyError<R::Error, W::Error>> {
+        loop {
+            match self.forward().await {
+                Ok(_) => {}
+                Err(ProxyError::Read(err)) if !err.is_fatal() => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    pub fn transform_mut(&mut self) -> &mut T {
+        &mut self.transform
+    }
+
+    pub fn parser_mut(&mut self) -> &mut SbusParserAsync<R> {
+        &mut self.parser
+    }
+
+    pub fn writer_mut(&mut self) -> &mut SbusWriterAsync<W> {
+        &mut self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SbusError;
+    use embedded_io_adapters::tokio_1::FromTokio;
+
+    #[tokio::test]
+    async fn test_run() {
+        let mut stream = SbusPacket::neutral().to_array()[12..].to_vec();
+        stream.extend_from_slice(&SbusPacket::neutral().to_array());
+        stream.extend_from_slice(&SbusPacket::failsafe_default().to_array());
+
+        let mut proxy = SbusProxyAsync::new(
+            SbusParserAsync::new(FromTokio::new(stream.as_slice())),
+            SbusWriterAsync::new(FromTokio::new(Vec::new())),
+            |packet: &mut SbusPacket| packet.flags.d1 = true,
+        );
+
+        assert!(matches!(
+            proxy.run().await,
+            Err(ProxyError::Read(SbusError::UnexpectedEof))
+        ));
+        let output = proxy.writer_mut().inner_mut().inner();
+        let frames: Vec<_> = output
+            .chunks(25)
+            .map(|frame| SbusPacket::from_array(frame.try_into().unwrap()).unwrap())
+            .collect();
+        assert_eq!(frames.len(), 2);
+        assert!(frames.iter().all(|frame| frame.flags.d1));
+        assert!(frames[1].flags.failsafe);
+    }
+}
@@ -0,0 +1,148 @@
+use core::convert::Infallible;
+
+use embedded_io::{Read, Write};
+
+use super::ProxyError;
+use crate::{PacketTransform, SbusPacket, SbusParser, SbusWriter};
+
+/// Forwards SBUS frames from a blocking reader to a blocking writer, passing them through a
+/// [`PacketTransform`]
+///
+/// Every frame is written as soon as it was read, so the outgoing stream keeps the timing of
+/// the incoming one. Flags are forwarded unless the transform changes them. Use a closure or an
+/// [`Overrides`](crate::Overrides) table as the transform.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # fn example<R: embedded_io::Read, W: embedded_io::Write>(receiver: R, flight_controller: W) {
+/// use sbus_rs::{SbusPacket, SbusParser, SbusProxy, SbusWriter};
+///
+/// let mut proxy = SbusProxy::new(
+///     SbusParser::new(receiver),
+///     SbusWriter::new(flight_controller),
+///     |packet: &mut SbusPacket| packet.channels[4] = 1811,
+/// );
+/// let _ = proxy.run();
+/// # }
+/// ```
+pub struct SbusProxy<R, W, T>
+where
+    R: Read,
+    W: Write,
+    T: PacketTransform,
+{
+    parser: SbusParser<R>,
+    writer: SbusWriter<W>,
+    transform: T,
+}
+
+impl<R, W, T> SbusProxy<R, W, T>
+where
+    R: Read,
+    W: Write,
+    T: PacketTransform,
+{
+    pub fn new(parser: SbusParser<R>, writer: SbusWriter<W>, transform: T) -> Self {
+        Self {
+            parser,
+            writer,
+            transform,
+        }
+    }
+
+    /// Reads the next valid frame with [`SbusParser::read_next_valid_frame`], transforms and
+    /// writes it, returning the packet that was written
+    pub fn forward(&mut self) -> Result<SbusPacket, ProxyError<R::Error, W::Error>> {
+        let mut packet = self
+            .parser
+            .read_next_valid_frame()
+            .map_err(ProxyError::Read)?;
+        self.transform.transform(&mut packet);
+        self.writer
+            .write_frame(&packet)
+            .map_err(ProxyError::Write)?;
+        Ok(packet)
+    }
+
+    /// Forwards frames forever
+    ///
+    /// The incoming stream is resynchronised on, so the proxy may start mid-frame and invalid
+    /// incoming frames are dropped. Only returns if reading or writing fails, or the transform
+    /// produces an out-of-range channel.
+    pub fn run(&mut self) -> Result<Infallible, ProxyError<R::Error, W::Error>> {
+        loop {
+            match self.forward() {
+                Ok(_) => {}
+                Err(ProxyError::Read(err)) if !err.is_fatal() => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    pub fn transform_mut(&mut self) -> &mut T {
+        &mut self.transform
+    }
+
+    pub fn parser_mut(&mut self) -> &mut SbusParser<R> {
+        &mut self.parser
+    }
+
+    pub fn writer_mut(&mut self) -> &mut SbusWriter<W> {
+        &mut self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Channel, ChannelOverride, Overrides, SbusError};
+    use embedded_io_adapters::std::FromStd;
+
+    #[test]
+    fn test_forward() {
+        let mut input = SbusPacket::neutral();
+        input.flags.failsafe = true;
+        // Started mid-frame, with garbage after the last frame
+        let mut stream = SbusPacket::neutral().to_array()[12..].to_vec();
+        stream.extend_from_slice(&input.to_array());
+        stream.extend_from_slice(&SbusPacket::neutral().to_array());
+        stream.extend_from_slice(&[0xAA; 25]);
+
+        let overrides = Overrides::new().with_channel(Channel::Ch5, ChannelOverride::Value(1811));
+        let mut proxy = SbusProxy::new(
+            SbusParser::new(FromStd::new(stream.as_slice())),
+            SbusWriter::new(FromStd::new(Vec::new())),
+            overrides,
+        );
+
+        let written = proxy.forward().unwrap();
+        assert_eq!(written.channels[4], 1811);
+        assert!(written.flags.failsafe);
+
+        proxy.transform_mut().clear();
+        assert!(matches!(
+            proxy.run(),
+            Err(ProxyError::Read(SbusError::UnexpectedEof))
+        ));
+
+        let output = proxy.writer_mut().inner_mut().inner();
+        assert_eq!(output[..25], written.to_array());
+        assert_eq!(output[25..], SbusPacket::neutral().to_array());
+    }
+
+    #[test]
+    fn test_out_of_range_transform() {
+        let stream = SbusPacket::neutral().to_array();
+        let mut proxy = SbusProxy::new(
+            SbusParser::new(FromStd::new(&stream[..])),
+            SbusWriter::new(FromStd::new(Vec::new())),
+            |packet: &mut SbusPacket| packet.channels[0] = 4000,
+        );
+
+        assert!(matches!(
+            proxy.forward(),
+            Err(ProxyError::Write(SbusError::OutOfRange { channel: 0, .. }))
+        ));
+    }
+}
@@ -0,0 +1,242 @@
+//! `#[repr(C)]` frame and packet types for casting DMA buffers and shared memory
+use core::slice;
+
+use crate::{
+    FrameKind, SbusError, SbusPacket, CHANNEL_COUNT, CHANNEL_MAX, FLAG_MASK, SBUS_FRAME_LENGTH,
+};
+
+/// A raw 25-byte SBUS frame, laid out exactly like the bytes on the wire
+///
+/// Every byte pattern is a valid `SbusRawFrame` and its alignment is 1, so buffers filled by
+/// DMA can be viewed as frames without copying, see [`SbusRawFrame::from_slice`]. With the
+/// `zerocopy` feature it implements the `zerocopy` traits for casting.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{SbusPacket, SbusRawFrame};
+///
+/// let mut dma_buffer = [0u8; 64];
+/// dma_buffer[..25].copy_from_slice(&SbusPacket::neutral().to_array());
+///
+/// let (frames, rest) = SbusRawFrame::from_slice(&dma_buffer);
+/// assert_eq!(frames.len(), 2);
+/// assert_eq!(rest.len(), 14);
+/// assert_eq!(frames[0].decode(), Ok(SbusPacket::neutral()));
+/// ```
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::FromZeroes,
+        zerocopy::FromBytes,
+        zerocopy::AsBytes,
+        zerocopy::Unaligned,
+        zerocopy::KnownLayout
+    )
+)]
+pub struct SbusRawFrame(pub [u8; SBUS_FRAME_LENGTH]);
+
+impl SbusRawFrame {
+    /// Views a byte array as a frame
+    pub fn from_ref(bytes: &[u8; SBUS_FRAME_LENGTH]) -> &Self {
+        // `SbusRawFrame` is a transparent wrapper around the array
+        unsafe { &*(bytes as *const [u8; SBUS_FRAME_LENGTH] as *const Self) }
+    }
+
+    /// Views a byte array as a mutable frame, e.g. to fill a transmit buffer in place
+    pub fn from_mut(bytes: &mut [u8; SBUS_FRAME_LENGTH]) -> &mut Self {
+        // `SbusRawFrame` is a transparent wrapper around the array
+        unsafe { &mut *(bytes as *mut [u8; SBUS_FRAME_LENGTH] as *mut Self) }
+    }
+
+    /// Views a buffer as back to back frames, returning the frames and the bytes after the
+    /// last complete one
+    ///
+    /// The frames are only split at multiples of [`SBUS_FRAME_LENGTH`], so use this on buffers
+    /// known to start with a frame, e.g. a DMA buffer restarted on the idle line.
+    pub fn from_slice(bytes: &[u8]) -> (&[Self], &[u8]) {
+        let count = bytes.len() / SBUS_FRAME_LENGTH;
+        let (frames, rest) = bytes.split_at(count * SBUS_FRAME_LENGTH);
+        // The frames have the size of `SBUS_FRAME_LENGTH` bytes and an alignment of 1
+        let frames = unsafe { slice::from_raw_parts(frames.as_ptr() as *const Self, count) };
+        (frames, rest)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; SBUS_FRAME_LENGTH] {
+        &self.0
+    }
+
+    /// Decodes the frame, see [`SbusPacket::from_array`]
+    pub fn decode(&self) -> Result<SbusPacket, SbusError> {
+        SbusPacket::from_array(&self.0)
+    }
+}
+
+impl From<SbusPacket> for SbusRawFrame {
+    fn from(packet: SbusPacket) -> Self {
+        Self(packet.to_array())
+    }
+}
+
+impl From<[u8; SBUS_FRAME_LENGTH]> for SbusRawFrame {
+    fn from(bytes: [u8; SBUS_FRAME_LENGTH]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl TryFrom<&SbusRawFrame> for SbusPacket {
+    type Error = SbusError;
+
+    fn try_from(frame: &SbusRawFrame) -> Result<Self, Self::Error> {
+        frame.decode()
+    }
+}
+
+/// A decoded packet with a stable C layout, for sharing packets through memory
+///
+/// The flags are kept as the flag byte of the frame and the frame kind as its footer, so the
+/// struct has no padding and every byte pattern is a valid `SbusRawPacket`. With the
+/// `zerocopy` feature it implements the `zerocopy` traits for casting. Converting it into an
+/// [`SbusPacket`] checks the values written by the other side.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::FromZeroes,
+        zerocopy::FromBytes,
+        zerocopy::AsBytes,
+        zerocopy::KnownLayout
+    )
+)]
+pub struct SbusRawPacket {
+    pub channels: [u16; CHANNEL_COUNT],
+    /// The flag byte, see [`Flags::to_byte`](crate::Flags::to_byte)
+    pub flags: u8,
+    /// The footer of the frame, see [`FrameKind::footer`]
+    pub footer: u8,
+}
+
+impl SbusRawPacket {
+    /// The packet sent as a frame of `kind`
+    pub const fn with_kind(packet: SbusPacket, kind: FrameKind) -> Self {
+        Self {
+            channels: packet.channels,
+            flags: packet.flags.to_byte(),
+            footer: kind.footer(),
+        }
+    }
+
+    /// The kind of frame told apart by the footer, `None` for footers of neither SBUS nor SBUS2
+    pub const fn kind(&self) -> Option<FrameKind> {
+        FrameKind::from_footer(self.footer)
+    }
+}
+
+impl From<SbusPacket> for SbusRawPacket {
+    fn from(packet: SbusPacket) -> Self {
+        Self::with_kind(packet, FrameKind::Sbus1)
+    }
+}
+
+impl TryFrom<SbusRawPacket> for SbusPacket {
+    type Error = SbusError;
+
+    /// Fails with [`SbusError::OutOfRange`] for channels above [`CHANNEL_MAX`],
+    /// [`SbusError::InvalidFlagByte`] for reserved flag bits and [`SbusError::InvalidFooter`]
+    /// for footers of neither SBUS nor SBUS2
+    fn try_from(packet: SbusRawPacket) -> Result<Self, Self::Error> {
+        if let Some((channel, &value)) = packet
+            .channels
+            .iter()
+            .enumerate()
+            .find(|&(_, &value)| value > CHANNEL_MAX)
+        {
+            return Err(SbusError::OutOfRange { channel, value });
+        }
+        if packet.flags & !FLAG_MASK != 0 {
+            return Err(SbusError::InvalidFlagByte(packet.flags));
+        }
+        if packet.kind().is_none() {
+            return Err(SbusError::InvalidFooter(packet.footer));
+        }
+        Ok(Self::new(packet.channels, packet.flags.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Channel;
+
+    #[test]
+    fn test_raw_frame() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch2] = 1500;
+        let mut bytes = packet.to_array();
+
+        assert_eq!(SbusRawFrame::from_ref(&bytes).decode(), Ok(packet));
+        SbusRawFrame::from_mut(&mut bytes).0[0] = 0x00;
+        assert!(matches!(
+            SbusPacket::try_from(SbusRawFrame::from_ref(&bytes)),
+            Err(SbusError::InvalidHeader(0x00))
+        ));
+        assert_eq!(SbusRawFrame::from(packet).as_bytes(), &packet.to_array());
+
+        let (frames, rest) = SbusRawFrame::from_slice(&[0x0F; 24]);
+        assert!(frames.is_empty());
+        assert_eq!(rest.len(), 24);
+    }
+
+    #[test]
+    fn test_raw_packet() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch16] = 2047;
+        packet.flags.failsafe = true;
+
+        let raw = SbusRawPacket::with_kind(packet, FrameKind::Sbus2(2));
+        assert_eq!(raw.flags, 0x08);
+        assert_eq!(raw.footer, 0x24);
+        assert_eq!(SbusPacket::try_from(raw), Ok(packet));
+
+        let mut invalid = raw;
+        invalid.channels[3] = 2048;
+        assert_eq!(
+            SbusPacket::try_from(invalid),
+            Err(SbusError::OutOfRange {
+                channel: 3,
+                value: 2048
+            })
+        );
+        let mut invalid = raw;
+        invalid.flags = 0x10;
+        assert_eq!(
+            SbusPacket::try_from(invalid),
+            Err(SbusError::InvalidFlagByte(0x10))
+        );
+        let mut invalid = raw;
+        invalid.footer = 0xFF;
+        assert!(matches!(
+            SbusPacket::try_from(invalid),
+            Err(SbusError::InvalidFooter(0xFF))
+        ));
+    }
+
+    #[cfg(feature = "zerocopy")]
+    #[test]
+    fn test_zerocopy() {
+        use zerocopy::{AsBytes, FromBytes};
+
+        let raw = SbusRawPacket::from(SbusPacket::neutral());
+        assert_eq!(raw.as_bytes().len(), 34);
+        assert_eq!(SbusRawPacket::read_from(raw.as_bytes()), Some(raw));
+
+        let bytes = SbusPacket::neutral().to_array();
+        let frame = SbusRawFrame::ref_from(&bytes[..]).unwrap();
+        assert_eq!(frame.decode(), Ok(SbusPacket::neutral()));
+    }
+}
@@ -0,0 +1,168 @@
+use core::time::Duration;
+
+use crate::{Deframer, FailsafeHandler, FailsafePolicy, SbusPacket, SbusStats, SBUS_FRAME_LENGTH};
+
+/// Tracks the most recent RC input from an SBUS link
+///
+/// Combines a [`Deframer`], its [`SbusStats`] and a [`FailsafeHandler`], so control loops can
+/// ask for the latest valid input instead of blocking on frame reads. Until the first frame
+/// arrives, and whenever failsafe is active, [`SbusReceiver::latest`] holds the substitute packet
+/// of the failsafe policy (or [`SbusPacket::failsafe_default`] if the policy has none).
+///
+/// Timestamps are measured from any fixed epoch, e.g. the boot time of the system.
+///
+/// # Example
+///
+/// ```rust
+/// use core::time::Duration;
+/// use sbus_rs::{FailsafePolicy, SbusReceiver};
+///
+/// let mut receiver = SbusReceiver::new(FailsafePolicy::HoldLast, Duration::from_millis(100));
+///
+/// // From the UART receive path
+/// # let received = [0u8; 0];
+/// receiver.push_bytes(&received, Duration::from_millis(10));
+///
+/// // From the control loop
+/// let now = Duration::from_millis(12);
+/// receiver.poll(now);
+/// if !receiver.is_stale(now, Duration::from_millis(50)) {
+///     let throttle = receiver.latest().channels[2];
+/// }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SbusReceiver {
+    deframer: Deframer,
+    failsafe: FailsafeHandler,
+    latest: SbusPacket,
+    last_valid_at: Option<Duration>,
+}
+
+impl SbusReceiver {
+    /// Creates a receiver applying `policy` when failsafe is flagged or no frame arrived within
+    /// `timeout`
+    pub const fn new(policy: FailsafePolicy, timeout: Duration) -> Self {
+        Self {
+            deframer: Deframer::new(),
+            failsafe: FailsafeHandler::new(policy, timeout),
+            latest: SbusPacket::failsafe_default(),
+            last_valid_at: None,
+        }
+    }
+
+    /// Feeds bytes received at `now`, returning whether at least one new packet was decoded
+    pub fn push_bytes(&mut self, bytes: &[u8], now: Duration) -> bool {
+        let mut updated = false;
+        // Feed in chunks the deframer can hold so no complete frame is overwritten
+        for chunk in bytes.chunks(SBUS_FRAME_LENGTH) {
+            self.deframer.push_bytes(chunk);
+            while let Some(packet) = self.deframer.try_parse() {
+                self.update(packet, now);
+                updated = true;
+            }
+        }
+        updated
+    }
+
+    /// Submits a packet received at `now`, e.g. from a reader-based parser
+    pub fn update(&mut self, packet: SbusPacket, now: Duration) {
+        self.latest = self.failsafe.process(packet, now);
+        self.last_valid_at = Some(now);
+    }
+
+    /// Applies the failsafe timeout at `now`
+    ///
+    /// Call this regularly, e.g. at the start of every control loop iteration.
+    pub fn poll(&mut self, now: Duration) {
+        if let Some(packet) = self.failsafe.poll(now) {
+            self.latest = packet;
+        } else if self.failsafe.is_active() {
+            self.latest = SbusPacket::failsafe_default();
+        }
+    }
+
+    /// The most recent packet, or the failsafe substitute while failsafe is active
+    pub fn latest(&self) -> &SbusPacket {
+        &self.latest
+    }
+
+    /// Time elapsed between the last valid frame and `now`, if a frame was ever received
+    pub fn age(&self, now: Duration) -> Option<Duration> {
+        self.last_valid_at.map(|at| now.saturating_sub(at))
+    }
+
+    /// Whether no valid frame has been received within `timeout` of `now`
+    pub fn is_stale(&self, now: Duration, timeout: Duration) -> bool {
+        self.age(now).is_none_or(|age| age > timeout)
+    }
+
+    /// Whether failsafe is currently active
+    pub fn is_failsafe(&self) -> bool {
+        self.failsafe.is_active()
+    }
+
+    /// Statistics about the frames and bytes seen so far
+    pub fn stats(&self) -> &SbusStats {
+        self.deframer.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.deframer.reset_stats();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::frame_with_flags;
+
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn test_latest_and_age() {
+        let mut receiver = SbusReceiver::new(FailsafePolicy::HoldLast, TIMEOUT);
+        assert_eq!(receiver.latest(), &SbusPacket::failsafe_default());
+        assert!(receiver.is_stale(Duration::ZERO, TIMEOUT));
+
+        let data = frame_with_flags(1500, 0);
+        assert!(!receiver.push_bytes(&data[..10], Duration::from_millis(1)));
+        assert!(receiver.push_bytes(&data[10..], Duration::from_millis(2)));
+
+        assert_eq!(receiver.latest().channels, [1500; 16]);
+        assert_eq!(
+            receiver.age(Duration::from_millis(12)),
+            Some(Duration::from_millis(10))
+        );
+        assert!(!receiver.is_stale(Duration::from_millis(12), TIMEOUT));
+        assert_eq!(receiver.stats().frames_ok, 1);
+    }
+
+    #[test]
+    fn test_timeout_applies_failsafe() {
+        let mut receiver = SbusReceiver::new(FailsafePolicy::neutral(), TIMEOUT);
+        receiver.push_bytes(&frame_with_flags(1500, 0), Duration::ZERO);
+
+        receiver.poll(Duration::from_millis(50));
+        assert!(!receiver.is_failsafe());
+        assert_eq!(receiver.latest().channels, [1500; 16]);
+
+        receiver.poll(Duration::from_millis(150));
+        assert!(receiver.is_failsafe());
+        assert_eq!(receiver.latest().channels, SbusPacket::neutral().channels);
+        assert!(receiver.latest().flags.failsafe);
+    }
+
+    #[test]
+    fn test_many_frames_in_one_push() {
+        let mut receiver = SbusReceiver::new(FailsafePolicy::HoldLast, TIMEOUT);
+        let mut data = [0u8; SBUS_FRAME_LENGTH * 4];
+        for (i, chunk) in data.chunks_mut(SBUS_FRAME_LENGTH).enumerate() {
+            chunk.copy_from_slice(&frame_with_flags(100 * i as u16, 0));
+        }
+
+        assert!(receiver.push_bytes(&data, Duration::ZERO));
+        assert_eq!(receiver.stats().frames_ok, 4);
+        assert_eq!(receiver.latest().channels, [300; 16]);
+    }
+}
@@ -0,0 +1,439 @@
+//! Recording SBUS sessions to replay them later
+
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+use crate::log::{
+    decode_error, encode_error, record_header, without_reader_error, ERROR_LENGTH, MAGIC,
+    RECORD_ERROR, RECORD_FRAME, VERSION,
+};
+use crate::{
+    ErrorEvent, FrameSource, LogRecord, SbusError, SbusPacket, TimestampedPacket, SBUS_FRAME_LENGTH,
+};
+
+/// Appends timestamped raw frames and errors to a log
+///
+/// The log starts with the magic bytes `SBUSLOG` and a version byte. Every record follows as
+/// a kind byte, the timestamp in microseconds as a little-endian `u64`, the payload length as a
+/// little-endian `u16` and the payload. Frames are stored as the 25 raw bytes, so the log can
+/// also hold frames that failed to decode.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use sbus_rs::{SbusError, SbusPacket, SbusRecorder};
+///
+/// let mut recorder = SbusRecorder::new(Vec::new()).unwrap();
+/// recorder
+///     .record_packet(&SbusPacket::neutral(), Duration::from_millis(0))
+///     .unwrap();
+/// recorder
+///     .record_error(&SbusError::<()>::InvalidHeader(0xAA), Duration::from_millis(7))
+///     .unwrap();
+///
+/// assert_eq!(recorder.frames(), 1);
+/// assert_eq!(recorder.error_index()[0].frame, 1);
+/// let log = recorder.finish().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SbusRecorder<W>
+where
+    W: Write,
+{
+    writer: W,
+    frames: u64,
+    errors: Vec<ErrorEvent>,
+}
+
+impl<W> SbusRecorder<W>
+where
+    W: Write,
+{
+    /// Starts a log by writing its header to `writer`
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        Ok(Self {
+            writer,
+            frames: 0,
+            errors: Vec::new(),
+        })
+    }
+
+    /// Appends a raw frame received at `timestamp`
+    pub fn record_frame(
+        &mut self,
+        frame: &[u8; SBUS_FRAME_LENGTH],
+        timestamp: Duration,
+    ) -> io::Result<()> {
+        self.write_record(RECORD_FRAME, timestamp, frame)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Appends the frame encoding `packet`, with channels truncated to 11 bits
+    pub fn record_packet(&mut self, packet: &SbusPacket, timestamp: Duration) -> io::Result<()> {
+        self.record_frame(&packet.to_array(), timestamp)
+    }
+
+    /// Appends an error that occurred at `timestamp` and adds it to the index
+    pub fn record_error<E>(&mut self, error: &SbusError<E>, timestamp: Duration) -> io::Result<()> {
+        let error = without_reader_error(error);
+        self.write_record(RECORD_ERROR, timestamp, &encode_error(&error))?;
+        self.errors.push(ErrorEvent {
+            frame: self.frames,
+            timestamp,
+            error,
+        });
+        Ok(())
+    }
+
+    /// Appends the result of reading a frame, e.g. from
+    /// [`SbusParser::read_frame`](crate::SbusParser::read_frame)
+    pub fn record_result<E>(
+        &mut self,
+        result: &Result<SbusPacket, SbusError<E>>,
+        timestamp: Duration,
+    ) -> io::Result<()> {
+        match result {
+            Ok(packet) => self.record_packet(packet, timestamp),
+            Err(error) => self.record_error(error, timestamp),
+        }
+    }
+
+    /// Number of frames recorded so far
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// The errors recorded so far
+    pub fn error_index(&self) -> &[ErrorEvent] {
+        &self.errors
+    }
+
+    /// Flushes the log and returns the writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_record(&mut self, kind: u8, timestamp: Duration, payload: &[u8]) -> io::Result<()> {
+        self.writer
+            .write_all(&record_header(kind, timestamp, payload.len()))?;
+        self.writer.write_all(payload)
+    }
+}
+
+/// Replays a log written by an [`SbusRecorder`]
+///
+/// As an [`Iterator`] the player yields the packets of all frames that decode, along with
+/// their recorded timestamps, as fast as they can be read. With the `async` feature, use
+/// `next_paced` to reproduce the original timing instead. Iteration ends at
+/// the end of the log or when it is corrupt.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use sbus_rs::{SbusPacket, SbusPlayer, SbusRecorder};
+///
+/// let mut recorder = SbusRecorder::new(Vec::new()).unwrap();
+/// for ms in [0, 7, 14] {
+///     recorder
+///         .record_packet(&SbusPacket::neutral(), Duration::from_millis(ms))
+///         .unwrap();
+/// }
+/// let log = recorder.finish().unwrap();
+///
+/// let player = SbusPlayer::new(log.as_slice()).unwrap();
+/// let gaps: Vec<_> = player.map(|stamped| stamped.gap).collect();
+/// assert_eq!(gaps, [None, Some(Duration::from_millis(7)), Some(Duration::from_millis(7))]);
+/// ```
+#[derive(Debug)]
+pub struct SbusPlayer<R>
+where
+    R: Read,
+{
+    reader: R,
+    frames: u64,
+    previous: Option<Duration>,
+}
+
+impl<R> SbusPlayer<R>
+where
+    R: Read,
+{
+    /// Opens a log, failing with [`io::ErrorKind::InvalidData`] if it doesn't start with a
+    /// supported header
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0; 8];
+        reader.read_exact(&mut header)?;
+        if header[..7] != *MAGIC || header[7] != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not an SBUS log",
+            ));
+        }
+        Ok(Self {
+            reader,
+            frames: 0,
+            previous: None,
+        })
+    }
+
+    /// Reads the next record, or `None` at the end of the log
+    ///
+    /// Records of unknown kinds, written by newer versions, are skipped.
+    pub fn next_record(&mut self) -> io::Result<Option<LogRecord>> {
+        loop {
+            let mut head = [0; 11];
+            match self.reader.read_exact(&mut head) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+                Err(err) => return Err(err),
+            }
+            let [kind, timestamp @ .., len_low, len_high] = head;
+            let timestamp = Duration::from_micros(u64::from_le_bytes(timestamp));
+            let len = u16::from_le_bytes([len_low, len_high]) as usize;
+
+            match kind {
+                RECORD_FRAME if len == SBUS_FRAME_LENGTH => {
+                    let mut frame = [0; SBUS_FRAME_LENGTH];
+                    self.reader.read_exact(&mut frame)?;
+                    self.frames += 1;
+                    return Ok(Some(LogRecord::Frame { frame, timestamp }));
+                }
+                RECORD_ERROR if len == ERROR_LENGTH => {
+                    let mut payload = [0; ERROR_LENGTH];
+                    self.reader.read_exact(&mut payload)?;
+                    let error = decode_error(&payload).ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidData, "unknown error code")
+                    })?;
+                    return Ok(Some(LogRecord::Error(ErrorEvent {
+                        frame: self.frames,
+                        timestamp,
+                        error,
+                    })));
+                }
+                RECORD_FRAME | RECORD_ERROR => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "invalid record length",
+                    ))
+                }
+                _ => {
+                    io::copy(&mut (&mut self.reader).take(len as u64), &mut io::sink())?;
+                }
+            }
+        }
+    }
+
+    /// Waits until the next packet is due relative to the previous one, then returns it
+    ///
+    /// The first packet is returned immediately.
+    #[cfg(feature = "async")]
+    pub async fn next_paced<D>(&mut self, delay: &mut D) -> Option<TimestampedPacket>
+    where
+        D: embedded_hal_async::delay::DelayNs,
+    {
+        let stamped = self.next()?;
+        if let Some(gap) = stamped.gap {
+            let micros = u32::try_from(gap.as_micros()).unwrap_or(u32::MAX);
+            delay.delay_us(micros).await;
+        }
+        Some(stamped)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> Iterator for SbusPlayer<R>
+where
+    R: Read,
+{
+    type Item = TimestampedPacket;
+
+    fn next(&mut self) -> Option<TimestampedPacket> {
+        loop {
+            let Ok(Some(record)) = self.next_record() else {
+                return None;
+            };
+            let LogRecord::Frame { frame, timestamp } = record else {
+                continue;
+            };
+            let Ok(packet) = SbusPacket::from_array(&frame) else {
+                continue;
+            };
+            let gap = self
+                .previous
+                .replace(timestamp)
+                .map(|previous| timestamp.saturating_sub(previous));
+            return Some(TimestampedPacket {
+                packet,
+                received_at: timestamp,
+                gap,
+            });
+        }
+    }
+}
+
+impl<R> FrameSource for SbusPlayer<R>
+where
+    R: Read,
+{
+    fn next_frame(&mut self) -> Option<SbusPacket> {
+        self.next().map(|stamped| stamped.packet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_format() {
+        let mut recorder = SbusRecorder::new(Vec::new()).unwrap();
+        let frame = SbusPacket::neutral().to_array();
+        recorder
+            .record_frame(&frame, Duration::from_micros(0x0102))
+            .unwrap();
+        recorder
+            .record_result(
+                &Err(SbusError::<io::Error>::Read(io::ErrorKind::Other.into())),
+                Duration::from_millis(1),
+            )
+            .unwrap();
+        recorder
+            .record_error(
+                &SbusError::<()>::OutOfRange {
+                    channel: 3,
+                    value: 4000,
+                },
+                Duration::from_millis(2),
+            )
+            .unwrap();
+
+        assert_eq!(recorder.frames(), 1);
+        assert_eq!(
+            recorder.error_index(),
+            [
+                ErrorEvent {
+                    frame: 1,
+                    timestamp: Duration::from_millis(1),
+                    error: SbusError::Read(()),
+                },
+                ErrorEvent {
+                    frame: 1,
+                    timestamp: Duration::from_millis(2),
+                    error: SbusError::OutOfRange {
+                        channel: 3,
+                        value: 4000,
+                    },
+                },
+            ]
+        );
+
+        let log = recorder.finish().unwrap();
+        assert_eq!(log[..8], *b"SBUSLOG\x01");
+        assert_eq!(log[8], RECORD_FRAME);
+        assert_eq!(log[9..17], 0x0102u64.to_le_bytes());
+        assert_eq!(log[17..19], [25, 0]);
+        assert_eq!(log[19..44], frame);
+
+        let out_of_range = &log[log.len() - ERROR_LENGTH..];
+        assert_eq!(out_of_range, [6, 3, 0, 0xA0, 0x0F]);
+        assert_eq!(log.len(), 8 + (11 + 25) + 2 * (11 + ERROR_LENGTH));
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut recorder = SbusRecorder::new(Vec::new()).unwrap();
+        let mut packet = SbusPacket::neutral();
+        recorder
+            .record_packet(&packet, Duration::from_millis(3))
+            .unwrap();
+        recorder
+            .record_error(
+                &SbusError::<()>::InvalidFooter(0x42),
+                Duration::from_millis(5),
+            )
+            .unwrap();
+        recorder
+            .record_frame(&[0xAA; SBUS_FRAME_LENGTH], Duration::from_millis(6))
+            .unwrap();
+        packet.channels[0] = 1500;
+        recorder
+            .record_packet(&packet, Duration::from_millis(10))
+            .unwrap();
+        let mut log = recorder.finish().unwrap();
+        // A record of a kind added in a later version
+        log.extend_from_slice(&[9, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 1, 2]);
+
+        let mut player = SbusPlayer::new(log.as_slice()).unwrap();
+        assert!(matches!(
+            player.next_record().unwrap(),
+            Some(LogRecord::Frame { .. })
+        ));
+        assert_eq!(
+            player.next_record().unwrap(),
+            Some(LogRecord::Error(ErrorEvent {
+                frame: 1,
+                timestamp: Duration::from_millis(5),
+                error: SbusError::InvalidFooter(0x42),
+            }))
+        );
+
+        let player = SbusPlayer::new(log.as_slice()).unwrap();
+        let replayed: Vec<_> = player.collect();
+        assert_eq!(replayed.len(), 2);
+        assert_eq!(replayed[1].packet, packet);
+        assert_eq!(replayed[1].received_at, Duration::from_millis(10));
+        assert_eq!(replayed[1].gap, Some(Duration::from_millis(7)));
+    }
+
+    #[test]
+    fn test_invalid_log() {
+        assert_eq!(
+            SbusPlayer::new(&b"SBUSLOG\x02"[..]).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(SbusPlayer::new(&b"SBUS"[..]).is_err());
+
+        let mut log = b"SBUSLOG\x01".to_vec();
+        log.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 1, 2, 3]);
+        let mut player = SbusPlayer::new(log.as_slice()).unwrap();
+        assert!(player.next_record().is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_next_paced() {
+        struct RecordingDelay(Vec<u32>);
+
+        impl embedded_hal_async::delay::DelayNs for RecordingDelay {
+            async fn delay_ns(&mut self, ns: u32) {
+                self.0.push(ns / 1000);
+            }
+
+            async fn delay_us(&mut self, us: u32) {
+                self.0.push(us);
+            }
+        }
+
+        let mut recorder = SbusRecorder::new(Vec::new()).unwrap();
+        for ms in [2, 9, 23] {
+            recorder
+                .record_packet(&SbusPacket::neutral(), Duration::from_millis(ms))
+                .unwrap();
+        }
+        let log = recorder.finish().unwrap();
+
+        let mut player = SbusPlayer::new(log.as_slice()).unwrap();
+        let mut delay = RecordingDelay(Vec::new());
+        while player.next_paced(&mut delay).await.is_some() {}
+        assert_eq!(delay.0, [7000, 14000]);
+    }
+}
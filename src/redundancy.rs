@@ -0,0 +1,268 @@
+use core::time::Duration;
+
+use crate::SbusPacket;
+
+/// One of the two receivers feeding a [`RedundantSource`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Source {
+    Primary,
+    Secondary,
+}
+
+/// A change of the selected receiver, `None` when neither is healthy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Switchover {
+    pub from: Option<Source>,
+    pub to: Option<Source>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+struct Input {
+    latest: Option<SbusPacket>,
+    received_at: Duration,
+}
+
+impl Input {
+    const fn new() -> Self {
+        Self {
+            latest: None,
+            received_at: Duration::ZERO,
+        }
+    }
+
+    fn is_healthy(&self, now: Duration, timeout: Duration) -> bool {
+        self.latest.is_some_and(|packet| {
+            !packet.flags.failsafe
+                && !packet.flags.frame_lost
+                && now.saturating_sub(self.received_at) <= timeout
+        })
+    }
+}
+
+/// Selects between two receivers, e.g. the two halves of a diversity setup
+///
+/// A receiver is healthy while its latest packet has neither the `failsafe` nor the
+/// `frame_lost` flag set and arrived within the timeout. The primary receiver is used whenever it
+/// is healthy, the secondary one otherwise. With [`with_revert`](Self::with_revert) disabled the
+/// secondary receiver stays selected until it fails itself.
+///
+/// Timestamps are measured from any fixed epoch, e.g. the boot time of the system.
+///
+/// # Example
+///
+/// ```rust
+/// use core::time::Duration;
+/// use sbus_rs::{RedundantSource, SbusPacket, Source, Switchover};
+///
+/// let mut source = RedundantSource::new(Duration::from_millis(50));
+/// let packet = SbusPacket::neutral();
+///
+/// source.push(Source::Secondary, packet, Duration::from_millis(0));
+/// assert_eq!(source.active(), Some(Source::Secondary));
+///
+/// let switchover = source.push(Source::Primary, packet, Duration::from_millis(3));
+/// assert_eq!(
+///     switchover,
+///     Some(Switchover { from: Some(Source::Secondary), to: Some(Source::Primary) })
+/// );
+///
+/// // The primary receiver goes quiet
+/// source.push(Source::Secondary, packet, Duration::from_millis(60));
+/// assert_eq!(source.active(), Some(Source::Secondary));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct RedundantSource {
+    timeout: Duration,
+    revert: bool,
+    primary: Input,
+    secondary: Input,
+    active: Option<Source>,
+    last_pushed: Option<Source>,
+    switchovers: u32,
+}
+
+impl RedundantSource {
+    /// Creates an arbiter considering a receiver lost when it sent nothing for `timeout`
+    pub const fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            revert: true,
+            primary: Input::new(),
+            secondary: Input::new(),
+            active: None,
+            last_pushed: None,
+            switchovers: 0,
+        }
+    }
+
+    /// Sets whether to return to the primary receiver as soon as it is healthy again, the
+    /// default
+    pub const fn with_revert(mut self, revert: bool) -> Self {
+        self.revert = revert;
+        self
+    }
+
+    /// Processes a packet from `source` received at `now`, returning the switchover it caused
+    pub fn push(
+        &mut self,
+        source: Source,
+        packet: SbusPacket,
+        now: Duration,
+    ) -> Option<Switchover> {
+        let input = self.input_mut(source);
+        input.latest = Some(packet);
+        input.received_at = now;
+        self.last_pushed = Some(source);
+        self.poll(now)
+    }
+
+    /// Checks both receivers for timeouts at `now`, returning the switchover it caused
+    ///
+    /// Call this whenever no packet was received, e.g. on every control loop iteration.
+    pub fn poll(&mut self, now: Duration) -> Option<Switchover> {
+        let primary = self.primary.is_healthy(now, self.timeout);
+        let secondary = self.secondary.is_healthy(now, self.timeout);
+
+        let selected = match self.active {
+            Some(Source::Secondary) if secondary && !self.revert => Some(Source::Secondary),
+            _ if primary => Some(Source::Primary),
+            _ if secondary => Some(Source::Secondary),
+            _ => None,
+        };
+        if selected == self.active {
+            return None;
+        }
+
+        let switchover = Switchover {
+            from: self.active,
+            to: selected,
+        };
+        self.active = selected;
+        self.switchovers = self.switchovers.saturating_add(1);
+        Some(switchover)
+    }
+
+    /// The selected receiver, or `None` while neither is healthy
+    pub const fn active(&self) -> Option<Source> {
+        self.active
+    }
+
+    /// The packet to act on
+    ///
+    /// This is the latest packet of the selected receiver. While neither is healthy it is the
+    /// latest packet received from either, so its flags tell why.
+    pub fn packet(&self) -> Option<&SbusPacket> {
+        let source = self.active.or(self.last_pushed)?;
+        self.input(source).latest.as_ref()
+    }
+
+    /// The latest packet received from `source`
+    pub fn latest(&self, source: Source) -> Option<&SbusPacket> {
+        self.input(source).latest.as_ref()
+    }
+
+    /// Whether `source` is healthy at `now`
+    pub fn is_healthy(&self, source: Source, now: Duration) -> bool {
+        self.input(source).is_healthy(now, self.timeout)
+    }
+
+    /// Number of switchovers so far, including the first selection and losing both receivers
+    pub const fn switchovers(&self) -> u32 {
+        self.switchovers
+    }
+
+    /// Forgets both receivers
+    pub fn reset(&mut self) {
+        *self = Self::new(self.timeout).with_revert(self.revert);
+    }
+
+    const fn input(&self, source: Source) -> &Input {
+        match source {
+            Source::Primary => &self.primary,
+            Source::Secondary => &self.secondary,
+        }
+    }
+
+    fn input_mut(&mut self, source: Source) -> &mut Input {
+        match source {
+            Source::Primary => &mut self.primary,
+            Source::Secondary => &mut self.secondary,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn switchover(from: Option<Source>, to: Option<Source>) -> Option<Switchover> {
+        Some(Switchover { from, to })
+    }
+
+    #[test]
+    fn test_failover() {
+        let mut source = RedundantSource::new(ms(20));
+        let good = SbusPacket::neutral();
+        let mut failsafe = good;
+        failsafe.flags.failsafe = true;
+
+        assert_eq!(source.packet(), None);
+        assert_eq!(
+            source.push(Source::Primary, good, ms(0)),
+            switchover(None, Some(Source::Primary))
+        );
+        assert_eq!(source.push(Source::Secondary, good, ms(1)), None);
+
+        assert_eq!(
+            source.push(Source::Primary, failsafe, ms(7)),
+            switchover(Some(Source::Primary), Some(Source::Secondary))
+        );
+        assert_eq!(
+            source.push(Source::Primary, good, ms(14)),
+            switchover(Some(Source::Secondary), Some(Source::Primary))
+        );
+
+        // Both time out
+        assert_eq!(source.poll(ms(40)), switchover(Some(Source::Primary), None));
+        assert_eq!(source.active(), None);
+        assert_eq!(source.packet(), Some(&good));
+
+        source.push(Source::Secondary, failsafe, ms(41));
+        assert_eq!(source.active(), None);
+        assert_eq!(source.packet(), Some(&failsafe));
+        assert_eq!(source.switchovers(), 4);
+
+        source.reset();
+        assert_eq!(source.switchovers(), 0);
+        assert_eq!(source.latest(Source::Secondary), None);
+    }
+
+    #[test]
+    fn test_no_revert() {
+        let mut source = RedundantSource::new(ms(20)).with_revert(false);
+        let good = SbusPacket::neutral();
+        let mut lost = good;
+        lost.flags.frame_lost = true;
+
+        source.push(Source::Primary, lost, ms(0));
+        source.push(Source::Secondary, good, ms(1));
+        assert_eq!(source.active(), Some(Source::Secondary));
+
+        assert_eq!(source.push(Source::Primary, good, ms(7)), None);
+        assert!(source.is_healthy(Source::Primary, ms(8)));
+        assert_eq!(
+            source.push(Source::Secondary, lost, ms(8)),
+            switchover(Some(Source::Secondary), Some(Source::Primary))
+        );
+    }
+}
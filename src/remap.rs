@@ -0,0 +1,136 @@
+use crate::{Channel, PacketTransform, SbusPacket, CHANNEL_FUTABA_MID};
+
+/// Reorders channels by choosing the input channel of every output channel
+///
+/// Several outputs may read the same input, so channels can be swapped, moved and duplicated.
+/// When a digital channel is routed to a proportional one it outputs [`CHANNEL_FUTABA_MAX`]
+/// when set and [`CHANNEL_FUTABA_MIN`] otherwise, a proportional channel routed to a digital
+/// one sets it above the centre of the Futaba range. Flags are kept.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{Channel, Remap, SbusPacket};
+///
+/// // Swap channels 1 and 3 and mirror channel 5 to channel 9
+/// let remap = Remap::new()
+///     .swap(Channel::Ch1, Channel::Ch3)
+///     .route(Channel::Ch9, Channel::Ch5);
+///
+/// let mut packet = SbusPacket::neutral();
+/// packet.channels[4] = 1811;
+/// assert_eq!(remap.apply(&packet).channels[8], 1811);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Remap {
+    sources: [Channel; 18],
+}
+
+impl Remap {
+    /// Passes every channel through unchanged
+    pub const IDENTITY: Self = Self {
+        sources: Channel::ALL,
+    };
+
+    /// Creates an identity remap, to be modified with [`route`](Self::route) and
+    /// [`swap`](Self::swap)
+    pub const fn new() -> Self {
+        Self::IDENTITY
+    }
+
+    /// Makes `output` take its value from `input`
+    pub const fn route(mut self, output: Channel, input: Channel) -> Self {
+        self.sources[output.index()] = input;
+        self
+    }
+
+    /// Exchanges the inputs of the outputs `a` and `b`
+    pub const fn swap(mut self, a: Channel, b: Channel) -> Self {
+        let source = self.sources[a.index()];
+        self.sources[a.index()] = self.sources[b.index()];
+        self.sources[b.index()] = source;
+        self
+    }
+
+    /// The input channel that `output` takes its value from
+    pub const fn source(&self, output: Channel) -> Channel {
+        self.sources[output.index()]
+    }
+
+    /// Returns the remapped copy of `packet`
+    pub fn apply(&self, packet: &SbusPacket) -> SbusPacket {
+        let mut output = *packet;
+        for (&target, &source) in Channel::ALL.iter().zip(self.sources.iter()) {
+            match (packet.digital(source), target.is_digital()) {
+                (_, false) => output[target] = packet.value(source),
+                (None, true) => {
+                    output.set_digital(target, packet[source] > CHANNEL_FUTABA_MID);
+                }
+                (Some(set), true) => {
+                    output.set_digital(target, set);
+                }
+            }
+        }
+        output
+    }
+}
+
+impl Default for Remap {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl PacketTransform for Remap {
+    fn transform(&mut self, packet: &mut SbusPacket) {
+        *packet = Remap::apply(self, packet);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::numbered_packet, CHANNEL_FUTABA_MAX};
+
+    #[test]
+    fn test_identity() {
+        let mut packet = numbered_packet();
+        packet.flags.d1 = true;
+        packet.flags.failsafe = true;
+        assert_eq!(Remap::new().apply(&packet), packet);
+    }
+
+    #[test]
+    fn test_swap_and_duplicate() {
+        let remap = Remap::new()
+            .swap(Channel::Ch1, Channel::Ch3)
+            .route(Channel::Ch9, Channel::Ch5);
+        let output = remap.apply(&numbered_packet());
+
+        assert_eq!(output[Channel::Ch1], 1002);
+        assert_eq!(output[Channel::Ch3], 1000);
+        assert_eq!(output[Channel::Ch5], 1004);
+        assert_eq!(output[Channel::Ch9], 1004);
+        assert_eq!(output[Channel::Ch2], 1001);
+        assert_eq!(remap.source(Channel::Ch3), Channel::Ch1);
+    }
+
+    #[test]
+    fn test_digital_routing() {
+        let mut remap = Remap::new()
+            .route(Channel::Ch16, Channel::Ch17)
+            .route(Channel::Ch18, Channel::Ch1);
+
+        let mut packet = numbered_packet();
+        packet.flags.d1 = true;
+        packet[Channel::Ch1] = CHANNEL_FUTABA_MAX;
+
+        let mut output = packet;
+        remap.transform(&mut output);
+        assert_eq!(output[Channel::Ch16], CHANNEL_FUTABA_MAX);
+        assert!(output.flags.d2);
+        assert!(output.flags.d1);
+    }
+}
@@ -0,0 +1,205 @@
+//! Conversions to and from ROS 2 `sensor_msgs/msg/Joy` messages
+//!
+//! The structs mirror the fields of `sensor_msgs/msg/Joy` and its `std_msgs/msg/Header`, so
+//! they can be copied into the message types generated by `r2r`, `rclrs` or whichever ROS 2
+//! client is in use. Axes span -1.0 to 1.0 over the Futaba range, buttons are 0 or 1.
+use std::string::String;
+use std::time::Duration;
+use std::vec::Vec;
+
+use crate::gamepad::{axis_span, axis_to_channel};
+use crate::{Channel, SbusPacket, CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MIN};
+
+/// The axis deflection from -1.0 to 1.0 of a channel value, the inverse of [`axis_to_channel`]
+fn channel_to_axis(value: u16) -> f32 {
+    let deflection = value as f32 - CHANNEL_FUTABA_MID as f32;
+    (deflection / axis_span(deflection)).clamp(-1.0, 1.0)
+}
+
+/// The fields of a `builtin_interfaces/msg/Time`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RosTime {
+    pub sec: i32,
+    pub nanosec: u32,
+}
+
+impl From<Duration> for RosTime {
+    fn from(duration: Duration) -> Self {
+        Self {
+            sec: i32::try_from(duration.as_secs()).unwrap_or(i32::MAX),
+            nanosec: duration.subsec_nanos(),
+        }
+    }
+}
+
+/// The fields of a `std_msgs/msg/Header`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RosHeader {
+    pub stamp: RosTime,
+    pub frame_id: String,
+}
+
+/// The fields of a `sensor_msgs/msg/Joy`
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RosJoy {
+    pub header: RosHeader,
+    pub axes: Vec<f32>,
+    pub buttons: Vec<i32>,
+}
+
+/// Which channels are reported as axes and which as buttons of a [`RosJoy`]
+///
+/// The default reports channels 1 to 16 as axes and the digital channels 17 and 18 as
+/// buttons.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{Channel, JoyMapping, SbusPacket};
+/// use std::time::Duration;
+///
+/// let mapping = JoyMapping::new(
+///     &[Channel::Ch1, Channel::Ch2, Channel::Ch3, Channel::Ch4],
+///     &[Channel::Ch5, Channel::Ch17],
+/// );
+/// let joy = mapping.to_joy(&SbusPacket::neutral(), Duration::from_secs(12), "rc");
+/// assert_eq!(joy.axes, [0.0; 4]);
+/// assert_eq!(joy.buttons, [0, 0]);
+/// assert_eq!(joy.header.stamp.sec, 12);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JoyMapping {
+    axes: Vec<Channel>,
+    buttons: Vec<Channel>,
+}
+
+impl JoyMapping {
+    pub fn new(axes: &[Channel], buttons: &[Channel]) -> Self {
+        Self {
+            axes: axes.to_vec(),
+            buttons: buttons.to_vec(),
+        }
+    }
+
+    pub fn axes(&self) -> &[Channel] {
+        &self.axes
+    }
+
+    pub fn buttons(&self) -> &[Channel] {
+        &self.buttons
+    }
+
+    /// The message reporting `packet`, stamped with `stamp`, e.g. the ROS time it was received
+    ///
+    /// Axes are clamped to -1.0 and 1.0, buttons are pressed above the centre.
+    pub fn to_joy(&self, packet: &SbusPacket, stamp: Duration, frame_id: &str) -> RosJoy {
+        RosJoy {
+            header: RosHeader {
+                stamp: stamp.into(),
+                frame_id: frame_id.into(),
+            },
+            axes: self
+                .axes
+                .iter()
+                .map(|&channel| channel_to_axis(packet.value(channel)))
+                .collect(),
+            buttons: self
+                .buttons
+                .iter()
+                .map(|&channel| i32::from(packet.value(channel) > CHANNEL_FUTABA_MID))
+                .collect(),
+        }
+    }
+
+    /// The packet carrying the axes and buttons of `joy`
+    ///
+    /// Channels missing from the mapping or the message are centred, and flags are cleared.
+    /// Mapped digital channels are set by positive axes and pressed buttons.
+    pub fn to_packet(&self, joy: &RosJoy) -> SbusPacket {
+        let mut packet = SbusPacket::neutral();
+        for (&channel, &axis) in self.axes.iter().zip(&joy.axes) {
+            if !packet.set_digital(channel, axis > 0.0) {
+                packet[channel] = axis_to_channel(axis);
+            }
+        }
+        for (&channel, &button) in self.buttons.iter().zip(&joy.buttons) {
+            if !packet.set_digital(channel, button != 0) {
+                packet[channel] = if button != 0 {
+                    CHANNEL_FUTABA_MAX
+                } else {
+                    CHANNEL_FUTABA_MIN
+                };
+            }
+        }
+        packet
+    }
+}
+
+impl Default for JoyMapping {
+    fn default() -> Self {
+        Self::new(&Channel::ALL[..16], &[Channel::Ch17, Channel::Ch18])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_joy() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch1] = CHANNEL_FUTABA_MIN;
+        packet[Channel::Ch2] = CHANNEL_FUTABA_MAX;
+        packet[Channel::Ch3] = 2047;
+        packet.flags.d2 = true;
+
+        let joy = JoyMapping::default().to_joy(&packet, Duration::from_millis(1500), "rc");
+        assert_eq!(
+            joy.header.stamp,
+            RosTime {
+                sec: 1,
+                nanosec: 500_000_000
+            }
+        );
+        assert_eq!(joy.header.frame_id, "rc");
+        assert_eq!(joy.axes.len(), 16);
+        assert_eq!(joy.axes[..4], [-1.0, 1.0, 1.0, 0.0]);
+        assert_eq!(joy.buttons, [0, 1]);
+    }
+
+    #[test]
+    fn test_to_packet() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch1] = CHANNEL_FUTABA_MIN;
+        packet[Channel::Ch4] = 1400;
+        packet[Channel::Ch5] = CHANNEL_FUTABA_MAX;
+        packet.flags.d1 = true;
+
+        let mapping = JoyMapping::default();
+        assert_eq!(
+            mapping.to_packet(&mapping.to_joy(&packet, Duration::ZERO, "")),
+            packet
+        );
+
+        // Buttons on analog channels, digital axes and a short message
+        let mapping = JoyMapping::new(
+            &[Channel::Ch17, Channel::Ch2, Channel::Ch3],
+            &[Channel::Ch5, Channel::Ch6],
+        );
+        let joy = RosJoy {
+            axes: vec![0.5, f32::NAN],
+            buttons: vec![1, 0],
+            ..RosJoy::default()
+        };
+        let decoded = mapping.to_packet(&joy);
+        assert!(decoded.flags.d1);
+        assert_eq!(decoded[Channel::Ch2], CHANNEL_FUTABA_MID);
+        assert_eq!(decoded[Channel::Ch3], CHANNEL_FUTABA_MID);
+        assert_eq!(decoded[Channel::Ch5], CHANNEL_FUTABA_MAX);
+        assert_eq!(decoded[Channel::Ch6], CHANNEL_FUTABA_MIN);
+    }
+}
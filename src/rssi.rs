@@ -0,0 +1,145 @@
+use crate::{
+    Channel, SbusPacket, CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MIN, CHANNEL_MAX,
+};
+
+/// How a channel carrying RSSI or link quality maps onto 0 to 100%
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RssiScale {
+    /// 0% at [`CHANNEL_FUTABA_MIN`] and 100% at [`CHANNEL_FUTABA_MAX`], 988 to 2012 µs, as
+    /// sent by most receivers
+    #[default]
+    Futaba,
+    /// 0% at 0 and 100% at [`CHANNEL_MAX`]
+    Full,
+    /// 0% at `min` and 100% at `max`, swap them for receivers sending lower values for a
+    /// better link
+    Raw { min: u16, max: u16 },
+}
+
+impl RssiScale {
+    const fn range(self) -> (u16, u16) {
+        match self {
+            RssiScale::Futaba => (CHANNEL_FUTABA_MIN, CHANNEL_FUTABA_MAX),
+            RssiScale::Full => (0, CHANNEL_MAX),
+            RssiScale::Raw { min, max } => (min, max),
+        }
+    }
+}
+
+/// Reads RSSI or link quality that a receiver injects into one of the channels
+///
+/// Many receivers can send RSSI or LQ on a channel instead of a control value, commonly
+/// channel 16 or 12. [`RssiChannel::rssi`] scales that channel to a percentage, and
+/// [`RssiChannel::control`] centres it so it isn't mistaken for a control input.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{Channel, RssiChannel, SbusPacket, CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MID};
+///
+/// let rssi = RssiChannel::new(Channel::Ch16);
+///
+/// let mut packet = SbusPacket::neutral();
+/// packet[Channel::Ch16] = CHANNEL_FUTABA_MAX;
+/// let (control, percent) = rssi.split(&packet);
+/// assert_eq!(percent, 100);
+/// assert_eq!(control[Channel::Ch16], CHANNEL_FUTABA_MID);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RssiChannel {
+    channel: Channel,
+    scale: RssiScale,
+}
+
+impl RssiChannel {
+    /// Reads RSSI from `channel` with the [`RssiScale::Futaba`] scale
+    pub const fn new(channel: Channel) -> Self {
+        Self {
+            channel,
+            scale: RssiScale::Futaba,
+        }
+    }
+
+    pub const fn with_scale(mut self, scale: RssiScale) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub const fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    pub const fn scale(&self) -> RssiScale {
+        self.scale
+    }
+
+    /// The RSSI in `packet` from 0 to 100%, clamped to the ends of the scale
+    pub fn rssi(&self, packet: &SbusPacket) -> u8 {
+        let (min, max) = self.scale.range();
+        if min == max {
+            return 0;
+        }
+        let value = packet.value(self.channel) as i32;
+        let percent = (value - min as i32) * 100 / (max as i32 - min as i32);
+        percent.clamp(0, 100) as u8
+    }
+
+    /// `packet` with the RSSI channel centred, or cleared for a digital channel
+    pub fn control(&self, packet: &SbusPacket) -> SbusPacket {
+        let mut packet = *packet;
+        if !packet.set_digital(self.channel, false) {
+            packet[self.channel] = CHANNEL_FUTABA_MID;
+        }
+        packet
+    }
+
+    /// The [`control`](Self::control) packet and the [`rssi`](Self::rssi) of `packet`
+    pub fn split(&self, packet: &SbusPacket) -> (SbusPacket, u8) {
+        (self.control(packet), self.rssi(packet))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scales() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch12] = 500;
+
+        let futaba = RssiChannel::new(Channel::Ch12);
+        assert_eq!(futaba.rssi(&packet), 20);
+        let full = futaba.with_scale(RssiScale::Full);
+        assert_eq!(full.rssi(&packet), 24);
+        let inverted = futaba.with_scale(RssiScale::Raw { min: 1000, max: 0 });
+        assert_eq!(inverted.rssi(&packet), 50);
+
+        packet[Channel::Ch12] = 0;
+        assert_eq!(futaba.rssi(&packet), 0);
+        assert_eq!(inverted.rssi(&packet), 100);
+        let empty = futaba.with_scale(RssiScale::Raw { min: 5, max: 5 });
+        assert_eq!(empty.rssi(&packet), 0);
+    }
+
+    #[test]
+    fn test_control() {
+        let mut packet = SbusPacket::neutral();
+        packet[Channel::Ch16] = 1000;
+        packet[Channel::Ch1] = 300;
+        packet.flags.d2 = true;
+
+        let (control, rssi) = RssiChannel::new(Channel::Ch16).split(&packet);
+        assert_eq!(rssi, 50);
+        assert_eq!(control[Channel::Ch16], CHANNEL_FUTABA_MID);
+        assert_eq!(control[Channel::Ch1], 300);
+
+        let digital = RssiChannel::new(Channel::Ch18);
+        assert_eq!(digital.rssi(&packet), 100);
+        assert!(!digital.control(&packet).flags.d2);
+    }
+}
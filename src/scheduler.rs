@@ -0,0 +1,219 @@
+use core::time::Duration;
+
+use crate::SbusFrameRate;
+
+/// Number of SBUS2 telemetry slots, in four slot groups of eight
+pub const SBUS2_SLOT_COUNT: usize = 32;
+/// Number of telemetry slots following each SBUS2 frame
+pub const SBUS2_SLOTS_PER_GROUP: usize = SBUS2_SLOT_COUNT / 4;
+/// Time from the end of an SBUS2 frame to the start of the first slot of its slot group
+pub const SBUS2_SLOT_DELAY: Duration = Duration::from_micros(2000);
+/// Time from the start of one telemetry slot to the start of the next
+pub const SBUS2_SLOT_INTERVAL: Duration = Duration::from_micros(660);
+
+/// Time from the end of an SBUS2 frame to the start of telemetry slot `index` of its group
+pub const fn slot_offset(index: u8) -> Duration {
+    Duration::from_micros(
+        SBUS2_SLOT_DELAY.as_micros() as u64 + SBUS2_SLOT_INTERVAL.as_micros() as u64 * index as u64,
+    )
+}
+
+/// A monotonic clock that can wait for a point in time
+///
+/// Implement this for the clock of your platform, e.g. with `embassy_time::Instant::now` and
+/// `Timer::at`, an RTIC monotonic, or `std::time::Instant` and `tokio::time::sleep_until`.
+#[allow(async_fn_in_trait)]
+pub trait FrameTimer {
+    /// The current time, measured from an arbitrary fixed point
+    fn now(&self) -> Duration;
+
+    /// Waits until [`FrameTimer::now`] reaches `deadline`, returning at once if it has passed
+    async fn wait_until(&mut self, deadline: Duration);
+}
+
+/// What a [`FrameScheduler`] event is due for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub enum ScheduleEvent {
+    /// Time to send the next frame
+    Frame,
+    /// The telemetry slot with the given index within the slot group opens, 0 to 7
+    Slot(u8),
+}
+
+/// Decides when frames are sent and when the SBUS2 telemetry slots after them open
+///
+/// Frames are due one [`SbusFrameRate::interval`] apart, starting right away. With
+/// [`FrameScheduler::with_slot_windows`], each frame is followed by the windows of the eight
+/// telemetry slots of its slot group, as far as they open before the next frame is due. When
+/// the caller falls behind by more than a whole interval, the schedule restarts from the
+/// current time instead of sending a burst of late frames.
+///
+/// With the `async` feature it is also a [`FrameTicker`](crate::FrameTicker) for the
+/// transmitter, waiting for the frames and skipping the slot windows.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn example<T: sbus_rs::FrameTimer>(timer: T) {
+/// use sbus_rs::{FrameScheduler, SbusFrameRate, ScheduleEvent};
+///
+/// let mut scheduler = FrameScheduler::new(timer, SbusFrameRate::Analog).with_slot_windows(true);
+/// loop {
+///     match scheduler.next().await {
+///         ScheduleEvent::Frame => { /* send a frame */ }
+///         ScheduleEvent::Slot(index) => { /* listen to or answer in a telemetry slot */ }
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameScheduler<T> {
+    timer: T,
+    rate: SbusFrameRate,
+    slot_windows: bool,
+    /// Start of the last frame, `None` before the first
+    frame_at: Option<Duration>,
+    /// Index of the next slot window after the last frame
+    next_slot: u8,
+}
+
+impl<T: FrameTimer> FrameScheduler<T> {
+    pub fn new(timer: T, rate: SbusFrameRate) -> Self {
+        Self {
+            timer,
+            rate,
+            slot_windows: false,
+            frame_at: None,
+            next_slot: 0,
+        }
+    }
+
+    /// Yields the telemetry slot windows after every frame
+    pub fn with_slot_windows(mut self, enabled: bool) -> Self {
+        self.slot_windows = enabled;
+        self
+    }
+
+    /// Changes the frame rate, taking effect from the next frame
+    pub fn set_rate(&mut self, rate: SbusFrameRate) {
+        self.rate = rate;
+    }
+
+    pub fn rate(&self) -> SbusFrameRate {
+        self.rate
+    }
+
+    /// The next event and when it is due, without waiting for it
+    pub fn peek(&self) -> (Duration, ScheduleEvent) {
+        let Some(frame_at) = self.frame_at else {
+            return (self.timer.now(), ScheduleEvent::Frame);
+        };
+        let next_frame = frame_at + self.rate.interval();
+        if self.slot_windows && (self.next_slot as usize) < SBUS2_SLOTS_PER_GROUP {
+            let slot_at = frame_at + self.rate.frame_time() + slot_offset(self.next_slot);
+            if slot_at < next_frame {
+                return (slot_at, ScheduleEvent::Slot(self.next_slot));
+            }
+        }
+        (next_frame, ScheduleEvent::Frame)
+    }
+
+    /// Waits for the next event and returns it
+    pub async fn next(&mut self) -> ScheduleEvent {
+        let (deadline, event) = self.peek();
+        self.timer.wait_until(deadline).await;
+        match event {
+            ScheduleEvent::Frame => {
+                let now = self.timer.now();
+                let late = now.saturating_sub(deadline) > self.rate.interval();
+                self.frame_at = Some(if late { now } else { deadline });
+                self.next_slot = 0;
+            }
+            ScheduleEvent::Slot(index) => self.next_slot = index + 1,
+        }
+        event
+    }
+
+    /// Starts over with a frame due right away
+    pub fn reset(&mut self) {
+        self.frame_at = None;
+        self.next_slot = 0;
+    }
+
+    pub fn timer_mut(&mut self) -> &mut T {
+        &mut self.timer
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: FrameTimer> crate::FrameTicker for FrameScheduler<T> {
+    async fn tick(&mut self) {
+        while self.next().await != ScheduleEvent::Frame {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A clock that jumps to every deadline it is asked to wait for
+    struct ManualTimer(Duration);
+
+    impl FrameTimer for ManualTimer {
+        fn now(&self) -> Duration {
+            self.0
+        }
+
+        async fn wait_until(&mut self, deadline: Duration) {
+            self.0 = self.0.max(deadline);
+        }
+    }
+
+    fn micros(event: (Duration, ScheduleEvent)) -> (u128, ScheduleEvent) {
+        (event.0.as_micros(), event.1)
+    }
+
+    #[tokio::test]
+    async fn test_frames() {
+        let mut scheduler =
+            FrameScheduler::new(ManualTimer(Duration::from_millis(5)), SbusFrameRate::Analog);
+        assert_eq!(micros(scheduler.peek()), (5000, ScheduleEvent::Frame));
+        assert_eq!(scheduler.next().await, ScheduleEvent::Frame);
+        assert_eq!(micros(scheduler.peek()), (19000, ScheduleEvent::Frame));
+        scheduler.next().await;
+
+        // Falling behind by more than an interval restarts the schedule
+        scheduler.timer_mut().0 = Duration::from_millis(60);
+        scheduler.next().await;
+        assert_eq!(micros(scheduler.peek()), (74000, ScheduleEvent::Frame));
+
+        scheduler.reset();
+        assert_eq!(micros(scheduler.peek()), (60000, ScheduleEvent::Frame));
+    }
+
+    #[tokio::test]
+    async fn test_slot_windows() {
+        let mut scheduler = FrameScheduler::new(ManualTimer(Duration::ZERO), SbusFrameRate::Analog)
+            .with_slot_windows(true);
+        assert_eq!(scheduler.next().await, ScheduleEvent::Frame);
+
+        let mut events = Vec::new();
+        for _ in 0..9 {
+            scheduler.next().await;
+            events.push((scheduler.timer_mut().0.as_micros(), scheduler.peek().1));
+        }
+        let slots: Vec<_> = events.iter().map(|(at, _)| *at).take(8).collect();
+        assert_eq!(slots, [5000, 5660, 6320, 6980, 7640, 8300, 8960, 9620]);
+        assert_eq!(events[7].1, ScheduleEvent::Frame);
+        assert_eq!(events[8].0, 14000);
+
+        // The slots of high speed mode partly overlap the next frame
+        scheduler.set_rate(SbusFrameRate::HighSpeed);
+        let mut count = 0;
+        while scheduler.next().await != ScheduleEvent::Frame {
+            count += 1;
+        }
+        assert_eq!(count, 4);
+    }
+}
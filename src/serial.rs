@@ -0,0 +1,49 @@
+//! Opening SBUS parsers and writers on serial ports with the `serialport` and `tokio-serial` crates
+use std::borrow::Cow;
+
+use serialport::{DataBits, Parity, SerialPortBuilder, StopBits};
+
+use crate::{SbusUartConfig, UartParity, UartStopBits};
+
+#[cfg(feature = "tokio")]
+mod asynch;
+#[cfg(feature = "serialport")]
+mod blocking;
+#[cfg(all(feature = "serialport", unix))]
+mod pty;
+
+#[cfg(feature = "tokio")]
+pub use asynch::*;
+#[cfg(feature = "serialport")]
+pub use blocking::*;
+#[cfg(all(feature = "serialport", unix))]
+pub use pty::*;
+
+impl SbusUartConfig {
+    /// A `serialport` builder for the port at `path` with this configuration
+    ///
+    /// `serialport` cannot invert the signal, so [`SbusUartConfig::inverted`] is not applied.
+    /// Use an adapter with a hardware inverter, or one configured to invert its lines.
+    pub fn serialport_builder<'a>(&self, path: impl Into<Cow<'a, str>>) -> SerialPortBuilder {
+        let data_bits = match self.data_bits {
+            5 => DataBits::Five,
+            6 => DataBits::Six,
+            7 => DataBits::Seven,
+            _ => DataBits::Eight,
+        };
+        let parity = match self.parity {
+            UartParity::None => Parity::None,
+            UartParity::Even => Parity::Even,
+            UartParity::Odd => Parity::Odd,
+        };
+        let stop_bits = match self.stop_bits {
+            UartStopBits::One => StopBits::One,
+            UartStopBits::Two => StopBits::Two,
+        };
+
+        serialport::new(path, self.baud_rate)
+            .data_bits(data_bits)
+            .parity(parity)
+            .stop_bits(stop_bits)
+    }
+}
@@ -0,0 +1,79 @@
+use std::borrow::Cow;
+
+use embedded_io_adapters::tokio_1::FromTokio;
+use tokio_serial::{SerialPortBuilderExt, SerialStream};
+
+use crate::{SbusParserAsync, SbusUartConfig, SbusWriterAsync};
+
+/// A serial port as opened by [`SbusParserAsync::open_serial`] and
+/// [`SbusWriterAsync::open_serial`]
+pub type SerialStreamIo = FromTokio<SerialStream>;
+
+impl SbusUartConfig {
+    /// Opens the serial port at `path` with this configuration for use with tokio
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn open_serial_stream<'a>(
+        &self,
+        path: impl Into<Cow<'a, str>>,
+    ) -> tokio_serial::Result<SerialStreamIo> {
+        self.serialport_builder(path)
+            .open_native_async()
+            .map(FromTokio::new)
+    }
+}
+
+impl SbusParserAsync<SerialStreamIo> {
+    /// Opens the serial port at `path` with the standard SBUS settings and returns a parser for it
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn open_serial<'a>(path: impl Into<Cow<'a, str>>) -> tokio_serial::Result<Self> {
+        Self::open_serial_with(path, &SbusUartConfig::new())
+    }
+
+    /// Opens the serial port at `path` with `config` and returns a parser for it
+    pub fn open_serial_with<'a>(
+        path: impl Into<Cow<'a, str>>,
+        config: &SbusUartConfig,
+    ) -> tokio_serial::Result<Self> {
+        config.open_serial_stream(path).map(Self::new)
+    }
+
+    /// Creates a parser for an already opened serial stream
+    pub fn from_serial_stream(stream: SerialStream) -> Self {
+        Self::new(FromTokio::new(stream))
+    }
+}
+
+impl SbusWriterAsync<SerialStreamIo> {
+    /// Opens the serial port at `path` with the standard SBUS settings and returns a writer for it
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn open_serial<'a>(path: impl Into<Cow<'a, str>>) -> tokio_serial::Result<Self> {
+        Self::open_serial_with(path, &SbusUartConfig::new())
+    }
+
+    /// Opens the serial port at `path` with `config` and returns a writer for it
+    pub fn open_serial_with<'a>(
+        path: impl Into<Cow<'a, str>>,
+        config: &SbusUartConfig,
+    ) -> tokio_serial::Result<Self> {
+        config.open_serial_stream(path).map(Self::new)
+    }
+
+    /// Creates a writer for an already opened serial stream
+    pub fn from_serial_stream(stream: SerialStream) -> Self {
+        Self::new(FromTokio::new(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_open_missing_port_fails() {
+        assert!(SbusParserAsync::open_serial("/dev/does-not-exist-sbus").is_err());
+        assert!(SbusWriterAsync::open_serial("/dev/does-not-exist-sbus").is_err());
+    }
+}
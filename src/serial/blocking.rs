@@ -0,0 +1,64 @@
+use std::borrow::Cow;
+
+use embedded_io_adapters::std::FromStd;
+use serialport::SerialPort;
+
+use crate::{SbusParser, SbusUartConfig, SbusWriter};
+
+/// A serial port as opened by [`SbusParser::open_serial`] and [`SbusWriter::open_serial`]
+pub type SerialPortIo = FromStd<Box<dyn SerialPort>>;
+
+impl SbusUartConfig {
+    /// Opens the serial port at `path` with this configuration
+    pub fn open_serialport<'a>(
+        &self,
+        path: impl Into<Cow<'a, str>>,
+    ) -> serialport::Result<SerialPortIo> {
+        self.serialport_builder(path).open().map(FromStd::new)
+    }
+}
+
+impl SbusParser<SerialPortIo> {
+    /// Opens the serial port at `path` with the standard SBUS settings and returns a parser for it
+    ///
+    /// Not every OS allows setting 100000 baud; opening fails if the baud rate is rejected.
+    pub fn open_serial<'a>(path: impl Into<Cow<'a, str>>) -> serialport::Result<Self> {
+        Self::open_serial_with(path, &SbusUartConfig::new())
+    }
+
+    /// Opens the serial port at `path` with `config` and returns a parser for it
+    pub fn open_serial_with<'a>(
+        path: impl Into<Cow<'a, str>>,
+        config: &SbusUartConfig,
+    ) -> serialport::Result<Self> {
+        config.open_serialport(path).map(Self::new)
+    }
+}
+
+impl SbusWriter<SerialPortIo> {
+    /// Opens the serial port at `path` with the standard SBUS settings and returns a writer for it
+    ///
+    /// Not every OS allows setting 100000 baud; opening fails if the baud rate is rejected.
+    pub fn open_serial<'a>(path: impl Into<Cow<'a, str>>) -> serialport::Result<Self> {
+        Self::open_serial_with(path, &SbusUartConfig::new())
+    }
+
+    /// Opens the serial port at `path` with `config` and returns a writer for it
+    pub fn open_serial_with<'a>(
+        path: impl Into<Cow<'a, str>>,
+        config: &SbusUartConfig,
+    ) -> serialport::Result<Self> {
+        config.open_serialport(path).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_missing_port_fails() {
+        assert!(SbusParser::open_serial("/dev/does-not-exist-sbus").is_err());
+        assert!(SbusWriter::open_serial("/dev/does-not-exist-sbus").is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use std::io;
+use std::thread;
+use std::time::Instant;
+
+use embedded_io_adapters::std::FromStd;
+use serialport::{SerialPort, TTYPort};
+
+use crate::{SbusError, SbusFrameRate, SbusPacket, SbusWriter};
+
+/// A pseudo-terminal acting as a fake SBUS receiver, e.g. for ArduPilot or PX4 SITL
+///
+/// Simulators open the terminal at [`SbusPty::path`] like a serial port and read the frames
+/// written to it. Writes block while the terminal's buffer is full, so frames are only sent as
+/// fast as they are read.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use sbus_rs::{SbusPacket, SbusPty};
+///
+/// let mut pty = SbusPty::open()?;
+/// println!("Serial port for the simulator: {}", pty.path().unwrap());
+///
+/// // Send the same packet forever, one frame every 14 ms
+/// pty.run(|| Some(SbusPacket::neutral())).ok();
+/// # Ok::<(), serialport::Error>(())
+/// ```
+pub struct SbusPty {
+    writer: SbusWriter<FromStd<TTYPort>>,
+    /// The end opened by simulators, kept open so the terminal outlives their connections
+    slave: TTYPort,
+    frame_rate: SbusFrameRate,
+}
+
+impl SbusPty {
+    /// Opens a new pseudo-terminal sending frames at [`SbusFrameRate::Analog`]
+    pub fn open() -> serialport::Result<Self> {
+        let (master, slave) = TTYPort::pair()?;
+        Ok(Self {
+            writer: SbusWriter::new(FromStd::new(master)),
+            slave,
+            frame_rate: SbusFrameRate::Analog,
+        })
+    }
+
+    /// Sets the interval between the frames sent by [`SbusPty::run`]
+    pub fn with_frame_rate(mut self, frame_rate: SbusFrameRate) -> Self {
+        self.frame_rate = frame_rate;
+        self
+    }
+
+    /// The path of the terminal to hand to the simulator, e.g. `/dev/pts/3`
+    pub fn path(&self) -> Option<String> {
+        self.slave.name()
+    }
+
+    /// Writes a single frame
+    pub fn write_frame(&mut self, packet: &SbusPacket) -> Result<(), SbusError<io::Error>> {
+        self.writer.write_frame(packet)
+    }
+
+    /// Writes the packets returned by `next` at the frame rate until it returns `None`
+    ///
+    /// When writing falls behind by more than a frame interval, e.g. while the simulator was
+    /// paused, the schedule restarts instead of catching up with a burst of frames.
+    pub fn run<F>(&mut self, mut next: F) -> Result<(), SbusError<io::Error>>
+    where
+        F: FnMut() -> Option<SbusPacket>,
+    {
+        let interval = self.frame_rate.interval();
+        let mut deadline = Instant::now();
+        while let Some(packet) = next() {
+            self.write_frame(&packet)?;
+            deadline += interval;
+            let now = Instant::now();
+            match deadline.checked_duration_since(now) {
+                Some(wait) => thread::sleep(wait),
+                None if now - deadline > interval => deadline = now,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn writer_mut(&mut self) -> &mut SbusWriter<FromStd<TTYPort>> {
+        &mut self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Channel, SBUS_FRAME_LENGTH};
+    use std::io::Read;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pty() {
+        let mut pty = SbusPty::open().unwrap();
+        assert!(pty.path().unwrap().starts_with("/dev/"));
+
+        let mut packet = SbusPacket::neutral();
+        let mut remaining = 3;
+        pty.run(|| {
+            packet[Channel::Ch1] = 100 * remaining;
+            remaining = remaining.checked_sub(1)?;
+            Some(packet)
+        })
+        .unwrap();
+
+        let mut frames = [0u8; 3 * SBUS_FRAME_LENGTH];
+        pty.slave.set_timeout(Duration::from_secs(1)).unwrap();
+        pty.slave.read_exact(&mut frames).unwrap();
+        let values: Vec<_> = frames
+            .chunks_exact(SBUS_FRAME_LENGTH)
+            .map(|frame| SbusPacket::from_array(frame.try_into().unwrap()).unwrap()[Channel::Ch1])
+            .collect();
+        assert_eq!(values, [300, 200, 100]);
+    }
+}
@@ -0,0 +1,261 @@
+//! Driving servos and ESCs from packets with `embedded-hal` PWM pins
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::{Channel, SbusPacket, CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MIN};
+
+/// Default pulse width in microseconds for [`CHANNEL_FUTABA_MIN`]
+pub const SERVO_PULSE_MIN: u16 = 1000;
+/// Default pulse width in microseconds for [`CHANNEL_FUTABA_MAX`]
+pub const SERVO_PULSE_MAX: u16 = 2000;
+/// Default PWM period in microseconds, 50 Hz as expected by analog servos
+pub const SERVO_PERIOD: u16 = 20_000;
+
+/// When [`ServoOutputs`] writes the duty cycle of a pin
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UpdatePolicy {
+    /// With every packet
+    #[default]
+    Always,
+    /// Only when its pulse width changed, sparing slow PWM drivers, e.g. on an I²C expander
+    OnChange,
+}
+
+/// A PWM pin driven by one channel
+#[derive(Debug)]
+pub struct Servo<P> {
+    pin: P,
+    channel: Channel,
+    min_us: u16,
+    max_us: u16,
+    /// The last pulse width written
+    pulse: Option<u16>,
+}
+
+impl<P: SetDutyCycle> Servo<P> {
+    /// Drives `pin` from `channel` with pulses from [`SERVO_PULSE_MIN`] to [`SERVO_PULSE_MAX`]
+    pub fn new(pin: P, channel: Channel) -> Self {
+        Self {
+            pin,
+            channel,
+            min_us: SERVO_PULSE_MIN,
+            max_us: SERVO_PULSE_MAX,
+            pulse: None,
+        }
+    }
+
+    /// Sets the pulse widths in microseconds for [`CHANNEL_FUTABA_MIN`] and
+    /// [`CHANNEL_FUTABA_MAX`], swap them to reverse the servo
+    ///
+    /// Values beyond the Futaba range are clamped to these pulse widths.
+    pub fn with_range(mut self, min_us: u16, max_us: u16) -> Self {
+        self.min_us = min_us;
+        self.max_us = max_us;
+        self
+    }
+
+    pub fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    /// The last pulse width written in microseconds, `None` before the first packet
+    pub fn pulse(&self) -> Option<u16> {
+        self.pulse
+    }
+
+    pub fn pin_mut(&mut self) -> &mut P {
+        &mut self.pin
+    }
+
+    pub fn into_inner(self) -> P {
+        self.pin
+    }
+
+    /// The pulse width in microseconds for `packet`, digital channels giving the range ends
+    pub fn pulse_for(&self, packet: &SbusPacket) -> u16 {
+        let value = packet
+            .value(self.channel)
+            .clamp(CHANNEL_FUTABA_MIN, CHANNEL_FUTABA_MAX);
+        let span = (CHANNEL_FUTABA_MAX - CHANNEL_FUTABA_MIN) as i32;
+        let offset = (value - CHANNEL_FUTABA_MIN) as i32;
+        let delta = self.max_us as i32 - self.min_us as i32;
+        (self.min_us as i32 + (delta * offset + span / 2 * delta.signum()) / span) as u16
+    }
+}
+
+/// Drives PWM pins, typically servos and ESCs, from selected channels
+///
+/// Each [`Servo`] maps one channel onto a pulse width and sets the duty cycle of its pin to
+/// that pulse width as a fraction of the PWM period, which has to match how the PWM timer was
+/// set up. Pins of different types can be driven together as `&mut dyn SetDutyCycle<Error = E>`.
+///
+/// Outputs are usually driven from packets passed through a
+/// [`FailsafeHandler`](crate::FailsafeHandler). Alternatively,
+/// [`ServoOutputs::with_hold_on_failsafe`] keeps the last pulse widths while packets have their
+/// `failsafe` flag set.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # fn example<P: embedded_hal::pwm::SetDutyCycle>(aileron: P, elevator: P, packet: sbus_rs::SbusPacket) {
+/// use sbus_rs::{Channel, Servo, ServoOutputs};
+///
+/// let mut outputs = ServoOutputs::new([
+///     Servo::new(aileron, Channel::Ch1),
+///     Servo::new(elevator, Channel::Ch2).with_range(2000, 1000),
+/// ]);
+///
+/// // For every received packet
+/// outputs.update(&packet).ok();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ServoOutputs<P, const N: usize> {
+    servos: [Servo<P>; N],
+    period_us: u16,
+    policy: UpdatePolicy,
+    hold_on_failsafe: bool,
+}
+
+impl<P: SetDutyCycle, const N: usize> ServoOutputs<P, N> {
+    pub fn new(servos: [Servo<P>; N]) -> Self {
+        Self {
+            servos,
+            period_us: SERVO_PERIOD,
+            policy: UpdatePolicy::Always,
+            hold_on_failsafe: false,
+        }
+    }
+
+    /// Sets the PWM period in microseconds, [`SERVO_PERIOD`] by default
+    pub fn with_period(mut self, period_us: u16) -> Self {
+        self.period_us = period_us;
+        self
+    }
+
+    /// Sets when pins are written, [`UpdatePolicy::Always`] by default
+    pub fn with_update_policy(mut self, policy: UpdatePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Keeps the last pulse widths while packets have their `failsafe` flag set
+    pub fn with_hold_on_failsafe(mut self, hold: bool) -> Self {
+        self.hold_on_failsafe = hold;
+        self
+    }
+
+    /// Sets every pin to the pulse width of its channel in `packet`
+    ///
+    /// Stops at the first pin failing to update; the remaining pins are updated with the next
+    /// packet.
+    pub fn update(&mut self, packet: &SbusPacket) -> Result<(), P::Error> {
+        if self.hold_on_failsafe && packet.flags.failsafe {
+            return Ok(());
+        }
+        for servo in &mut self.servos {
+            let pulse = servo.pulse_for(packet);
+            if self.policy == UpdatePolicy::OnChange && servo.pulse == Some(pulse) {
+                continue;
+            }
+            servo
+                .pin
+                .set_duty_cycle_fraction(pulse.min(self.period_us), self.period_us)?;
+            servo.pulse = Some(pulse);
+        }
+        Ok(())
+    }
+
+    pub fn servos(&self) -> &[Servo<P>; N] {
+        &self.servos
+    }
+
+    pub fn servos_mut(&mut self) -> &mut [Servo<P>; N] {
+        &mut self.servos
+    }
+
+    pub fn into_inner(self) -> [Servo<P>; N] {
+        self.servos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::Infallible;
+
+    /// A 16-bit PWM pin recording the duty cycles written
+    #[derive(Default)]
+    struct MockPin(Vec<u16>);
+
+    impl embedded_hal::pwm::ErrorType for MockPin {
+        type Error = Infallible;
+    }
+
+    impl SetDutyCycle for MockPin {
+        fn max_duty_cycle(&self) -> u16 {
+            20_000
+        }
+
+        fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+            self.0.push(duty);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_pulse_widths() {
+        let servo = Servo::new(MockPin::default(), Channel::Ch1);
+        let reversed = Servo::new(MockPin::default(), Channel::Ch1).with_range(2000, 1000);
+        let digital = Servo::new(MockPin::default(), Channel::Ch17);
+
+        let mut packet = SbusPacket::neutral();
+        assert_eq!(servo.pulse_for(&packet), 1500);
+        assert_eq!(reversed.pulse_for(&packet), 1500);
+        assert_eq!(digital.pulse_for(&packet), 1000);
+
+        packet[Channel::Ch1] = CHANNEL_FUTABA_MIN;
+        packet.flags.d1 = true;
+        assert_eq!(servo.pulse_for(&packet), 1000);
+        assert_eq!(reversed.pulse_for(&packet), 2000);
+        assert_eq!(digital.pulse_for(&packet), 2000);
+
+        // Values beyond the Futaba range are clamped
+        packet[Channel::Ch1] = 2047;
+        assert_eq!(servo.pulse_for(&packet), 2000);
+    }
+
+    #[test]
+    fn test_update() {
+        let mut outputs = ServoOutputs::new([
+            Servo::new(MockPin::default(), Channel::Ch1),
+            Servo::new(MockPin::default(), Channel::Ch2),
+        ])
+        .with_update_policy(UpdatePolicy::OnChange)
+        .with_hold_on_failsafe(true);
+
+        let mut packet = SbusPacket::neutral();
+        outputs.update(&packet).unwrap();
+        packet[Channel::Ch2] = CHANNEL_FUTABA_MAX;
+        outputs.update(&packet).unwrap();
+
+        let mut failsafe = SbusPacket::neutral();
+        failsafe.flags.failsafe = true;
+        outputs.update(&failsafe).unwrap();
+
+        assert_eq!(outputs.servos()[1].pulse(), Some(2000));
+        let [first, second] = outputs.into_inner();
+        assert_eq!(first.into_inner().0, [1500]);
+        assert_eq!(second.into_inner().0, [1500, 2000]);
+    }
+
+    #[test]
+    fn test_period() {
+        let mut outputs =
+            ServoOutputs::new([Servo::new(MockPin::default(), Channel::Ch3)]).with_period(2500);
+        outputs.update(&SbusPacket::neutral()).unwrap();
+        // 1500 µs of a 2500 µs period
+        assert_eq!(outputs.servos_mut()[0].pin_mut().0, [12_000]);
+    }
+}
@@ -0,0 +1,345 @@
+use crate::{
+    Channel, FrameSource, SbusPacket, CHANNEL_COUNT, CHANNEL_FUTABA_MID, CHANNEL_MAX,
+    SBUS_FRAME_LENGTH,
+};
+
+/// The course of a simulated channel over time, in frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Waveform {
+    /// A stick or switch that doesn't move
+    Constant(u16),
+    /// Moves linearly from `min` to `max` and back within `period` frames
+    Sweep { min: u16, max: u16, period: u32 },
+    /// Jumps between `low` for the first half of `period` frames and `high` for the second
+    Step { low: u16, high: u16, period: u32 },
+}
+
+impl Waveform {
+    /// The value in frame number `frame`
+    pub const fn value(self, frame: u64) -> u16 {
+        match self {
+            Waveform::Constant(value) => value,
+            Waveform::Sweep { min, max, period } => {
+                let (phase, half) = phase(frame, period);
+                let span = max as i64 - min as i64;
+                let offset = if phase < half {
+                    span * phase as i64 / half as i64
+                } else {
+                    span * (2 * half - phase) as i64 / half as i64
+                };
+                (min as i64 + offset) as u16
+            }
+            Waveform::Step { low, high, period } => {
+                let (phase, half) = phase(frame, period);
+                if phase < half {
+                    low
+                } else {
+                    high
+                }
+            }
+        }
+    }
+}
+
+/// The position within a period, and half the period, at least 1
+const fn phase(frame: u64, period: u32) -> (u64, u64) {
+    let half = if period < 2 { 1 } else { period as u64 / 2 };
+    (frame % (2 * half), half)
+}
+
+/// A way a simulated frame is damaged on the wire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Corruption {
+    BadHeader,
+    BadFooter,
+    /// A single flipped bit in the channel data
+    BitFlip,
+    /// The frame ends early
+    Truncated,
+}
+
+/// Generates realistic SBUS packet streams for tests and bench setups
+///
+/// Every channel follows a [`Waveform`], with optional noise on top. Lost frames, failsafe
+/// events and corrupted frames are injected at random, with rates given in events per thousand
+/// frames. The simulator is deterministic for a given seed.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{Channel, SbusParser, SbusSimulator, Waveform};
+///
+/// let mut simulator = SbusSimulator::new()
+///     .with_seed(7)
+///     .with_channel(Channel::Ch1, Waveform::Sweep { min: 172, max: 1811, period: 100 })
+///     .with_noise(4)
+///     .with_corruption(50);
+///
+/// let packet = simulator.next_packet();
+/// assert!((168..=176).contains(&packet.channels[0]));
+///
+/// // Raw frames for a parser, or a writer on a bench
+/// let mut buffer = [0; 25];
+/// let frame = simulator.next_frame(&mut buffer);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SbusSimulator {
+    waveforms: [Waveform; CHANNEL_COUNT],
+    digital: [bool; 2],
+    noise: u16,
+    frame_lost_rate: u16,
+    failsafe_rate: u16,
+    failsafe_frames: u32,
+    corruption_rate: u16,
+    state: u32,
+    frame: u64,
+    failsafe_remaining: u32,
+}
+
+impl SbusSimulator {
+    /// Creates a simulator with all channels centred and no disturbances
+    pub const fn new() -> Self {
+        Self {
+            waveforms: [Waveform::Constant(CHANNEL_FUTABA_MID); CHANNEL_COUNT],
+            digital: [false; 2],
+            noise: 0,
+            frame_lost_rate: 0,
+            failsafe_rate: 0,
+            failsafe_frames: 0,
+            corruption_rate: 0,
+            state: 0x2545_F491,
+            frame: 0,
+            failsafe_remaining: 0,
+        }
+    }
+
+    /// Seeds the random number generator
+    pub const fn with_seed(mut self, seed: u32) -> Self {
+        // Xorshift gets stuck on zero
+        self.state = if seed == 0 { 0x2545_F491 } else { seed };
+        self
+    }
+
+    /// Sets the waveform of a proportional channel, the digital channels are ignored
+    pub const fn with_channel(mut self, channel: Channel, waveform: Waveform) -> Self {
+        if !channel.is_digital() {
+            self.waveforms[channel.index()] = waveform;
+        }
+        self
+    }
+
+    /// Sets a digital channel, the proportional channels are ignored
+    pub const fn with_digital(mut self, channel: Channel, value: bool) -> Self {
+        match channel {
+            Channel::Ch17 => self.digital[0] = value,
+            Channel::Ch18 => self.digital[1] = value,
+            _ => {}
+        }
+        self
+    }
+
+    /// Adds uniform noise of up to `amplitude` in either direction to every channel
+    pub const fn with_noise(mut self, amplitude: u16) -> Self {
+        self.noise = amplitude;
+        self
+    }
+
+    /// Sets the `frame_lost` flag on `per_mille` of the frames
+    pub const fn with_frame_lost(mut self, per_mille: u16) -> Self {
+        self.frame_lost_rate = per_mille;
+        self
+    }
+
+    /// Starts a failsafe event lasting `frames` frames on `per_mille` of the frames
+    pub const fn with_failsafe(mut self, per_mille: u16, frames: u32) -> Self {
+        self.failsafe_rate = per_mille;
+        self.failsafe_frames = frames;
+        self
+    }
+
+    /// Damages `per_mille` of the frames returned by [`next_frame`](Self::next_frame)
+    pub const fn with_corruption(mut self, per_mille: u16) -> Self {
+        self.corruption_rate = per_mille;
+        self
+    }
+
+    /// Number of frames generated so far
+    pub const fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Generates the next packet
+    pub fn next_packet(&mut self) -> SbusPacket {
+        let mut packet = SbusPacket::neutral();
+        for (value, waveform) in packet.channels.iter_mut().zip(self.waveforms) {
+            let noise = if self.noise == 0 {
+                0
+            } else {
+                self.random(2 * self.noise as u32 + 1) as i32 - self.noise as i32
+            };
+            let noisy = waveform.value(self.frame) as i32 + noise;
+            *value = noisy.clamp(0, CHANNEL_MAX as i32) as u16;
+        }
+        packet.flags.d1 = self.digital[0];
+        packet.flags.d2 = self.digital[1];
+
+        if self.failsafe_remaining == 0 && self.chance(self.failsafe_rate) {
+            self.failsafe_remaining = self.failsafe_frames;
+        }
+        if self.failsafe_remaining > 0 {
+            self.failsafe_remaining -= 1;
+            packet.flags.failsafe = true;
+            packet.flags.frame_lost = true;
+        } else if self.chance(self.frame_lost_rate) {
+            packet.flags.frame_lost = true;
+        }
+
+        self.frame += 1;
+        packet
+    }
+
+    /// Generates the next frame into `buffer`, returning the bytes to send
+    ///
+    /// Corrupted frames are damaged as one of the [`Corruption`] kinds, truncated frames are
+    /// returned shorter.
+    pub fn next_frame<'a>(&mut self, buffer: &'a mut [u8; SBUS_FRAME_LENGTH]) -> &'a [u8] {
+        *buffer = self.next_packet().to_array();
+        if !self.chance(self.corruption_rate) {
+            return buffer;
+        }
+        match self.next_corruption() {
+            Corruption::BadHeader => buffer[0] = !buffer[0],
+            Corruption::BadFooter => buffer[SBUS_FRAME_LENGTH - 1] = 0x55,
+            Corruption::BitFlip => {
+                let bit = self.random(22 * 8);
+                buffer[1 + bit as usize / 8] ^= 1 << (bit % 8);
+            }
+            Corruption::Truncated => {
+                let len = 1 + self.random(SBUS_FRAME_LENGTH as u32 - 2) as usize;
+                return &buffer[..len];
+            }
+        }
+        buffer
+    }
+
+    fn next_corruption(&mut self) -> Corruption {
+        match self.random(4) {
+            0 => Corruption::BadHeader,
+            1 => Corruption::BadFooter,
+            2 => Corruption::BitFlip,
+            _ => Corruption::Truncated,
+        }
+    }
+
+    fn chance(&mut self, per_mille: u16) -> bool {
+        per_mille > 0 && self.random(1000) < per_mille as u32
+    }
+
+    /// A uniformly distributed number below `bound`
+    fn random(&mut self, bound: u32) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        ((x as u64 * bound as u64) >> 32) as u32
+    }
+}
+
+impl Default for SbusSimulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameSource for SbusSimulator {
+    fn next_frame(&mut self) -> Option<SbusPacket> {
+        Some(self.next_packet())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SbusError;
+
+    #[test]
+    fn test_waveforms() {
+        let sweep = Waveform::Sweep {
+            min: 100,
+            max: 200,
+            period: 10,
+        };
+        let values: Vec<_> = (0..11).map(|frame| sweep.value(frame)).collect();
+        assert_eq!(
+            values,
+            [100, 120, 140, 160, 180, 200, 180, 160, 140, 120, 100]
+        );
+
+        let step = Waveform::Step {
+            low: 1,
+            high: 2,
+            period: 4,
+        };
+        let values: Vec<_> = (0..6).map(|frame| step.value(frame)).collect();
+        assert_eq!(values, [1, 1, 2, 2, 1, 1]);
+
+        assert_eq!(Waveform::Constant(5).value(1234), 5);
+    }
+
+    #[test]
+    fn test_noise_and_flags() {
+        let mut simulator = SbusSimulator::new()
+            .with_channel(Channel::Ch2, Waveform::Constant(0))
+            .with_digital(Channel::Ch18, true)
+            .with_noise(10)
+            .with_failsafe(100, 5);
+
+        let mut failsafe_run = 0;
+        for _ in 0..1000 {
+            let packet = simulator.next_packet();
+            assert!(packet.channels[0].abs_diff(CHANNEL_FUTABA_MID) <= 10);
+            assert!(packet.channels[1] <= 10);
+            assert!(packet.flags.d2);
+            if packet.flags.failsafe {
+                assert!(packet.flags.frame_lost);
+                failsafe_run += 1;
+            } else {
+                assert!(failsafe_run == 0 || failsafe_run % 5 == 0);
+            }
+        }
+        assert!(failsafe_run > 0);
+        assert_eq!(simulator.frame(), 1000);
+
+        let mut a = SbusSimulator::new().with_seed(3).with_noise(100);
+        let mut b = SbusSimulator::new().with_seed(3).with_noise(100);
+        assert_eq!(a.next_packet(), b.next_packet());
+    }
+
+    #[test]
+    fn test_corruption() {
+        let mut simulator = SbusSimulator::new().with_corruption(1000);
+        let mut buffer = [0; SBUS_FRAME_LENGTH];
+        for _ in 0..100 {
+            let frame = simulator.next_frame(&mut buffer);
+            let result = <[u8; SBUS_FRAME_LENGTH]>::try_from(frame)
+                .map_err(|_| SbusError::InvalidLength(frame.len()))
+                .and_then(|frame| SbusPacket::from_array(&frame));
+            // Bit flips in the channel data still decode
+            if let Ok(packet) = result {
+                assert_ne!(packet, SbusPacket::neutral());
+            }
+        }
+
+        let mut clean = SbusSimulator::new();
+        assert_eq!(
+            clean.next_frame(&mut buffer),
+            SbusPacket::neutral().to_array()
+        );
+    }
+}
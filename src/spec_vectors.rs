@@ -0,0 +1,428 @@
+//! SBUS frames built from the protocol specification, with their decoded values
+//!
+//! Enabled by the `spec-vectors` feature. The vectors cover the documented channel ranges of
+//! Futaba, FrSky and clone receivers, SBUS2 footers, lost frames and failsafe, and corrupted
+//! frames. They are assembled byte by byte from the protocol rather than decoded by this crate,
+//! so they can check a whole UART pipeline, including the parsers.
+//!
+//! None of them was captured from a receiver, so they do not reproduce quirks of a particular
+//! receiver's firmware.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sbus_rs::spec_vectors::SPEC_VECTORS;
+//!
+//! for vector in SPEC_VECTORS {
+//!     assert_eq!(vector.decode(), vector.expected, "{}", vector.name);
+//! }
+//! ```
+
+use crate::{Flags, SbusError, SbusPacket, ValidationPolicy, CHANNEL_COUNT, SBUS_FRAME_LENGTH};
+
+/// A raw frame and the result of decoding it
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SpecVector {
+    /// A unique, snake case name
+    pub name: &'static str,
+    pub description: &'static str,
+    /// The validation the expected result holds for
+    pub policy: ValidationPolicy,
+    pub frame: [u8; SBUS_FRAME_LENGTH],
+    pub expected: Result<SbusPacket, SbusError>,
+}
+
+impl SpecVector {
+    /// Decodes the frame with this crate under the vector's policy
+    pub fn decode(&self) -> Result<SbusPacket, SbusError> {
+        SbusPacket::from_array_with(&self.frame, self.policy)
+    }
+
+    /// Whether the frame should decode
+    pub const fn is_valid(&self) -> bool {
+        self.expected.is_ok()
+    }
+}
+
+/// Looks up a vector by name
+pub fn find(name: &str) -> Option<&'static SpecVector> {
+    SPEC_VECTORS.iter().find(|vector| vector.name == name)
+}
+
+/// All vectors
+pub static SPEC_VECTORS: &[SpecVector] = &[
+    SpecVector {
+        name: "neutral_throttle_low",
+        description: "Sticks centred and the throttle, channel 3, at its minimum",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0x2B, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x00, 0x00,
+        ],
+        expected: Ok(SbusPacket::new(
+            [
+                992, 992, 172, 992, 992, 992, 992, 992, 992, 992, 992, 992, 992, 992, 992, 992,
+            ],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "futaba_range",
+        description: "Futaba receiver ranges, centred at 1024 with the endpoints at 368 and 1680",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0x00, 0x04, 0x20, 0x5C, 0x00, 0x08, 0x69, 0xB8, 0x00, 0x10, 0x80, 0x00, 0x04,
+            0x20, 0x00, 0x01, 0x08, 0x40, 0x00, 0x02, 0x10, 0x80, 0x00, 0x00,
+        ],
+        expected: Ok(SbusPacket::new(
+            [
+                1024, 1024, 368, 1024, 1680, 368, 1024, 1024, 1024, 1024, 1024, 1024, 1024, 1024,
+                1024, 1024,
+            ],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "frsky_range",
+        description: "FrSky receiver endpoints at 172 and 1811 on alternate channels",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0xAC, 0x98, 0x38, 0x2B, 0x26, 0xCE, 0x8A, 0x89, 0xB3, 0x62, 0xE2, 0xAC, 0x98,
+            0x38, 0x2B, 0x26, 0xCE, 0x8A, 0x89, 0xB3, 0x62, 0xE2, 0x00, 0x00,
+        ],
+        expected: Ok(SbusPacket::new(
+            [
+                172, 1811, 172, 1811, 172, 1811, 172, 1811, 172, 1811, 172, 1811, 172, 1811, 172,
+                1811,
+            ],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "full_scale",
+        description: "The extremes of the 11 bit range on alternate channels",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0x00, 0xF8, 0x3F, 0x00, 0xFE, 0x0F, 0x80, 0xFF, 0x03, 0xE0, 0xFF, 0x00, 0xF8,
+            0x3F, 0x00, 0xFE, 0x0F, 0x80, 0xFF, 0x03, 0xE0, 0xFF, 0x00, 0x00,
+        ],
+        expected: Ok(SbusPacket::new(
+            [
+                0, 2047, 0, 2047, 0, 2047, 0, 2047, 0, 2047, 0, 2047, 0, 2047, 0, 2047,
+            ],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "digital_channels",
+        description: "Both digital channels set",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x03, 0x00,
+        ],
+        expected: Ok(SbusPacket::new(
+            [992; CHANNEL_COUNT],
+            Flags {
+                d1: true,
+                d2: true,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "frame_lost",
+        description: "A single lost frame, with the flag set but the channels held",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x04, 0x00,
+        ],
+        expected: Ok(SbusPacket::new(
+            [992; CHANNEL_COUNT],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: true,
+            },
+        )),
+    },
+    SpecVector {
+        name: "failsafe",
+        description:
+            "Failsafe, with both the failsafe and the frame lost flags set as receivers do",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x0C, 0x00,
+        ],
+        expected: Ok(SbusPacket::new(
+            [992; CHANNEL_COUNT],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: true,
+                frame_lost: true,
+            },
+        )),
+    },
+    SpecVector {
+        name: "sbus2_slot_group_0",
+        description: "An SBUS2 frame followed by telemetry slot group 0, footer `0x04`",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x00, 0x04,
+        ],
+        expected: Ok(SbusPacket::new(
+            [992; CHANNEL_COUNT],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "sbus2_slot_group_1",
+        description: "An SBUS2 frame followed by telemetry slot group 1, footer `0x14`",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x00, 0x14,
+        ],
+        expected: Ok(SbusPacket::new(
+            [992; CHANNEL_COUNT],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "sbus2_slot_group_2",
+        description: "An SBUS2 frame followed by telemetry slot group 2, footer `0x24`",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x00, 0x24,
+        ],
+        expected: Ok(SbusPacket::new(
+            [992; CHANNEL_COUNT],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "sbus2_slot_group_3",
+        description: "An SBUS2 frame followed by telemetry slot group 3, footer `0x34`",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x00, 0x34,
+        ],
+        expected: Ok(SbusPacket::new(
+            [992; CHANNEL_COUNT],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "clone_reserved_flags",
+        description: "Reserved flag bits set, as some clone receivers do, which are ignored",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xF0, 0x00,
+        ],
+        expected: Ok(SbusPacket::new(
+            [992; CHANNEL_COUNT],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "clone_reserved_flags_strict",
+        description: "Reserved flag bits set, ignored by strict validation too unless the flag \
+                      check is enabled",
+        policy: ValidationPolicy::Strict,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xF0, 0x00,
+        ],
+        expected: Ok(SbusPacket::new(
+            [992; CHANNEL_COUNT],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "clone_nonstandard_footer",
+        description: "A clone receiver footer of `0xFF`, only accepted by lenient validation",
+        policy: ValidationPolicy::Lenient,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x00, 0xFF,
+        ],
+        expected: Ok(SbusPacket::new(
+            [992; CHANNEL_COUNT],
+            Flags {
+                d1: false,
+                d2: false,
+                failsafe: false,
+                frame_lost: false,
+            },
+        )),
+    },
+    SpecVector {
+        name: "corrupt_footer",
+        description: "A nonstandard footer of `0xFF`",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0F, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x00, 0xFF,
+        ],
+        expected: Err(SbusError::InvalidFooter(0xFF)),
+    },
+    SpecVector {
+        name: "corrupt_header",
+        description: "A damaged header of `0x0E`",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x0E, 0xE0, 0x03, 0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03,
+            0x1F, 0xF8, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x00, 0x00,
+        ],
+        expected: Err(SbusError::InvalidHeader(0x0E)),
+    },
+    SpecVector {
+        name: "misaligned",
+        description: "The neutral throttle low frame read from its fourth byte on",
+        policy: ValidationPolicy::Standard,
+        frame: [
+            0x1F, 0x2B, 0xC0, 0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0xE0, 0x03, 0x1F, 0xF8, 0xC0,
+            0x07, 0x3E, 0xF0, 0x81, 0x0F, 0x7C, 0x00, 0x00, 0x0F, 0xE0, 0x03,
+        ],
+        expected: Err(SbusError::InvalidHeader(0x1F)),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrameKind;
+
+    #[test]
+    fn test_spec_vectors_decode() {
+        for vector in SPEC_VECTORS {
+            assert_eq!(vector.decode(), vector.expected, "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn test_spec_vectors_encode() {
+        for vector in SPEC_VECTORS
+            .iter()
+            .filter(|vector| vector.policy != ValidationPolicy::Lenient)
+        {
+            if let Ok(packet) = &vector.expected {
+                if vector.frame[SBUS_FRAME_LENGTH - 2] & 0xF0 == 0 {
+                    let kind = FrameKind::from_footer(vector.frame[SBUS_FRAME_LENGTH - 1]).unwrap();
+                    assert_eq!(
+                        packet.to_array_with_kind(kind),
+                        vector.frame,
+                        "{}",
+                        vector.name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        for (i, vector) in SPEC_VECTORS.iter().enumerate() {
+            assert_eq!(find(vector.name), Some(vector));
+            assert!(SPEC_VECTORS[..i]
+                .iter()
+                .all(|other| other.name != vector.name));
+        }
+        assert!(
+            find("failsafe")
+                .unwrap()
+                .expected
+                .as_ref()
+                .unwrap()
+                .flags
+                .failsafe
+        );
+        assert!(!find("corrupt_header").unwrap().is_valid());
+        assert!(find("missing").is_none());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_spec_vectors_parser() {
+        use crate::SbusParser;
+        use embedded_io_adapters::std::FromStd;
+
+        let stream: Vec<u8> = SPEC_VECTORS
+            .iter()
+            .filter(|vector| vector.policy == ValidationPolicy::Standard)
+            .flat_map(|vector| vector.frame)
+            .collect();
+        let mut parser = SbusParser::new(FromStd::new(std::io::Cursor::new(stream)));
+        for vector in SPEC_VECTORS
+            .iter()
+            .filter(|vector| vector.policy == ValidationPolicy::Standard)
+        {
+            assert_eq!(
+                parser.read_frame().ok(),
+                vector.expected.as_ref().ok().copied(),
+                "{}",
+                vector.name
+            );
+        }
+    }
+}
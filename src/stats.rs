@@ -0,0 +1,75 @@
+use crate::{SbusError, SbusPacket};
+
+/// Counters describing the health of an SBUS link, as seen by a parser
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SbusStats {
+    /// Frames that were decoded successfully
+    pub frames_ok: u32,
+    /// Frames rejected because of an invalid header
+    pub invalid_header: u32,
+    /// Frames rejected because of an invalid footer
+    pub invalid_footer: u32,
+    /// Frames rejected because reserved bits of the flag byte were set
+    pub invalid_flag_byte: u32,
+    /// Bytes skipped while resynchronising on the stream
+    pub bytes_discarded: u32,
+    /// Bytes the UART reported a parity, framing or noise error for
+    pub byte_errors: u32,
+    /// Number of consecutive decoded frames with the `frame_lost` flag set
+    pub consecutive_frame_lost: u32,
+}
+
+impl SbusStats {
+    pub const fn new() -> Self {
+        Self {
+            frames_ok: 0,
+            invalid_header: 0,
+            invalid_footer: 0,
+            invalid_flag_byte: 0,
+            bytes_discarded: 0,
+            byte_errors: 0,
+            consecutive_frame_lost: 0,
+        }
+    }
+
+    /// Resets all counters to zero
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Updates the counters with the outcome of a frame read
+    pub(crate) fn record<E>(&mut self, result: &Result<SbusPacket, SbusError<E>>) {
+        match result {
+            Ok(packet) => {
+                self.frames_ok = self.frames_ok.saturating_add(1);
+                self.consecutive_frame_lost = if packet.flags.frame_lost {
+                    self.consecutive_frame_lost.saturating_add(1)
+                } else {
+                    0
+                };
+            }
+            Err(SbusError::InvalidHeader(_)) => {
+                self.invalid_header = self.invalid_header.saturating_add(1)
+            }
+            Err(SbusError::InvalidFooter(_)) => {
+                self.invalid_footer = self.invalid_footer.saturating_add(1)
+            }
+            Err(SbusError::InvalidFlagByte(_)) => {
+                self.invalid_flag_byte = self.invalid_flag_byte.saturating_add(1)
+            }
+            Err(_) => {}
+        }
+    }
+
+    pub(crate) fn record_byte_error(&mut self) {
+        self.byte_errors = self.byte_errors.saturating_add(1);
+    }
+
+    pub(crate) fn record_discarded(&mut self, count: usize) {
+        self.bytes_discarded = self
+            .bytes_discarded
+            .saturating_add(u32::try_from(count).unwrap_or(u32::MAX));
+    }
+}
@@ -0,0 +1,163 @@
+//! [`proptest`] strategies generating packets and frames
+//!
+//! Enabled by the `proptest` feature, meant for `[dev-dependencies]`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use proptest::prelude::*;
+//! use proptest::test_runner::TestRunner;
+//! use sbus_rs::{strategies, SbusPacket};
+//!
+//! TestRunner::default()
+//!     .run(&strategies::packet_frame(), |(packet, frame)| {
+//!         prop_assert_eq!(SbusPacket::from_array(&frame), Ok(packet));
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+
+use proptest::prelude::*;
+use std::vec::Vec;
+
+use crate::{
+    Corruption, Flags, FrameKind, SbusPacket, ValidationPolicy, CHANNEL_FUTABA_MAX,
+    CHANNEL_FUTABA_MID, CHANNEL_FUTABA_MIN, CHANNEL_MAX, SBUS_FRAME_LENGTH, SBUS_HEADER,
+};
+
+/// Any value an SBUS channel can hold
+pub fn channel_value() -> impl Strategy<Value = u16> {
+    0..=CHANNEL_MAX
+}
+
+/// The edges of the channel range and the Futaba endpoints, with their neighbours
+pub fn boundary_channel_value() -> impl Strategy<Value = u16> {
+    prop_oneof![
+        Just(0),
+        Just(1),
+        Just(CHANNEL_FUTABA_MIN - 1),
+        Just(CHANNEL_FUTABA_MIN),
+        Just(CHANNEL_FUTABA_MID),
+        Just(CHANNEL_FUTABA_MAX),
+        Just(CHANNEL_FUTABA_MAX + 1),
+        Just(CHANNEL_MAX - 1),
+        Just(CHANNEL_MAX),
+    ]
+}
+
+/// Any combination of flags
+pub fn flags() -> impl Strategy<Value = Flags> {
+    any::<[bool; 4]>().prop_map(|[d1, d2, failsafe, frame_lost]| Flags {
+        d1,
+        d2,
+        failsafe,
+        frame_lost,
+    })
+}
+
+/// SBUS frames and SBUS2 frames of the standard slot groups 0 to 3
+pub fn frame_kind() -> impl Strategy<Value = FrameKind> {
+    prop_oneof![Just(FrameKind::Sbus1), (0u8..4).prop_map(FrameKind::Sbus2)]
+}
+
+/// Any valid packet
+pub fn packet() -> impl Strategy<Value = SbusPacket> {
+    (prop::array::uniform16(channel_value()), flags())
+        .prop_map(|(channels, flags)| SbusPacket::new(channels, flags))
+}
+
+/// Valid packets with every channel at a [`boundary_channel_value`]
+pub fn boundary_packet() -> impl Strategy<Value = SbusPacket> {
+    (prop::array::uniform16(boundary_channel_value()), flags())
+        .prop_map(|(channels, flags)| SbusPacket::new(channels, flags))
+}
+
+/// A valid packet and its encoded frame, of any [`frame_kind`]
+pub fn packet_frame() -> impl Strategy<Value = (SbusPacket, [u8; SBUS_FRAME_LENGTH])> {
+    (packet(), frame_kind()).prop_map(|(packet, kind)| (packet, packet.to_array_with_kind(kind)))
+}
+
+/// Any encoded valid frame
+pub fn valid_frame() -> impl Strategy<Value = [u8; SBUS_FRAME_LENGTH]> {
+    packet_frame().prop_map(|(_, frame)| frame)
+}
+
+/// Any kind of corruption
+pub fn corruption() -> impl Strategy<Value = Corruption> {
+    prop_oneof![
+        Just(Corruption::BadHeader),
+        Just(Corruption::BadFooter),
+        Just(Corruption::BitFlip),
+        Just(Corruption::Truncated),
+    ]
+}
+
+/// A valid frame damaged in one way, with the kind of damage
+///
+/// Bad headers and footers are bytes that [`ValidationPolicy::Standard`] rejects, bit flips hit
+/// the channel data, and truncated frames are 1 to 24 bytes long.
+pub fn corrupted_frame() -> impl Strategy<Value = (Corruption, Vec<u8>)> {
+    (
+        valid_frame(),
+        corruption(),
+        any::<u8>(),
+        0..(22 * 8usize),
+        1..SBUS_FRAME_LENGTH,
+    )
+        .prop_map(|(frame, corruption, byte, bit, len)| {
+            let mut bytes = frame.to_vec();
+            match corruption {
+                Corruption::BadHeader => bytes[0] = bad_byte(byte, |b| b != SBUS_HEADER),
+                Corruption::BadFooter => {
+                    bytes[SBUS_FRAME_LENGTH - 1] =
+                        bad_byte(byte, |b| !ValidationPolicy::Standard.accepts_footer(b))
+                }
+                Corruption::BitFlip => bytes[1 + bit / 8] ^= 1 << (bit % 8),
+                Corruption::Truncated => bytes.truncate(len),
+            }
+            (corruption, bytes)
+        })
+}
+
+/// The first byte from `start` on, wrapping around, that is `bad`
+fn bad_byte(start: u8, bad: impl Fn(u8) -> bool) -> u8 {
+    (0..=u8::MAX)
+        .map(|offset| start.wrapping_add(offset))
+        .find(|&b| bad(b))
+        .unwrap_or(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SbusError;
+
+    proptest! {
+        #[test]
+        fn test_packet_frame((packet, frame) in packet_frame()) {
+            prop_assert_eq!(SbusPacket::from_array(&frame), Ok(packet));
+        }
+
+        #[test]
+        fn test_boundary_packet(packet in boundary_packet()) {
+            prop_assert_eq!(SbusPacket::from_array(&packet.to_array()), Ok(packet));
+        }
+
+        #[test]
+        fn test_corrupted_frame((corruption, bytes) in corrupted_frame()) {
+            let result = SbusPacket::try_from(bytes.as_slice());
+            match corruption {
+                Corruption::BadHeader => {
+                    prop_assert!(matches!(result, Err(SbusError::InvalidHeader(_))))
+                }
+                Corruption::BadFooter => {
+                    prop_assert!(matches!(result, Err(SbusError::InvalidFooter(_))))
+                }
+                Corruption::BitFlip => prop_assert!(result.is_ok()),
+                Corruption::Truncated => {
+                    prop_assert_eq!(result, Err(SbusError::InvalidLength(bytes.len())))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use core::marker::PhantomData;
+
+use crate::{CHANNEL_FUTABA_MAX, CHANNEL_FUTABA_MIN};
+
+/// The positions of a switch, from lowest to highest channel value
+pub trait SwitchPosition: Copy + 'static {
+    /// All positions in order
+    const POSITIONS: &'static [Self];
+}
+
+/// A two-position switch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TwoPosition {
+    Low,
+    High,
+}
+
+impl SwitchPosition for TwoPosition {
+    const POSITIONS: &'static [Self] = &[TwoPosition::Low, TwoPosition::High];
+}
+
+/// A three-position switch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThreePosition {
+    Low,
+    Middle,
+    High,
+}
+
+impl SwitchPosition for ThreePosition {
+    const POSITIONS: &'static [Self] = &[
+        ThreePosition::Low,
+        ThreePosition::Middle,
+        ThreePosition::High,
+    ];
+}
+
+/// A six-position switch, as used for flight mode selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SixPosition {
+    P1,
+    P2,
+    P3,
+    P4,
+    P5,
+    P6,
+}
+
+impl SwitchPosition for SixPosition {
+    const POSITIONS: &'static [Self] = &[
+        SixPosition::P1,
+        SixPosition::P2,
+        SixPosition::P3,
+        SixPosition::P4,
+        SixPosition::P5,
+        SixPosition::P6,
+    ];
+}
+
+const MAX_THRESHOLDS: usize = 5;
+
+/// Decodes the position of a switch from a channel value without chattering at the thresholds
+///
+/// Leaving a position requires crossing the threshold by more than the hysteresis, and the new
+/// position must be seen in the debounce number of consecutive frames before it is reported.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{SwitchDecoder, ThreePosition};
+///
+/// let mut mode = SwitchDecoder::<ThreePosition>::new().with_debounce(2);
+/// assert_eq!(mode.update(172), ThreePosition::Low);
+/// assert_eq!(mode.update(992), ThreePosition::Low);
+/// assert_eq!(mode.update(992), ThreePosition::Middle);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct SwitchDecoder<P> {
+    thresholds: [u16; MAX_THRESHOLDS],
+    hysteresis: u16,
+    debounce: u8,
+    current: Option<usize>,
+    pending: usize,
+    pending_frames: u8,
+    _position: PhantomData<P>,
+}
+
+impl<P> SwitchDecoder<P>
+where
+    P: SwitchPosition,
+{
+    /// Creates a decoder with thresholds evenly spaced over the Futaba range, a hysteresis of 20
+    /// and no debouncing
+    pub fn new() -> Self {
+        let positions = P::POSITIONS.len();
+        assert!(
+            (2..=MAX_THRESHOLDS + 1).contains(&positions),
+            "switches need 2 to 6 positions"
+        );
+
+        let span = (CHANNEL_FUTABA_MAX - CHANNEL_FUTABA_MIN) as usize;
+        let mut thresholds = [u16::MAX; MAX_THRESHOLDS];
+        for (i, threshold) in thresholds.iter_mut().take(positions - 1).enumerate() {
+            // Halfway between the evenly spaced position centres
+            *threshold = CHANNEL_FUTABA_MIN + (span * (2 * i + 1) / (2 * (positions - 1))) as u16;
+        }
+
+        Self {
+            thresholds,
+            hysteresis: 20,
+            debounce: 1,
+            current: None,
+            pending: 0,
+            pending_frames: 0,
+            _position: PhantomData,
+        }
+    }
+
+    /// Sets the channel values separating the positions, in ascending order
+    ///
+    /// # Panics
+    ///
+    /// Panics unless there is exactly one threshold less than the number of positions.
+    pub fn with_thresholds(mut self, thresholds: &[u16]) -> Self {
+        assert_eq!(thresholds.len(), P::POSITIONS.len() - 1);
+        self.thresholds[..thresholds.len()].copy_from_slice(thresholds);
+        self
+    }
+
+    /// Sets how far a threshold must be crossed to leave the current position
+    pub fn with_hysteresis(mut self, hysteresis: u16) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// Sets in how many consecutive frames a new position must be seen before it is reported,
+    /// 0 and 1 switch immediately
+    pub fn with_debounce(mut self, frames: u8) -> Self {
+        self.debounce = frames.max(1);
+        self
+    }
+
+    /// Decodes the next channel value, returning the debounced position
+    ///
+    /// The first value is decoded without debouncing.
+    pub fn update(&mut self, value: u16) -> P {
+        let raw = self.decode(value);
+        let current = match self.current {
+            Some(current) if raw == current => {
+                self.pending_frames = 0;
+                current
+            }
+            Some(current) => {
+                if raw == self.pending && self.pending_frames > 0 {
+                    self.pending_frames = self.pending_frames.saturating_add(1);
+                } else {
+                    self.pending = raw;
+                    self.pending_frames = 1;
+                }
+                if self.pending_frames >= self.debounce {
+                    self.pending_frames = 0;
+                    raw
+                } else {
+                    current
+                }
+            }
+            None => raw,
+        };
+        self.current = Some(current);
+        P::POSITIONS[current]
+    }
+
+    /// The current position, or `None` before the first update
+    pub fn position(&self) -> Option<P> {
+        self.current.map(|current| P::POSITIONS[current])
+    }
+
+    /// Forgets the current position
+    pub fn reset(&mut self) {
+        self.current = None;
+        self.pending_frames = 0;
+    }
+
+    fn decode(&self, value: u16) -> usize {
+        let thresholds = &self.thresholds[..P::POSITIONS.len() - 1];
+        thresholds
+            .iter()
+            .enumerate()
+            .filter(|&(boundary, &threshold)| match self.current {
+                // Moving up past the boundary needs the value to clear it by the hysteresis
+                Some(current) if current <= boundary => {
+                    value > threshold.saturating_add(self.hysteresis)
+                }
+                Some(_) => value >= threshold.saturating_sub(self.hysteresis),
+                None => value >= threshold,
+            })
+            .count()
+    }
+}
+
+impl<P> Default for SwitchDecoder<P>
+where
+    P: SwitchPosition,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CHANNEL_FUTABA_MID;
+
+    #[test]
+    fn test_positions() {
+        let mut two = SwitchDecoder::<TwoPosition>::new();
+        assert_eq!(two.position(), None);
+        assert_eq!(two.update(CHANNEL_FUTABA_MIN), TwoPosition::Low);
+        assert_eq!(two.update(CHANNEL_FUTABA_MAX), TwoPosition::High);
+
+        let mut three = SwitchDecoder::<ThreePosition>::new();
+        assert_eq!(three.update(CHANNEL_FUTABA_MID), ThreePosition::Middle);
+        assert_eq!(three.update(CHANNEL_FUTABA_MAX), ThreePosition::High);
+        assert_eq!(three.update(CHANNEL_FUTABA_MIN), ThreePosition::Low);
+
+        let mut six = SwitchDecoder::<SixPosition>::new();
+        let step = (CHANNEL_FUTABA_MAX - CHANNEL_FUTABA_MIN) / 5;
+        for (i, &position) in SixPosition::POSITIONS.iter().enumerate() {
+            assert_eq!(six.update(CHANNEL_FUTABA_MIN + step * i as u16), position);
+        }
+    }
+
+    #[test]
+    fn test_hysteresis() {
+        let mut switch = SwitchDecoder::<TwoPosition>::new()
+            .with_thresholds(&[1000])
+            .with_hysteresis(10);
+
+        assert_eq!(switch.update(995), TwoPosition::Low);
+        assert_eq!(switch.update(1005), TwoPosition::Low);
+        assert_eq!(switch.update(1011), TwoPosition::High);
+        assert_eq!(switch.update(995), TwoPosition::High);
+        assert_eq!(switch.update(989), TwoPosition::Low);
+    }
+
+    #[test]
+    fn test_debounce() {
+        let mut switch = SwitchDecoder::<ThreePosition>::new().with_debounce(3);
+
+        assert_eq!(switch.update(CHANNEL_FUTABA_MIN), ThreePosition::Low);
+        assert_eq!(switch.update(CHANNEL_FUTABA_MAX), ThreePosition::Low);
+        assert_eq!(switch.update(CHANNEL_FUTABA_MAX), ThreePosition::Low);
+        // A glitch to another position restarts the count
+        assert_eq!(switch.update(CHANNEL_FUTABA_MID), ThreePosition::Low);
+        assert_eq!(switch.update(CHANNEL_FUTABA_MAX), ThreePosition::Low);
+        assert_eq!(switch.update(CHANNEL_FUTABA_MAX), ThreePosition::Low);
+        assert_eq!(switch.update(CHANNEL_FUTABA_MAX), ThreePosition::High);
+
+        switch.reset();
+        assert_eq!(switch.update(CHANNEL_FUTABA_MIN), ThreePosition::Low);
+    }
+}
@@ -0,0 +1,219 @@
+//! Helpers for writing deterministic tests against the parsers
+//!
+//! Enabled by the `test-util` feature, meant for `[dev-dependencies]`.
+
+use std::collections::VecDeque;
+use std::vec::Vec;
+
+use crate::{fixtures, Corruption, SbusPacket, CHANNEL_COUNT, SBUS_FRAME_LENGTH};
+
+/// A valid frame with all channels and flags zero
+pub const fn create_valid_frame() -> [u8; SBUS_FRAME_LENGTH] {
+    fixtures::frame(0)
+}
+
+/// A valid frame with the given channels and raw flags byte
+pub const fn create_frame(channels: &[u16; CHANNEL_COUNT], flags: u8) -> [u8; SBUS_FRAME_LENGTH] {
+    fixtures::frame_with_channels(channels, flags)
+}
+
+/// `count` copies of `frame` back to back
+pub fn create_stream(frame: &[u8; SBUS_FRAME_LENGTH], count: usize) -> Vec<u8> {
+    frame.repeat(count)
+}
+
+/// Damages a frame, truncated frames lose their last byte
+pub fn corrupt_frame(frame: &[u8; SBUS_FRAME_LENGTH], corruption: Corruption) -> Vec<u8> {
+    let mut bytes = frame.to_vec();
+    match corruption {
+        Corruption::BadHeader => bytes[0] = !bytes[0],
+        Corruption::BadFooter => bytes[SBUS_FRAME_LENGTH - 1] = 0x55,
+        Corruption::BitFlip => bytes[1] ^= 0x01,
+        Corruption::Truncated => {
+            bytes.pop();
+        }
+    }
+    bytes
+}
+
+/// A reader playing back scripted bytes and errors
+///
+/// Each read returns at most one chunk, or up to `chunk_size` bytes of it, so short reads at
+/// awkward offsets can be tested. Once the script is exhausted, reads return 0 bytes, which
+/// the parsers treat as the end of the stream.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::test_util::MockSbusSource;
+/// use sbus_rs::{Corruption, SbusError, SbusPacket, SbusParser};
+///
+/// let packet = SbusPacket::neutral();
+/// let source = MockSbusSource::new()
+///     .with_packet(&packet)
+///     .with_corrupted(&packet, Corruption::BadFooter)
+///     .with_chunk_size(3);
+///
+/// let mut parser = SbusParser::new(source);
+/// assert_eq!(parser.read_frame(), Ok(packet));
+/// assert!(matches!(parser.read_frame(), Err(SbusError::InvalidFooter(_))));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockSbusSource {
+    script: VecDeque<Step>,
+    chunk_size: Option<usize>,
+    read: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Step {
+    Bytes(Vec<u8>),
+    Error(embedded_io::ErrorKind),
+}
+
+impl MockSbusSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues raw bytes, returned as one chunk
+    pub fn with_bytes(mut self, bytes: &[u8]) -> Self {
+        self.push_bytes(bytes);
+        self
+    }
+
+    /// Queues an encoded frame
+    pub fn with_frame(self, frame: &[u8; SBUS_FRAME_LENGTH]) -> Self {
+        self.with_bytes(frame)
+    }
+
+    /// Queues the frame of a packet
+    pub fn with_packet(self, packet: &SbusPacket) -> Self {
+        self.with_frame(&packet.to_array())
+    }
+
+    /// Queues the frame of a packet, damaged as `corruption`
+    pub fn with_corrupted(self, packet: &SbusPacket, corruption: Corruption) -> Self {
+        self.with_bytes(&corrupt_frame(&packet.to_array(), corruption))
+    }
+
+    /// Queues a read error
+    pub fn with_error(mut self, kind: embedded_io::ErrorKind) -> Self {
+        self.push_error(kind);
+        self
+    }
+
+    /// Limits the number of bytes returned by a single read
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = Some(chunk_size.max(1));
+        self
+    }
+
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        if !bytes.is_empty() {
+            self.script.push_back(Step::Bytes(bytes.to_vec()));
+        }
+    }
+
+    pub fn push_error(&mut self, kind: embedded_io::ErrorKind) {
+        self.script.push_back(Step::Error(kind));
+    }
+
+    /// Whether all scripted bytes and errors have been read
+    pub fn is_exhausted(&self) -> bool {
+        self.script.is_empty()
+    }
+
+    /// Number of bytes read so far
+    pub fn bytes_read(&self) -> usize {
+        self.read
+    }
+
+    fn next_chunk(&mut self, buf: &mut [u8]) -> Result<usize, embedded_io::ErrorKind> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let Some(step) = self.script.front_mut() else {
+            return Ok(0);
+        };
+        let bytes = match step {
+            Step::Error(kind) => {
+                let kind = *kind;
+                self.script.pop_front();
+                return Err(kind);
+            }
+            Step::Bytes(bytes) => bytes,
+        };
+
+        let len = bytes
+            .len()
+            .min(buf.len())
+            .min(self.chunk_size.unwrap_or(usize::MAX));
+        buf[..len].copy_from_slice(&bytes[..len]);
+        bytes.drain(..len);
+        if bytes.is_empty() {
+            self.script.pop_front();
+        }
+        self.read += len;
+        Ok(len)
+    }
+}
+
+impl embedded_io::ErrorType for MockSbusSource {
+    type Error = embedded_io::ErrorKind;
+}
+
+impl embedded_io::Read for MockSbusSource {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.next_chunk(buf)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_io_async::Read for MockSbusSource {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.next_chunk(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SbusError, CHANNEL_MAX};
+
+    #[test]
+    fn test_frames() {
+        let frame = create_frame(&[CHANNEL_MAX; CHANNEL_COUNT], 0x0C);
+        let packet = SbusPacket::from_array(&frame).unwrap();
+        assert_eq!(packet.channels, [CHANNEL_MAX; CHANNEL_COUNT]);
+        assert!(packet.flags.failsafe && packet.flags.frame_lost);
+
+        assert_eq!(create_stream(&frame, 3).len(), 3 * SBUS_FRAME_LENGTH);
+        assert!(SbusPacket::from_array(&create_valid_frame()).is_ok());
+        assert_eq!(corrupt_frame(&frame, Corruption::Truncated).len(), 24);
+    }
+
+    #[test]
+    fn test_mock_source() {
+        use crate::SbusParser;
+        use embedded_io::ErrorKind;
+
+        let packet = SbusPacket::neutral();
+        let source = MockSbusSource::new()
+            .with_packet(&packet)
+            .with_error(ErrorKind::TimedOut)
+            .with_corrupted(&packet, Corruption::BadHeader)
+            .with_frame(&packet.to_array())
+            .with_chunk_size(7);
+
+        let mut parser = SbusParser::new(source);
+        assert_eq!(parser.read_frame(), Ok(packet));
+        assert!(parser.read_frame().is_err());
+        assert!(matches!(
+            parser.read_frame(),
+            Err(SbusError::InvalidHeader(_))
+        ));
+        assert_eq!(parser.read_frame(), Ok(packet));
+        assert_eq!(parser.read_frame(), Err(SbusError::UnexpectedEof));
+    }
+}
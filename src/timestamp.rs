@@ -0,0 +1,218 @@
+use core::time::Duration;
+
+#[cfg(any(feature = "blocking", feature = "async"))]
+use crate::SbusError;
+use crate::SbusPacket;
+
+/// A source of timestamps measured from any fixed epoch
+///
+/// Implemented for closures returning a [`Duration`], so any timer can be plugged in, e.g.
+/// `|| Duration::from_micros(timer.now().ticks())` for a `fugit` based HAL timer.
+pub trait Clock {
+    fn now(&mut self) -> Duration;
+}
+
+impl<F> Clock for F
+where
+    F: FnMut() -> Duration,
+{
+    fn now(&mut self) -> Duration {
+        self()
+    }
+}
+
+/// A [`Clock`] backed by [`std::time::Instant`], with its epoch at construction
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StdClock {
+    epoch: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl StdClock {
+    pub fn new() -> Self {
+        Self {
+            epoch: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    fn now(&mut self) -> Duration {
+        self.epoch.elapsed()
+    }
+}
+
+/// A packet along with the time it was received
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+pub struct TimestampedPacket {
+    pub packet: SbusPacket,
+    /// When the packet was received, measured from the epoch of the clock
+    pub received_at: Duration,
+    /// Time since the previous packet, `None` for the first packet
+    pub gap: Option<Duration>,
+}
+
+impl TimestampedPacket {
+    /// Whether the packet arrived more than `interval` after the previous one
+    pub fn is_late(&self, interval: Duration) -> bool {
+        self.gap.is_some_and(|gap| gap > interval)
+    }
+}
+
+/// Wraps a parser and timestamps every packet it returns with a [`Clock`]
+///
+/// The timestamps can be fed to [`LinkQuality::update_at`](crate::LinkQuality::update_at) or
+/// [`SbusReceiver::update`](crate::SbusReceiver::update) to base link decisions on time rather
+/// than on frame counts.
+///
+/// # Example
+///
+/// ```rust
+/// use core::time::Duration;
+/// use embedded_io_adapters::std::FromStd;
+/// use sbus_rs::{SbusParser, TimestampedParser, SBUS_FOOTER, SBUS_FRAME_LENGTH, SBUS_HEADER};
+/// use std::io::Cursor;
+///
+/// let mut frame = [0u8; SBUS_FRAME_LENGTH];
+/// frame[0] = SBUS_HEADER;
+/// frame[SBUS_FRAME_LENGTH - 1] = SBUS_FOOTER;
+///
+/// let mut now = Duration::ZERO;
+/// let clock = move || {
+///     now += Duration::from_millis(14);
+///     now
+/// };
+/// let parser = SbusParser::new(FromStd::new(Cursor::new(frame.repeat(2))));
+/// let mut parser = TimestampedParser::new(parser, clock);
+///
+/// assert_eq!(parser.read_frame().unwrap().gap, None);
+/// assert_eq!(parser.read_frame().unwrap().gap, Some(Duration::from_millis(14)));
+/// ```
+#[derive(Debug)]
+pub struct TimestampedParser<P, C> {
+    parser: P,
+    clock: C,
+    last_received_at: Option<Duration>,
+}
+
+impl<P, C> TimestampedParser<P, C>
+where
+    C: Clock,
+{
+    pub fn new(parser: P, clock: C) -> Self {
+        Self {
+            parser,
+            clock,
+            last_received_at: None,
+        }
+    }
+
+    /// Timestamps a packet received now, e.g. from a parser not wrapped by this type
+    pub fn stamp(&mut self, packet: SbusPacket) -> TimestampedPacket {
+        let received_at = self.clock.now();
+        let gap = self
+            .last_received_at
+            .map(|last| received_at.saturating_sub(last));
+        self.last_received_at = Some(received_at);
+        TimestampedPacket {
+            packet,
+            received_at,
+            gap,
+        }
+    }
+
+    /// When the last packet was received
+    pub fn last_received_at(&self) -> Option<Duration> {
+        self.last_received_at
+    }
+
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.parser
+    }
+
+    pub fn into_inner(self) -> P {
+        self.parser
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<R, C> TimestampedParser<crate::SbusParser<R>, C>
+where
+    R: embedded_io::Read,
+    C: Clock,
+{
+    /// Reads the next complete SBUS frame and timestamps it
+    pub fn read_frame(&mut self) -> Result<TimestampedPacket, SbusError<R::Error>> {
+        let packet = self.parser.read_frame()?;
+        Ok(self.stamp(packet))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R, C> TimestampedParser<crate::SbusParserAsync<R>, C>
+where
+    R: embedded_io_async::Read,
+    C: Clock,
+{
+    /// Reads the next valid SBUS frame and timestamps it
+    pub async fn read_next_valid_frame(
+        &mut self,
+    ) -> Result<TimestampedPacket, SbusError<R::Error>> {
+        let packet = self.parser.read_next_valid_frame().await?;
+        Ok(self.stamp(packet))
+    }
+}
+
+impl<C> TimestampedParser<crate::SBusPacketParser, C>
+where
+    C: Clock,
+{
+    /// Returns the next valid packet from the buffered bytes, timestamped with the current time
+    pub fn try_parse(&mut self) -> Option<TimestampedPacket> {
+        let packet = self.parser.try_parse()?;
+        Some(self.stamp(packet))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SBusPacketParser, SBUS_FOOTER, SBUS_FRAME_LENGTH, SBUS_HEADER};
+
+    #[test]
+    fn test_gaps_and_late_frames() {
+        let mut frame = [0u8; SBUS_FRAME_LENGTH];
+        frame[0] = SBUS_HEADER;
+        frame[SBUS_FRAME_LENGTH - 1] = SBUS_FOOTER;
+
+        let mut times = [10, 24, 60].map(Duration::from_millis).into_iter();
+        let clock = move || times.next().unwrap();
+        let mut parser = TimestampedParser::new(SBusPacketParser::new(), clock);
+
+        let mut packets = Vec::new();
+        for _ in 0..3 {
+            parser.inner_mut().push_bytes(&frame);
+            packets.push(parser.try_parse().unwrap());
+        }
+
+        assert_eq!(packets[0].received_at, Duration::from_millis(10));
+        assert_eq!(packets[0].gap, None);
+        assert_eq!(packets[1].gap, Some(Duration::from_millis(14)));
+        assert_eq!(packets[2].gap, Some(Duration::from_millis(36)));
+
+        let interval = Duration::from_millis(14);
+        assert!(!packets[1].is_late(interval));
+        assert!(packets[2].is_late(interval));
+        assert_eq!(parser.last_received_at(), Some(Duration::from_millis(60)));
+    }
+}
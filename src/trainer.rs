@@ -0,0 +1,208 @@
+use crate::{Channel, ChannelMap, SbusPacket, CHANNEL_COUNT, CHANNEL_FUTABA_MID};
+
+/// Who controls a channel while the trainer link is engaged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Authority {
+    /// The instructor's radio
+    Master,
+    /// The student's radio
+    Student,
+    /// A mix, with the given percentage, clamped to 100, coming from the student
+    Blend(u8),
+}
+
+impl Authority {
+    const fn blend(self, master: u16, student: u16) -> u16 {
+        let percent = match self {
+            Authority::Master => 0,
+            Authority::Student => 100,
+            Authority::Blend(percent) if percent > 100 => 100,
+            Authority::Blend(percent) => percent as i32,
+        };
+        let delta = (student as i32 - master as i32) * percent / 100;
+        (master as i32 + delta) as u16
+    }
+}
+
+/// Combines the packets of an instructor's and a student's radio, like the trainer port of a
+/// transmitter
+///
+/// While engaged, each proportional channel is taken from the master, the student or a blend of
+/// both according to its [`Authority`]. Otherwise, or when the student packet is missing or has
+/// its `failsafe` or `frame_lost` flag set, the master packet is passed through. Flags and the
+/// digital channels always come from the master.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{Channel, ChannelMap, SbusPacket, TrainerBlender};
+///
+/// // The student flies the sticks while the instructor holds channel 5 high
+/// let trainer = TrainerBlender::for_sticks(&ChannelMap::AETR).with_switch(Channel::Ch5);
+///
+/// let mut master = SbusPacket::neutral();
+/// let mut student = SbusPacket::neutral();
+/// student.channels[0] = 1500;
+/// student.channels[5] = 1500;
+///
+/// assert_eq!(trainer.blend(&master, Some(&student)), master);
+///
+/// master.channels[4] = 1811;
+/// let output = trainer.blend(&master, Some(&student));
+/// assert_eq!(output.channels[0], 1500);
+/// assert_eq!(output.channels[5], 992);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrainerBlender {
+    authority: [Authority; CHANNEL_COUNT],
+    switch: Option<Channel>,
+    engaged: bool,
+}
+
+impl TrainerBlender {
+    /// Creates an engaged blender giving the student all channels
+    pub const fn new() -> Self {
+        Self::with_authority(Authority::Student)
+    }
+
+    /// Creates an engaged blender giving all channels the same `authority`
+    pub const fn with_authority(authority: Authority) -> Self {
+        Self {
+            authority: [authority; CHANNEL_COUNT],
+            switch: None,
+            engaged: true,
+        }
+    }
+
+    /// Creates an engaged blender giving the student the four stick channels of `map` and the
+    /// master all others
+    pub const fn for_sticks(map: &ChannelMap) -> Self {
+        Self::with_authority(Authority::Master)
+            .with_channel(map.roll(), Authority::Student)
+            .with_channel(map.pitch(), Authority::Student)
+            .with_channel(map.throttle(), Authority::Student)
+            .with_channel(map.yaw(), Authority::Student)
+    }
+
+    /// Sets the authority over one channel, the digital channels always follow the master
+    pub const fn with_channel(mut self, channel: Channel, authority: Authority) -> Self {
+        if !channel.is_digital() {
+            self.authority[channel.index()] = authority;
+        }
+        self
+    }
+
+    /// Engages the trainer link only while `channel` of the master packet is above centre,
+    /// or while it is set for the digital channels
+    pub const fn with_switch(mut self, channel: Channel) -> Self {
+        self.switch = Some(channel);
+        self
+    }
+
+    /// The authority over a proportional channel, or `None` for the digital channels
+    pub fn authority(&self, channel: Channel) -> Option<Authority> {
+        self.authority.get(channel.index()).copied()
+    }
+
+    pub fn set_authority(&mut self, channel: Channel, authority: Authority) {
+        *self = self.with_channel(channel, authority);
+    }
+
+    /// Engages or releases the trainer link, ignored while a switch channel is set
+    pub fn set_engaged(&mut self, engaged: bool) {
+        self.engaged = engaged;
+    }
+
+    /// Whether the trainer link is engaged for the `master` packet
+    pub fn is_engaged(&self, master: &SbusPacket) -> bool {
+        match self.switch {
+            Some(channel) => master.value(channel) > CHANNEL_FUTABA_MID,
+            None => self.engaged,
+        }
+    }
+
+    /// Returns the combined packet to output
+    pub fn blend(&self, master: &SbusPacket, student: Option<&SbusPacket>) -> SbusPacket {
+        let student = match student {
+            Some(student) if !student.flags.failsafe && !student.flags.frame_lost => student,
+            _ => return *master,
+        };
+        if !self.is_engaged(master) {
+            return *master;
+        }
+
+        let mut output = *master;
+        for ((value, &from), authority) in output
+            .channels
+            .iter_mut()
+            .zip(student.channels.iter())
+            .zip(self.authority.iter())
+        {
+            *value = authority.blend(*value, from);
+        }
+        output
+    }
+}
+
+impl Default for TrainerBlender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authority() {
+        assert_eq!(Authority::Master.blend(1000, 2000), 1000);
+        assert_eq!(Authority::Student.blend(1000, 2000), 2000);
+        assert_eq!(Authority::Blend(25).blend(1000, 2000), 1250);
+        assert_eq!(Authority::Blend(25).blend(2000, 1000), 1750);
+        assert_eq!(Authority::Blend(200).blend(2000, 1000), 1000);
+    }
+
+    #[test]
+    fn test_blend() {
+        let mut trainer = TrainerBlender::with_authority(Authority::Blend(50))
+            .with_channel(Channel::Ch2, Authority::Master)
+            .with_channel(Channel::Ch17, Authority::Student);
+        assert_eq!(trainer.authority(Channel::Ch17), None);
+
+        let mut master = SbusPacket::neutral();
+        master.flags.d1 = true;
+        let mut student = SbusPacket::neutral();
+        student.channels = [1192; CHANNEL_COUNT];
+
+        let output = trainer.blend(&master, Some(&student));
+        assert_eq!(output.channels[0], 1092);
+        assert_eq!(output.channels[1], 992);
+        assert!(output.flags.d1);
+
+        assert_eq!(trainer.blend(&master, None), master);
+        student.flags.frame_lost = true;
+        assert_eq!(trainer.blend(&master, Some(&student)), master);
+
+        student.flags.frame_lost = false;
+        trainer.set_engaged(false);
+        assert_eq!(trainer.blend(&master, Some(&student)), master);
+    }
+
+    #[test]
+    fn test_switch() {
+        let trainer = TrainerBlender::new().with_switch(Channel::Ch18);
+        let mut master = SbusPacket::neutral();
+        assert!(!trainer.is_engaged(&master));
+        master.flags.d2 = true;
+        assert!(trainer.is_engaged(&master));
+
+        let trainer = TrainerBlender::for_sticks(&ChannelMap::TAER);
+        assert_eq!(trainer.authority(Channel::Ch1), Some(Authority::Student));
+        assert_eq!(trainer.authority(Channel::Ch5), Some(Authority::Master));
+    }
+}
@@ -0,0 +1,285 @@
+//! Receiving SBUS2 frames and answering in their telemetry slots on the same wire
+use core::time::Duration;
+
+use embedded_hal_async::delay::DelayNs;
+use embedded_io_async::{Read, Write};
+
+use crate::{
+    slot_offset, FrameKind, ProxyError, SbusError, SbusPacket, SbusParserAsync,
+    SBUS2_SLOTS_PER_GROUP, SBUS2_SLOT_COUNT,
+};
+
+/// Time to send the three bytes of a telemetry slot at 100000 baud, 8E2
+const SLOT_TRANSMIT_TIME: Duration = Duration::from_micros(360);
+
+/// The ID byte starting telemetry slot `slot`, 0 to 31
+///
+/// The slot number is sent bit-reversed in the upper five bits, e.g. `0x03`, `0x83`, `0x43`
+/// for the first slots of group 0 and `0x13` for the first slot of group 1.
+pub const fn telemetry_slot_id(slot: u8) -> u8 {
+    (slot & 0x1F).reverse_bits() | 0x03
+}
+
+/// Switches a half-duplex line between receiving and transmitting
+///
+/// Implement this for the driver enable pin of an RS-485 style transceiver, or for a UART
+/// that has to be put into transmit mode explicitly.
+pub trait LineDirection {
+    fn set_transmit(&mut self, transmit: bool);
+}
+
+/// For UARTs that switch direction on their own, e.g. in single-wire half-duplex mode
+impl LineDirection for () {
+    fn set_transmit(&mut self, _transmit: bool) {}
+}
+
+/// Receives SBUS2 frames and sends telemetry in the slots following them
+///
+/// After each SBUS2 frame the transceiver waits for the slots of the frame's slot group with
+/// `delay` and sends the data set for them with [`SbusTransceiver::set_slot`], switching the
+/// line with a [`LineDirection`] around every slot. Slots without data are left to other
+/// sensors. Timing starts when the frame has been read, so keep the reader's latency low.
+///
+/// On a single wire the telemetry bytes, whether sent by this transceiver or by other sensors,
+/// are read back as well and are discarded by the parser while it resynchronises.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn example<R, W, D>(rx: R, tx: W, delay: D)
+/// # where
+/// #     R: embedded_io_async::Read,
+/// #     W: embedded_io_async::Write,
+/// #     D: embedded_hal_async::delay::DelayNs,
+/// # {
+/// use sbus_rs::SbusTransceiver;
+///
+/// let mut transceiver = SbusTransceiver::new(rx, tx, delay);
+/// loop {
+///     // Report a voltage of 12.34 V in slot 1
+///     transceiver.set_slot(1, 1234u16.to_be_bytes());
+///     if let Ok(packet) = transceiver.receive().await {
+///         // Use the packet
+///     }
+/// }
+/// # }
+/// ```
+pub struct SbusTransceiver<R, W, D, L = ()>
+where
+    R: Read,
+    W: Write,
+    D: DelayNs,
+    L: LineDirection,
+{
+    parser: SbusParserAsync<R>,
+    writer: W,
+    delay: D,
+    direction: L,
+    slots: [Option<[u8; 2]>; SBUS2_SLOT_COUNT],
+    inverted: bool,
+}
+
+impl<R, W, D> SbusTransceiver<R, W, D>
+where
+    R: Read,
+    W: Write,
+    D: DelayNs,
+{
+    pub fn new(reader: R, writer: W, delay: D) -> Self {
+        Self {
+            parser: SbusParserAsync::new(reader),
+            writer,
+            delay,
+            direction: (),
+            slots: [None; SBUS2_SLOT_COUNT],
+            inverted: false,
+        }
+    }
+}
+
+impl<R, W, D, L> SbusTransceiver<R, W, D, L>
+where
+    R: Read,
+    W: Write,
+    D: DelayNs,
+    L: LineDirection,
+{
+    /// Switches the line with `direction` before and after every telemetry slot
+    pub fn with_direction<L2: LineDirection>(self, direction: L2) -> SbusTransceiver<R, W, D, L2> {
+        SbusTransceiver {
+            parser: self.parser,
+            writer: self.writer,
+            delay: self.delay,
+            direction,
+            slots: self.slots,
+            inverted: self.inverted,
+        }
+    }
+
+    /// Flips every received and sent byte, for UARTs without an inverter
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.parser = self.parser.with_inverted(inverted);
+        self.inverted = inverted;
+        self
+    }
+
+    /// Sets the two data bytes sent in telemetry slot `slot`, slots above 31 are ignored
+    pub fn set_slot(&mut self, slot: u8, data: [u8; 2]) {
+        if let Some(entry) = self.slots.get_mut(slot as usize) {
+            *entry = Some(data);
+        }
+    }
+
+    /// Stops sending in telemetry slot `slot`
+    pub fn clear_slot(&mut self, slot: u8) {
+        if let Some(entry) = self.slots.get_mut(slot as usize) {
+            *entry = None;
+        }
+    }
+
+    /// The data sent in telemetry slot `slot`
+    pub fn slot(&self, slot: u8) -> Option<[u8; 2]> {
+        self.slots.get(slot as usize).copied().flatten()
+    }
+
+    /// Reads the next valid frame and, for an SBUS2 frame, sends the telemetry of its slot
+    /// group before returning it
+    ///
+    /// Frames are read with [`SbusParserAsync::read_next_valid_frame`], the kind of the frame
+    /// is left in [`SbusParserAsync::last_frame_kind`].
+    pub async fn receive(&mut self) -> Result<SbusPacket, ProxyError<R::Error, W::Error>> {
+        let packet = self
+            .parser
+            .read_next_valid_frame()
+            .await
+            .map_err(ProxyError::Read)?;
+        if let FrameKind::Sbus2(group) = self.parser.last_frame_kind() {
+            self.send_slots(group).await.map_err(ProxyError::Write)?;
+        }
+        Ok(packet)
+    }
+
+    /// Sends the slots with data of the standard slot groups 0 to 3
+    async fn send_slots(&mut self, group: u8) -> Result<(), SbusError<W::Error>> {
+        let Some(slots) = self
+            .slots
+            .chunks_exact(SBUS2_SLOTS_PER_GROUP)
+            .nth(group as usize)
+        else {
+            return Ok(());
+        };
+
+        let mut elapsed = Duration::ZERO;
+        for (index, data) in slots.iter().enumerate() {
+            let Some(data) = data else {
+                continue;
+            };
+            let start = slot_offset(index as u8);
+            let wait = start.saturating_sub(elapsed);
+            self.delay
+                .delay_us(u32::try_from(wait.as_micros()).unwrap_or(u32::MAX))
+                .await;
+
+            let slot = (group as usize * SBUS2_SLOTS_PER_GROUP + index) as u8;
+            let mut bytes = [telemetry_slot_id(slot), data[0], data[1]];
+            if self.inverted {
+                bytes = bytes.map(|byte| !byte);
+            }
+            self.direction.set_transmit(true);
+            let result = match self.writer.write_all(&bytes).await {
+                Ok(()) => self.writer.flush().await,
+                Err(err) => Err(err),
+            };
+            self.direction.set_transmit(false);
+            result.map_err(SbusError::Write)?;
+            elapsed = start + SLOT_TRANSMIT_TIME;
+        }
+        Ok(())
+    }
+
+    pub fn parser(&self) -> &SbusParserAsync<R> {
+        &self.parser
+    }
+
+    pub fn parser_mut(&mut self) -> &mut SbusParserAsync<R> {
+        &mut self.parser
+    }
+
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_io_adapters::tokio_1::FromTokio;
+    use std::io::Cursor;
+
+    /// Records the requested delays instead of waiting
+    #[derive(Default)]
+    struct RecordingDelay(Vec<u32>);
+
+    impl DelayNs for RecordingDelay {
+        async fn delay_ns(&mut self, ns: u32) {
+            self.0.push(ns / 1000);
+        }
+    }
+
+    #[derive(Default)]
+    struct Switches(Vec<bool>);
+
+    impl LineDirection for &mut Switches {
+        fn set_transmit(&mut self, transmit: bool) {
+            self.0.push(transmit);
+        }
+    }
+
+    #[test]
+    fn test_slot_ids() {
+        let group_0: Vec<_> = (0..8).map(telemetry_slot_id).collect();
+        assert_eq!(group_0, [0x03, 0x83, 0x43, 0xC3, 0x23, 0xA3, 0x63, 0xE3]);
+        assert_eq!(telemetry_slot_id(8), 0x13);
+        assert_eq!(telemetry_slot_id(16), 0x0B);
+        assert_eq!(telemetry_slot_id(31), 0xFB);
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_slots() {
+        let mut data = SbusPacket::neutral().to_array().to_vec();
+        data.extend_from_slice(&SbusPacket::neutral().to_array_with_kind(FrameKind::Sbus2(1)));
+
+        let mut switches = Switches::default();
+        let mut transceiver = SbusTransceiver::new(
+            FromTokio::new(Cursor::new(data)),
+            FromTokio::new(Vec::new()),
+            RecordingDelay::default(),
+        )
+        .with_direction(&mut switches);
+        transceiver.set_slot(0, [0xAA, 0xBB]);
+        transceiver.set_slot(9, [0x01, 0x02]);
+        transceiver.set_slot(11, [0x03, 0x04]);
+        transceiver.set_slot(40, [0x05, 0x06]);
+        transceiver.clear_slot(11);
+        transceiver.set_slot(12, [0x03, 0x04]);
+
+        // Plain SBUS frames get no telemetry
+        transceiver.receive().await.unwrap();
+        assert_eq!(transceiver.parser().last_frame_kind(), FrameKind::Sbus1);
+        assert!(transceiver.writer_mut().inner().is_empty());
+
+        transceiver.receive().await.unwrap();
+        assert_eq!(transceiver.parser().last_frame_kind(), FrameKind::Sbus2(1));
+        assert_eq!(
+            transceiver.writer_mut().inner(),
+            &[0x93, 0x01, 0x02, 0x33, 0x03, 0x04]
+        );
+        assert_eq!(transceiver.delay.0, [2660, 3 * 660 - 360]);
+        assert!(matches!(
+            transceiver.receive().await,
+            Err(ProxyError::Read(SbusError::UnexpectedEof))
+        ));
+        drop(transceiver);
+        assert_eq!(switches.0, [true, false, true, false]);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::SbusPacket;
+
+/// A processing step modifying packets on their way from a receiver to a consumer
+///
+/// Transforms take `&mut self` so they can keep state between frames. Closures taking
+/// `&mut SbusPacket` are transforms, and tuples of transforms apply their elements in order.
+pub trait PacketTransform {
+    /// Modifies `packet` in place
+    fn transform(&mut self, packet: &mut SbusPacket);
+
+    /// Returns a transformed copy of `packet`
+    fn apply(&mut self, packet: &SbusPacket) -> SbusPacket {
+        let mut packet = *packet;
+        self.transform(&mut packet);
+        packet
+    }
+}
+
+impl<F> PacketTransform for F
+where
+    F: FnMut(&mut SbusPacket),
+{
+    fn transform(&mut self, packet: &mut SbusPacket) {
+        self(packet)
+    }
+}
+
+/// The empty transform, leaving packets unchanged
+impl PacketTransform for () {
+    fn transform(&mut self, _packet: &mut SbusPacket) {}
+}
+
+macro_rules! impl_tuple_transform {
+    ($($name:ident),+) => {
+        impl<$($name: PacketTransform),+> PacketTransform for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn transform(&mut self, packet: &mut SbusPacket) {
+                let ($($name,)+) = self;
+                $($name.transform(packet);)+
+            }
+        }
+    };
+}
+
+impl_tuple_transform!(A, B);
+impl_tuple_transform!(A, B, C);
+impl_tuple_transform!(A, B, C, D);
+impl_tuple_transform!(A, B, C, D, E);
+impl_tuple_transform!(A, B, C, D, E, F);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain() {
+        let double = |packet: &mut SbusPacket| packet.channels[0] *= 2;
+        let add_one = |packet: &mut SbusPacket| packet.channels[0] += 1;
+
+        let mut packet = SbusPacket::neutral();
+        packet.channels[0] = 100;
+
+        assert_eq!((double, add_one).apply(&packet).channels[0], 201);
+        assert_eq!((add_one, double).apply(&packet).channels[0], 202);
+        assert_eq!(packet.channels[0], 100);
+    }
+}
@@ -0,0 +1,208 @@
+use core::convert::Infallible;
+
+use embedded_io_async::Write;
+
+use crate::{SbusError, SbusPacket, SbusWriterAsync};
+
+/// A periodic timer deciding when the next frame is sent
+///
+/// Implement this for the timer of your executor, e.g. by wrapping `embassy_time::Ticker` or
+/// `tokio::time::Interval` created with the
+/// [`SbusFrameRate::interval`](crate::SbusFrameRate::interval) of the desired frame rate.
+#[allow(async_fn_in_trait)]
+pub trait FrameTicker {
+    /// Waits until the next frame should be sent
+    async fn tick(&mut self);
+}
+
+/// Sends SBUS frames at the fixed rate of a [`FrameTicker`]
+///
+/// SBUS receivers expect a continuous stream of frames, so the transmitter sends the latest
+/// packet on every tick and re-sends the previous packet whenever the application hasn't
+/// provided a new one. With [`SbusTransmitter::with_failsafe_after`] it gives up on a stalled
+/// application after a number of repeats and sends a failsafe frame instead, like a real
+/// transmitter losing its link.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn example<W: embedded_io_async::Write, T: sbus_rs::FrameTicker>(uart: W, ticker: T) {
+/// use sbus_rs::{SbusPacket, SbusTransmitter};
+///
+/// let mut transmitter = SbusTransmitter::new(uart, ticker, SbusPacket::failsafe_default());
+/// let mut throttle = 172;
+///
+/// // Send a new throttle value every frame
+/// let _ = transmitter
+///     .run_with(|| {
+///         throttle = (throttle + 1).min(1811);
+///         let mut packet = SbusPacket::neutral();
+///         packet.channels[2] = throttle;
+///         Some(packet)
+///     })
+///     .await;
+/// # }
+/// ```
+pub struct SbusTransmitter<W, T>
+where
+    W: Write,
+    T: FrameTicker,
+{
+    writer: SbusWriterAsync<W>,
+    ticker: T,
+    packet: SbusPacket,
+    /// Repeats of the same packet before switching to failsafe, `None` to repeat forever
+    max_repeats: Option<u16>,
+    failsafe: SbusPacket,
+    /// Frames sent since the last update
+    sent_since_update: u16,
+}
+
+impl<W, T> SbusTransmitter<W, T>
+where
+    W: Write,
+    T: FrameTicker,
+{
+    /// Creates a transmitter that sends `initial` until the first update
+    pub fn new(writer: W, ticker: T, initial: SbusPacket) -> Self {
+        Self {
+            writer: SbusWriterAsync::new(writer),
+            ticker,
+            packet: initial,
+            max_repeats: None,
+            failsafe: SbusPacket::failsafe_default(),
+            sent_since_update: 0,
+        }
+    }
+
+    /// Sends `failsafe` once the latest packet was repeated `max_repeats` times without an
+    /// update, until the next update
+    ///
+    /// Without this the latest packet is repeated forever.
+    pub fn with_failsafe_after(mut self, max_repeats: u16, failsafe: SbusPacket) -> Self {
+        self.max_repeats = Some(max_repeats);
+        self.failsafe = failsafe;
+        self
+    }
+
+    /// Sets the packet sent from the next tick onwards
+    pub fn update(&mut self, packet: SbusPacket) {
+        self.packet = packet;
+        self.sent_since_update = 0;
+    }
+
+    /// The latest packet, sent on the next tick unless the transmitter is in failsafe
+    pub fn latest(&self) -> &SbusPacket {
+        &self.packet
+    }
+
+    /// Whether the next tick sends the failsafe frame because updates stalled
+    pub fn is_failsafe(&self) -> bool {
+        self.max_repeats
+            .is_some_and(|max_repeats| self.sent_since_update > max_repeats)
+    }
+
+    /// Waits for the next tick and sends the latest packet, or the failsafe frame
+    pub async fn send_next(&mut self) -> Result<(), SbusError<W::Error>> {
+        self.ticker.tick().await;
+        let packet = if self.is_failsafe() {
+            &self.failsafe
+        } else {
+            &self.packet
+        };
+        self.writer.write_frame(packet).await?;
+        self.sent_since_update = self.sent_since_update.saturating_add(1);
+        Ok(())
+    }
+
+    /// Sends frames forever, asking `next` for a new packet before every frame
+    ///
+    /// When `next` returns `None` the previous packet is sent again. Only returns if writing
+    /// fails or a packet holds an out-of-range channel.
+    pub async fn run_with<F>(&mut self, mut next: F) -> Result<Infallible, SbusError<W::Error>>
+    where
+        F: FnMut() -> Option<SbusPacket>,
+    {
+        loop {
+            if let Some(packet) = next() {
+                self.update(packet);
+            }
+            self.send_next().await?;
+        }
+    }
+
+    pub fn writer_mut(&mut self) -> &mut W {
+        self.writer.inner_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_io_adapters::tokio_1::FromTokio;
+
+    struct CountingTicker(usize);
+
+    impl FrameTicker for CountingTicker {
+        async fn tick(&mut self) {
+            self.0 += 1;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resends_last_packet() {
+        let mut transmitter = SbusTransmitter::new(
+            FromTokio::new(Vec::new()),
+            CountingTicker(0),
+            SbusPacket::failsafe_default(),
+        );
+
+        transmitter.send_next().await.unwrap();
+        transmitter.update(SbusPacket::neutral());
+        transmitter.send_next().await.unwrap();
+        transmitter.send_next().await.unwrap();
+
+        assert_eq!(transmitter.ticker.0, 3);
+        let written = transmitter.writer_mut().inner();
+        let frames: Vec<_> = written
+            .chunks(25)
+            .map(|frame| SbusPacket::from_array(frame.try_into().unwrap()).unwrap())
+            .collect();
+        assert_eq!(
+            frames,
+            [
+                SbusPacket::failsafe_default(),
+                SbusPacket::neutral(),
+                SbusPacket::neutral()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failsafe_after_repeats() {
+        let mut failsafe = SbusPacket::failsafe_default();
+        failsafe.channels[2] = 172;
+        let mut transmitter = SbusTransmitter::new(
+            FromTokio::new(Vec::new()),
+            CountingTicker(0),
+            SbusPacket::neutral(),
+        )
+        .with_failsafe_after(2, failsafe);
+
+        for _ in 0..4 {
+            transmitter.send_next().await.unwrap();
+        }
+        assert!(transmitter.is_failsafe());
+        transmitter.update(SbusPacket::neutral());
+        assert!(!transmitter.is_failsafe());
+        transmitter.send_next().await.unwrap();
+
+        let written = transmitter.writer_mut().inner();
+        let failsafe_flags: Vec<_> = written
+            .chunks(25)
+            .map(|frame| SbusPacket::from_array(frame.try_into().unwrap()).unwrap())
+            .map(|packet| packet.flags.failsafe)
+            .collect();
+        assert_eq!(failsafe_flags, [false, false, false, true, false]);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{SbusFrameRate, SBUS_BAUD_RATE, SBUS_FAST_BAUD_RATE};
+
+/// UART parity setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UartParity {
+    None,
+    Even,
+    Odd,
+}
+
+/// UART stop bits setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UartStopBits {
+    One,
+    Two,
+}
+
+/// Describes how a UART must be configured to receive or transmit SBUS
+///
+/// SBUS runs at 100000 baud (200000 for fast SBUS) with 8 data bits, even parity and 2 stop
+/// bits, on an inverted signal. Most SBUS problems come down to a UART that is not set up
+/// exactly like this, so use this descriptor as the single source of truth when configuring
+/// the peripheral.
+///
+/// `inverted` tells whether the signal still needs to be inverted. It is `true` by default;
+/// set it to `false` when an external hardware inverter is fitted.
+///
+/// # Example
+///
+/// ```rust
+/// use sbus_rs::{SbusFrameRate, SbusUartConfig, UartParity, UartStopBits};
+///
+/// let config = SbusUartConfig::for_frame_rate(SbusFrameRate::Fast);
+/// assert_eq!(config.baud_rate, 200_000);
+/// assert_eq!(config.parity, UartParity::Even);
+/// assert_eq!(config.stop_bits, UartStopBits::Two);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SbusUartConfig {
+    pub baud_rate: u32,
+    pub data_bits: u8,
+    pub parity: UartParity,
+    pub stop_bits: UartStopBits,
+    pub inverted: bool,
+}
+
+impl Default for SbusUartConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SbusUartConfig {
+    /// Standard SBUS: 100000 baud, 8E2, inverted
+    pub const fn new() -> Self {
+        Self {
+            baud_rate: SBUS_BAUD_RATE,
+            data_bits: 8,
+            parity: UartParity::Even,
+            stop_bits: UartStopBits::Two,
+            inverted: true,
+        }
+    }
+
+    /// Fast SBUS: 200000 baud, 8E2, inverted
+    pub const fn fast() -> Self {
+        let mut config = Self::new();
+        config.baud_rate = SBUS_FAST_BAUD_RATE;
+        config
+    }
+
+    /// The configuration matching the baud rate of `frame_rate`
+    pub const fn for_frame_rate(frame_rate: SbusFrameRate) -> Self {
+        let mut config = Self::new();
+        config.baud_rate = frame_rate.baud_rate();
+        config
+    }
+
+    /// Sets whether the signal still needs to be inverted
+    pub const fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{FrameKind, SbusError, FLAG_MASK, SBUS_FOOTER, SBUS_FRAME_LENGTH, SBUS_HEADER};
+
+/// The bytes marking the start and the end of a frame
+///
+/// Some clone hardware and inverted capture workflows use sync bytes other than the SBUS
+/// defaults of [`SBUS_HEADER`] and [`SBUS_FOOTER`]. SBUS2 footers are recognised regardless of
+/// the configured footer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SyncBytes {
+    pub header: u8,
+    pub footer: u8,
+}
+
+impl SyncBytes {
+    /// The standard SBUS header `0x0F` and footer `0x00`
+    pub const SBUS: Self = Self::new(SBUS_HEADER, SBUS_FOOTER);
+
+    pub const fn new(header: u8, footer: u8) -> Self {
+        Self { header, footer }
+    }
+}
+
+impl Default for SyncBytes {
+    fn default() -> Self {
+        Self::SBUS
+    }
+}
+
+/// How strictly frames are checked before they are decoded
+///
+/// Some receiver clones emit nonstandard footers, which [`ValidationPolicy::Lenient`] tolerates
+/// at the cost of a higher chance of locking onto a misaligned frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt-03", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ValidationPolicy {
+    /// Only the SBUS footer `0x00` is accepted
+    Strict,
+    /// The SBUS footer and the SBUS2 footers are accepted
+    #[default]
+    Standard,
+    /// Any footer is accepted, only the header is checked
+    Lenient,
+}
+
+impl ValidationPolicy {
+    /// Whether `footer` is an acceptable footer byte under this policy
+    pub const fn accepts_footer(self, footer: u8) -> bool {
+        self.accepts_footer_of(footer, SyncBytes::SBUS)
+    }
+
+    /// Whether `footer` is an acceptable footer byte under this policy when frames end with
+    /// `sync.footer`
+    pub const fn accepts_footer_of(self, footer: u8, sync: SyncBytes) -> bool {
+        match self {
+            ValidationPolicy::Strict => footer == sync.footer,
+            ValidationPolicy::Standard => {
+                footer == sync.footer || FrameKind::from_footer(footer).is_some()
+            }
+            ValidationPolicy::Lenient => true,
+        }
+    }
+
+    /// Checks the header and footer of a raw frame under this policy
+    pub fn validate(self, frame: &[u8; SBUS_FRAME_LENGTH]) -> Result<(), SbusError> {
+        self.validate_with_sync(frame, SyncBytes::SBUS)
+    }
+
+    /// Checks a raw frame under this policy, expecting `sync` as its header and footer
+    pub fn validate_with_sync(
+        self,
+        frame: &[u8; SBUS_FRAME_LENGTH],
+        sync: SyncBytes,
+    ) -> Result<(), SbusError> {
+        let header = frame[0];
+        let footer = frame[SBUS_FRAME_LENGTH - 1];
+
+        if header != sync.header {
+            Err(SbusError::InvalidHeader(header))
+        } else if !self.accepts_footer_of(footer, sync) {
+            Err(SbusError::InvalidFooter(footer))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Fails with [`SbusError::InvalidFlagByte`] if reserved bits of the flag byte are set, see
+/// [`FLAG_MASK`]
+///
+/// Frames are decoded ignoring those bits, this is the opt-in check of
+/// [`Deframer::with_flag_check`](crate::Deframer::with_flag_check), independent of the
+/// [`ValidationPolicy`].
+pub const fn validate_flag_byte(frame: &[u8; SBUS_FRAME_LENGTH]) -> Result<(), SbusError> {
+    let flags = frame[SBUS_FRAME_LENGTH - 2];
+    if flags & !FLAG_MASK == 0 {
+        Ok(())
+    } else {
+        Err(SbusError::InvalidFlagByte(flags))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::frame_with_footer;
+
+    #[test]
+    fn test_footers_per_policy() {
+        for (footer, strict, standard) in [
+            (0x00, true, true),
+            (0x14, false, true),
+            (0x55, false, false),
+        ] {
+            let expected = |ok: bool| {
+                if ok {
+                    Ok(())
+                } else {
+                    Err(SbusError::InvalidFooter(footer))
+                }
+            };
+            assert_eq!(
+                ValidationPolicy::Strict.validate(&frame_with_footer(footer)),
+                expected(strict)
+            );
+            assert_eq!(
+                ValidationPolicy::Standard.validate(&frame_with_footer(footer)),
+                expected(standard)
+            );
+            assert_eq!(
+                ValidationPolicy::Lenient.validate(&frame_with_footer(footer)),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_reserved_flag_bits() {
+        let mut frame = frame_with_footer(0x14);
+        frame[SBUS_FRAME_LENGTH - 2] = 0x1C;
+        assert_eq!(
+            validate_flag_byte(&frame),
+            Err(SbusError::InvalidFlagByte(0x1C))
+        );
+        assert_eq!(ValidationPolicy::Standard.validate(&frame), Ok(()));
+
+        frame[SBUS_FRAME_LENGTH - 2] = 0x0C;
+        assert_eq!(validate_flag_byte(&frame), Ok(()));
+    }
+
+    #[test]
+    fn test_custom_sync_bytes() {
+        let sync = SyncBytes::new(0xF0, 0xFF);
+        let mut frame = frame_with_footer(0xFF);
+        frame[0] = 0xF0;
+
+        assert_eq!(
+            ValidationPolicy::Strict.validate_with_sync(&frame, sync),
+            Ok(())
+        );
+        assert_eq!(
+            ValidationPolicy::Strict.validate(&frame),
+            Err(SbusError::InvalidHeader(0xF0))
+        );
+
+        frame[SBUS_FRAME_LENGTH - 1] = 0x00;
+        assert_eq!(
+            ValidationPolicy::Strict.validate_with_sync(&frame, sync),
+            Err(SbusError::InvalidFooter(0x00))
+        );
+    }
+
+    #[test]
+    fn test_header_always_checked() {
+        let mut frame = frame_with_footer(0x00);
+        frame[0] = 0x0E;
+        assert_eq!(
+            ValidationPolicy::Lenient.validate(&frame),
+            Err(SbusError::InvalidHeader(0x0E))
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! SBus Frame writer
+
+#[cfg(feature = "async")]
+mod asynch;
+#[cfg(feature = "blocking")]
+mod blocking;
+
+#[cfg(feature = "async")]
+pub use asynch::SbusWriterAsync;
+#[cfg(feature = "blocking")]
+pub use blocking::SbusWriter;
+
+use crate::{SbusError, SbusPacket, SBUS_FRAME_LENGTH};
+
+/// Frames staged for a single write by the writers' `write_frames`
+const BATCH_FRAMES: usize = 4;
+
+/// Encodes as many of `packets` as fit into `buffer`, stopping at the first packet with an
+/// out-of-range channel
+///
+/// Returns the number of frames encoded and the error of the packet that stopped encoding.
+fn stage_frames(
+    packets: &[SbusPacket],
+    inverted: bool,
+    buffer: &mut [u8; BATCH_FRAMES * SBUS_FRAME_LENGTH],
+) -> (usize, Option<SbusError>) {
+    let mut count = 0;
+    for (packet, slot) in packets
+        .iter()
+        .zip(buffer.chunks_exact_mut(SBUS_FRAME_LENGTH))
+    {
+        let mut frame = match packet.try_to_array() {
+            Ok(frame) => frame,
+            Err(err) => return (count, Some(err)),
+        };
+        if inverted {
+            frame = frame.map(|byte| !byte);
+        }
+        slot.copy_from_slice(&frame);
+        count += 1;
+    }
+    (count, None)
+}
@@ -0,0 +1,128 @@
+use embedded_io_async::Write;
+
+use crate::packet::SbusPacket;
+use crate::{SbusError, TransmitAsync, SBUS_FRAME_LENGTH};
+
+/// Writer for SBUS frames to an async I/O sink
+pub struct SbusWriterAsync<W>
+where
+    W: Write,
+{
+    writer: W,
+    inverted: bool,
+}
+
+impl<W> SbusWriterAsync<W>
+where
+    W: Write,
+{
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            inverted: false,
+        }
+    }
+
+    /// Flips every byte after encoding, for UARTs transmitting SBUS without an inverter
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    /// Asynchronously encodes and writes a complete SBUS frame
+    ///
+    /// The frame is staged and handed to the writer in one piece rather than byte by byte,
+    /// which keeps it together on USB-CDC and DMA-backed UARTs.
+    ///
+    /// Fails with [`SbusError::OutOfRange`] without writing anything if a channel exceeds
+    /// [`CHANNEL_MAX`](crate::CHANNEL_MAX).
+    pub async fn write_frame(&mut self, packet: &SbusPacket) -> Result<(), SbusError<W::Error>> {
+        let mut frame = packet
+            .try_to_array()
+            .map_err(SbusError::with_reader_error)?;
+        if self.inverted {
+            frame = frame.map(|byte| !byte);
+        }
+        self.writer
+            .write_all(&frame)
+            .await
+            .map_err(SbusError::Write)?;
+        self.writer.flush().await.map_err(SbusError::Write)
+    }
+
+    /// Encodes and writes several SBUS frames, handing them to the writer a few frames at a
+    /// time
+    ///
+    /// Stops at the first packet with a channel exceeding [`CHANNEL_MAX`](crate::CHANNEL_MAX)
+    /// and fails with [`SbusError::OutOfRange`], after writing the frames before it.
+    pub async fn write_frames(
+        &mut self,
+        packets: &[SbusPacket],
+    ) -> Result<(), SbusError<W::Error>> {
+        let mut buffer = [0u8; super::BATCH_FRAMES * SBUS_FRAME_LENGTH];
+        let mut packets = packets;
+        while !packets.is_empty() {
+            let (count, err) = super::stage_frames(packets, self.inverted, &mut buffer);
+            self.writer
+                .write_all(&buffer[..count * SBUS_FRAME_LENGTH])
+                .await
+                .map_err(SbusError::Write)?;
+            if let Some(err) = err {
+                self.writer.flush().await.map_err(SbusError::Write)?;
+                return Err(err.with_reader_error());
+            }
+            packets = &packets[count..];
+        }
+        self.writer.flush().await.map_err(SbusError::Write)
+    }
+
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+impl<W> TransmitAsync for SbusWriterAsync<W>
+where
+    W: Write,
+{
+    type Error = SbusError<W::Error>;
+
+    async fn transmit(&mut self, packet: &SbusPacket) -> Result<(), Self::Error> {
+        self.write_frame(packet).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_io_adapters::tokio_1::FromTokio;
+
+    #[tokio::test]
+    async fn test_write_frame_async() {
+        let packet = SbusPacket::failsafe_default();
+        let mut writer = SbusWriterAsync::new(FromTokio::new(Vec::new()));
+
+        writer.write_frame(&packet).await.unwrap();
+        writer.write_frame(&packet).await.unwrap();
+
+        let written = writer.inner_mut().inner();
+        assert_eq!(written.len(), 50);
+        assert_eq!(SbusPacket::from_array(&packet.to_array()), Ok(packet));
+        assert_eq!(written[..25], packet.to_array());
+    }
+
+    #[tokio::test]
+    async fn test_write_frames_async() {
+        let packets = [SbusPacket::neutral(), SbusPacket::failsafe_default()];
+        let mut writer = SbusWriterAsync::new(FromTokio::new(Vec::new())).with_inverted(true);
+
+        writer.write_frames(&packets).await.unwrap();
+
+        let written = writer.inner_mut().inner();
+        assert_eq!(written.len(), 50);
+        assert_eq!(
+            written[25..],
+            SbusPacket::failsafe_default().to_array().map(|b| !b)
+        );
+    }
+}
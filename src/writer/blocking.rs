@@ -0,0 +1,190 @@
+use embedded_io::Write;
+
+use crate::packet::SbusPacket;
+use crate::{SbusError, Transmit, SBUS_FRAME_LENGTH};
+
+/// Writer for SBUS frames to a blocking I/O sink
+pub struct SbusWriter<W>
+where
+    W: Write,
+{
+    writer: W,
+    inverted: bool,
+}
+
+impl<W> SbusWriter<W>
+where
+    W: Write,
+{
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            inverted: false,
+        }
+    }
+
+    /// Flips every byte after encoding, for UARTs transmitting SBUS without an inverter
+    pub fn with_inverted(mut self, inverted: bool) -> Self {
+        self.inverted = inverted;
+        self
+    }
+
+    /// Encodes and writes a complete SBUS frame
+    ///
+    /// The frame is staged and handed to the writer in one piece rather than byte by byte,
+    /// which keeps it together on USB-CDC and DMA-backed UARTs.
+    ///
+    /// Fails with [`SbusError::OutOfRange`] without writing anything if a channel exceeds
+    /// [`CHANNEL_MAX`](crate::CHANNEL_MAX).
+    pub fn write_frame(&mut self, packet: &SbusPacket) -> Result<(), SbusError<W::Error>> {
+        let mut frame = packet
+            .try_to_array()
+            .map_err(SbusError::with_reader_error)?;
+        if self.inverted {
+            frame = frame.map(|byte| !byte);
+        }
+        self.writer.write_all(&frame).map_err(SbusError::Write)?;
+        self.writer.flush().map_err(SbusError::Write)
+    }
+
+    /// Encodes and writes several SBUS frames, handing them to the writer a few frames at a
+    /// time
+    ///
+    /// Stops at the first packet with a channel exceeding [`CHANNEL_MAX`](crate::CHANNEL_MAX)
+    /// and fails with [`SbusError::OutOfRange`], after writing the frames before it.
+    pub fn write_frames(&mut self, packets: &[SbusPacket]) -> Result<(), SbusError<W::Error>> {
+        let mut buffer = [0u8; super::BATCH_FRAMES * SBUS_FRAME_LENGTH];
+        let mut packets = packets;
+        while !packets.is_empty() {
+            let (count, err) = super::stage_frames(packets, self.inverted, &mut buffer);
+            self.writer
+                .write_all(&buffer[..count * SBUS_FRAME_LENGTH])
+                .map_err(SbusError::Write)?;
+            if let Some(err) = err {
+                self.writer.flush().map_err(SbusError::Write)?;
+                return Err(err.with_reader_error());
+            }
+            packets = &packets[count..];
+        }
+        self.writer.flush().map_err(SbusError::Write)
+    }
+
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+}
+
+impl<W> Transmit for SbusWriter<W>
+where
+    W: Write,
+{
+    type Error = SbusError<W::Error>;
+
+    fn transmit(&mut self, packet: &SbusPacket) -> Result<(), Self::Error> {
+        self.write_frame(packet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_io_adapters::std::FromStd;
+
+    #[test]
+    fn test_write_frame() {
+        let packet = SbusPacket::neutral_throttle_low(2);
+        let mut writer = SbusWriter::new(FromStd::new(Vec::new()));
+
+        writer.write_frame(&packet).unwrap();
+
+        let written = writer.inner_mut().inner();
+        assert_eq!(written[..], packet.to_array());
+        assert_eq!(
+            SbusPacket::from_array(written[..].try_into().unwrap()),
+            Ok(packet)
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_channel_rejected() {
+        let mut packet = SbusPacket::neutral();
+        packet.channels[7] = 4000;
+        let mut writer = SbusWriter::new(FromStd::new(Vec::new()));
+
+        assert!(matches!(
+            writer.write_frame(&packet),
+            Err(SbusError::OutOfRange {
+                channel: 7,
+                value: 4000
+            })
+        ));
+        assert!(writer.inner_mut().inner().is_empty());
+    }
+
+    #[test]
+    fn test_writer_error() {
+        let mut buffer = [0u8; 10];
+        let mut writer = SbusWriter::new(&mut buffer[..]);
+        assert_eq!(
+            writer.write_frame(&SbusPacket::neutral()),
+            Err(SbusError::Write(embedded_io::SliceWriteError::Full))
+        );
+    }
+
+    #[test]
+    fn test_inverted_round_trip() {
+        use crate::SbusParser;
+        use std::io::Cursor;
+
+        let packet = SbusPacket::neutral_throttle_low(2);
+        let mut writer = SbusWriter::new(FromStd::new(Vec::new())).with_inverted(true);
+        writer.write_frame(&packet).unwrap();
+
+        let written = writer.inner_mut().inner().clone();
+        assert_eq!(written[0], !crate::SBUS_HEADER);
+
+        let mut parser = SbusParser::new(FromStd::new(Cursor::new(written))).with_inverted(true);
+        assert_eq!(parser.read_frame().unwrap(), packet);
+    }
+
+    /// Records the size of every write
+    #[derive(Default)]
+    struct WriteSizes(Vec<usize>, Vec<u8>);
+
+    impl embedded_io::ErrorType for WriteSizes {
+        type Error = core::convert::Infallible;
+    }
+
+    impl Write for WriteSizes {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            self.0.push(buf.len());
+            self.1.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_coalesced_writes() {
+        let mut writer = SbusWriter::new(WriteSizes::default());
+        writer.write_frame(&SbusPacket::neutral()).unwrap();
+        assert_eq!(writer.inner_mut().0, [25]);
+
+        let mut packets = [SbusPacket::neutral(); 6];
+        writer.inner_mut().0.clear();
+        writer.write_frames(&packets).unwrap();
+        assert_eq!(writer.inner_mut().0, [100, 50]);
+        assert_eq!(writer.inner_mut().1.len(), 7 * 25);
+
+        packets[5].channels[0] = 4000;
+        writer.inner_mut().0.clear();
+        assert!(matches!(
+            writer.write_frames(&packets),
+            Err(SbusError::OutOfRange { channel: 0, .. })
+        ));
+        assert_eq!(writer.inner_mut().0, [100, 25]);
+    }
+}
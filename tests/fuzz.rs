@@ -1,7 +1,8 @@
 use arbitrary::Arbitrary;
 use libfuzzer_sys::fuzz_target;
 use sbus_rs::{
-    pack_channels, SbusError, SbusPacket, CHANNEL_MAX, SBUS_FOOTER, SBUS_FRAME_LENGTH, SBUS_HEADER,
+    pack_channels, FrameKind, SbusError, SbusPacket, CHANNEL_MAX, SBUS_FOOTER, SBUS_FRAME_LENGTH,
+    SBUS_HEADER,
 };
 
 #[derive(Debug, Arbitrary)]
@@ -28,39 +29,31 @@ fuzz_target!(|frame: FuzzedSbusFrame| {
 });
 
 use proptest::prelude::*;
+#[cfg(feature = "proptest")]
+use sbus_rs::strategies;
 
+#[cfg(feature = "proptest")]
 proptest! {
     // Test that valid frames are always parsed correctly
         #[test]
         #[ignore]
     fn test_valid_frame_parsing(
-        channels in prop::array::uniform16(0..=2047u16),
-        flags in 0u8..=0x0F
+        (expected, buffer) in strategies::packet_frame()
     ) {
-        let mut buffer = [0u8; SBUS_FRAME_LENGTH];
-        buffer[0] = SBUS_HEADER;
-        buffer[SBUS_FRAME_LENGTH-1] = SBUS_FOOTER;
-
-        // Pack channels into the buffer
-        pack_channels(&mut buffer, &channels);
-        buffer[23] = flags;
-
         let result = SbusPacket::from_array(&buffer);
         prop_assert!(result.is_ok());
 
         if let Ok(packet) = result {
             // Verify all channels were parsed correctly
-            for (i, (&expected, &actual)) in channels.iter().zip(packet.channels.iter()).enumerate() {
+            for (i, (&expected, &actual)) in expected.channels.iter().zip(packet.channels.iter()).enumerate() {
                 prop_assert_eq!(expected, actual, "Channel {} mismatch", i);
             }
-            // Verify flags
-            prop_assert_eq!(packet.flags.d1, flags & 0x01 != 0);
-            prop_assert_eq!(packet.flags.d2, flags & 0x02 != 0);
-            prop_assert_eq!(packet.flags.frame_lost, flags & 0x04 != 0);
-            prop_assert_eq!(packet.flags.failsafe, flags & 0x08 != 0);
+            prop_assert_eq!(packet.flags, expected.flags);
         }
     }
+}
 
+proptest! {
     // Test that frames with invalid headers are rejected
         #[test]
         #[ignore]
@@ -83,7 +76,7 @@ proptest! {
         #[ignore]
     fn test_invalid_footer_rejection(
         payload in prop::collection::vec(any::<u8>(), SBUS_FRAME_LENGTH-2),
-        footer in (0u8..=0xFF).prop_filter("non-sbus footers", |f| *f != SBUS_FOOTER)
+        footer in (0u8..=0xFF).prop_filter("non-sbus footers", |f| FrameKind::from_footer(*f).is_none())
     ) {
         let mut buffer = [0u8; SBUS_FRAME_LENGTH];
         buffer[0] = SBUS_HEADER;
@@ -30,7 +30,69 @@ fn test_partial_frame_reading() {
     let mut parser = SbusParser::new(FromStd::new(cursor));
 
     let result = parser.read_frame();
-    assert!(matches!(result, Err(SbusError::ReadError)));
+    assert!(matches!(result, Err(SbusError::UnexpectedEof)));
+}
+
+#[test]
+fn test_push_parser_byte_at_a_time() {
+    let mut parser = SBusPacketParser::new();
+    let mut parsed = 0;
+
+    for _ in 0..10 {
+        for byte in create_valid_frame() {
+            parser.push_bytes(&[byte]);
+            if parser.try_parse().is_some() {
+                parsed += 1;
+            }
+        }
+    }
+    assert_eq!(parsed, 10);
+}
+
+#[test]
+fn test_push_parser_clear_buffer() {
+    let frame = create_valid_frame();
+    let mut parser = SBusPacketParser::new();
+
+    parser.push_bytes(&frame[..20]);
+    parser.clear_buffer();
+    parser.push_bytes(&frame[20..]);
+    assert!(parser.try_parse().is_none());
+
+    parser.push_bytes(&frame);
+    assert!(parser.try_parse().is_some());
+}
+
+#[cfg(feature = "test-util")]
+#[test]
+fn test_mock_source_short_reads() {
+    use sbus_rs::test_util::MockSbusSource;
+
+    let packet = SbusPacket::failsafe_default();
+    let source = MockSbusSource::new()
+        .with_packet(&packet)
+        .with_corrupted(&packet, Corruption::Truncated)
+        .with_chunk_size(1);
+    let mut parser = SbusParser::new(source);
+
+    assert_eq!(parser.read_frame(), Ok(packet));
+    assert!(parser.read_frame().is_err());
+}
+
+#[test]
+fn test_push_parser_peek() {
+    let frame = create_valid_frame();
+    let mut parser = SBusPacketParser::new();
+
+    parser.push_bytes(&frame[..20]);
+    assert_eq!(parser.buffered_len(), 20);
+    assert!(parser.peek_frame().is_none());
+
+    parser.push_bytes(&frame[20..]);
+    let peeked = parser.peek_frame().expect("A complete frame is buffered");
+    assert_eq!(parser.buffered_len(), SBUS_FRAME_LENGTH);
+    assert_eq!(parser.try_parse(), Some(peeked));
+    assert_eq!(parser.buffered_len(), 0);
 }
 
 fn create_valid_frame() -> [u8; SBUS_FRAME_LENGTH] {